@@ -0,0 +1,124 @@
+-- Создание таблицы компаний
+CREATE TABLE IF NOT EXISTS companies (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(255) NOT NULL,
+    description TEXT NULL,
+    website VARCHAR(255) NULL,
+    email VARCHAR(320) NULL,
+    phone VARCHAR(32) NULL,
+    timezone VARCHAR(64) NOT NULL DEFAULT 'Europe/Moscow', -- IANA имя часового пояса
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE TRIGGER update_companies_updated_at BEFORE UPDATE ON companies
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Участники компании и их роли
+CREATE TABLE IF NOT EXISTS company_members (
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(32) NOT NULL CHECK (role IN ('owner', 'manager', 'staff')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (company_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_company_members_user ON company_members(user_id);
+
+-- Филиалы компании
+CREATE TABLE IF NOT EXISTS locations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    address VARCHAR(512) NULL,
+    city VARCHAR(255) NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_locations_company ON locations(company_id);
+
+CREATE TRIGGER update_locations_updated_at BEFORE UPDATE ON locations
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Услуги компании, длительность и буферы в минутах, цена в минимальных единицах валюты
+CREATE TABLE IF NOT EXISTS services (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT NULL,
+    duration_minutes INTEGER NOT NULL CHECK (duration_minutes > 0),
+    buffer_before_minutes INTEGER NOT NULL DEFAULT 0 CHECK (buffer_before_minutes >= 0),
+    buffer_after_minutes INTEGER NOT NULL DEFAULT 0 CHECK (buffer_after_minutes >= 0),
+    slot_step_minutes INTEGER NOT NULL DEFAULT 15 CHECK (slot_step_minutes > 0),
+    price_amount BIGINT NOT NULL DEFAULT 0 CHECK (price_amount >= 0),
+    currency CHAR(3) NOT NULL DEFAULT 'RUB',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_services_company ON services(company_id);
+
+CREATE TRIGGER update_services_updated_at BEFORE UPDATE ON services
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Сотрудники компании (аккаунт пользователя не обязателен)
+CREATE TABLE IF NOT EXISTS staff (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    display_name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_staff_company ON staff(company_id);
+
+CREATE TRIGGER update_staff_updated_at BEFORE UPDATE ON staff
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Какие услуги оказывает сотрудник
+CREATE TABLE IF NOT EXISTS staff_services (
+    staff_id UUID NOT NULL REFERENCES staff(id) ON DELETE CASCADE,
+    service_id UUID NOT NULL REFERENCES services(id) ON DELETE CASCADE,
+    PRIMARY KEY (staff_id, service_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_staff_services_service ON staff_services(service_id);
+
+-- Еженедельное расписание: день недели ISO (1 - понедельник), локальное время компании
+CREATE TABLE IF NOT EXISTS staff_schedules (
+    id SERIAL PRIMARY KEY,
+    staff_id UUID NOT NULL REFERENCES staff(id) ON DELETE CASCADE,
+    location_id UUID NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    weekday SMALLINT NOT NULL CHECK (weekday BETWEEN 1 AND 7),
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    CHECK (end_time > start_time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_staff_schedules_staff ON staff_schedules(staff_id);
+
+-- Исключения из расписания: выходные, перерывы и дополнительные часы
+CREATE TABLE IF NOT EXISTS staff_schedule_exceptions (
+    id SERIAL PRIMARY KEY,
+    staff_id UUID NOT NULL REFERENCES staff(id) ON DELETE CASCADE,
+    location_id UUID NULL REFERENCES locations(id) ON DELETE CASCADE,
+    exception_date DATE NOT NULL,
+    start_time TIME NULL,
+    end_time TIME NULL,
+    is_available BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (
+        (start_time IS NULL AND end_time IS NULL AND NOT is_available)
+        OR (start_time IS NOT NULL AND end_time IS NOT NULL AND end_time > start_time)
+    ),
+    CHECK (NOT is_available OR location_id IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_staff_schedule_exceptions_staff_date
+    ON staff_schedule_exceptions(staff_id, exception_date);
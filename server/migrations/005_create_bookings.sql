@@ -0,0 +1,31 @@
+-- Расширение для исключающих ограничений по uuid + диапазону времени
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+-- Создание таблицы записей
+CREATE TABLE IF NOT EXISTS bookings (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    customer_id UUID NOT NULL REFERENCES users(id),
+    company_id UUID NOT NULL REFERENCES companies(id),
+    service_id UUID NOT NULL REFERENCES services(id),
+    staff_id UUID NOT NULL REFERENCES staff(id),
+    location_id UUID NOT NULL REFERENCES locations(id),
+    during TSTZRANGE NOT NULL,  -- время приема
+    blocked TSTZRANGE NOT NULL, -- время приема вместе с буферами услуги
+    status VARCHAR(32) NOT NULL DEFAULT 'confirmed'
+        CHECK (status IN ('pending', 'confirmed', 'cancelled')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (NOT isempty(during) AND blocked @> during),
+
+    -- У одного сотрудника не может быть пересекающихся активных записей
+    CONSTRAINT bookings_staff_no_overlap EXCLUDE USING gist (
+        staff_id WITH =,
+        blocked WITH &&
+    ) WHERE (status IN ('pending', 'confirmed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_bookings_customer ON bookings(customer_id);
+CREATE INDEX IF NOT EXISTS idx_bookings_company ON bookings(company_id);
+
+CREATE TRIGGER update_bookings_updated_at BEFORE UPDATE ON bookings
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
@@ -0,0 +1,45 @@
+-- Расширенный набор статусов записи
+ALTER TABLE bookings DROP CONSTRAINT IF EXISTS bookings_status_check;
+UPDATE bookings SET status = 'cancelled_by_customer' WHERE status = 'cancelled';
+ALTER TABLE bookings ADD CONSTRAINT bookings_status_check CHECK (status IN (
+    'pending', 'confirmed', 'checked_in', 'completed',
+    'cancelled_by_customer', 'cancelled_by_company', 'no_show'
+));
+
+-- Клиент в статусе checked_in все еще занимает время сотрудника
+ALTER TABLE bookings DROP CONSTRAINT IF EXISTS bookings_staff_no_overlap;
+ALTER TABLE bookings ADD CONSTRAINT bookings_staff_no_overlap EXCLUDE USING gist (
+    staff_id WITH =,
+    blocked WITH &&
+) WHERE (status IN ('pending', 'confirmed', 'checked_in'));
+
+-- История изменений записи
+CREATE TABLE IF NOT EXISTS booking_events (
+    id BIGSERIAL PRIMARY KEY,
+    booking_id UUID NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    event_type VARCHAR(32) NOT NULL CHECK (event_type IN ('created', 'status_changed', 'rescheduled')),
+    from_status VARCHAR(32) NULL,
+    to_status VARCHAR(32) NOT NULL,
+    previous_during TSTZRANGE NULL,
+    new_during TSTZRANGE NULL,
+    actor_id UUID NOT NULL REFERENCES users(id),
+    reason TEXT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_events_booking ON booking_events(booking_id, created_at);
+
+-- Политики отмены и переноса для клиентов
+CREATE TABLE IF NOT EXISTS booking_policies (
+    company_id UUID PRIMARY KEY REFERENCES companies(id) ON DELETE CASCADE,
+    customer_cancel_min_hours INTEGER NOT NULL DEFAULT 0 CHECK (customer_cancel_min_hours >= 0),
+    customer_reschedule_min_hours INTEGER NOT NULL DEFAULT 0 CHECK (customer_reschedule_min_hours >= 0),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_booking_policies_updated_at BEFORE UPDATE ON booking_policies
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Событие создания для уже существующих записей
+INSERT INTO booking_events (booking_id, event_type, to_status, new_during, actor_id, created_at)
+SELECT id, 'created', status, during, customer_id, created_at FROM bookings;
@@ -0,0 +1,21 @@
+-- Временные брони слотов хранятся в bookings со статусом 'held': так одно исключающее
+-- ограничение не дает пересечься ни записям, ни броням одного сотрудника
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS hold_expires_at TIMESTAMP WITH TIME ZONE NULL;
+
+ALTER TABLE bookings DROP CONSTRAINT IF EXISTS bookings_status_check;
+ALTER TABLE bookings ADD CONSTRAINT bookings_status_check CHECK (status IN (
+    'held', 'pending', 'confirmed', 'checked_in', 'completed',
+    'cancelled_by_customer', 'cancelled_by_company', 'no_show'
+));
+
+ALTER TABLE bookings ADD CONSTRAINT bookings_hold_expiry_check
+    CHECK ((status = 'held') = (hold_expires_at IS NOT NULL));
+
+ALTER TABLE bookings DROP CONSTRAINT IF EXISTS bookings_staff_no_overlap;
+ALTER TABLE bookings ADD CONSTRAINT bookings_staff_no_overlap EXCLUDE USING gist (
+    staff_id WITH =,
+    blocked WITH &&
+) WHERE (status IN ('held', 'pending', 'confirmed', 'checked_in'));
+
+-- Для фонового снятия истекших броней
+CREATE INDEX IF NOT EXISTS idx_bookings_hold_expires ON bookings(hold_expires_at) WHERE status = 'held';
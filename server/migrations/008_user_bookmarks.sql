@@ -0,0 +1,9 @@
+-- Закладки пользователей на компании
+CREATE TABLE IF NOT EXISTS user_bookmarks (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, company_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_bookmarks_user_created ON user_bookmarks(user_id, created_at DESC);
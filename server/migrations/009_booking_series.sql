@@ -0,0 +1,34 @@
+-- Серии повторяющихся записей (правило RRULE из RFC 5545)
+CREATE TABLE IF NOT EXISTS booking_series (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    customer_id UUID NOT NULL REFERENCES users(id),
+    company_id UUID NOT NULL REFERENCES companies(id),
+    service_id UUID NOT NULL REFERENCES services(id),
+    staff_id UUID NOT NULL REFERENCES staff(id),
+    location_id UUID NULL REFERENCES locations(id),
+    rrule TEXT NOT NULL,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL, -- начало первого вхождения (DTSTART)
+    timezone VARCHAR(64) NOT NULL,               -- часовой пояс, в котором считались вхождения
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_series_customer ON booking_series(customer_id);
+
+CREATE TRIGGER update_booking_series_updated_at BEFORE UPDATE ON booking_series
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Каждое вхождение - обычная запись; recurrence_id - исходное время по правилу,
+-- не меняется при переносе вхождения
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS series_id UUID NULL REFERENCES booking_series(id);
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS recurrence_id TIMESTAMP WITH TIME ZONE NULL;
+
+CREATE INDEX IF NOT EXISTS idx_bookings_series ON bookings(series_id, recurrence_id) WHERE series_id IS NOT NULL;
+
+-- Ограничение можно отложить до конца транзакции: при переносе "этого и следующих"
+-- вхождения временно занимают время друг друга
+ALTER TABLE bookings DROP CONSTRAINT IF EXISTS bookings_staff_no_overlap;
+ALTER TABLE bookings ADD CONSTRAINT bookings_staff_no_overlap EXCLUDE USING gist (
+    staff_id WITH =,
+    blocked WITH &&
+) WHERE (status IN ('held', 'pending', 'confirmed', 'checked_in')) DEFERRABLE INITIALLY IMMEDIATE;
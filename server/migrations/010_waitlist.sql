@@ -0,0 +1,35 @@
+-- Лист ожидания на дни без свободного времени
+CREATE TABLE IF NOT EXISTS waitlist_entries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    service_id UUID NOT NULL REFERENCES services(id) ON DELETE CASCADE,
+    staff_id UUID NULL REFERENCES staff(id) ON DELETE CASCADE,       -- NULL - подойдет любой сотрудник
+    location_id UUID NULL REFERENCES locations(id) ON DELETE CASCADE,
+    desired_date DATE NOT NULL,                                       -- день в часовом поясе компании
+    status VARCHAR(32) NOT NULL DEFAULT 'waiting'
+        CHECK (status IN ('waiting', 'offered', 'booked', 'declined', 'expired', 'cancelled')),
+    -- Предложение освободившегося слота: бронь в bookings и срок ответа
+    hold_id UUID NULL REFERENCES bookings(id) ON DELETE SET NULL,
+    offered_staff_id UUID NULL REFERENCES staff(id) ON DELETE SET NULL,
+    offered_starts_at TIMESTAMP WITH TIME ZONE NULL,
+    offer_expires_at TIMESTAMP WITH TIME ZONE NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (status <> 'offered' OR offer_expires_at IS NOT NULL)
+);
+
+-- На одну услугу и день клиент стоит в очереди один раз
+CREATE UNIQUE INDEX IF NOT EXISTS idx_waitlist_customer_day
+    ON waitlist_entries(customer_id, service_id, desired_date) WHERE status IN ('waiting', 'offered');
+
+-- Очередь на день компании в порядке записи
+CREATE INDEX IF NOT EXISTS idx_waitlist_queue
+    ON waitlist_entries(company_id, desired_date, created_at) WHERE status = 'waiting';
+
+-- Для фонового перевода просроченных предложений следующему в очереди
+CREATE INDEX IF NOT EXISTS idx_waitlist_offer_expires
+    ON waitlist_entries(offer_expires_at) WHERE status = 'offered';
+
+CREATE TRIGGER update_waitlist_entries_updated_at BEFORE UPDATE ON waitlist_entries
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
@@ -0,0 +1,58 @@
+-- Групповые услуги: занятие на `capacity` мест, каждая запись занимает одно место
+ALTER TABLE services ADD COLUMN IF NOT EXISTS capacity INTEGER NOT NULL DEFAULT 1 CHECK (capacity >= 1);
+
+-- Занятие групповой услуги у сотрудника; создается первой записью на это время
+CREATE TABLE IF NOT EXISTS group_sessions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id),
+    service_id UUID NOT NULL REFERENCES services(id),
+    staff_id UUID NOT NULL REFERENCES staff(id),
+    location_id UUID NOT NULL REFERENCES locations(id),
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    capacity INTEGER NOT NULL CHECK (capacity >= 1), -- вместимость на момент создания занятия
+    seats_taken INTEGER NOT NULL DEFAULT 0,          -- ведется триггером по записям
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT group_sessions_seats_check CHECK (seats_taken BETWEEN 0 AND capacity),
+    CONSTRAINT group_sessions_staff_service_start_key UNIQUE (staff_id, service_id, starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_sessions_company_start ON group_sessions(company_id, starts_at);
+
+CREATE TRIGGER update_group_sessions_updated_at BEFORE UPDATE ON group_sessions
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS session_id UUID NULL REFERENCES group_sessions(id);
+
+-- На одно занятие клиент записывается один раз
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bookings_session_customer ON bookings(session_id, customer_id)
+    WHERE session_id IS NOT NULL AND status IN ('held', 'pending', 'confirmed', 'checked_in');
+
+-- Записи одного занятия делят время сотрудника; любые другие пересечения по-прежнему запрещены
+ALTER TABLE bookings DROP CONSTRAINT IF EXISTS bookings_staff_no_overlap;
+ALTER TABLE bookings ADD CONSTRAINT bookings_staff_no_overlap EXCLUDE USING gist (
+    staff_id WITH =,
+    blocked WITH &&,
+    (COALESCE(session_id, id)) WITH <>
+) WHERE (status IN ('held', 'pending', 'confirmed', 'checked_in')) DEFERRABLE INITIALLY IMMEDIATE;
+
+-- Счетчик занятых мест меняется инкрементом: строка занятия блокируется, и параллельные
+-- записи выстраиваются в очередь, а проверка seats_taken <= capacity не дает переполнить занятие
+CREATE OR REPLACE FUNCTION update_group_session_seats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') AND OLD.session_id IS NOT NULL
+       AND OLD.status IN ('held', 'pending', 'confirmed', 'checked_in') THEN
+        UPDATE group_sessions SET seats_taken = seats_taken - 1 WHERE id = OLD.session_id;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') AND NEW.session_id IS NOT NULL
+       AND NEW.status IN ('held', 'pending', 'confirmed', 'checked_in') THEN
+        UPDATE group_sessions SET seats_taken = seats_taken + 1 WHERE id = NEW.session_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER bookings_group_session_seats AFTER INSERT OR UPDATE OF status, session_id OR DELETE ON bookings
+    FOR EACH ROW EXECUTE PROCEDURE update_group_session_seats();
@@ -0,0 +1,64 @@
+-- Ресурсы филиала: кабинеты и оборудование, которые услуга занимает вместе с сотрудником
+CREATE TABLE IF NOT EXISTS resources (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    location_id UUID NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_resources_company ON resources(company_id);
+
+CREATE TRIGGER update_resources_updated_at BEFORE UPDATE ON resources
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Требование услуги: нужен один любой ресурс из вариантов ("1 из [кабинет А, кабинет Б]")
+CREATE TABLE IF NOT EXISTS service_resource_requirements (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    service_id UUID NOT NULL REFERENCES services(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    UNIQUE (service_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS service_resource_options (
+    requirement_id UUID NOT NULL REFERENCES service_resource_requirements(id) ON DELETE CASCADE,
+    resource_id UUID NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    PRIMARY KEY (requirement_id, resource_id)
+);
+
+-- Ресурсы, выбранные для записи (по одному на требование услуги)
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS resource_ids UUID[] NOT NULL DEFAULT '{}';
+
+-- Занятость ресурсов активными записями; ведется триггером по bookings.
+-- Записи одного группового занятия делят ресурсы так же, как время сотрудника
+CREATE TABLE IF NOT EXISTS booking_resources (
+    booking_id UUID NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    resource_id UUID NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    session_id UUID NULL,
+    blocked TSTZRANGE NOT NULL,
+    PRIMARY KEY (booking_id, resource_id),
+    CONSTRAINT booking_resources_no_overlap EXCLUDE USING gist (
+        resource_id WITH =,
+        blocked WITH &&,
+        (COALESCE(session_id, booking_id)) WITH <>
+    ) DEFERRABLE INITIALLY IMMEDIATE
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_resources_resource ON booking_resources USING gist (resource_id, blocked);
+
+CREATE OR REPLACE FUNCTION sync_booking_resources()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM booking_resources WHERE booking_id = NEW.id;
+    IF NEW.status IN ('held', 'pending', 'confirmed', 'checked_in') THEN
+        INSERT INTO booking_resources (booking_id, resource_id, session_id, blocked)
+        SELECT NEW.id, resource_id, NEW.session_id, NEW.blocked
+        FROM unnest(NEW.resource_ids) AS resource_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER bookings_sync_resources AFTER INSERT OR UPDATE OF status, blocked, session_id, resource_ids ON bookings
+    FOR EACH ROW EXECUTE PROCEDURE sync_booking_resources();
@@ -0,0 +1,18 @@
+-- Подписки на календарь по секретной ссылке.
+-- Личная лента (staff_id IS NULL) содержит записи пользователя как клиента и как сотрудника,
+-- лента сотрудника выдается руководителям компании. Храним только хеш токена
+CREATE TABLE IF NOT EXISTS calendar_feeds (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    company_id UUID NULL REFERENCES companies(id) ON DELETE CASCADE,
+    staff_id UUID NULL REFERENCES staff(id) ON DELETE CASCADE,
+    token_hash BYTEA NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMP WITH TIME ZONE NULL,
+    CHECK ((company_id IS NULL) = (staff_id IS NULL))
+);
+
+-- У пользователя одна действующая ссылка на каждую ленту
+CREATE UNIQUE INDEX IF NOT EXISTS idx_calendar_feeds_active
+    ON calendar_feeds(user_id, COALESCE(staff_id, '00000000-0000-0000-0000-000000000000'::uuid))
+    WHERE revoked_at IS NULL;
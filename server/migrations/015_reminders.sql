@@ -0,0 +1,29 @@
+-- Правила напоминаний компании: за сколько минут до начала записи напомнить клиенту
+CREATE TABLE IF NOT EXISTS reminder_rules (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    offset_minutes INTEGER NOT NULL CHECK (offset_minutes > 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (company_id, offset_minutes)
+);
+
+-- Напоминания о записях. Планируются в той же транзакции, что и создание или перенос записи;
+-- при переносе и отмене запланированные строки отменяются, отправленные остаются в истории
+CREATE TABLE IF NOT EXISTS booking_reminders (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    booking_id UUID NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    rule_id UUID NULL REFERENCES reminder_rules(id) ON DELETE SET NULL,
+    offset_minutes INTEGER NOT NULL,
+    remind_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'scheduled'
+        CHECK (status IN ('scheduled', 'sent', 'cancelled', 'missed')),
+    sent_at TIMESTAMP WITH TIME ZONE NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Не больше одного запланированного напоминания по правилу на запись
+CREATE UNIQUE INDEX IF NOT EXISTS idx_booking_reminders_scheduled
+    ON booking_reminders(booking_id, rule_id) WHERE status = 'scheduled';
+CREATE INDEX IF NOT EXISTS idx_booking_reminders_due ON booking_reminders(remind_at)
+    WHERE status = 'scheduled';
+CREATE INDEX IF NOT EXISTS idx_booking_reminders_booking ON booking_reminders(booking_id);
@@ -0,0 +1,40 @@
+-- Очередь фоновых задач. Воркеры забирают задачи через FOR UPDATE SKIP LOCKED;
+-- задача, исчерпавшая попытки, переходит в dead и ждет ручного повтора администратором
+CREATE TABLE IF NOT EXISTS jobs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    kind VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5 CHECK (max_attempts > 0),
+    run_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- Аренда задачи воркером; задачу упавшего воркера после истечения заберет другой
+    locked_until TIMESTAMP WITH TIME ZONE NULL,
+    -- Ключ задачи, которая должна стоять в очереди не более одного раза
+    dedupe_key VARCHAR(128) NULL,
+    last_error TEXT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_due ON jobs(run_at) WHERE status IN ('pending', 'running');
+CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status, updated_at);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_dedupe ON jobs(dedupe_key)
+    WHERE status = 'pending' AND dedupe_key IS NOT NULL;
+
+CREATE TRIGGER update_jobs_updated_at BEFORE UPDATE ON jobs
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Будим воркеры, слушающие канал jobs, как только появляется задача к выполнению
+CREATE OR REPLACE FUNCTION notify_job_pending()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('jobs', NEW.kind);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_jobs_pending AFTER INSERT OR UPDATE OF status ON jobs
+    FOR EACH ROW WHEN (NEW.status = 'pending') EXECUTE PROCEDURE notify_job_pending();
@@ -0,0 +1,53 @@
+-- События занятости слотов для подписчиков потока GET /v1/companies/{id}/slots/stream.
+-- Триггер сообщает в канал slot_events, когда запись (или бронь) начинает или перестает занимать
+-- время сотрудника; NOTIFY доставляется после коммита во все экземпляры сервера
+CREATE OR REPLACE FUNCTION notify_slot_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    old_blocking BOOLEAN := FALSE;
+    new_blocking BOOLEAN := FALSE;
+    moved BOOLEAN := FALSE;
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        old_blocking := OLD.status IN ('held', 'pending', 'confirmed', 'checked_in');
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        new_blocking := NEW.status IN ('held', 'pending', 'confirmed', 'checked_in');
+    END IF;
+    IF TG_OP = 'UPDATE' THEN
+        moved := OLD.blocked IS DISTINCT FROM NEW.blocked
+            OR OLD.staff_id IS DISTINCT FROM NEW.staff_id
+            OR OLD.location_id IS DISTINCT FROM NEW.location_id;
+    END IF;
+
+    IF old_blocking AND (NOT new_blocking OR moved) THEN
+        PERFORM pg_notify('slot_events', json_build_object(
+            'kind', 'freed',
+            'company_id', OLD.company_id,
+            'service_id', OLD.service_id,
+            'staff_id', OLD.staff_id,
+            'location_id', OLD.location_id,
+            'session_id', OLD.session_id,
+            'starts_at', lower(OLD.during),
+            'ends_at', upper(OLD.during)
+        )::text);
+    END IF;
+    IF new_blocking AND (NOT old_blocking OR moved) THEN
+        PERFORM pg_notify('slot_events', json_build_object(
+            'kind', 'taken',
+            'company_id', NEW.company_id,
+            'service_id', NEW.service_id,
+            'staff_id', NEW.staff_id,
+            'location_id', NEW.location_id,
+            'session_id', NEW.session_id,
+            'starts_at', lower(NEW.during),
+            'ends_at', upper(NEW.during)
+        )::text);
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER bookings_notify_slot_change
+    AFTER INSERT OR DELETE OR UPDATE OF status, blocked, staff_id, location_id ON bookings
+    FOR EACH ROW EXECUTE PROCEDURE notify_slot_change();
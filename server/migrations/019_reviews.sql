@@ -0,0 +1,59 @@
+-- Отзывы клиентов: один на завершенную запись, оценка 1-5 и текст
+CREATE TABLE IF NOT EXISTS reviews (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    booking_id UUID NOT NULL UNIQUE REFERENCES bookings(id) ON DELETE CASCADE,
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    service_id UUID NOT NULL REFERENCES services(id),
+    staff_id UUID NOT NULL REFERENCES staff(id),
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rating INTEGER NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    text TEXT NULL,
+    -- Публичный ответ компании
+    reply TEXT NULL,
+    replied_at TIMESTAMP WITH TIME ZONE NULL,
+    -- Скрытый модератором отзыв не показывается и не учитывается в рейтинге
+    is_hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    hidden_reason TEXT NULL,
+    hidden_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    hidden_at TIMESTAMP WITH TIME ZONE NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reviews_company ON reviews(company_id, created_at DESC) WHERE NOT is_hidden;
+CREATE INDEX IF NOT EXISTS idx_reviews_staff ON reviews(staff_id, created_at DESC) WHERE NOT is_hidden;
+
+CREATE TRIGGER update_reviews_updated_at BEFORE UPDATE ON reviews
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Сводный рейтинг хранится в компании, чтобы по нему сортировать поиск без агрегации
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS rating_avg DOUBLE PRECISION NULL;
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS rating_count INTEGER NOT NULL DEFAULT 0;
+
+-- Пересчет по видимым отзывам: строка компании блокируется, и параллельные отзывы
+-- пересчитывают рейтинг по очереди
+CREATE OR REPLACE FUNCTION refresh_company_rating()
+RETURNS TRIGGER AS $$
+DECLARE
+    target UUID;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target := OLD.company_id;
+    ELSE
+        target := NEW.company_id;
+    END IF;
+
+    UPDATE companies c
+    SET rating_avg = stats.rating_avg, rating_count = stats.rating_count
+    FROM (
+        SELECT round(avg(rating), 2)::float8 AS rating_avg, count(*)::int AS rating_count
+        FROM reviews
+        WHERE company_id = target AND NOT is_hidden
+    ) stats
+    WHERE c.id = target;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER reviews_refresh_company_rating AFTER INSERT OR UPDATE OF rating, is_hidden OR DELETE ON reviews
+    FOR EACH ROW EXECUTE PROCEDURE refresh_company_rating();
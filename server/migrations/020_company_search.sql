@@ -0,0 +1,83 @@
+-- Поиск компаний: рубрики, координаты филиалов и полнотекстовый документ компании
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS categories TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS search_vector TSVECTOR NOT NULL DEFAULT ''::tsvector;
+
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION NULL;
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION NULL;
+ALTER TABLE locations DROP CONSTRAINT IF EXISTS locations_coordinates_check;
+ALTER TABLE locations ADD CONSTRAINT locations_coordinates_check CHECK (
+    (latitude IS NULL) = (longitude IS NULL)
+    AND (latitude IS NULL OR (latitude BETWEEN -90 AND 90 AND longitude BETWEEN -180 AND 180))
+);
+
+CREATE INDEX IF NOT EXISTS idx_companies_search ON companies USING gin(search_vector);
+CREATE INDEX IF NOT EXISTS idx_companies_categories ON companies USING gin(categories);
+CREATE INDEX IF NOT EXISTS idx_locations_city ON locations(lower(city)) WHERE deleted_at IS NULL;
+
+-- Документ компании на русском и английском: название (вес A), рубрики и названия услуг (B),
+-- описание (C)
+CREATE OR REPLACE FUNCTION company_search_document(
+    target UUID,
+    company_name TEXT,
+    company_description TEXT,
+    company_categories TEXT[]
+)
+RETURNS TSVECTOR AS $$
+    SELECT setweight(to_tsvector('russian', coalesce(company_name, '')), 'A')
+        || setweight(to_tsvector('english', coalesce(company_name, '')), 'A')
+        || setweight(to_tsvector('russian', array_to_string(company_categories, ' ')), 'B')
+        || setweight(to_tsvector('english', array_to_string(company_categories, ' ')), 'B')
+        || setweight(to_tsvector('russian', coalesce(s.names, '')), 'B')
+        || setweight(to_tsvector('english', coalesce(s.names, '')), 'B')
+        || setweight(to_tsvector('russian', coalesce(company_description, '')), 'C')
+        || setweight(to_tsvector('english', coalesce(company_description, '')), 'C')
+    FROM (
+        SELECT string_agg(name, ' ') AS names
+        FROM services
+        WHERE company_id = target AND deleted_at IS NULL
+    ) s
+$$ language 'sql' STABLE;
+
+CREATE OR REPLACE FUNCTION refresh_company_search_document()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.search_vector := company_search_document(NEW.id, NEW.name, NEW.description, NEW.categories);
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER companies_search_document BEFORE INSERT OR UPDATE OF name, description, categories ON companies
+    FOR EACH ROW EXECUTE PROCEDURE refresh_company_search_document();
+
+-- Названия услуг входят в документ компании
+CREATE OR REPLACE FUNCTION refresh_company_search_from_services()
+RETURNS TRIGGER AS $$
+DECLARE
+    target UUID;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target := OLD.company_id;
+    ELSE
+        target := NEW.company_id;
+    END IF;
+
+    UPDATE companies
+    SET search_vector = company_search_document(id, name, description, categories)
+    WHERE id = target;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER services_company_search_document AFTER INSERT OR UPDATE OF name, deleted_at OR DELETE ON services
+    FOR EACH ROW EXECUTE PROCEDURE refresh_company_search_from_services();
+
+UPDATE companies SET search_vector = company_search_document(id, name, description, categories);
+
+-- Расстояние по большому кругу в километрах (формула гаверсинусов)
+CREATE OR REPLACE FUNCTION geo_distance_km(lat1 FLOAT8, lon1 FLOAT8, lat2 FLOAT8, lon2 FLOAT8)
+RETURNS FLOAT8 AS $$
+    SELECT 2 * 6371.0088 * asin(least(1.0, sqrt(
+        power(sin(radians(lat2 - lat1) / 2), 2)
+        + cos(radians(lat1)) * cos(radians(lat2)) * power(sin(radians(lon2 - lon1) / 2), 2)
+    )))
+$$ language 'sql' IMMUTABLE;
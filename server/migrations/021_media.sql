@@ -0,0 +1,28 @@
+-- Загруженные изображения: аватары пользователей, логотипы и галереи компаний.
+-- Файлы лежат в хранилище (локальный каталог или S3), здесь - их ключи и размеры
+CREATE TABLE IF NOT EXISTS media (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('avatar', 'logo', 'gallery')),
+    -- Аватар принадлежит пользователю, логотип и галерея - компании
+    user_id UUID NULL REFERENCES users(id) ON DELETE CASCADE,
+    company_id UUID NULL REFERENCES companies(id) ON DELETE CASCADE,
+    uploaded_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    storage_key TEXT NOT NULL,
+    thumbnail_key TEXT NOT NULL,
+    content_type VARCHAR(32) NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT media_owner CHECK (
+        CASE kind
+            WHEN 'avatar' THEN user_id IS NOT NULL AND company_id IS NULL
+            ELSE company_id IS NOT NULL AND user_id IS NULL
+        END
+    )
+);
+
+-- Аватар и логотип существуют в одном экземпляре, новый заменяет прежний
+CREATE UNIQUE INDEX IF NOT EXISTS idx_media_user_avatar ON media(user_id) WHERE kind = 'avatar';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_media_company_logo ON media(company_id) WHERE kind = 'logo';
+CREATE INDEX IF NOT EXISTS idx_media_company_gallery ON media(company_id, created_at) WHERE kind = 'gallery';
@@ -0,0 +1,29 @@
+-- Внутренние заметки компании о клиенте (аллергии, предпочтения). Клиенту не показываются
+CREATE TABLE IF NOT EXISTS customer_notes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    author_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL CHECK (length(body) > 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_notes_customer ON customer_notes(company_id, customer_id, created_at DESC);
+
+CREATE TRIGGER update_customer_notes_updated_at BEFORE UPDATE ON customer_notes
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Метки клиента в компании, например «vip» или «аллергия»
+CREATE TABLE IF NOT EXISTS customer_tags (
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tag VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (company_id, customer_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_tags_tag ON customer_tags(company_id, tag);
+
+-- Список клиентов компании собирается из ее записей
+CREATE INDEX IF NOT EXISTS idx_bookings_company_customer ON bookings(company_id, customer_id);
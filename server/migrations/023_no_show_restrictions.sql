@@ -0,0 +1,22 @@
+-- Правило компании для клиентов с неявками: после no_show_limit неявок за no_show_window_days дней
+-- новые записи требуют подтверждения компании или онлайн-запись закрывается. NULL - правило выключено
+ALTER TABLE booking_policies ADD COLUMN IF NOT EXISTS no_show_limit INTEGER NULL CHECK (no_show_limit > 0);
+ALTER TABLE booking_policies ADD COLUMN IF NOT EXISTS no_show_window_days INTEGER NOT NULL DEFAULT 90
+    CHECK (no_show_window_days > 0);
+ALTER TABLE booking_policies ADD COLUMN IF NOT EXISTS no_show_action VARCHAR(32) NOT NULL DEFAULT 'require_confirmation'
+    CHECK (no_show_action IN ('require_confirmation', 'blocked'));
+
+-- Ограничение, заданное руководителем компании вручную; действует вместо правила
+CREATE TABLE IF NOT EXISTS customer_restriction_overrides (
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    restriction VARCHAR(32) NOT NULL CHECK (restriction IN ('none', 'require_confirmation', 'blocked')),
+    reason TEXT NULL,
+    set_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (company_id, customer_id)
+);
+
+-- Подсчет неявок клиента за окно правила
+CREATE INDEX IF NOT EXISTS idx_bookings_no_show ON bookings(company_id, customer_id, lower(during))
+    WHERE status = 'no_show';
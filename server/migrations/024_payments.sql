@@ -0,0 +1,38 @@
+-- Депозит за запись на услугу. Суммы хранятся в минимальных единицах валюты (копейки, центы),
+-- валюта депозита - валюта услуги. NULL - запись без предоплаты
+ALTER TABLE services ADD COLUMN IF NOT EXISTS deposit_amount BIGINT NULL CHECK (deposit_amount > 0);
+
+-- Клиент, отменивший запись не позднее чем за столько часов до начала, получает депозит обратно
+ALTER TABLE booking_policies ADD COLUMN IF NOT EXISTS deposit_refund_min_hours INTEGER NOT NULL DEFAULT 24
+    CHECK (deposit_refund_min_hours >= 0);
+
+-- Депозит записи. Строка появляется вместе с записью в статусе pending, платеж в платежной
+-- системе создается, когда клиент переходит к оплате; после неудачи можно начать заново
+CREATE TABLE IF NOT EXISTS payments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    booking_id UUID NOT NULL UNIQUE REFERENCES bookings(id) ON DELETE CASCADE,
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    currency CHAR(3) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending' CHECK (status IN (
+        'pending', 'succeeded', 'failed', 'cancelled', 'refund_pending', 'refunded'
+    )),
+    provider VARCHAR(32) NULL,
+    provider_payment_id VARCHAR(255) NULL,
+    -- Секрет для оплаты на стороне клиента (client_secret Stripe)
+    client_secret TEXT NULL,
+    refund_amount BIGINT NULL CHECK (refund_amount > 0 AND refund_amount <= amount),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    paid_at TIMESTAMP WITH TIME ZONE NULL,
+    refunded_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_payments_provider ON payments(provider, provider_payment_id)
+    WHERE provider_payment_id IS NOT NULL;
+-- Поиск неоплаченных депозитов с истекшим сроком
+CREATE INDEX IF NOT EXISTS idx_payments_unpaid ON payments(created_at) WHERE status IN ('pending', 'failed');
+
+CREATE TRIGGER update_payments_updated_at BEFORE UPDATE ON payments
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
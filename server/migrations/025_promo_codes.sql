@@ -0,0 +1,44 @@
+-- Промокоды компаний. Процентная скидка - discount_value от 1 до 100, фиксированная - сумма
+-- в минимальных единицах валюты currency. Пустые service_ids и location_ids - без ограничений
+CREATE TABLE IF NOT EXISTS promo_codes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    -- Хранится в верхнем регистре, вводится без учета регистра
+    code VARCHAR(32) NOT NULL,
+    discount_type VARCHAR(16) NOT NULL CHECK (discount_type IN ('percent', 'fixed')),
+    discount_value BIGINT NOT NULL CHECK (discount_value > 0),
+    currency CHAR(3) NULL,
+    valid_from TIMESTAMP WITH TIME ZONE NULL,
+    valid_until TIMESTAMP WITH TIME ZONE NULL,
+    max_redemptions INTEGER NULL CHECK (max_redemptions > 0),
+    max_per_customer INTEGER NULL CHECK (max_per_customer > 0),
+    service_ids UUID[] NOT NULL DEFAULT '{}',
+    location_ids UUID[] NOT NULL DEFAULT '{}',
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Счетчик использований; увеличивается вместе с созданием записи под блокировкой строки
+    redemption_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (company_id, code),
+    CHECK (discount_type <> 'percent' OR discount_value <= 100),
+    CHECK ((discount_type = 'fixed') = (currency IS NOT NULL)),
+    CHECK (valid_from IS NULL OR valid_until IS NULL OR valid_from < valid_until),
+    CHECK (max_redemptions IS NULL OR redemption_count <= max_redemptions)
+);
+
+CREATE TRIGGER update_promo_codes_updated_at BEFORE UPDATE ON promo_codes
+    FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+-- Использование промокода записью, создается в одной транзакции с записью
+CREATE TABLE IF NOT EXISTS promo_redemptions (
+    booking_id UUID PRIMARY KEY REFERENCES bookings(id) ON DELETE CASCADE,
+    promo_code_id UUID NOT NULL REFERENCES promo_codes(id) ON DELETE CASCADE,
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    price_amount BIGINT NOT NULL CHECK (price_amount >= 0),
+    discount_amount BIGINT NOT NULL CHECK (discount_amount >= 0 AND discount_amount <= price_amount),
+    currency CHAR(3) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Лимит на клиента
+CREATE INDEX IF NOT EXISTS idx_promo_redemptions_customer ON promo_redemptions(promo_code_id, customer_id);
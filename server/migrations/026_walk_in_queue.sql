@@ -0,0 +1,57 @@
+-- Электронная очередь филиала для клиентов без записи. Очередь ведется по дням: queue_date -
+-- дата в часовом поясе компании на момент постановки, номера талонов начинаются с 1 каждый день
+CREATE TABLE IF NOT EXISTS queue_tickets (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    company_id UUID NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    location_id UUID NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    service_id UUID NULL REFERENCES services(id) ON DELETE SET NULL,
+    -- Клиент из приложения; талон, выданный киоском, хранит только имя
+    customer_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    customer_name VARCHAR(100) NULL,
+    queue_date DATE NOT NULL,
+    number INTEGER NOT NULL CHECK (number > 0),
+    status VARCHAR(16) NOT NULL DEFAULT 'waiting'
+        CHECK (status IN ('waiting', 'called', 'served', 'no_show', 'cancelled', 'expired')),
+    -- Сотрудник, вызвавший клиента
+    called_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    joined_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    called_at TIMESTAMP WITH TIME ZONE NULL,
+    finished_at TIMESTAMP WITH TIME ZONE NULL,
+    UNIQUE (location_id, queue_date, number),
+    CHECK (customer_id IS NOT NULL OR customer_name IS NOT NULL)
+);
+
+-- Клиент стоит в очереди филиала не больше чем с одним талоном
+CREATE UNIQUE INDEX IF NOT EXISTS idx_queue_tickets_active_customer ON queue_tickets(location_id, customer_id)
+    WHERE status IN ('waiting', 'called');
+CREATE INDEX IF NOT EXISTS idx_queue_tickets_waiting ON queue_tickets(location_id, queue_date, number)
+    WHERE status = 'waiting';
+-- Средняя длительность обслуживания по истории филиала
+CREATE INDEX IF NOT EXISTS idx_queue_tickets_served ON queue_tickets(location_id, finished_at)
+    WHERE status = 'served';
+
+-- Последний выданный номер талона за день; строка блокируется на время выдачи
+CREATE TABLE IF NOT EXISTS queue_counters (
+    location_id UUID NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    queue_date DATE NOT NULL,
+    last_number INTEGER NOT NULL,
+    PRIMARY KEY (location_id, queue_date)
+);
+
+-- Любое изменение очереди сообщается в канал queue_events после коммита, подписчики
+-- пересчитывают свое место в очереди
+CREATE OR REPLACE FUNCTION notify_queue_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('queue_events', json_build_object(
+        'company_id', NEW.company_id,
+        'location_id', NEW.location_id,
+        'queue_date', NEW.queue_date
+    )::text);
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER queue_tickets_notify_change
+    AFTER INSERT OR UPDATE OF status ON queue_tickets
+    FOR EACH ROW EXECUTE PROCEDURE notify_queue_change();
@@ -0,0 +1,69 @@
+-- Аналитика компании: отчеты читают материализованные представления, которые фоновая
+-- задача периодически обновляет. День записи считается в часовом поясе компании
+
+-- Записи по дням, услугам, сотрудникам и филиалам. Брони слотов ('held') не учитываются.
+-- Время сотрудника считается по активным и завершенным записям; записи одного группового
+-- занятия занимают его время один раз. Выручка - по оказанным услугам (checked_in и
+-- completed): цена и скидка берутся из использования промокода, иначе текущая цена услуги
+CREATE MATERIALIZED VIEW IF NOT EXISTS analytics_booking_daily AS
+WITH counted AS (
+    SELECT b.id, b.company_id, b.service_id, b.staff_id, b.location_id, b.status,
+           (lower(b.during) AT TIME ZONE c.timezone)::date AS day,
+           EXTRACT(EPOCH FROM upper(b.during) - lower(b.during)) / 60 AS minutes,
+           b.status IN ('pending', 'confirmed', 'checked_in', 'completed') AS occupies,
+           ROW_NUMBER() OVER (
+               PARTITION BY COALESCE(b.session_id, b.id),
+                            b.status IN ('pending', 'confirmed', 'checked_in', 'completed')
+               ORDER BY b.id
+           ) = 1 AS first_in_session,
+           COALESCE(r.price_amount, s.price_amount) AS price_amount,
+           COALESCE(r.discount_amount, 0) AS discount_amount,
+           COALESCE(r.currency, s.currency) AS currency
+    FROM bookings b
+    JOIN companies c ON c.id = b.company_id
+    JOIN services s ON s.id = b.service_id
+    LEFT JOIN promo_redemptions r ON r.booking_id = b.id
+    WHERE b.status <> 'held'
+)
+SELECT company_id, day, service_id, staff_id, location_id, currency,
+       COUNT(*) AS bookings,
+       COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+       COUNT(*) FILTER (WHERE status = 'cancelled_by_customer') AS cancelled_by_customer,
+       COUNT(*) FILTER (WHERE status = 'cancelled_by_company') AS cancelled_by_company,
+       COUNT(*) FILTER (WHERE status = 'no_show') AS no_show,
+       COALESCE(SUM(minutes) FILTER (WHERE occupies AND first_in_session), 0)::bigint AS booked_minutes,
+       COUNT(*) FILTER (WHERE status IN ('checked_in', 'completed')) AS visits,
+       COALESCE(SUM(price_amount - discount_amount) FILTER (WHERE status IN ('checked_in', 'completed')), 0)
+           ::bigint AS revenue,
+       COALESCE(SUM(discount_amount) FILTER (WHERE status IN ('checked_in', 'completed')), 0)::bigint AS discount
+FROM counted
+GROUP BY company_id, day, service_id, staff_id, location_id, currency;
+
+-- Уникальный индекс нужен для REFRESH ... CONCURRENTLY: отчеты не блокируются на время обновления
+CREATE UNIQUE INDEX IF NOT EXISTS idx_analytics_booking_daily_key
+    ON analytics_booking_daily(company_id, day, service_id, staff_id, location_id, currency);
+
+-- Дни, в которые у клиента была неотмененная запись в компании, и день его первой такой записи.
+-- Клиент новый в день первой записи и постоянный во все следующие
+CREATE MATERIALIZED VIEW IF NOT EXISTS analytics_customer_days AS
+SELECT company_id, customer_id, day,
+       MIN(day) OVER (PARTITION BY company_id, customer_id) AS first_day
+FROM (
+    SELECT DISTINCT b.company_id, b.customer_id, (lower(b.during) AT TIME ZONE c.timezone)::date AS day
+    FROM bookings b
+    JOIN companies c ON c.id = b.company_id
+    WHERE b.status IN ('pending', 'confirmed', 'checked_in', 'completed')
+) visits;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_analytics_customer_days_key
+    ON analytics_customer_days(company_id, day, customer_id);
+
+-- Когда представление обновлялось в последний раз; отчеты показывают, насколько свежи данные
+CREATE TABLE IF NOT EXISTS analytics_refreshes (
+    view_name VARCHAR(64) PRIMARY KEY,
+    refreshed_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+INSERT INTO analytics_refreshes (view_name, refreshed_at)
+VALUES ('analytics_booking_daily', NOW()), ('analytics_customer_days', NOW())
+ON CONFLICT (view_name) DO NOTHING;
@@ -0,0 +1,52 @@
+-- Цена услуги на момент записи: выручка в аналитике не меняется вслед за текущей ценой услуги.
+-- Бронь слота ('held') получает цену, когда становится записью
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS price_amount BIGINT NULL CHECK (price_amount >= 0);
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS currency CHAR(3) NULL;
+
+-- Для прежних записей цена не сохранялась: берем ее из использования промокода, иначе текущую цену услуги
+UPDATE bookings b
+SET price_amount = COALESCE(
+        (SELECT r.price_amount FROM promo_redemptions r WHERE r.booking_id = b.id), s.price_amount),
+    currency = COALESCE(
+        (SELECT r.currency FROM promo_redemptions r WHERE r.booking_id = b.id), s.currency)
+FROM services s
+WHERE s.id = b.service_id AND b.status <> 'held' AND b.price_amount IS NULL;
+
+-- Выручка по дням теперь считается по цене, сохраненной в записи; скидка - из использования промокода
+DROP MATERIALIZED VIEW IF EXISTS analytics_booking_daily;
+
+CREATE MATERIALIZED VIEW analytics_booking_daily AS
+WITH counted AS (
+    SELECT b.id, b.company_id, b.service_id, b.staff_id, b.location_id, b.status,
+           (lower(b.during) AT TIME ZONE c.timezone)::date AS day,
+           EXTRACT(EPOCH FROM upper(b.during) - lower(b.during)) / 60 AS minutes,
+           b.status IN ('pending', 'confirmed', 'checked_in', 'completed') AS occupies,
+           ROW_NUMBER() OVER (
+               PARTITION BY COALESCE(b.session_id, b.id),
+                            b.status IN ('pending', 'confirmed', 'checked_in', 'completed')
+               ORDER BY b.id
+           ) = 1 AS first_in_session,
+           b.price_amount,
+           COALESCE(r.discount_amount, 0) AS discount_amount,
+           b.currency
+    FROM bookings b
+    JOIN companies c ON c.id = b.company_id
+    LEFT JOIN promo_redemptions r ON r.booking_id = b.id
+    WHERE b.status <> 'held'
+)
+SELECT company_id, day, service_id, staff_id, location_id, currency,
+       COUNT(*) AS bookings,
+       COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+       COUNT(*) FILTER (WHERE status = 'cancelled_by_customer') AS cancelled_by_customer,
+       COUNT(*) FILTER (WHERE status = 'cancelled_by_company') AS cancelled_by_company,
+       COUNT(*) FILTER (WHERE status = 'no_show') AS no_show,
+       COALESCE(SUM(minutes) FILTER (WHERE occupies AND first_in_session), 0)::bigint AS booked_minutes,
+       COUNT(*) FILTER (WHERE status IN ('checked_in', 'completed')) AS visits,
+       COALESCE(SUM(price_amount - discount_amount) FILTER (WHERE status IN ('checked_in', 'completed')), 0)
+           ::bigint AS revenue,
+       COALESCE(SUM(discount_amount) FILTER (WHERE status IN ('checked_in', 'completed')), 0)::bigint AS discount
+FROM counted
+GROUP BY company_id, day, service_id, staff_id, location_id, currency;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_analytics_booking_daily_key
+    ON analytics_booking_daily(company_id, day, service_id, staff_id, location_id, currency);
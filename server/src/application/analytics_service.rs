@@ -0,0 +1,327 @@
+use crate::application::slot_engine::{StaffAvailability, working_minutes};
+use crate::application::slot_service::company_timezone;
+use crate::domain::entities::{
+    AnalyticsQuery, AnalyticsReport, AnalyticsReportKind, AnalyticsRows, Company, UtilizationReportRow,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{AnalyticsRepository, AnalyticsService, CompanyRepository};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Максимальный период отчета в днях
+const MAX_REPORT_DAYS: i64 = 366;
+
+/// Доля занятого времени с точностью до десятой процента; в нерабочий день доли нет
+pub fn utilization(booked_minutes: i64, available_minutes: i64) -> Option<f64> {
+    if available_minutes <= 0 {
+        return None;
+    }
+    Some((booked_minutes as f64 / available_minutes as f64 * 1000.0).round() / 1000.0)
+}
+
+/// Колонки CSV по отчетам, в порядке полей строки отчета. Заголовок пишется и для пустого отчета
+fn csv_columns(kind: AnalyticsReportKind) -> &'static [&'static str] {
+    match kind {
+        AnalyticsReportKind::Bookings => &[
+            "date", "service_id", "service_name", "staff_id", "staff_name", "bookings", "completed", "cancelled",
+            "no_show",
+        ],
+        AnalyticsReportKind::Utilization => &[
+            "date", "staff_id", "staff_name", "available_minutes", "booked_minutes", "utilization",
+        ],
+        AnalyticsReportKind::Cancellations => &[
+            "date", "bookings", "cancelled_by_customer", "cancelled_by_company", "no_show", "cancellation_rate",
+            "no_show_rate",
+        ],
+        AnalyticsReportKind::Revenue => &["date", "currency", "visits", "revenue", "discount"],
+        AnalyticsReportKind::Customers => &["date", "new_customers", "returning_customers"],
+    }
+}
+
+fn write_csv<T: Serialize>(columns: &[&str], rows: &[T]) -> Result<String, AppError> {
+    let csv_error = |e: csv::Error| AppError::Internal(format!("Ошибка формирования CSV: {}", e));
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+    writer.write_record(columns).map_err(csv_error)?;
+    for row in rows {
+        writer.serialize(row).map_err(csv_error)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("Ошибка формирования CSV: {}", e)))?;
+    String::from_utf8(bytes).map_err(|e| AppError::Internal(format!("Ошибка формирования CSV: {}", e)))
+}
+
+/// Строки отчета в CSV: значения экранируются по RFC 4180, пустая доля - пустая ячейка
+pub fn report_to_csv(kind: AnalyticsReportKind, rows: &AnalyticsRows) -> Result<String, AppError> {
+    let columns = csv_columns(kind);
+    match rows {
+        AnalyticsRows::Bookings(rows) => write_csv(columns, rows),
+        AnalyticsRows::Utilization(rows) => write_csv(columns, rows),
+        AnalyticsRows::Cancellations(rows) => write_csv(columns, rows),
+        AnalyticsRows::Revenue(rows) => write_csv(columns, rows),
+        AnalyticsRows::Customers(rows) => write_csv(columns, rows),
+    }
+}
+
+pub struct AnalyticsServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    analytics_repository: Arc<dyn AnalyticsRepository + Send + Sync>,
+}
+
+impl AnalyticsServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        analytics_repository: Arc<dyn AnalyticsRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            analytics_repository,
+        }
+    }
+
+    /// Отчеты видят владелец и менеджеры: в них выручка компании
+    async fn require_manager(&self, user_id: Uuid, company_id: Uuid) -> Result<Company, AppError> {
+        let company = self
+            .company_repository
+            .find_company(company_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Компания не найдена".to_string()))?;
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => Ok(company),
+            _ => Err(AppError::Forbidden("Отчеты доступны владельцу и менеджерам компании".to_string())),
+        }
+    }
+
+    /// Рабочее время по расписанию считается здесь, занятое - в представлении аналитики.
+    /// В отчет попадают действующие сотрудники с рабочим или занятым временем в этот день
+    async fn utilization_report(
+        &self,
+        company_id: Uuid,
+        timezone: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<UtilizationReportRow>, AppError> {
+        let staff = self.company_repository.list_staff(company_id).await?;
+        let staff_ids: Vec<Uuid> = staff.iter().map(|member| member.id).collect();
+        let weekly = self.company_repository.list_weekly_schedules(&staff_ids).await?;
+        let exceptions = self
+            .company_repository
+            .list_schedule_exceptions(&staff_ids, from, to)
+            .await?;
+        let booked: HashMap<(NaiveDate, Uuid), i64> = self
+            .analytics_repository
+            .staff_booked_minutes(company_id, from, to)
+            .await?
+            .into_iter()
+            .map(|row| ((row.date, row.staff_id), row.booked_minutes))
+            .collect();
+
+        let availability: Vec<StaffAvailability> = staff
+            .iter()
+            .map(|member| StaffAvailability {
+                staff_id: member.id,
+                weekly: weekly.iter().filter(|s| s.staff_id == member.id).cloned().collect(),
+                exceptions: exceptions.iter().filter(|e| e.staff_id == member.id).cloned().collect(),
+                busy: Vec::new(),
+                sessions: Vec::new(),
+            })
+            .collect();
+
+        let mut rows = Vec::new();
+        let mut date = from;
+        while date <= to {
+            for (member, availability) in staff.iter().zip(&availability) {
+                let available_minutes = working_minutes(timezone, date, availability);
+                let booked_minutes = booked.get(&(date, member.id)).copied().unwrap_or(0);
+                if available_minutes == 0 && booked_minutes == 0 {
+                    continue;
+                }
+                rows.push(UtilizationReportRow {
+                    date,
+                    staff_id: member.id,
+                    staff_name: member.display_name.clone(),
+                    available_minutes,
+                    booked_minutes,
+                    utilization: utilization(booked_minutes, available_minutes),
+                });
+            }
+            date += Duration::days(1);
+        }
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl AnalyticsService for AnalyticsServiceImpl {
+    async fn report(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        kind: AnalyticsReportKind,
+        query: &AnalyticsQuery,
+    ) -> Result<AnalyticsReport, AppError> {
+        let company = self.require_manager(user_id, company_id).await?;
+        if query.from > query.to {
+            return Err(AppError::Validation("Начало периода должно быть не позже конца".to_string()));
+        }
+        if (query.to - query.from).num_days() >= MAX_REPORT_DAYS {
+            return Err(AppError::Validation(format!(
+                "Период отчета не может быть длиннее {} дней",
+                MAX_REPORT_DAYS
+            )));
+        }
+        let timezone = company_timezone(&company)?;
+
+        let (from, to) = (query.from, query.to);
+        let repository = &self.analytics_repository;
+        let rows = match kind {
+            AnalyticsReportKind::Bookings => AnalyticsRows::Bookings(repository.bookings_report(company_id, from, to).await?),
+            AnalyticsReportKind::Utilization => {
+                AnalyticsRows::Utilization(self.utilization_report(company_id, timezone, from, to).await?)
+            }
+            AnalyticsReportKind::Cancellations => {
+                AnalyticsRows::Cancellations(repository.cancellations_report(company_id, from, to).await?)
+            }
+            AnalyticsReportKind::Revenue => AnalyticsRows::Revenue(repository.revenue_report(company_id, from, to).await?),
+            AnalyticsReportKind::Customers => {
+                AnalyticsRows::Customers(repository.customers_report(company_id, from, to).await?)
+            }
+        };
+
+        Ok(AnalyticsReport {
+            report: kind,
+            from,
+            to,
+            timezone: company.timezone,
+            refreshed_at: repository.refreshed_at().await?,
+            rows,
+        })
+    }
+
+    async fn report_csv(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        kind: AnalyticsReportKind,
+        query: &AnalyticsQuery,
+    ) -> Result<String, AppError> {
+        let report = self.report(user_id, company_id, kind, query).await?;
+        report_to_csv(kind, &report.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{
+        BookingsReportRow, CancellationsReportRow, CustomersReportRow, RevenueReportRow,
+    };
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()
+    }
+
+    #[test]
+    fn utilization_is_rounded_and_empty_on_days_off() {
+        assert_eq!(utilization(200, 600), Some(0.333));
+        assert_eq!(utilization(0, 480), Some(0.0));
+        assert_eq!(utilization(60, 0), None);
+    }
+
+    #[test]
+    fn csv_columns_match_row_fields() {
+        fn header<T: Serialize>(row: T) -> String {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.serialize(row).unwrap();
+            let text = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+            text.lines().next().unwrap().to_string()
+        }
+        let id = Uuid::from_u128(1);
+        let samples = [
+            (
+                AnalyticsReportKind::Bookings,
+                header(BookingsReportRow {
+                    date: date(),
+                    service_id: id,
+                    service_name: String::new(),
+                    staff_id: id,
+                    staff_name: String::new(),
+                    bookings: 0,
+                    completed: 0,
+                    cancelled: 0,
+                    no_show: 0,
+                }),
+            ),
+            (
+                AnalyticsReportKind::Utilization,
+                header(UtilizationReportRow {
+                    date: date(),
+                    staff_id: id,
+                    staff_name: String::new(),
+                    available_minutes: 0,
+                    booked_minutes: 0,
+                    utilization: None,
+                }),
+            ),
+            (
+                AnalyticsReportKind::Cancellations,
+                header(CancellationsReportRow {
+                    date: date(),
+                    bookings: 0,
+                    cancelled_by_customer: 0,
+                    cancelled_by_company: 0,
+                    no_show: 0,
+                    cancellation_rate: 0.0,
+                    no_show_rate: 0.0,
+                }),
+            ),
+            (
+                AnalyticsReportKind::Revenue,
+                header(RevenueReportRow {
+                    date: date(),
+                    currency: String::new(),
+                    visits: 0,
+                    revenue: 0,
+                    discount: 0,
+                }),
+            ),
+            (
+                AnalyticsReportKind::Customers,
+                header(CustomersReportRow {
+                    date: date(),
+                    new_customers: 0,
+                    returning_customers: 0,
+                }),
+            ),
+        ];
+        for (kind, header) in samples {
+            assert_eq!(csv_columns(kind).join(","), header, "{}", kind.as_str());
+        }
+    }
+
+    #[test]
+    fn csv_escapes_values_and_keeps_header_for_empty_report() {
+        let rows = AnalyticsRows::Utilization(vec![UtilizationReportRow {
+            date: date(),
+            staff_id: Uuid::from_u128(1),
+            staff_name: "Иванова, \"старший\" мастер".to_string(),
+            available_minutes: 0,
+            booked_minutes: 30,
+            utilization: None,
+        }]);
+        assert_eq!(
+            report_to_csv(AnalyticsReportKind::Utilization, &rows).unwrap(),
+            "date,staff_id,staff_name,available_minutes,booked_minutes,utilization\n\
+             2025-06-02,00000000-0000-0000-0000-000000000001,\"Иванова, \"\"старший\"\" мастер\",0,30,\n"
+        );
+        assert_eq!(
+            report_to_csv(AnalyticsReportKind::Customers, &AnalyticsRows::Customers(Vec::new())).unwrap(),
+            "date,new_customers,returning_customers\n"
+        );
+    }
+}
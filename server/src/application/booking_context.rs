@@ -0,0 +1,216 @@
+use crate::application::booking_lifecycle::{customer_restriction, initial_status};
+use crate::application::slot_engine::{blocked_interval, compute_available_slots, AvailableSlot, SlotRequest};
+use crate::application::slot_service::{
+    company_timezone, load_resource_availability, load_staff_availability, slot_rules,
+};
+use crate::domain::entities::{
+    Booking, BookingStatus, BusyInterval, Company, CompanyRole, CustomerRestrictionStatus, RestrictionOverride,
+    Service, StaffMember,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, Clock, CompanyRepository};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Проверенный слот вместе с интервалом, который он занимает с учетом буферов
+pub(crate) struct ResolvedSlot {
+    pub(crate) slot: AvailableSlot,
+    pub(crate) blocked_from: DateTime<Utc>,
+    pub(crate) blocked_to: DateTime<Utc>,
+}
+
+/// Проверки, общие для сервисов записи: компания, услуга и сотрудник, свободное время
+/// и ограничения клиента
+#[derive(Clone)]
+pub(crate) struct BookingContext {
+    pub(crate) company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    pub(crate) booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+    pub(crate) clock: Arc<dyn Clock + Send + Sync>,
+}
+
+impl BookingContext {
+    pub(crate) fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            booking_repository,
+            clock,
+        }
+    }
+
+    pub(crate) async fn require_company(&self, company_id: Uuid) -> Result<Company, AppError> {
+        self.company_repository
+            .find_company(company_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Компания не найдена".to_string()))
+    }
+
+    pub(crate) async fn require_service(&self, company_id: Uuid, service_id: Uuid) -> Result<Service, AppError> {
+        self.company_repository
+            .find_service(company_id, service_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Услуга не найдена".to_string()))
+    }
+
+    pub(crate) async fn require_staff_for(&self, company_id: Uuid, staff_id: Uuid, service: &Service) -> Result<StaffMember, AppError> {
+        let staff = self
+            .company_repository
+            .find_staff(company_id, staff_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Сотрудник не найден".to_string()))?;
+        if !staff.service_ids.contains(&service.id) {
+            return Err(AppError::Validation("Сотрудник не оказывает эту услугу".to_string()));
+        }
+        Ok(staff)
+    }
+
+    /// Запись видна клиенту и сотрудникам компании, остальным отвечаем "не найдена"
+    pub(crate) async fn load_visible_booking(
+        &self,
+        user_id: Uuid,
+        booking_id: Uuid,
+    ) -> Result<(Booking, Option<CompanyRole>), AppError> {
+        let booking = self
+            .booking_repository
+            .find_booking(booking_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Запись не найдена".to_string()))?;
+
+        let role = self
+            .company_repository
+            .get_member_role(booking.company_id, user_id)
+            .await?;
+        if booking.customer_id != user_id && role.is_none() {
+            return Err(AppError::NotFound("Запись не найдена".to_string()));
+        }
+
+        Ok((booking, role))
+    }
+
+    /// Проверить, что время есть в расписании сотрудника, не занято и для него хватает ресурсов.
+    /// `own_bookings` - переносимые записи, их время и ресурсы не считаются занятыми
+    pub(crate) async fn resolve_slot(
+        &self,
+        company: &Company,
+        service: &Service,
+        staff: &StaffMember,
+        starts_at: DateTime<Utc>,
+        location_id: Option<Uuid>,
+        own_bookings: &[Uuid],
+    ) -> Result<ResolvedSlot, AppError> {
+        let timezone = company_timezone(company)?;
+
+        // Время должно попадать в расписание сотрудника; занятость проверяем отдельно,
+        // чтобы отличать "нет такого слота" от "слот уже занят"
+        let rules = slot_rules(service);
+        let date = starts_at.with_timezone(&timezone).date_naive();
+        let mut availability = load_staff_availability(
+            self.company_repository.as_ref(),
+            self.booking_repository.as_ref(),
+            service,
+            &[staff.id],
+            date,
+            date,
+        )
+        .await?;
+        let mut busy = std::mem::take(&mut availability[0].busy);
+        busy.retain(|b| !own_bookings.contains(&b.booking_id));
+        let mut resources = load_resource_availability(
+            self.company_repository.as_ref(),
+            self.booking_repository.as_ref(),
+            service,
+            date,
+            date,
+        )
+        .await?;
+        for option in resources.iter_mut().flatten() {
+            option.busy.retain(|b| !own_bookings.contains(&b.booking_id));
+        }
+
+        let request = SlotRequest {
+            timezone,
+            from: date,
+            to: date,
+            now: self.clock.now(),
+            rules,
+            resources,
+        };
+        let slot = compute_available_slots(&request, &availability)
+            .into_iter()
+            .find(|slot| {
+                slot.start == starts_at && location_id.is_none_or(|location_id| location_id == slot.location_id)
+            })
+            .ok_or_else(|| AppError::Validation("Выбранное время недоступно для записи".to_string()))?;
+
+        // Записи того же группового занятия время не занимают
+        let (blocked_from, blocked_to) = blocked_interval(slot.start, &rules);
+        let shares_session = |b: &BusyInterval| slot.session_id.is_some() && b.session_id == slot.session_id;
+        if busy
+            .iter()
+            .any(|b| b.start < blocked_to && blocked_from < b.end && !shares_session(b))
+        {
+            return Err(AppError::Conflict("Слот уже занят".to_string()));
+        }
+
+        Ok(ResolvedSlot {
+            slot,
+            blocked_from,
+            blocked_to,
+        })
+    }
+
+    /// Действующее ограничение клиента и ручная настройка, если она есть
+    pub(crate) async fn load_restriction(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<(CustomerRestrictionStatus, Option<RestrictionOverride>), AppError> {
+        let policy = self.booking_repository.get_policy(company_id).await?;
+        let since = self.clock.now() - Duration::days(policy.no_show_window_days as i64);
+        let no_show_count = self
+            .booking_repository
+            .count_no_shows(company_id, customer_id, since)
+            .await?;
+        let manual = self
+            .booking_repository
+            .find_restriction_override(company_id, customer_id)
+            .await?;
+
+        let status = CustomerRestrictionStatus {
+            company_id,
+            customer_id,
+            restriction: customer_restriction(&policy, no_show_count, manual.as_ref().map(|m| m.restriction)),
+            no_show_count,
+            no_show_limit: policy.no_show_limit,
+            no_show_window_days: policy.no_show_window_days,
+            overridden: manual.is_some(),
+        };
+        Ok((status, manual))
+    }
+
+    /// Статус, с которым создается запись клиента: confirmed, pending при ограничении
+    /// или отказ, если онлайн-запись для клиента закрыта
+    pub(crate) async fn new_booking_status(&self, company_id: Uuid, customer_id: Uuid) -> Result<BookingStatus, AppError> {
+        let (status, _) = self.load_restriction(company_id, customer_id).await?;
+        initial_status(status.restriction)
+    }
+
+    /// Статус новой записи клиента на услугу. Запись на услугу с депозитом ждет оплаты,
+    /// после нее подтверждается так же, как запись без депозита
+    pub(crate) async fn initial_booking_status(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+        service: &Service,
+    ) -> Result<BookingStatus, AppError> {
+        let status = self.new_booking_status(company_id, customer_id).await?;
+        if service.deposit_amount.is_none() {
+            return Ok(status);
+        }
+        Ok(BookingStatus::Pending)
+    }
+}
@@ -0,0 +1,287 @@
+//! Правила жизненного цикла записи: допустимые переходы статусов и политики
+//! отмены/переноса. Функции чистые, решения о доступе принимаются по роли.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::entities::{BookingPolicy, BookingStatus, CompanyRole, CustomerRestriction};
+use crate::domain::errors::AppError;
+
+/// Кто меняет запись
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookingActor {
+    Customer,
+    Company(CompanyRole),
+}
+
+/// Проверить, что переход статуса допустим и разрешен этому участнику
+pub fn check_transition(
+    from: BookingStatus,
+    to: BookingStatus,
+    actor: BookingActor,
+) -> Result<(), AppError> {
+    use BookingStatus::*;
+
+    let allowed = matches!(
+        (from, to),
+        (Pending, Confirmed)
+            | (Pending | Confirmed, CancelledByCustomer)
+            | (Pending | Confirmed, CancelledByCompany)
+            | (Confirmed, CheckedIn)
+            | (Confirmed, NoShow)
+            | (Confirmed | CheckedIn, Completed)
+    );
+    if !allowed {
+        return Err(AppError::Conflict(format!(
+            "Недопустимый переход статуса: {} -> {}",
+            from.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let permitted = match actor {
+        BookingActor::Customer => to == CancelledByCustomer,
+        BookingActor::Company(role) => match to {
+            CancelledByCustomer => false,
+            CancelledByCompany => role.can_manage(),
+            _ => true,
+        },
+    };
+    if !permitted {
+        return Err(AppError::Forbidden(
+            "Недостаточно прав для изменения статуса записи".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Можно ли еще отменить/перенести запись: не позднее чем за `min_hours` до начала
+pub fn within_policy_window(now: DateTime<Utc>, starts_at: DateTime<Utc>, min_hours: i32) -> bool {
+    now + Duration::hours(min_hours as i64) <= starts_at
+}
+
+/// Проверить политику компании для действий клиента
+pub fn check_customer_cancel(
+    policy: &BookingPolicy,
+    now: DateTime<Utc>,
+    starts_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+    if within_policy_window(now, starts_at, policy.customer_cancel_min_hours) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!(
+            "Отменить запись можно не позднее чем за {} ч. до начала",
+            policy.customer_cancel_min_hours
+        )))
+    }
+}
+
+pub fn check_customer_reschedule(
+    policy: &BookingPolicy,
+    now: DateTime<Utc>,
+    starts_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+    if within_policy_window(now, starts_at, policy.customer_reschedule_min_hours) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!(
+            "Перенести запись можно не позднее чем за {} ч. до начала",
+            policy.customer_reschedule_min_hours
+        )))
+    }
+}
+
+/// Неявку можно отметить только после начала приема
+pub fn check_no_show_time(now: DateTime<Utc>, starts_at: DateTime<Utc>) -> Result<(), AppError> {
+    if now < starts_at {
+        return Err(AppError::Conflict(
+            "Отметить неявку можно только после начала приема".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Ограничение клиента: ручная настройка руководителя важнее правила о неявках
+pub fn customer_restriction(
+    policy: &BookingPolicy,
+    no_show_count: i64,
+    manual: Option<CustomerRestriction>,
+) -> CustomerRestriction {
+    if let Some(restriction) = manual {
+        return restriction;
+    }
+    match policy.no_show_limit {
+        Some(limit) if no_show_count >= limit as i64 => policy.no_show_action,
+        _ => CustomerRestriction::None,
+    }
+}
+
+/// Статус новой записи клиента с учетом ограничения
+pub fn initial_status(restriction: CustomerRestriction) -> Result<BookingStatus, AppError> {
+    match restriction {
+        CustomerRestriction::None => Ok(BookingStatus::Confirmed),
+        CustomerRestriction::RequireConfirmation => Ok(BookingStatus::Pending),
+        CustomerRestriction::Blocked => Err(AppError::Forbidden(
+            "Онлайн-запись в эту компанию для вас закрыта из-за пропущенных визитов, обратитесь в компанию".to_string(),
+        )),
+    }
+}
+
+/// Сколько оплаченного депозита вернуть клиенту после смены статуса записи. Отмена компанией
+/// возвращает весь депозит, отмена клиентом - если он успел до `deposit_refund_min_hours`
+/// до начала; после визита или неявки депозит остается у компании
+pub fn deposit_refund(
+    policy: &BookingPolicy,
+    status: BookingStatus,
+    cancelled_at: DateTime<Utc>,
+    starts_at: DateTime<Utc>,
+    amount: i64,
+) -> i64 {
+    match status {
+        BookingStatus::CancelledByCompany => amount,
+        BookingStatus::CancelledByCustomer
+            if within_policy_window(cancelled_at, starts_at, policy.deposit_refund_min_hours) =>
+        {
+            amount
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use BookingStatus::*;
+    use uuid::Uuid;
+
+    const MANAGER: BookingActor = BookingActor::Company(CompanyRole::Manager);
+    const STAFF: BookingActor = BookingActor::Company(CompanyRole::Staff);
+
+    #[test]
+    fn company_walks_booking_through_happy_path() {
+        assert!(check_transition(Pending, Confirmed, STAFF).is_ok());
+        assert!(check_transition(Confirmed, CheckedIn, STAFF).is_ok());
+        assert!(check_transition(CheckedIn, Completed, STAFF).is_ok());
+    }
+
+    #[test]
+    fn terminal_statuses_cannot_change() {
+        for from in [Completed, CancelledByCustomer, CancelledByCompany, NoShow] {
+            for to in [Pending, Confirmed, CheckedIn, Completed, CancelledByCompany, NoShow] {
+                assert!(matches!(
+                    check_transition(from, to, MANAGER),
+                    Err(AppError::Conflict(_))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn checked_in_booking_cannot_be_marked_no_show() {
+        assert!(matches!(
+            check_transition(CheckedIn, NoShow, MANAGER),
+            Err(AppError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn customer_can_only_cancel() {
+        assert!(check_transition(Confirmed, CancelledByCustomer, BookingActor::Customer).is_ok());
+        assert!(matches!(
+            check_transition(Pending, Confirmed, BookingActor::Customer),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            check_transition(Confirmed, CancelledByCompany, BookingActor::Customer),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn only_managers_cancel_on_behalf_of_company() {
+        assert!(check_transition(Confirmed, CancelledByCompany, MANAGER).is_ok());
+        assert!(matches!(
+            check_transition(Confirmed, CancelledByCompany, STAFF),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            check_transition(Confirmed, CancelledByCustomer, MANAGER),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn policy_window_is_inclusive() {
+        let starts_at = DateTime::parse_from_rfc3339("2025-06-02T10:00:00Z").unwrap().with_timezone(&Utc);
+        let policy = BookingPolicy {
+            customer_cancel_min_hours: 24,
+            customer_reschedule_min_hours: 2,
+            ..BookingPolicy::default_for(Uuid::nil())
+        };
+
+        assert!(check_customer_cancel(&policy, starts_at - Duration::hours(24), starts_at).is_ok());
+        assert!(check_customer_cancel(&policy, starts_at - Duration::hours(23), starts_at).is_err());
+        assert!(check_customer_reschedule(&policy, starts_at - Duration::hours(3), starts_at).is_ok());
+        assert!(check_customer_reschedule(&policy, starts_at - Duration::minutes(90), starts_at).is_err());
+    }
+
+    #[test]
+    fn no_show_cannot_be_marked_before_start() {
+        let starts_at = DateTime::parse_from_rfc3339("2025-06-02T10:00:00Z").unwrap().with_timezone(&Utc);
+        assert!(check_no_show_time(starts_at, starts_at).is_ok());
+        assert!(matches!(
+            check_no_show_time(starts_at - Duration::minutes(1), starts_at),
+            Err(AppError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn restriction_follows_no_show_rule_unless_overridden() {
+        let policy = BookingPolicy {
+            no_show_limit: Some(3),
+            no_show_action: CustomerRestriction::Blocked,
+            ..BookingPolicy::default_for(Uuid::nil())
+        };
+        assert_eq!(customer_restriction(&policy, 2, None), CustomerRestriction::None);
+        assert_eq!(customer_restriction(&policy, 3, None), CustomerRestriction::Blocked);
+        assert_eq!(
+            customer_restriction(&policy, 5, Some(CustomerRestriction::None)),
+            CustomerRestriction::None
+        );
+        assert_eq!(
+            customer_restriction(&policy, 0, Some(CustomerRestriction::RequireConfirmation)),
+            CustomerRestriction::RequireConfirmation
+        );
+
+        let disabled = BookingPolicy::default_for(Uuid::nil());
+        assert_eq!(customer_restriction(&disabled, 100, None), CustomerRestriction::None);
+    }
+
+    #[test]
+    fn restricted_customers_book_as_pending_or_not_at_all() {
+        assert_eq!(initial_status(CustomerRestriction::None).unwrap(), Confirmed);
+        assert_eq!(initial_status(CustomerRestriction::RequireConfirmation).unwrap(), Pending);
+        assert!(matches!(
+            initial_status(CustomerRestriction::Blocked),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn deposit_is_refunded_for_early_or_company_cancellation() {
+        let starts_at = Utc::now();
+        let policy = BookingPolicy {
+            deposit_refund_min_hours: 24,
+            ..BookingPolicy::default_for(Uuid::nil())
+        };
+        let early = starts_at - Duration::hours(24);
+        let late = starts_at - Duration::hours(3);
+
+        assert_eq!(deposit_refund(&policy, CancelledByCustomer, early, starts_at, 50000), 50000);
+        assert_eq!(deposit_refund(&policy, CancelledByCustomer, late, starts_at, 50000), 0);
+        assert_eq!(deposit_refund(&policy, CancelledByCompany, late, starts_at, 50000), 50000);
+        for status in [Confirmed, Completed, NoShow] {
+            assert_eq!(deposit_refund(&policy, status, early, starts_at, 50000), 0);
+        }
+    }
+}
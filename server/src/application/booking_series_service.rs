@@ -0,0 +1,287 @@
+use crate::application::booking_lifecycle::{
+    check_customer_cancel, check_customer_reschedule, check_transition, BookingActor,
+};
+use crate::application::booking_context::BookingContext;
+use crate::application::recurrence::{local_to_utc, RecurrenceRule};
+use crate::application::slot_service::company_timezone;
+use crate::application::waitlist_service::release_to_waitlist;
+use crate::domain::entities::{
+    Booking, BookingReschedule, BookingSeries, BookingSeriesResponse, BookingStatus, CancelSeriesRequest,
+    CompanyRole, CreateBookingSeriesRequest, NewBooking, NewBookingSeries, RescheduleSeriesRequest,
+    SeriesChangeResponse, SeriesConflict,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, BookingSeriesService, Clock, CompanyRepository, WaitlistService};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Вхождения, которые еще можно отменить или перенести
+fn is_changeable(booking: &Booking) -> bool {
+    matches!(booking.status, BookingStatus::Pending | BookingStatus::Confirmed)
+}
+
+/// Занятость или отсутствие слота попадают в отчет, остальные ошибки прерывают операцию
+fn into_conflict(starts_at: chrono::DateTime<chrono::Utc>, error: AppError) -> Result<SeriesConflict, AppError> {
+    match error {
+        AppError::Validation(reason) | AppError::Conflict(reason) => Ok(SeriesConflict { starts_at, reason }),
+        other => Err(other),
+    }
+}
+
+pub struct BookingSeriesServiceImpl {
+    context: BookingContext,
+    /// Время отмененных вхождений предлагается листу ожидания
+    waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+}
+
+impl BookingSeriesServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            context: BookingContext::new(company_repository, booking_repository, clock),
+            waitlist_service,
+        }
+    }
+
+    /// Серия видна клиенту и сотрудникам компании
+    async fn load_visible_series(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+    ) -> Result<(BookingSeries, Option<CompanyRole>), AppError> {
+        let series = self.context
+            .booking_repository
+            .find_series(series_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Серия не найдена".to_string()))?;
+
+        let role = self.context
+            .company_repository
+            .get_member_role(series.company_id, user_id)
+            .await?;
+        if series.customer_id != user_id && role.is_none() {
+            return Err(AppError::NotFound("Серия не найдена".to_string()));
+        }
+
+        Ok((series, role))
+    }
+
+    /// Вхождение `from_booking_id` и все следующие по исходному времени, которые еще можно изменить
+    async fn following_occurrences(&self, series_id: Uuid, from_booking_id: Uuid) -> Result<Vec<Booking>, AppError> {
+        let bookings = self.context.booking_repository.list_series_bookings(series_id).await?;
+        let position = bookings
+            .iter()
+            .position(|booking| booking.id == from_booking_id)
+            .ok_or_else(|| AppError::NotFound("Запись не относится к серии".to_string()))?;
+        if !is_changeable(&bookings[position]) {
+            return Err(AppError::Conflict("Вхождение нельзя изменить в текущем статусе".to_string()));
+        }
+
+        Ok(bookings.into_iter().skip(position).filter(is_changeable).collect())
+    }
+}
+
+#[async_trait]
+impl BookingSeriesService for BookingSeriesServiceImpl {
+    async fn create_series(
+        &self,
+        customer_id: Uuid,
+        data: CreateBookingSeriesRequest,
+    ) -> Result<BookingSeriesResponse, AppError> {
+        let company = self.context.require_company(data.company_id).await?;
+        let service = self.context.require_service(company.id, data.service_id).await?;
+        let staff = self.context.require_staff_for(company.id, data.staff_id, &service).await?;
+        let timezone = company_timezone(&company)?;
+        // Депозит оплачивается за отдельную запись, серия на такую услугу не оформляется
+        if service.deposit_amount.is_some() {
+            return Err(AppError::Validation("На услугу с депозитом нельзя записаться серией".to_string()));
+        }
+        let status = self.context.new_booking_status(company.id, customer_id).await?;
+
+        let rule = RecurrenceRule::parse(&data.rrule)?;
+        let starts = rule.occurrences(data.starts_at.with_timezone(&timezone).naive_local(), timezone)?;
+
+        // Каждое вхождение проверяем так же, как отдельную запись
+        let mut occurrences = Vec::with_capacity(starts.len());
+        let mut conflicts = Vec::new();
+        for starts_at in starts {
+            match self.context
+                .resolve_slot(&company, &service, &staff, starts_at, data.location_id, &[])
+                .await
+            {
+                Ok(resolved) => occurrences.push(NewBooking {
+                    customer_id,
+                    company_id: company.id,
+                    service_id: service.id,
+                    staff_id: staff.id,
+                    location_id: resolved.slot.location_id,
+                    starts_at: resolved.slot.start,
+                    ends_at: resolved.slot.end,
+                    blocked_from: resolved.blocked_from,
+                    blocked_to: resolved.blocked_to,
+                    status,
+                    capacity: service.capacity,
+                    resource_ids: resolved.slot.resource_ids.clone(),
+                    promo: None,
+                }),
+                Err(error) => conflicts.push(into_conflict(starts_at, error)?),
+            }
+        }
+
+        if !conflicts.is_empty() && !data.skip_conflicts {
+            return Ok(BookingSeriesResponse {
+                series: None,
+                bookings: Vec::new(),
+                conflicts,
+            });
+        }
+        if occurrences.is_empty() {
+            return Err(AppError::Validation("Ни одно вхождение серии недоступно для записи".to_string()));
+        }
+
+        let (series, bookings) = self.context
+            .booking_repository
+            .create_series(
+                &NewBookingSeries {
+                    customer_id,
+                    company_id: company.id,
+                    service_id: service.id,
+                    staff_id: staff.id,
+                    location_id: data.location_id,
+                    rrule: data.rrule.trim().to_string(),
+                    starts_at: data.starts_at,
+                    timezone: company.timezone.clone(),
+                },
+                &occurrences,
+            )
+            .await?;
+
+        Ok(BookingSeriesResponse {
+            series: Some(series),
+            bookings,
+            conflicts,
+        })
+    }
+
+    async fn get_series(&self, user_id: Uuid, series_id: Uuid) -> Result<BookingSeriesResponse, AppError> {
+        let (series, _) = self.load_visible_series(user_id, series_id).await?;
+        let bookings = self.context.booking_repository.list_series_bookings(series.id).await?;
+
+        Ok(BookingSeriesResponse {
+            series: Some(series),
+            bookings,
+            conflicts: Vec::new(),
+        })
+    }
+
+    async fn cancel_following(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        data: CancelSeriesRequest,
+    ) -> Result<SeriesChangeResponse, AppError> {
+        let (series, role) = self.load_visible_series(user_id, series_id).await?;
+        let affected = self.following_occurrences(series.id, data.from_booking_id).await?;
+
+        // Клиент серии отменяет как клиент, даже если он сотрудник этой компании
+        let (actor, status) = match role {
+            Some(role) if series.customer_id != user_id => {
+                (BookingActor::Company(role), BookingStatus::CancelledByCompany)
+            }
+            _ => (BookingActor::Customer, BookingStatus::CancelledByCustomer),
+        };
+        for booking in &affected {
+            check_transition(booking.status, status, actor)?;
+        }
+        if actor == BookingActor::Customer {
+            let policy = self.context.booking_repository.get_policy(series.company_id).await?;
+            check_customer_cancel(&policy, self.context.clock.now(), affected[0].starts_at)?;
+        }
+
+        let reason = data.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+        let targets: Vec<(Uuid, BookingStatus)> = affected.iter().map(|b| (b.id, b.status)).collect();
+        let bookings = self.context
+            .booking_repository
+            .change_status_many(&targets, status, user_id, reason)
+            .await?;
+        for booking in &bookings {
+            release_to_waitlist(self.waitlist_service.as_ref(), booking).await;
+        }
+
+        Ok(SeriesChangeResponse {
+            bookings,
+            conflicts: Vec::new(),
+        })
+    }
+
+    async fn reschedule_following(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        data: RescheduleSeriesRequest,
+    ) -> Result<SeriesChangeResponse, AppError> {
+        let (series, role) = self.load_visible_series(user_id, series_id).await?;
+        let affected = self.following_occurrences(series.id, data.from_booking_id).await?;
+
+        if role.is_none() {
+            let policy = self.context.booking_repository.get_policy(series.company_id).await?;
+            check_customer_reschedule(&policy, self.context.clock.now(), affected[0].starts_at)?;
+        }
+
+        let company = self.context.require_company(series.company_id).await?;
+        let service = self.context.require_service(company.id, series.service_id).await?;
+        let timezone = company_timezone(&company)?;
+
+        // Сдвиг считаем в местном времени: "со вторника 10:00 на среду 11:00" остается
+        // таким же и после перевода часов
+        let shift = data.starts_at.with_timezone(&timezone).naive_local()
+            - affected[0].starts_at.with_timezone(&timezone).naive_local();
+        // Переносимые вхождения освобождаются вместе, их время и ресурсы не считаем занятыми
+        let own_bookings: Vec<Uuid> = affected.iter().map(|booking| booking.id).collect();
+        let mut changes = Vec::with_capacity(affected.len());
+        let mut conflicts = Vec::new();
+        for booking in &affected {
+            let staff_id = data.staff_id.unwrap_or(booking.staff_id);
+            let staff = self.context.require_staff_for(company.id, staff_id, &service).await?;
+            let starts_at = local_to_utc(timezone, booking.starts_at.with_timezone(&timezone).naive_local() + shift);
+            match self.context
+                .resolve_slot(&company, &service, &staff, starts_at, None, &own_bookings)
+                .await
+            {
+                Ok(resolved) => changes.push((
+                    booking.id,
+                    BookingReschedule {
+                        staff_id: staff.id,
+                        location_id: resolved.slot.location_id,
+                        starts_at: resolved.slot.start,
+                        ends_at: resolved.slot.end,
+                        blocked_from: resolved.blocked_from,
+                        blocked_to: resolved.blocked_to,
+                        capacity: service.capacity,
+                        resource_ids: resolved.slot.resource_ids.clone(),
+                    },
+                )),
+                Err(error) => conflicts.push(into_conflict(starts_at, error)?),
+            }
+        }
+
+        // Переносим все или ничего
+        if !conflicts.is_empty() {
+            return Ok(SeriesChangeResponse {
+                bookings: Vec::new(),
+                conflicts,
+            });
+        }
+
+        let bookings = self.context.booking_repository.reschedule_many(&changes, user_id).await?;
+        Ok(SeriesChangeResponse {
+            bookings,
+            conflicts: Vec::new(),
+        })
+    }
+}
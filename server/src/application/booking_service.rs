@@ -0,0 +1,266 @@
+use crate::application::booking_context::BookingContext;
+use crate::application::booking_lifecycle::{
+    check_customer_cancel, check_customer_reschedule, check_no_show_time, check_transition, BookingActor,
+};
+use crate::application::payment_service::settle_deposit;
+use crate::application::promo_code_service::price_booking;
+use crate::application::waitlist_service::release_to_waitlist;
+use crate::domain::entities::{
+    Booking, BookingEvent, BookingPolicy, BookingReschedule, BookingStatus, ChangeBookingStatusRequest,
+    CreateBookingRequest, CustomerRestriction, DEFAULT_DEPOSIT_REFUND_MIN_HOURS, DEFAULT_NO_SHOW_WINDOW_DAYS, NewBooking,
+    PaymentStatus, RescheduleBookingRequest, UpdateBookingPolicyRequest,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{
+    BookingRepository, BookingService, Clock, CompanyRepository, PaymentRepository, PromoCodeRepository,
+    WaitlistService,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Наибольшее окно подсчета неявок, дней
+const MAX_NO_SHOW_WINDOW_DAYS: i32 = 365;
+
+pub struct BookingServiceImpl {
+    context: BookingContext,
+    payment_repository: Arc<dyn PaymentRepository + Send + Sync>,
+    promo_repository: Arc<dyn PromoCodeRepository + Send + Sync>,
+    /// Освободившееся при отмене время предлагается листу ожидания
+    waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+}
+
+impl BookingServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        payment_repository: Arc<dyn PaymentRepository + Send + Sync>,
+        promo_repository: Arc<dyn PromoCodeRepository + Send + Sync>,
+        waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            context: BookingContext::new(company_repository, booking_repository, clock),
+            payment_repository,
+            promo_repository,
+            waitlist_service,
+        }
+    }
+
+    /// Запись с неоплаченным депозитом подтверждает только оплата, а не сотрудник
+    async fn check_deposit_paid(&self, booking_id: Uuid) -> Result<(), AppError> {
+        let payment = self.payment_repository.find_booking_payment(booking_id).await?;
+        if payment.is_some_and(|payment| matches!(payment.status, PaymentStatus::Pending | PaymentStatus::Failed)) {
+            return Err(AppError::Conflict("Депозит по записи еще не оплачен".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BookingService for BookingServiceImpl {
+    async fn create_booking(&self, customer_id: Uuid, data: CreateBookingRequest) -> Result<Booking, AppError> {
+        let company = self.context.require_company(data.company_id).await?;
+        let service = self.context.require_service(company.id, data.service_id).await?;
+        let staff = self.context.require_staff_for(company.id, data.staff_id, &service).await?;
+        let status = self.context.initial_booking_status(company.id, customer_id, &service).await?;
+
+        let resolved = self.context
+            .resolve_slot(&company, &service, &staff, data.starts_at, data.location_id, &[])
+            .await?;
+        let promo = match data.promo_code.as_deref() {
+            Some(promo_code) => {
+                let (_, redemption) = price_booking(
+                    self.promo_repository.as_ref(),
+                    customer_id,
+                    &service,
+                    Some(resolved.slot.location_id),
+                    Some(promo_code),
+                    self.context.clock.now(),
+                )
+                .await?;
+                redemption
+            }
+            None => None,
+        };
+
+        // Окончательно гонку за слот и лимиты промокода решает БД
+        self.context
+            .booking_repository
+            .create_booking(&NewBooking {
+                customer_id,
+                company_id: company.id,
+                service_id: service.id,
+                staff_id: staff.id,
+                location_id: resolved.slot.location_id,
+                starts_at: resolved.slot.start,
+                ends_at: resolved.slot.end,
+                blocked_from: resolved.blocked_from,
+                blocked_to: resolved.blocked_to,
+                status,
+                capacity: service.capacity,
+                resource_ids: resolved.slot.resource_ids.clone(),
+                promo,
+            })
+            .await
+    }
+
+    async fn get_booking(&self, user_id: Uuid, booking_id: Uuid) -> Result<Booking, AppError> {
+        let (booking, _) = self.context.load_visible_booking(user_id, booking_id).await?;
+        Ok(booking)
+    }
+
+    async fn change_status(
+        &self,
+        user_id: Uuid,
+        booking_id: Uuid,
+        data: ChangeBookingStatusRequest,
+    ) -> Result<Booking, AppError> {
+        let (booking, role) = self.context.load_visible_booking(user_id, booking_id).await?;
+
+        // Сотрудник, записавшийся к своей же компании, отменяет запись как клиент
+        let actor = match role {
+            Some(role) if !(booking.customer_id == user_id && data.status == BookingStatus::CancelledByCustomer) => {
+                BookingActor::Company(role)
+            }
+            _ => BookingActor::Customer,
+        };
+        check_transition(booking.status, data.status, actor)?;
+        if booking.status == BookingStatus::Pending && data.status == BookingStatus::Confirmed {
+            self.check_deposit_paid(booking.id).await?;
+        }
+        if data.status == BookingStatus::NoShow {
+            check_no_show_time(self.context.clock.now(), booking.starts_at)?;
+        }
+
+        if actor == BookingActor::Customer {
+            let policy = self.context.booking_repository.get_policy(booking.company_id).await?;
+            check_customer_cancel(&policy, self.context.clock.now(), booking.starts_at)?;
+        }
+
+        let reason = data.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+        let changed = self.context
+            .booking_repository
+            .change_status(booking.id, booking.status, data.status, user_id, reason)
+            .await?;
+
+        if changed.status.is_cancelled() {
+            settle_deposit(
+                self.payment_repository.as_ref(),
+                self.context.booking_repository.as_ref(),
+                &changed,
+                self.context.clock.now(),
+            )
+            .await;
+            release_to_waitlist(self.waitlist_service.as_ref(), &changed).await;
+        }
+        Ok(changed)
+    }
+
+    async fn reschedule_booking(
+        &self,
+        user_id: Uuid,
+        booking_id: Uuid,
+        data: RescheduleBookingRequest,
+    ) -> Result<Booking, AppError> {
+        let (booking, role) = self.context.load_visible_booking(user_id, booking_id).await?;
+        if !booking.status.is_active() {
+            return Err(AppError::Conflict("Запись нельзя перенести в текущем статусе".to_string()));
+        }
+
+        if role.is_none() {
+            let policy = self.context.booking_repository.get_policy(booking.company_id).await?;
+            check_customer_reschedule(&policy, self.context.clock.now(), booking.starts_at)?;
+        }
+
+        let company = self.context.require_company(booking.company_id).await?;
+        let service = self.context.require_service(company.id, booking.service_id).await?;
+        let staff_id = data.staff_id.unwrap_or(booking.staff_id);
+        let staff = self.context.require_staff_for(company.id, staff_id, &service).await?;
+
+        let resolved = self.context
+            .resolve_slot(&company, &service, &staff, data.starts_at, data.location_id, &[booking.id])
+            .await?;
+
+        self.context
+            .booking_repository
+            .reschedule_booking(
+                booking.id,
+                &BookingReschedule {
+                    staff_id: staff.id,
+                    location_id: resolved.slot.location_id,
+                    starts_at: resolved.slot.start,
+                    ends_at: resolved.slot.end,
+                    blocked_from: resolved.blocked_from,
+                    blocked_to: resolved.blocked_to,
+                    capacity: service.capacity,
+                    resource_ids: resolved.slot.resource_ids.clone(),
+                },
+                user_id,
+            )
+            .await
+    }
+
+    async fn booking_history(&self, user_id: Uuid, booking_id: Uuid) -> Result<Vec<BookingEvent>, AppError> {
+        let (booking, _) = self.context.load_visible_booking(user_id, booking_id).await?;
+        self.context.booking_repository.list_booking_events(booking.id).await
+    }
+
+    async fn get_policy(&self, company_id: Uuid) -> Result<BookingPolicy, AppError> {
+        let company = self.context.require_company(company_id).await?;
+        self.context.booking_repository.get_policy(company.id).await
+    }
+
+    async fn update_policy(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: UpdateBookingPolicyRequest,
+    ) -> Result<BookingPolicy, AppError> {
+        let company = self.context.require_company(company_id).await?;
+        match self.context.company_repository.get_member_role(company.id, user_id).await? {
+            Some(role) if role.can_manage() => {}
+            _ => {
+                return Err(AppError::Forbidden(
+                    "Недостаточно прав для управления компанией".to_string(),
+                ))
+            }
+        }
+
+        let deposit_refund_min_hours = data
+            .deposit_refund_min_hours
+            .unwrap_or(DEFAULT_DEPOSIT_REFUND_MIN_HOURS);
+        if data.customer_cancel_min_hours < 0 || data.customer_reschedule_min_hours < 0 || deposit_refund_min_hours < 0 {
+            return Err(AppError::Validation("Количество часов не может быть отрицательным".to_string()));
+        }
+        if data.no_show_limit.is_some_and(|limit| limit < 1) {
+            return Err(AppError::Validation("Порог неявок должен быть не меньше 1".to_string()));
+        }
+        let no_show_window_days = data.no_show_window_days.unwrap_or(DEFAULT_NO_SHOW_WINDOW_DAYS);
+        if !(1..=MAX_NO_SHOW_WINDOW_DAYS).contains(&no_show_window_days) {
+            return Err(AppError::Validation(format!(
+                "Окно подсчета неявок должно быть от 1 до {} дней",
+                MAX_NO_SHOW_WINDOW_DAYS
+            )));
+        }
+        let no_show_action = data.no_show_action.unwrap_or(CustomerRestriction::RequireConfirmation);
+        if no_show_action == CustomerRestriction::None {
+            return Err(AppError::Validation(
+                "Действие при неявках: require_confirmation или blocked".to_string(),
+            ));
+        }
+
+        self.context
+            .booking_repository
+            .save_policy(&BookingPolicy {
+                company_id: company.id,
+                customer_cancel_min_hours: data.customer_cancel_min_hours,
+                customer_reschedule_min_hours: data.customer_reschedule_min_hours,
+                no_show_limit: data.no_show_limit,
+                no_show_window_days,
+                no_show_action,
+                deposit_refund_min_hours,
+            })
+            .await
+    }
+}
@@ -0,0 +1,75 @@
+use crate::domain::entities::{BookmarksQuery, BookmarksResponse};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookmarkRepository, BookmarkService, CompanyRepository};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Размер страницы закладок по умолчанию и максимальный
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+pub struct BookmarkServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    bookmark_repository: Arc<dyn BookmarkRepository + Send + Sync>,
+}
+
+impl BookmarkServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        bookmark_repository: Arc<dyn BookmarkRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            bookmark_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl BookmarkService for BookmarkServiceImpl {
+    async fn add_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+
+        // Повторное добавление не ошибка: PUT идемпотентен
+        self.bookmark_repository.add_bookmark(user_id, company_id).await?;
+        Ok(())
+    }
+
+    async fn remove_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        self.bookmark_repository.remove_bookmark(user_id, company_id).await?;
+        Ok(())
+    }
+
+    async fn is_bookmarked(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError> {
+        self.bookmark_repository.is_bookmarked(user_id, company_id).await
+    }
+
+    async fn list_bookmarks(&self, user_id: Uuid, query: BookmarksQuery) -> Result<BookmarksResponse, AppError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(AppError::Validation(format!(
+                "limit должен быть от 1 до {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        if offset < 0 {
+            return Err(AppError::Validation("offset не может быть отрицательным".to_string()));
+        }
+
+        let (bookmarks, total) = self
+            .bookmark_repository
+            .list_bookmarks(user_id, limit, offset)
+            .await?;
+
+        Ok(BookmarksResponse {
+            bookmarks,
+            total,
+            limit,
+            offset,
+        })
+    }
+}
@@ -0,0 +1,220 @@
+use crate::application::icalendar::{render_calendar, IcsEvent};
+use crate::domain::entities::{BookingStatus, CalendarEvent, CalendarFeed, CalendarFeedResponse};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{CalendarRepository, CalendarService, Clock, CompanyRepository};
+use async_trait::async_trait;
+use chrono_tz::Tz;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct CalendarServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    calendar_repository: Arc<dyn CalendarRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+/// Секретная часть ссылки: 244 случайных бита в hex
+fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn feed_response(feed: CalendarFeed, token: String) -> CalendarFeedResponse {
+    CalendarFeedResponse {
+        feed,
+        url: format!("/v1/calendar/{}.ics", token),
+        token,
+    }
+}
+
+fn event_timezone(event: &CalendarEvent) -> Result<Tz, AppError> {
+    event
+        .timezone
+        .parse()
+        .map_err(|_| AppError::Internal(format!("Неизвестный часовой пояс компании: {}", event.timezone)))
+}
+
+fn event_location(event: &CalendarEvent) -> String {
+    match &event.location_address {
+        Some(address) => format!("{}, {}", event.location_name, address),
+        None => event.location_name.clone(),
+    }
+}
+
+/// Запись глазами клиента: услуга, компания и специалист
+fn customer_view(event: &CalendarEvent) -> Result<IcsEvent, AppError> {
+    Ok(IcsEvent {
+        id: event.booking_id,
+        summary: format!("{} — {}", event.service_name, event.company_name),
+        description: Some(format!("Специалист: {}", event.staff_name)),
+        location: Some(event_location(event)),
+        starts_at: event.starts_at,
+        ends_at: event.ends_at,
+        timezone: event_timezone(event)?,
+        status: event.status,
+        sequence: event.sequence,
+        updated_at: event.updated_at,
+    })
+}
+
+/// Записи глазами сотрудника: имя клиента в заголовке, групповое занятие - одним событием
+fn staff_view(events: &[&CalendarEvent]) -> Result<Vec<IcsEvent>, AppError> {
+    let mut result: Vec<IcsEvent> = Vec::with_capacity(events.len());
+    let mut sessions: Vec<(Uuid, usize, i32)> = Vec::new();
+    for event in events {
+        if let Some(session_id) = event.session_id
+            && let Some((_, index, attendees)) = sessions.iter_mut().find(|(id, _, _)| *id == session_id)
+        {
+            let merged = &mut result[*index];
+            *attendees += 1;
+            merged.summary = format!("{} (участников: {})", event.service_name, attendees);
+            merged.sequence = merged.sequence.max(event.sequence);
+            merged.updated_at = merged.updated_at.max(event.updated_at);
+            // Занятие подтверждено, если подтверждена хотя бы одна запись на него
+            if merged.status == BookingStatus::Pending {
+                merged.status = event.status;
+            }
+            continue;
+        }
+
+        let (id, summary) = match event.session_id {
+            Some(session_id) => {
+                sessions.push((session_id, result.len(), 1));
+                (session_id, format!("{} (участников: 1)", event.service_name))
+            }
+            None => (event.booking_id, format!("{}: {}", event.service_name, event.customer_name)),
+        };
+        result.push(IcsEvent {
+            id,
+            summary,
+            description: Some(format!("Специалист: {}", event.staff_name)),
+            location: Some(event_location(event)),
+            starts_at: event.starts_at,
+            ends_at: event.ends_at,
+            timezone: event_timezone(event)?,
+            status: event.status,
+            sequence: event.sequence,
+            updated_at: event.updated_at,
+        });
+    }
+    Ok(result)
+}
+
+impl CalendarServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        calendar_repository: Arc<dyn CalendarRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            calendar_repository,
+            clock,
+        }
+    }
+
+    async fn require_manager(&self, company_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => Ok(()),
+            _ => Err(AppError::Forbidden("Недостаточно прав для управления компанией".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl CalendarService for CalendarServiceImpl {
+    async fn booking_calendar(&self, user_id: Uuid, booking_id: Uuid) -> Result<String, AppError> {
+        let event = self
+            .calendar_repository
+            .find_event(booking_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Запись не найдена".to_string()))?;
+
+        let ics_event = if event.customer_id == user_id {
+            customer_view(&event)?
+        } else if self
+            .company_repository
+            .get_member_role(event.company_id, user_id)
+            .await?
+            .is_some()
+        {
+            staff_view(&[&event])?.remove(0)
+        } else {
+            return Err(AppError::NotFound("Запись не найдена".to_string()));
+        };
+
+        let name = ics_event.summary.clone();
+        Ok(render_calendar(&name, &[ics_event]))
+    }
+
+    async fn create_user_feed(&self, user_id: Uuid) -> Result<CalendarFeedResponse, AppError> {
+        let token = generate_token();
+        let feed = self.calendar_repository.create_feed(user_id, None, &token).await?;
+        Ok(feed_response(feed, token))
+    }
+
+    async fn revoke_user_feed(&self, user_id: Uuid) -> Result<(), AppError> {
+        if !self.calendar_repository.revoke_feed(user_id, None).await? {
+            return Err(AppError::NotFound("Ссылка на календарь не выпущена".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn create_staff_feed(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        staff_id: Uuid,
+    ) -> Result<CalendarFeedResponse, AppError> {
+        self.require_manager(company_id, user_id).await?;
+        if self.company_repository.find_staff(company_id, staff_id).await?.is_none() {
+            return Err(AppError::NotFound("Сотрудник не найден".to_string()));
+        }
+
+        let token = generate_token();
+        let feed = self
+            .calendar_repository
+            .create_feed(user_id, Some((company_id, staff_id)), &token)
+            .await?;
+        Ok(feed_response(feed, token))
+    }
+
+    async fn revoke_staff_feed(&self, user_id: Uuid, company_id: Uuid, staff_id: Uuid) -> Result<(), AppError> {
+        self.require_manager(company_id, user_id).await?;
+        if !self.calendar_repository.revoke_feed(user_id, Some(staff_id)).await? {
+            return Err(AppError::NotFound("Ссылка на календарь не выпущена".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn feed_calendar(&self, token: &str) -> Result<String, AppError> {
+        let not_found = || AppError::NotFound("Календарь не найден".to_string());
+        let feed = self.calendar_repository.find_feed(token).await?.ok_or_else(not_found)?;
+        let now = self.clock.now();
+
+        match (feed.company_id, feed.staff_id) {
+            (Some(company_id), Some(staff_id)) => {
+                // Ссылка перестает работать, если выпустивший ее больше не руководитель
+                if self.require_manager(company_id, feed.user_id).await.is_err() {
+                    return Err(not_found());
+                }
+                let staff = self
+                    .company_repository
+                    .find_staff(company_id, staff_id)
+                    .await?
+                    .ok_or_else(not_found)?;
+                let events = self.calendar_repository.list_staff_events(staff_id, now).await?;
+                let events: Vec<&CalendarEvent> = events.iter().collect();
+                Ok(render_calendar(&staff.display_name, &staff_view(&events)?))
+            }
+            _ => {
+                let events = self.calendar_repository.list_user_events(feed.user_id, now).await?;
+                let (own, assigned): (Vec<&CalendarEvent>, Vec<&CalendarEvent>) =
+                    events.iter().partition(|event| event.customer_id == feed.user_id);
+                let mut ics_events = own.into_iter().map(customer_view).collect::<Result<Vec<_>, _>>()?;
+                ics_events.extend(staff_view(&assigned)?);
+                ics_events.sort_by_key(|event| event.starts_at);
+                Ok(render_calendar("Мои записи", &ics_events))
+            }
+        }
+    }
+}
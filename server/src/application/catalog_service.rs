@@ -0,0 +1,321 @@
+use crate::domain::entities::{
+    Company, CreateCompanyRequest, CreateLocationRequest, CreateResourceRequest, CreateScheduleExceptionRequest,
+    CreateServiceRequest, CreateStaffRequest, Location, Resource, ScheduleException, Service,
+    ServiceResourceRequirement, StaffMember, StaffSchedule, UpdateScheduleRequest, UpdateServiceResourcesRequest,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{CatalogService, CompanyRepository};
+use async_trait::async_trait;
+use chrono_tz::Tz;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct CatalogServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+}
+
+/// Сколько рубрик может быть у компании и их максимальная длина
+const MAX_CATEGORIES: usize = 10;
+const MAX_CATEGORY_LENGTH: usize = 64;
+
+/// Рубрики в нижнем регистре без повторов: по ним фильтрует и считает фасеты поиск
+fn normalize_categories(categories: &[String]) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(categories.len());
+    for category in categories {
+        let category = category.trim().to_lowercase();
+        if category.is_empty() || category.chars().count() > MAX_CATEGORY_LENGTH {
+            return Err(AppError::Validation(format!(
+                "Рубрика должна быть непустой и не длиннее {} символов",
+                MAX_CATEGORY_LENGTH
+            )));
+        }
+        if !normalized.contains(&category) {
+            normalized.push(category);
+        }
+    }
+    if normalized.len() > MAX_CATEGORIES {
+        return Err(AppError::Validation(format!(
+            "У компании может быть не больше {} рубрик",
+            MAX_CATEGORIES
+        )));
+    }
+    Ok(normalized)
+}
+
+impl CatalogServiceImpl {
+    pub fn new(company_repository: Arc<dyn CompanyRepository + Send + Sync>) -> Self {
+        Self { company_repository }
+    }
+
+    /// Проверить что компания существует и пользователь может ей управлять
+    async fn require_manager(&self, company_id: Uuid, user_id: Uuid) -> Result<Company, AppError> {
+        let company = self.get_company(company_id).await?;
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => Ok(company),
+            _ => Err(AppError::Forbidden("Недостаточно прав для управления компанией".to_string())),
+        }
+    }
+
+    async fn require_staff(&self, company_id: Uuid, staff_id: Uuid) -> Result<StaffMember, AppError> {
+        self.company_repository
+            .find_staff(company_id, staff_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Сотрудник не найден".to_string()))
+    }
+
+    async fn require_location(&self, company_id: Uuid, location_id: Uuid) -> Result<Location, AppError> {
+        self.company_repository
+            .find_location(company_id, location_id)
+            .await?
+            .ok_or_else(|| AppError::Validation("Филиал не найден в компании".to_string()))
+    }
+
+    async fn require_service(&self, company_id: Uuid, service_id: Uuid) -> Result<Service, AppError> {
+        self.company_repository
+            .find_service(company_id, service_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Услуга не найдена".to_string()))
+    }
+}
+
+#[async_trait]
+impl CatalogService for CatalogServiceImpl {
+    async fn create_company(&self, user_id: Uuid, data: CreateCompanyRequest) -> Result<Company, AppError> {
+        if data.name.trim().is_empty() {
+            return Err(AppError::Validation("Название компании обязательно".to_string()));
+        }
+        if data.timezone.parse::<Tz>().is_err() {
+            return Err(AppError::Validation(format!(
+                "Неизвестный часовой пояс: {}",
+                data.timezone
+            )));
+        }
+        let data = CreateCompanyRequest {
+            categories: normalize_categories(&data.categories)?,
+            ..data
+        };
+
+        Ok(self.company_repository.create_company(user_id, &data).await?)
+    }
+
+    async fn get_company(&self, company_id: Uuid) -> Result<Company, AppError> {
+        self.company_repository
+            .find_company(company_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Компания не найдена".to_string()))
+    }
+
+    async fn create_location(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateLocationRequest,
+    ) -> Result<Location, AppError> {
+        self.require_manager(company_id, user_id).await?;
+        if data.name.trim().is_empty() {
+            return Err(AppError::Validation("Название филиала обязательно".to_string()));
+        }
+        match (data.latitude, data.longitude) {
+            (None, None) => {}
+            (Some(latitude), Some(longitude))
+                if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) => {}
+            _ => {
+                return Err(AppError::Validation(
+                    "Координаты филиала задаются вместе: широта от -90 до 90, долгота от -180 до 180".to_string(),
+                ));
+            }
+        }
+
+        Ok(self.company_repository.create_location(company_id, &data).await?)
+    }
+
+    async fn create_service(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateServiceRequest,
+    ) -> Result<Service, AppError> {
+        self.require_manager(company_id, user_id).await?;
+
+        if data.name.trim().is_empty() {
+            return Err(AppError::Validation("Название услуги обязательно".to_string()));
+        }
+        if data.duration_minutes <= 0 {
+            return Err(AppError::Validation("Длительность услуги должна быть больше нуля".to_string()));
+        }
+        if data.buffer_before_minutes.unwrap_or(0) < 0 || data.buffer_after_minutes.unwrap_or(0) < 0 {
+            return Err(AppError::Validation("Буферы не могут быть отрицательными".to_string()));
+        }
+        if data.slot_step_minutes.is_some_and(|step| step <= 0) {
+            return Err(AppError::Validation("Шаг слотов должен быть больше нуля".to_string()));
+        }
+        if data.price_amount.is_some_and(|price| price < 0) {
+            return Err(AppError::Validation("Цена не может быть отрицательной".to_string()));
+        }
+        if let Some(deposit) = data.deposit_amount {
+            if deposit <= 0 {
+                return Err(AppError::Validation("Депозит должен быть больше нуля".to_string()));
+            }
+            if deposit > data.price_amount.unwrap_or(0) {
+                return Err(AppError::Validation("Депозит не может быть больше цены услуги".to_string()));
+            }
+        }
+        if data.currency.as_ref().is_some_and(|currency| currency.len() != 3) {
+            return Err(AppError::Validation("Код валюты должен состоять из 3 букв".to_string()));
+        }
+        if data.capacity.is_some_and(|capacity| capacity < 1) {
+            return Err(AppError::Validation("Вместимость занятия должна быть не меньше 1".to_string()));
+        }
+
+        Ok(self.company_repository.create_service(company_id, &data).await?)
+    }
+
+    async fn create_staff(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateStaffRequest,
+    ) -> Result<StaffMember, AppError> {
+        self.require_manager(company_id, user_id).await?;
+
+        if data.display_name.trim().is_empty() {
+            return Err(AppError::Validation("Имя сотрудника обязательно".to_string()));
+        }
+        for service_id in &data.service_ids {
+            if self.company_repository.find_service(company_id, *service_id).await?.is_none() {
+                return Err(AppError::Validation(format!(
+                    "Услуга {} не найдена в компании",
+                    service_id
+                )));
+            }
+        }
+
+        Ok(self.company_repository.create_staff(company_id, &data).await?)
+    }
+
+    async fn update_schedule(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        staff_id: Uuid,
+        data: UpdateScheduleRequest,
+    ) -> Result<Vec<StaffSchedule>, AppError> {
+        self.require_manager(company_id, user_id).await?;
+        self.require_staff(company_id, staff_id).await?;
+
+        for entry in &data.entries {
+            if !(1..=7).contains(&entry.weekday) {
+                return Err(AppError::Validation("День недели должен быть от 1 до 7".to_string()));
+            }
+            if entry.end_time <= entry.start_time {
+                return Err(AppError::Validation(
+                    "Время окончания должно быть позже времени начала".to_string(),
+                ));
+            }
+            self.require_location(company_id, entry.location_id).await?;
+        }
+
+        Ok(self
+            .company_repository
+            .replace_weekly_schedule(staff_id, &data.entries)
+            .await?)
+    }
+
+    async fn add_schedule_exception(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        staff_id: Uuid,
+        data: CreateScheduleExceptionRequest,
+    ) -> Result<ScheduleException, AppError> {
+        self.require_manager(company_id, user_id).await?;
+        self.require_staff(company_id, staff_id).await?;
+
+        match (data.start_time, data.end_time) {
+            (None, None) if !data.is_available => {}
+            (Some(start), Some(end)) if end > start => {}
+            _ => {
+                return Err(AppError::Validation(
+                    "Укажите корректный интервал или выходной на весь день".to_string(),
+                ));
+            }
+        }
+        if data.is_available {
+            match data.location_id {
+                Some(location_id) => {
+                    self.require_location(company_id, location_id).await?;
+                }
+                None => {
+                    return Err(AppError::Validation(
+                        "Для дополнительных часов нужно указать филиал".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(self
+            .company_repository
+            .add_schedule_exception(staff_id, &data)
+            .await?)
+    }
+
+    async fn create_resource(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateResourceRequest,
+    ) -> Result<Resource, AppError> {
+        self.require_manager(company_id, user_id).await?;
+        if data.name.trim().is_empty() {
+            return Err(AppError::Validation("Название ресурса обязательно".to_string()));
+        }
+        self.require_location(company_id, data.location_id).await?;
+
+        Ok(self.company_repository.create_resource(company_id, &data).await?)
+    }
+
+    async fn list_resources(&self, company_id: Uuid) -> Result<Vec<Resource>, AppError> {
+        let company = self.get_company(company_id).await?;
+        Ok(self.company_repository.list_resources(company.id).await?)
+    }
+
+    async fn get_service_resources(
+        &self,
+        company_id: Uuid,
+        service_id: Uuid,
+    ) -> Result<Vec<ServiceResourceRequirement>, AppError> {
+        let service = self.require_service(company_id, service_id).await?;
+        Ok(self.company_repository.list_service_requirements(service.id).await?)
+    }
+
+    async fn update_service_resources(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        service_id: Uuid,
+        data: UpdateServiceResourcesRequest,
+    ) -> Result<Vec<ServiceResourceRequirement>, AppError> {
+        self.require_manager(company_id, user_id).await?;
+        let service = self.require_service(company_id, service_id).await?;
+
+        let resources = self.company_repository.list_resources(company_id).await?;
+        for requirement in &data.requirements {
+            if requirement.resource_ids.is_empty() {
+                return Err(AppError::Validation("В требовании нужен хотя бы один ресурс".to_string()));
+            }
+            for resource_id in &requirement.resource_ids {
+                if !resources.iter().any(|resource| resource.id == *resource_id) {
+                    return Err(AppError::Validation(format!(
+                        "Ресурс {} не найден в компании",
+                        resource_id
+                    )));
+                }
+            }
+        }
+
+        Ok(self
+            .company_repository
+            .replace_service_requirements(service.id, &data.requirements)
+            .await?)
+    }
+}
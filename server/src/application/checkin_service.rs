@@ -0,0 +1,107 @@
+use crate::application::booking_context::BookingContext;
+use crate::application::checkin_token::{self, CheckinClaims};
+use crate::application::qr_code;
+use crate::domain::entities::{
+    Booking, BookingStatus, ChangeBookingStatusRequest, CheckinRequest, CheckinToken, QrImage, QrImageFormat,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, BookingService, CheckinService, Clock, CompanyRepository};
+use async_trait::async_trait;
+use chrono::{Duration, SubsecRound};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Сколько действует QR-код: приложение клиента обновляет его чаще
+const CHECKIN_TOKEN_TTL_MINUTES: i64 = 5;
+
+pub struct CheckinServiceImpl {
+    context: BookingContext,
+    /// Приход отмечается обычной сменой статуса записи
+    booking_service: Arc<dyn BookingService + Send + Sync>,
+    /// Секрет подписи токенов QR-кодов
+    checkin_secret: String,
+}
+
+impl CheckinServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        booking_service: Arc<dyn BookingService + Send + Sync>,
+        checkin_secret: String,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            context: BookingContext::new(company_repository, booking_repository, clock),
+            booking_service,
+            checkin_secret,
+        }
+    }
+}
+
+#[async_trait]
+impl CheckinService for CheckinServiceImpl {
+    async fn checkin_token(&self, customer_id: Uuid, booking_id: Uuid) -> Result<CheckinToken, AppError> {
+        // QR-код показывает только сам клиент, сотрудникам запись видна, но код им не нужен
+        let booking = self.context
+            .booking_repository
+            .find_booking(booking_id)
+            .await?
+            .filter(|booking| booking.customer_id == customer_id)
+            .ok_or_else(|| AppError::NotFound("Запись не найдена".to_string()))?;
+        if booking.status != BookingStatus::Confirmed {
+            return Err(AppError::Conflict(
+                "Отметиться можно только по подтвержденной записи".to_string(),
+            ));
+        }
+
+        // Срок в токене хранится с точностью до секунды
+        let claims = CheckinClaims {
+            booking_id: booking.id,
+            location_id: booking.location_id,
+            expires_at: self.context.clock.now().trunc_subsecs(0) + Duration::minutes(CHECKIN_TOKEN_TTL_MINUTES),
+        };
+        Ok(CheckinToken {
+            booking_id: booking.id,
+            location_id: booking.location_id,
+            token: checkin_token::issue(&self.checkin_secret, &claims),
+            expires_at: claims.expires_at,
+        })
+    }
+
+    async fn checkin_qr(&self, customer_id: Uuid, booking_id: Uuid, format: QrImageFormat) -> Result<QrImage, AppError> {
+        let token = self.checkin_token(customer_id, booking_id).await?;
+        qr_code::render(&token.token, format)
+    }
+
+    async fn check_in(&self, user_id: Uuid, company_id: Uuid, data: CheckinRequest) -> Result<Booking, AppError> {
+        let claims = checkin_token::verify(&self.checkin_secret, &data.token, self.context.clock.now())?;
+        if claims.location_id != data.location_id {
+            return Err(AppError::Validation("QR-код выдан для другого филиала".to_string()));
+        }
+
+        let (booking, role) = self.context.load_visible_booking(user_id, claims.booking_id).await?;
+        if role.is_none() {
+            return Err(AppError::Forbidden(
+                "Отмечать приход могут только сотрудники компании".to_string(),
+            ));
+        }
+        if booking.company_id != company_id {
+            return Err(AppError::Validation("QR-код выдан для записи другой компании".to_string()));
+        }
+        // После переноса в другой филиал старый код недействителен
+        if booking.location_id != claims.location_id {
+            return Err(AppError::Validation("QR-код выдан для другого филиала".to_string()));
+        }
+
+        self.booking_service
+            .change_status(
+                user_id,
+                booking.id,
+                ChangeBookingStatusRequest {
+                    status: BookingStatus::CheckedIn,
+                    reason: None,
+                },
+            )
+            .await
+    }
+}
@@ -0,0 +1,101 @@
+//! Токен отметки о приходе по QR-коду. Строка `{booking_id}.{location_id}.{expires_at}.{подпись}`:
+//! срок действия - секунды Unix, подпись - HMAC-SHA256 первых трех частей секретом сервера в hex.
+//! Токен привязан к записи и филиалу, поэтому в другом филиале и для другой записи не подходит.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::hmac_signature;
+use crate::domain::errors::AppError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckinClaims {
+    pub booking_id: Uuid,
+    pub location_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+fn invalid() -> AppError {
+    AppError::Validation("Недействительный QR-код".to_string())
+}
+
+pub fn issue(secret: &str, claims: &CheckinClaims) -> String {
+    let payload = format!(
+        "{}.{}.{}",
+        claims.booking_id,
+        claims.location_id,
+        claims.expires_at.timestamp()
+    );
+    let signature = hmac_signature::sign_hex(secret.as_bytes(), &[payload.as_bytes()]);
+    format!("{}.{}", payload, signature)
+}
+
+/// Проверить подпись за постоянное время, затем срок действия
+pub fn verify(secret: &str, token: &str, now: DateTime<Utc>) -> Result<CheckinClaims, AppError> {
+    let (payload, signature) = token.trim().rsplit_once('.').ok_or_else(invalid)?;
+    if !hmac_signature::verify_hex(secret.as_bytes(), &[payload.as_bytes()], signature) {
+        return Err(invalid());
+    }
+
+    let mut parts = payload.split('.');
+    let (Some(booking_id), Some(location_id), Some(expires_at), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let claims = CheckinClaims {
+        booking_id: booking_id.parse().map_err(|_| invalid())?,
+        location_id: location_id.parse().map_err(|_| invalid())?,
+        expires_at: expires_at
+            .parse()
+            .ok()
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+            .ok_or_else(invalid)?,
+    };
+    if claims.expires_at <= now {
+        return Err(AppError::Validation("QR-код устарел, обновите его в приложении".to_string()));
+    }
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn claims(now: DateTime<Utc>) -> CheckinClaims {
+        CheckinClaims {
+            booking_id: Uuid::new_v4(),
+            location_id: Uuid::new_v4(),
+            expires_at: DateTime::from_timestamp(now.timestamp(), 0).unwrap() + Duration::minutes(5),
+        }
+    }
+
+    #[test]
+    fn token_round_trips_until_it_expires() {
+        let now = Utc::now();
+        let claims = claims(now);
+        let token = issue("secret", &claims);
+        assert_eq!(verify("secret", &token, now).unwrap(), claims);
+        assert!(verify("secret", &token, now + Duration::minutes(5)).is_err());
+    }
+
+    #[test]
+    fn token_rejects_other_secret_and_changed_parts() {
+        let now = Utc::now();
+        let claims = claims(now);
+        let token = issue("secret", &claims);
+        assert!(verify("other", &token, now).is_err());
+
+        let moved = token.replacen(&claims.location_id.to_string(), &Uuid::new_v4().to_string(), 1);
+        assert!(verify("secret", &moved, now).is_err());
+        let extended = token.replacen(
+            &claims.expires_at.timestamp().to_string(),
+            &(claims.expires_at.timestamp() + 3600).to_string(),
+            1,
+        );
+        assert!(verify("secret", &extended, now).is_err());
+        assert!(verify("secret", "garbage", now).is_err());
+        assert!(verify("secret", "a.b.c.zz", now).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::application::booking_context::BookingContext;
+use crate::domain::entities::{
+    CustomerRestrictionDetails, CustomerRestrictionStatus, SetRestrictionOverrideRequest,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, Clock, CompanyRepository, CustomerRestrictionService, UserAuthRepository};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Максимальная длина причины ручного ограничения в символах
+const MAX_REASON_LENGTH: usize = 500;
+
+pub struct CustomerRestrictionServiceImpl {
+    context: BookingContext,
+    /// Администраторы платформы снимают и задают ограничения в любой компании
+    user_repository: Arc<dyn UserAuthRepository + Send + Sync>,
+}
+
+impl CustomerRestrictionServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        user_repository: Arc<dyn UserAuthRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            context: BookingContext::new(company_repository, booking_repository, clock),
+            user_repository,
+        }
+    }
+
+    /// Смотреть ограничение могут сотрудники компании и администраторы платформы
+    async fn require_read_access(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        let company = self.context.require_company(company_id).await?;
+        let role = self.context.company_repository.get_member_role(company.id, user_id).await?;
+        if role.is_some() || self.is_platform_admin(user_id).await? {
+            return Ok(());
+        }
+        Err(AppError::Forbidden("Доступно только сотрудникам компании".to_string()))
+    }
+
+    /// Задавать ограничение вручную могут руководители компании и администраторы платформы
+    async fn require_override_access(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        let company = self.context.require_company(company_id).await?;
+        let role = self.context.company_repository.get_member_role(company.id, user_id).await?;
+        if role.is_some_and(|role| role.can_manage()) || self.is_platform_admin(user_id).await? {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(
+            "Недостаточно прав для управления компанией".to_string(),
+        ))
+    }
+
+    async fn is_platform_admin(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        Ok(user.is_some_and(|user| user.is_admin()))
+    }
+
+    async fn restriction_details(&self, company_id: Uuid, customer_id: Uuid) -> Result<CustomerRestrictionDetails, AppError> {
+        let (status, restriction_override) = self.context.load_restriction(company_id, customer_id).await?;
+        Ok(CustomerRestrictionDetails {
+            status,
+            restriction_override,
+        })
+    }
+}
+
+#[async_trait]
+impl CustomerRestrictionService for CustomerRestrictionServiceImpl {
+    async fn my_restriction(&self, customer_id: Uuid, company_id: Uuid) -> Result<CustomerRestrictionStatus, AppError> {
+        let company = self.context.require_company(company_id).await?;
+        let (status, _) = self.context.load_restriction(company.id, customer_id).await?;
+        Ok(status)
+    }
+
+    async fn customer_restriction(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<CustomerRestrictionDetails, AppError> {
+        self.require_read_access(user_id, company_id).await?;
+        self.restriction_details(company_id, customer_id).await
+    }
+
+    async fn set_override(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        data: SetRestrictionOverrideRequest,
+    ) -> Result<CustomerRestrictionDetails, AppError> {
+        self.require_override_access(user_id, company_id).await?;
+        let reason = data.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+        if reason.is_some_and(|r| r.chars().count() > MAX_REASON_LENGTH) {
+            return Err(AppError::Validation(format!(
+                "Причина не может быть длиннее {} символов",
+                MAX_REASON_LENGTH
+            )));
+        }
+
+        self.context
+            .booking_repository
+            .save_restriction_override(company_id, customer_id, data.restriction, reason, user_id)
+            .await?;
+        self.restriction_details(company_id, customer_id).await
+    }
+
+    async fn clear_override(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<CustomerRestrictionDetails, AppError> {
+        self.require_override_access(user_id, company_id).await?;
+
+        self.context
+            .booking_repository
+            .delete_restriction_override(company_id, customer_id)
+            .await?;
+        self.restriction_details(company_id, customer_id).await
+    }
+}
@@ -0,0 +1,272 @@
+use crate::domain::entities::{
+    CompanyRole, CustomerNote, CustomerNoteRequest, CustomerProfile, CustomerSummary, CustomersQuery,
+    CustomersResponse, UpdateCustomerTagsRequest,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{CompanyRepository, CustomerRepository, CustomerService};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Размер страницы клиентов по умолчанию и максимальный
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Максимальная длина заметки в символах
+const MAX_NOTE_LENGTH: usize = 4000;
+
+/// Ограничения на метки клиента
+const MAX_TAGS: usize = 20;
+const MAX_TAG_LENGTH: usize = 50;
+
+pub struct CustomerServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    customer_repository: Arc<dyn CustomerRepository + Send + Sync>,
+}
+
+/// Метки без пробелов по краям, в нижнем регистре, без повторов и по алфавиту
+pub fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut normalized = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            return Err(AppError::Validation("Метка не может быть пустой".to_string()));
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(AppError::Validation(format!(
+                "Метка не может быть длиннее {} символов",
+                MAX_TAG_LENGTH
+            )));
+        }
+        normalized.push(tag);
+    }
+    normalized.sort();
+    normalized.dedup();
+    if normalized.len() > MAX_TAGS {
+        return Err(AppError::Validation(format!("У клиента может быть не больше {} меток", MAX_TAGS)));
+    }
+    Ok(normalized)
+}
+
+fn normalize_note(body: String) -> Result<String, AppError> {
+    let body = body.trim().to_string();
+    if body.is_empty() {
+        return Err(AppError::Validation("Заметка не может быть пустой".to_string()));
+    }
+    if body.chars().count() > MAX_NOTE_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Заметка не может быть длиннее {} символов",
+            MAX_NOTE_LENGTH
+        )));
+    }
+    Ok(body)
+}
+
+impl CustomerServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        customer_repository: Arc<dyn CustomerRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            customer_repository,
+        }
+    }
+
+    /// Клиентов видят все участники компании: сотрудникам на приеме тоже нужны заметки об аллергиях
+    async fn require_member(&self, user_id: Uuid, company_id: Uuid) -> Result<CompanyRole, AppError> {
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+        self.company_repository
+            .get_member_role(company_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::Forbidden("Доступно только сотрудникам компании".to_string()))
+    }
+
+    /// Участник компании и клиент, о котором идет речь. Свою карточку сотрудник не видит:
+    /// заметки о клиенте не должны попадать к нему самому
+    async fn require_customer(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<(CompanyRole, CustomerSummary), AppError> {
+        let role = self.require_member(user_id, company_id).await?;
+        if user_id == customer_id {
+            return Err(AppError::Forbidden("Нельзя просматривать собственную карточку клиента".to_string()));
+        }
+        let customer = self
+            .customer_repository
+            .find_customer(company_id, customer_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Клиент не найден".to_string()))?;
+        Ok((role, customer))
+    }
+
+    /// Заметка клиента, которую пользователь вправе изменить
+    async fn editable_note(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        note_id: Uuid,
+    ) -> Result<CustomerNote, AppError> {
+        let (role, _) = self.require_customer(user_id, company_id, customer_id).await?;
+        let note = self
+            .customer_repository
+            .find_note(company_id, customer_id, note_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Заметка не найдена".to_string()))?;
+        if note.author_id != Some(user_id) && !role.can_manage() {
+            return Err(AppError::Forbidden(
+                "Изменять чужие заметки могут только руководители компании".to_string(),
+            ));
+        }
+        Ok(note)
+    }
+}
+
+#[async_trait]
+impl CustomerService for CustomerServiceImpl {
+    async fn list_customers(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        query: CustomersQuery,
+    ) -> Result<CustomersResponse, AppError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(AppError::Validation(format!(
+                "limit должен быть от 1 до {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        if offset < 0 {
+            return Err(AppError::Validation("offset не может быть отрицательным".to_string()));
+        }
+        self.require_member(user_id, company_id).await?;
+
+        let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+        let tag = query.tag.map(|tag| tag.trim().to_lowercase()).filter(|tag| !tag.is_empty());
+        let (customers, total) = self
+            .customer_repository
+            .list_customers(company_id, search, tag.as_deref(), limit, offset)
+            .await?;
+
+        Ok(CustomersResponse {
+            customers,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    async fn get_customer(&self, user_id: Uuid, company_id: Uuid, customer_id: Uuid) -> Result<CustomerProfile, AppError> {
+        let (_, customer) = self.require_customer(user_id, company_id, customer_id).await?;
+        let bookings = self
+            .customer_repository
+            .list_customer_bookings(company_id, customer_id)
+            .await?;
+        let notes = self.customer_repository.list_notes(company_id, customer_id).await?;
+
+        Ok(CustomerProfile {
+            customer,
+            bookings,
+            notes,
+        })
+    }
+
+    async fn add_note(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        data: CustomerNoteRequest,
+    ) -> Result<CustomerNote, AppError> {
+        let body = normalize_note(data.body)?;
+        self.require_customer(user_id, company_id, customer_id).await?;
+
+        self.customer_repository
+            .create_note(company_id, customer_id, user_id, &body)
+            .await
+    }
+
+    async fn update_note(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        note_id: Uuid,
+        data: CustomerNoteRequest,
+    ) -> Result<CustomerNote, AppError> {
+        let body = normalize_note(data.body)?;
+        self.editable_note(user_id, company_id, customer_id, note_id).await?;
+
+        self.customer_repository
+            .update_note(note_id, &body)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Заметка не найдена".to_string()))
+    }
+
+    async fn delete_note(&self, user_id: Uuid, company_id: Uuid, customer_id: Uuid, note_id: Uuid) -> Result<(), AppError> {
+        self.editable_note(user_id, company_id, customer_id, note_id).await?;
+
+        if self.customer_repository.delete_note(note_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Заметка не найдена".to_string()))
+        }
+    }
+
+    async fn update_tags(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        data: UpdateCustomerTagsRequest,
+    ) -> Result<Vec<String>, AppError> {
+        let tags = normalize_tags(data.tags)?;
+        self.require_customer(user_id, company_id, customer_id).await?;
+
+        self.customer_repository
+            .replace_tags(company_id, customer_id, &tags)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn tags_are_trimmed_lowercased_and_deduplicated() {
+        assert_eq!(
+            normalize_tags(tags(&[" VIP ", "аллергия", "vip", "Аллергия"])).unwrap(),
+            tags(&["vip", "аллергия"])
+        );
+        assert_eq!(normalize_tags(Vec::new()).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn invalid_tags_are_rejected() {
+        assert!(matches!(normalize_tags(tags(&["vip", "  "])), Err(AppError::Validation(_))));
+        assert!(matches!(
+            normalize_tags(vec!["я".repeat(MAX_TAG_LENGTH + 1)]),
+            Err(AppError::Validation(_))
+        ));
+        let many: Vec<String> = (0..=MAX_TAGS).map(|i| format!("tag{}", i)).collect();
+        assert!(matches!(normalize_tags(many), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn note_must_have_text() {
+        assert_eq!(normalize_note("  аллергия на латекс \n".to_string()).unwrap(), "аллергия на латекс");
+        assert!(matches!(normalize_note(" \n ".to_string()), Err(AppError::Validation(_))));
+    }
+}
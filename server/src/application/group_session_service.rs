@@ -0,0 +1,94 @@
+use crate::application::recurrence::local_to_utc;
+use crate::application::slot_service::company_timezone;
+use crate::domain::entities::{GroupSession, GroupSessionDetails, SessionsQuery};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, CompanyRepository, GroupSessionService};
+use async_trait::async_trait;
+use chrono::{Days, NaiveTime};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Максимальная длина запрашиваемого периода в днях
+const MAX_RANGE_DAYS: i64 = 31;
+
+pub struct GroupSessionServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+}
+
+impl GroupSessionServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            booking_repository,
+        }
+    }
+
+    /// Списки участников видят только сотрудники компании
+    async fn require_member(&self, company_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(_) => Ok(()),
+            None => Err(AppError::Forbidden("Недостаточно прав для просмотра занятий".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl GroupSessionService for GroupSessionServiceImpl {
+    async fn list_sessions(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        query: SessionsQuery,
+    ) -> Result<Vec<GroupSession>, AppError> {
+        if query.to < query.from {
+            return Err(AppError::Validation("Дата окончания раньше даты начала".to_string()));
+        }
+        if (query.to - query.from).num_days() >= MAX_RANGE_DAYS {
+            return Err(AppError::Validation(format!(
+                "Период не может быть больше {} дней",
+                MAX_RANGE_DAYS
+            )));
+        }
+
+        let company = self
+            .company_repository
+            .find_company(company_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Компания не найдена".to_string()))?;
+        self.require_member(company.id, user_id).await?;
+        let timezone = company_timezone(&company)?;
+
+        let from = local_to_utc(timezone, query.from.and_time(NaiveTime::MIN));
+        let next_day = query
+            .to
+            .checked_add_days(Days::new(1))
+            .ok_or_else(|| AppError::Validation("Некорректная дата окончания".to_string()))?;
+        let to = local_to_utc(timezone, next_day.and_time(NaiveTime::MIN));
+
+        self.booking_repository
+            .list_company_sessions(company.id, query.service, from, to)
+            .await
+    }
+
+    async fn get_session(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<GroupSessionDetails, AppError> {
+        self.require_member(company_id, user_id).await?;
+        let session = self
+            .booking_repository
+            .find_group_session(session_id)
+            .await?
+            .filter(|session| session.company_id == company_id)
+            .ok_or_else(|| AppError::NotFound("Занятие не найдено".to_string()))?;
+        let attendees = self.booking_repository.list_session_attendees(session.id).await?;
+
+        Ok(GroupSessionDetails { session, attendees })
+    }
+}
@@ -0,0 +1,48 @@
+//! HMAC-SHA256 для подписей сервера: запросов вебхуков, уведомлений платежных систем,
+//! токенов отметки о приходе и запросов к S3. Сообщение передается частями и подписывается
+//! так, будто части записаны подряд.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+fn mac(key: &[u8], parts: &[&[u8]]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC принимает ключ любой длины");
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+pub fn sign(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    mac(key, parts).finalize().into_bytes().to_vec()
+}
+
+pub fn sign_hex(key: &[u8], parts: &[&[u8]]) -> String {
+    hex::encode(sign(key, parts))
+}
+
+/// Проверить подпись за постоянное время
+pub fn verify(key: &[u8], parts: &[&[u8]], signature: &[u8]) -> bool {
+    mac(key, parts).verify_slice(signature).is_ok()
+}
+
+/// Проверить подпись в hex; строка не в hex - неверная подпись
+pub fn verify_hex(key: &[u8], parts: &[&[u8]], signature: &str) -> bool {
+    hex::decode(signature).is_ok_and(|signature| verify(key, parts, &signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts_are_signed_as_one_message() {
+        // RFC 4231, тест 2
+        let expected = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        assert_eq!(sign_hex(b"Jefe", &[b"what do ya want for nothing?"]), expected);
+        assert_eq!(sign_hex(b"Jefe", &[b"what do ya", b" want ", b"for nothing?"]), expected);
+        assert!(verify_hex(b"Jefe", &[b"what do ya want for nothing?"], expected));
+        assert!(!verify_hex(b"jefe", &[b"what do ya want for nothing?"], expected));
+        assert!(!verify_hex(b"Jefe", &[b"what do ya want for nothing?"], "zz"));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::application::booking_context::BookingContext;
+use crate::domain::entities::{Booking, BookingStatus, CreateHoldRequest, NewBooking, SlotHold};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, Clock, CompanyRepository, HoldService};
+use async_trait::async_trait;
+use chrono::Duration;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Сколько минут держится бронь слота, если не задано иное
+pub const DEFAULT_HOLD_TTL_MINUTES: i64 = 10;
+
+pub struct HoldServiceImpl {
+    context: BookingContext,
+    hold_ttl: Duration,
+}
+
+impl HoldServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            context: BookingContext::new(company_repository, booking_repository, clock),
+            hold_ttl: Duration::minutes(DEFAULT_HOLD_TTL_MINUTES),
+        }
+    }
+
+    pub fn with_hold_ttl(mut self, hold_ttl: Duration) -> Self {
+        self.hold_ttl = hold_ttl;
+        self
+    }
+}
+
+#[async_trait]
+impl HoldService for HoldServiceImpl {
+    async fn create_hold(&self, customer_id: Uuid, data: CreateHoldRequest) -> Result<SlotHold, AppError> {
+        let company = self.context.require_company(data.company_id).await?;
+        let service = self.context.require_service(company.id, data.service_id).await?;
+        let staff = self.context.require_staff_for(company.id, data.staff_id, &service).await?;
+        // Клиенту с закрытой онлайн-записью незачем держать слот
+        self.context.new_booking_status(company.id, customer_id).await?;
+
+        let resolved = self.context
+            .resolve_slot(&company, &service, &staff, data.starts_at, data.location_id, &[])
+            .await?;
+
+        let hold = NewBooking {
+            customer_id,
+            company_id: company.id,
+            service_id: service.id,
+            staff_id: staff.id,
+            location_id: resolved.slot.location_id,
+            starts_at: resolved.slot.start,
+            ends_at: resolved.slot.end,
+            blocked_from: resolved.blocked_from,
+            blocked_to: resolved.blocked_to,
+            status: BookingStatus::Held,
+            capacity: service.capacity,
+            resource_ids: resolved.slot.resource_ids.clone(),
+            promo: None,
+        };
+        self.context
+            .booking_repository
+            .create_hold(&hold, self.context.clock.now() + self.hold_ttl)
+            .await
+    }
+
+    async fn confirm_hold(&self, customer_id: Uuid, hold_id: Uuid) -> Result<Booking, AppError> {
+        let hold = match self.context.booking_repository.find_booking(hold_id).await? {
+            Some(hold) if hold.customer_id == customer_id => hold,
+            _ => return Err(AppError::NotFound("Бронь не найдена".to_string())),
+        };
+        // Ограничение проверяется еще раз: неявка могла быть отмечена, пока бронь держалась
+        let service = self.context.require_service(hold.company_id, hold.service_id).await?;
+        let status = self.context.initial_booking_status(hold.company_id, customer_id, &service).await?;
+
+        self.context
+            .booking_repository
+            .convert_hold(hold_id, customer_id, status, self.context.clock.now())
+            .await
+    }
+
+    async fn release_hold(&self, customer_id: Uuid, hold_id: Uuid) -> Result<(), AppError> {
+        if self.context.booking_repository.release_hold(hold_id, customer_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Бронь не найдена".to_string()))
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, Clock, PaymentService, WaitlistService};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Фоновая задача, снимающая истекшие брони слотов
+pub struct HoldSweeper {
+    booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+    /// Просроченные предложения из листа ожидания передаются следующим в очереди
+    waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+    /// Записи, депозит по которым не оплачен вовремя, отменяются
+    payment_service: Arc<dyn PaymentService + Send + Sync>,
+}
+
+impl HoldSweeper {
+    pub fn new(
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+        payment_service: Arc<dyn PaymentService + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            booking_repository,
+            clock,
+            waitlist_service,
+            payment_service,
+        }
+    }
+
+    /// Один проход: снять брони, истекшие к текущему моменту часов.
+    /// Брони предложений из листа ожидания обрабатываются первыми, чтобы слот сразу ушел дальше
+    pub async fn run_once(&self) -> Result<u64, AppError> {
+        if let Err(e) = self.waitlist_service.process_expired_offers().await {
+            eprintln!("{}", e);
+        }
+        if let Err(e) = self.payment_service.expire_unpaid_deposits().await {
+            eprintln!("{}", e);
+        }
+
+        self.booking_repository
+            .release_expired_holds(self.clock.now())
+            .await
+    }
+
+    /// Запустить проходы с заданным интервалом в рантайме actix
+    pub fn spawn(self, every: Duration) {
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    eprintln!("{}", e);
+                }
+            }
+        });
+    }
+}
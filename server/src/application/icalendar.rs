@@ -0,0 +1,357 @@
+//! Выгрузка записей в iCalendar (RFC 5545). Время событий пишется в местном времени
+//! компании с TZID, а для каждого часового пояса добавляется VTIMEZONE с фактическими
+//! переходами за годы, которые покрывают события: календарь телефона покажет то же
+//! время, что и расписание, даже если его база часовых поясов устарела.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+
+use crate::domain::entities::BookingStatus;
+
+/// Домен в UID событий; UID не меняется при переносе и смене статуса записи
+pub const UID_DOMAIN: &str = "locationx";
+
+const PRODUCT_ID: &str = "-//LocationX//Bookings//RU";
+
+/// RFC 5545 ограничивает строку 75 октетами без учета CRLF
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Событие календаря, тексты уже подготовлены для того, кто его увидит
+#[derive(Debug, Clone)]
+pub struct IcsEvent {
+    /// Запись или групповое занятие, которому соответствует событие
+    pub id: uuid::Uuid,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub timezone: Tz,
+    pub status: BookingStatus,
+    pub sequence: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Календарь с событиями и описаниями их часовых поясов
+pub fn render_calendar(name: &str, events: &[IcsEvent]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+        // Подсказка клиентам подписки, как часто обновлять ленту
+        "REFRESH-INTERVAL;VALUE=DURATION:PT1H".to_string(),
+        "X-PUBLISHED-TTL:PT1H".to_string(),
+    ];
+
+    // Годы, которые нужно описать для каждого часового пояса
+    let mut zones: BTreeMap<&str, (Tz, i32, i32)> = BTreeMap::new();
+    for event in events {
+        let first = event.starts_at.with_timezone(&event.timezone).year();
+        let last = event.ends_at.with_timezone(&event.timezone).year();
+        zones
+            .entry(event.timezone.name())
+            .and_modify(|(_, from, to)| {
+                *from = (*from).min(first);
+                *to = (*to).max(last);
+            })
+            .or_insert((event.timezone, first, last));
+    }
+    for (timezone, first_year, last_year) in zones.into_values() {
+        lines.extend(vtimezone(timezone, first_year, last_year));
+    }
+
+    for event in events {
+        lines.extend(vevent(event));
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+fn vevent(event: &IcsEvent) -> Vec<String> {
+    let tzid = event.timezone.name();
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@{}", event.id, UID_DOMAIN),
+        format!("DTSTAMP:{}", format_utc(event.updated_at)),
+        format!("LAST-MODIFIED:{}", format_utc(event.updated_at)),
+        format!("SEQUENCE:{}", event.sequence),
+        format!(
+            "DTSTART;TZID={}:{}",
+            tzid,
+            format_local(event.starts_at.with_timezone(&event.timezone).naive_local())
+        ),
+        format!(
+            "DTEND;TZID={}:{}",
+            tzid,
+            format_local(event.ends_at.with_timezone(&event.timezone).naive_local())
+        ),
+        format!("SUMMARY:{}", escape_text(&event.summary)),
+    ];
+    if let Some(description) = &event.description {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    if let Some(location) = &event.location {
+        lines.push(format!("LOCATION:{}", escape_text(location)));
+    }
+    lines.push(format!("STATUS:{}", event_status(event.status)));
+    lines.push("TRANSP:OPAQUE".to_string());
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+fn event_status(status: BookingStatus) -> &'static str {
+    match status {
+        BookingStatus::Held | BookingStatus::Pending => "TENTATIVE",
+        BookingStatus::Confirmed | BookingStatus::CheckedIn | BookingStatus::Completed => "CONFIRMED",
+        BookingStatus::CancelledByCustomer | BookingStatus::CancelledByCompany | BookingStatus::NoShow => {
+            "CANCELLED"
+        }
+    }
+}
+
+/// Смещение от UTC в секундах и признак летнего времени в момент `instant`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Observance {
+    offset: i32,
+    dst: bool,
+}
+
+fn observance_at(timezone: Tz, instant: DateTime<Utc>) -> Observance {
+    let offset = timezone.offset_from_utc_datetime(&instant.naive_utc());
+    Observance {
+        offset: offset.fix().local_minus_utc(),
+        dst: !offset.dst_offset().is_zero(),
+    }
+}
+
+fn abbreviation_at(timezone: Tz, instant: DateTime<Utc>) -> Option<String> {
+    timezone
+        .offset_from_utc_datetime(&instant.naive_utc())
+        .abbreviation()
+        .map(str::to_string)
+}
+
+/// Моменты смены смещения в `[from, to)`: перебираем дни, внутри дня ищем секунду перехода
+fn transitions(timezone: Tz, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let mut result = Vec::new();
+    let mut day = from;
+    while day < to {
+        let next = (day + Duration::days(1)).min(to);
+        if observance_at(timezone, day) != observance_at(timezone, next) {
+            let (mut before, mut after) = (day, next);
+            while after - before > Duration::seconds(1) {
+                let middle = before + (after - before) / 2;
+                if observance_at(timezone, middle) == observance_at(timezone, day) {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+            result.push(after);
+        }
+        day = next;
+    }
+    result
+}
+
+/// VTIMEZONE с правилами, действовавшими с 1 января `first_year` до конца `last_year`
+fn vtimezone(timezone: Tz, first_year: i32, last_year: i32) -> Vec<String> {
+    let from = Utc.with_ymd_and_hms(first_year, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(last_year + 1, 1, 1, 0, 0, 0).unwrap();
+
+    let mut lines = vec!["BEGIN:VTIMEZONE".to_string(), format!("TZID:{}", timezone.name())];
+    let initial = observance_at(timezone, from);
+    lines.extend(observance_lines(timezone, from, initial, initial));
+    let mut previous = initial;
+    for instant in transitions(timezone, from, to) {
+        let current = observance_at(timezone, instant);
+        lines.extend(observance_lines(timezone, instant, previous, current));
+        previous = current;
+    }
+    lines.push("END:VTIMEZONE".to_string());
+    lines
+}
+
+/// Правило с момента `instant`; DTSTART по RFC 5545 записывается в прежнем местном времени
+fn observance_lines(timezone: Tz, instant: DateTime<Utc>, before: Observance, after: Observance) -> Vec<String> {
+    let kind = if after.dst { "DAYLIGHT" } else { "STANDARD" };
+    let onset = instant.naive_utc() + Duration::seconds(before.offset as i64);
+    let mut lines = vec![
+        format!("BEGIN:{}", kind),
+        format!("DTSTART:{}", format_local(onset)),
+        format!("TZOFFSETFROM:{}", format_offset(before.offset)),
+        format!("TZOFFSETTO:{}", format_offset(after.offset)),
+    ];
+    if let Some(name) = abbreviation_at(timezone, instant) {
+        lines.push(format!("TZNAME:{}", escape_text(&name)));
+    }
+    lines.push(format!("END:{}", kind));
+    lines
+}
+
+fn format_utc(instant: DateTime<Utc>) -> String {
+    instant.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn format_local(local: NaiveDateTime) -> String {
+    local.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// `+0300`, `-0930`; секунды пишутся только для исторических смещений вроде `+023017`
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+    let (hours, minutes, rest) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if rest == 0 {
+        format!("{}{:02}{:02}", sign, hours, minutes)
+    } else {
+        format!("{}{:02}{:02}{:02}", sign, hours, minutes, rest)
+    }
+}
+
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Перенос длинной строки с CRLF и пробелом, не разрывая символы UTF-8
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut octets = 0;
+    for c in line.chars() {
+        // Продолжение начинается с пробела, он тоже занимает место в строке
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    fn event(timezone: &str, starts_at: &str) -> IcsEvent {
+        let starts_at = utc(starts_at);
+        IcsEvent {
+            id: Uuid::nil(),
+            summary: "Консультация, первичная; кабинет 5".to_string(),
+            description: None,
+            location: Some("Главный филиал".to_string()),
+            starts_at,
+            ends_at: starts_at + Duration::hours(1),
+            timezone: timezone.parse().unwrap(),
+            status: BookingStatus::Confirmed,
+            sequence: 2,
+            updated_at: utc("2025-06-01T08:30:00Z"),
+        }
+    }
+
+    fn unfold(calendar: &str) -> Vec<String> {
+        calendar.replace("\r\n ", "").split("\r\n").map(str::to_string).collect()
+    }
+
+    #[test]
+    fn event_is_written_in_local_time_with_stable_uid() {
+        let calendar = render_calendar("Записи", &[event("Europe/Moscow", "2025-06-02T06:00:00Z")]);
+        let lines = unfold(&calendar);
+
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert!(lines.contains(&format!("UID:{}@locationx", Uuid::nil())));
+        assert!(lines.contains(&"DTSTART;TZID=Europe/Moscow:20250602T090000".to_string()));
+        assert!(lines.contains(&"DTEND;TZID=Europe/Moscow:20250602T100000".to_string()));
+        assert!(lines.contains(&"DTSTAMP:20250601T083000Z".to_string()));
+        assert!(lines.contains(&"SEQUENCE:2".to_string()));
+        assert!(lines.contains(&"SUMMARY:Консультация\\, первичная\\; кабинет 5".to_string()));
+        assert!(lines.contains(&"STATUS:CONFIRMED".to_string()));
+
+        // В Москве нет перехода на летнее время: одно стандартное правило
+        let start = lines.iter().position(|l| l == "BEGIN:VTIMEZONE").unwrap();
+        let end = lines.iter().position(|l| l == "END:VTIMEZONE").unwrap();
+        assert_eq!(
+            lines[start..=end],
+            [
+                "BEGIN:VTIMEZONE",
+                "TZID:Europe/Moscow",
+                "BEGIN:STANDARD",
+                "DTSTART:20250101T030000",
+                "TZOFFSETFROM:+0300",
+                "TZOFFSETTO:+0300",
+                "TZNAME:MSK",
+                "END:STANDARD",
+                "END:VTIMEZONE",
+            ]
+        );
+    }
+
+    #[test]
+    fn vtimezone_lists_dst_transitions_of_the_event_years() {
+        let calendar = render_calendar(
+            "Записи",
+            &[
+                event("Europe/Berlin", "2025-03-18T09:00:00Z"),
+                event("Europe/Berlin", "2026-01-10T09:00:00Z"),
+            ],
+        );
+        let lines = unfold(&calendar);
+
+        assert_eq!(lines.iter().filter(|l| *l == "BEGIN:VTIMEZONE").count(), 1);
+        // Переходы записываются в местном времени до перехода
+        for expected in [
+            "DTSTART:20250330T020000",
+            "DTSTART:20251026T030000",
+            "DTSTART:20260329T020000",
+            "DTSTART:20261025T030000",
+        ] {
+            assert!(lines.contains(&expected.to_string()), "нет {}", expected);
+        }
+        assert_eq!(lines.iter().filter(|l| *l == "BEGIN:DAYLIGHT").count(), 2);
+        assert!(lines.contains(&"TZOFFSETTO:+0200".to_string()));
+        assert!(lines.contains(&"TZNAME:CEST".to_string()));
+        assert!(lines.contains(&"DTSTART;TZID=Europe/Berlin:20250318T100000".to_string()));
+    }
+
+    #[test]
+    fn long_lines_are_folded_without_breaking_utf8() {
+        let line = format!("SUMMARY:{}", "запись ".repeat(30));
+        let folded = fold_line(&line);
+
+        assert!(folded.split("\r\n").all(|part| part.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn offsets_and_statuses_follow_rfc_5545() {
+        assert_eq!(format_offset(3 * 3600), "+0300");
+        assert_eq!(format_offset(-(9 * 3600 + 30 * 60)), "-0930");
+        assert_eq!(format_offset(2 * 3600 + 30 * 60 + 17), "+023017");
+        assert_eq!(event_status(BookingStatus::Pending), "TENTATIVE");
+        assert_eq!(event_status(BookingStatus::CancelledByCompany), "CANCELLED");
+        assert_eq!(escape_text("a\\b\nc"), "a\\\\b\\nc");
+    }
+}
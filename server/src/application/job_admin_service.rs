@@ -0,0 +1,46 @@
+use crate::domain::entities::{JobRecord, JobsQuery};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{JobAdminService, JobRepository, UserAuthRepository};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct JobAdminServiceImpl {
+    user_repository: Arc<dyn UserAuthRepository + Send + Sync>,
+    job_repository: Arc<dyn JobRepository + Send + Sync>,
+}
+
+impl JobAdminServiceImpl {
+    pub fn new(
+        user_repository: Arc<dyn UserAuthRepository + Send + Sync>,
+        job_repository: Arc<dyn JobRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            user_repository,
+            job_repository,
+        }
+    }
+
+    async fn require_admin(&self, user_id: Uuid) -> Result<(), AppError> {
+        match self.user_repository.find_by_id(user_id).await? {
+            Some(user) if user.is_admin() => Ok(()),
+            _ => Err(AppError::Forbidden("Доступно только администраторам".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl JobAdminService for JobAdminServiceImpl {
+    async fn list_jobs(&self, user_id: Uuid, query: JobsQuery) -> Result<Vec<JobRecord>, AppError> {
+        self.require_admin(user_id).await?;
+        self.job_repository.list_jobs(&query).await
+    }
+
+    async fn retry_job(&self, user_id: Uuid, job_id: Uuid) -> Result<JobRecord, AppError> {
+        self.require_admin(user_id).await?;
+        self.job_repository
+            .retry_dead(job_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Задача не найдена среди упавших".to_string()))
+    }
+}
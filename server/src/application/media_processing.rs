@@ -0,0 +1,186 @@
+//! Обработка загруженных изображений. Формат определяется по содержимому, а не по
+//! заголовкам клиента; картинка декодируется с ограничением размеров и заново кодируется,
+//! поэтому в хранилище не попадают метаданные (EXIF с геометкой) и чужие данные в хвосте файла.
+
+use crate::domain::entities::MediaKind;
+use crate::domain::errors::AppError;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+
+/// Наибольшая сторона сохраняемого изображения, большие уменьшаются
+pub const MAX_IMAGE_SIDE: u32 = 2048;
+
+/// Наибольшая сторона исходника: защита от картинок, которые при декодировании занимают гигабайты
+const MAX_SOURCE_SIDE: u32 = 12_000;
+const MAX_DECODE_ALLOC: u64 = 512 * 1024 * 1024;
+
+const JPEG_QUALITY: u8 = 85;
+
+/// Готовое к сохранению изображение и его миниатюра в одном формате
+#[derive(Debug)]
+pub struct ProcessedImage {
+    pub bytes: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Размер миниатюры: аватар обрезается до квадрата, логотип и снимки галереи вписываются целиком
+fn thumbnail(image: &DynamicImage, kind: MediaKind) -> DynamicImage {
+    match kind {
+        MediaKind::Avatar => image.resize_to_fill(256, 256, FilterType::Triangle),
+        MediaKind::Logo => image.resize(256, 256, FilterType::Triangle),
+        MediaKind::Gallery => image.resize(480, 480, FilterType::Triangle),
+    }
+}
+
+/// Поддерживаемый формат по сигнатуре файла
+pub fn sniff_format(bytes: &[u8]) -> Option<ImageFormat> {
+    match image::guess_format(bytes) {
+        Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)) => Some(format),
+        _ => None,
+    }
+}
+
+/// Тип содержимого по расширению ключа файла
+pub fn content_type_for_key(key: &str) -> Option<&'static str> {
+    match key.rsplit_once('.')?.1 {
+        "jpg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        _ => None,
+    }
+}
+
+fn decode(bytes: &[u8], format: ImageFormat) -> Result<DynamicImage, AppError> {
+    let invalid = |e: image::ImageError| AppError::Validation(format!("Не удалось прочитать изображение: {}", e));
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_SIDE);
+    limits.max_image_height = Some(MAX_SOURCE_SIDE);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    // Снимки с телефона хранят поворот в EXIF; применяем его до того, как метаданные пропадут
+    let mut decoder = reader.into_decoder().map_err(invalid)?;
+    let orientation = decoder.orientation().map_err(invalid)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Картинки с прозрачностью сохраняются в PNG, остальные - в JPEG
+fn encode(image: &DynamicImage, with_alpha: bool) -> Result<Vec<u8>, AppError> {
+    let mut buffer = Vec::new();
+    let result = if with_alpha {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(image::codecs::png::PngEncoder::new(&mut buffer))
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, JPEG_QUALITY),
+        )
+    };
+    result.map_err(|e| AppError::Internal(format!("Ошибка кодирования изображения: {}", e)))?;
+    Ok(buffer)
+}
+
+/// Проверить, декодировать и перекодировать изображение, построить миниатюру.
+/// Работает с процессором, поэтому вызывается вне асинхронного исполнителя
+pub fn process_image(bytes: &[u8], kind: MediaKind) -> Result<ProcessedImage, AppError> {
+    let format = sniff_format(bytes).ok_or_else(|| {
+        AppError::UnsupportedMediaType("Поддерживаются изображения JPEG, PNG и WebP".to_string())
+    })?;
+    let mut image = decode(bytes, format)?;
+    if image.width() > MAX_IMAGE_SIDE || image.height() > MAX_IMAGE_SIDE {
+        image = image.resize(MAX_IMAGE_SIDE, MAX_IMAGE_SIDE, FilterType::Lanczos3);
+    }
+
+    let with_alpha = image.color().has_alpha();
+    let (content_type, extension) = if with_alpha {
+        ("image/png", "png")
+    } else {
+        ("image/jpeg", "jpg")
+    };
+    Ok(ProcessedImage {
+        bytes: encode(&image, with_alpha)?,
+        thumbnail: encode(&thumbnail(&image, kind), with_alpha)?,
+        content_type,
+        extension,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(width, height, Rgba([10, 120, 200, 128]));
+        let mut buffer = Vec::new();
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+            .unwrap();
+        buffer
+    }
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_pixel(width, height, Rgb([200, 80, 40]));
+        let mut buffer = Vec::new();
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Jpeg)
+            .unwrap();
+        buffer
+    }
+
+    #[test]
+    fn format_is_sniffed_from_content() {
+        assert_eq!(sniff_format(&png(2, 2)), Some(ImageFormat::Png));
+        assert_eq!(sniff_format(&jpeg(2, 2)), Some(ImageFormat::Jpeg));
+        assert_eq!(sniff_format(b"GIF89a\x01\x00\x01\x00"), None);
+        assert_eq!(sniff_format(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), None);
+        assert_eq!(sniff_format(b""), None);
+    }
+
+    #[test]
+    fn large_image_is_downscaled_and_keeps_alpha() {
+        let processed = process_image(&png(4096, 512), MediaKind::Gallery).unwrap();
+        assert_eq!((processed.width, processed.height), (2048, 256));
+        assert_eq!(processed.content_type, "image/png");
+        let thumbnail = image::load_from_memory(&processed.thumbnail).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (480, 60));
+        assert!(thumbnail.color().has_alpha());
+    }
+
+    #[test]
+    fn avatar_thumbnail_is_square_jpeg() {
+        let processed = process_image(&jpeg(300, 200), MediaKind::Avatar).unwrap();
+        assert_eq!((processed.width, processed.height), (300, 200));
+        assert_eq!(processed.extension, "jpg");
+        assert_eq!(sniff_format(&processed.bytes), Some(ImageFormat::Jpeg));
+        let thumbnail = image::load_from_memory(&processed.thumbnail).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (256, 256));
+    }
+
+    #[test]
+    fn truncated_or_foreign_files_are_rejected() {
+        assert!(matches!(
+            process_image(b"%PDF-1.7", MediaKind::Logo),
+            Err(AppError::UnsupportedMediaType(_))
+        ));
+        let mut broken = png(64, 64);
+        broken.truncate(40);
+        assert!(matches!(process_image(&broken, MediaKind::Logo), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn content_type_follows_stored_extension() {
+        assert_eq!(content_type_for_key("avatars/1/2.jpg"), Some("image/jpeg"));
+        assert_eq!(content_type_for_key("avatars/1/2_thumb.png"), Some("image/png"));
+        assert_eq!(content_type_for_key("avatars/1/2.svg"), None);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::application::media_processing::{content_type_for_key, process_image};
+use crate::domain::entities::{CompanyMedia, MediaAsset, MediaImage, MediaKind, NewMedia};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{CompanyRepository, MediaRepository, MediaService, MediaStorage};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Наибольший размер загружаемого файла по умолчанию
+pub const DEFAULT_MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// Сколько снимков может быть в галерее компании
+const MAX_GALLERY_IMAGES: usize = 30;
+
+pub struct MediaServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    media_repository: Arc<dyn MediaRepository + Send + Sync>,
+    storage: Arc<dyn MediaStorage + Send + Sync>,
+    max_upload_bytes: usize,
+}
+
+impl MediaServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        media_repository: Arc<dyn MediaRepository + Send + Sync>,
+        storage: Arc<dyn MediaStorage + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            media_repository,
+            storage,
+            max_upload_bytes: DEFAULT_MAX_UPLOAD_BYTES,
+        }
+    }
+
+    pub fn with_max_upload_bytes(mut self, max_upload_bytes: usize) -> Self {
+        self.max_upload_bytes = max_upload_bytes;
+        self
+    }
+
+    /// Загружать изображения компании могут только ее руководители
+    async fn require_manager(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => Ok(()),
+            _ => Err(AppError::Forbidden(
+                "Недостаточно прав для управления компанией".to_string(),
+            )),
+        }
+    }
+
+    fn to_image(&self, asset: &MediaAsset) -> MediaImage {
+        MediaImage {
+            id: asset.id,
+            url: self.storage.url(&asset.storage_key),
+            thumbnail_url: self.storage.url(&asset.thumbnail_key),
+            content_type: asset.content_type.clone(),
+            width: asset.width,
+            height: asset.height,
+        }
+    }
+
+    /// Файлы удаленных записей убираем без отката: осиротевший файл безвреден,
+    /// а ошибка хранилища не должна отменять уже сохраненное изменение
+    async fn remove_files(&self, assets: &[MediaAsset]) {
+        for asset in assets {
+            for key in [&asset.storage_key, &asset.thumbnail_key] {
+                if let Err(e) = self.storage.delete(key).await {
+                    eprintln!("Не удалось удалить файл {}: {}", key, e);
+                }
+            }
+        }
+    }
+
+    /// Обработать файл, записать оригинал с миниатюрой в хранилище и сохранить запись
+    async fn store(
+        &self,
+        kind: MediaKind,
+        uploaded_by: Uuid,
+        owner_id: Uuid,
+        bytes: Vec<u8>,
+    ) -> Result<MediaImage, AppError> {
+        if bytes.is_empty() {
+            return Err(AppError::Validation("Файл пуст".to_string()));
+        }
+        if bytes.len() > self.max_upload_bytes {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Файл больше {} байт",
+                self.max_upload_bytes
+            )));
+        }
+
+        // Декодирование занимает процессор на сотни миллисекунд, поэтому не в потоке запросов
+        let processed = tokio::task::spawn_blocking(move || process_image(&bytes, kind))
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка обработки изображения: {}", e)))??;
+
+        let id = Uuid::new_v4();
+        let prefix = match kind {
+            MediaKind::Avatar => format!("avatars/{}", owner_id),
+            MediaKind::Logo => format!("companies/{}/logo", owner_id),
+            MediaKind::Gallery => format!("companies/{}/gallery", owner_id),
+        };
+        let media = NewMedia {
+            id,
+            kind,
+            user_id: (kind == MediaKind::Avatar).then_some(owner_id),
+            company_id: (kind != MediaKind::Avatar).then_some(owner_id),
+            uploaded_by,
+            storage_key: format!("{}/{}.{}", prefix, id, processed.extension),
+            thumbnail_key: format!("{}/{}_thumb.{}", prefix, id, processed.extension),
+            content_type: processed.content_type.to_string(),
+            width: processed.width as i32,
+            height: processed.height as i32,
+            size_bytes: processed.bytes.len() as i64,
+        };
+
+        self.storage
+            .put(&media.storage_key, processed.content_type, processed.bytes)
+            .await?;
+        self.storage
+            .put(&media.thumbnail_key, processed.content_type, processed.thumbnail)
+            .await?;
+
+        let (asset, replaced) = match self.media_repository.save_media(&media).await {
+            Ok(saved) => saved,
+            Err(e) => {
+                for key in [&media.storage_key, &media.thumbnail_key] {
+                    let _ = self.storage.delete(key).await;
+                }
+                return Err(e);
+            }
+        };
+        self.remove_files(&replaced).await;
+        Ok(self.to_image(&asset))
+    }
+
+    async fn delete(&self, asset: MediaAsset) -> Result<(), AppError> {
+        if let Some(deleted) = self.media_repository.delete_media(asset.id).await? {
+            self.remove_files(&[deleted]).await;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MediaService for MediaServiceImpl {
+    fn max_upload_bytes(&self) -> usize {
+        self.max_upload_bytes
+    }
+
+    async fn upload_avatar(&self, user_id: Uuid, bytes: Vec<u8>) -> Result<MediaImage, AppError> {
+        self.store(MediaKind::Avatar, user_id, user_id, bytes).await
+    }
+
+    async fn delete_avatar(&self, user_id: Uuid) -> Result<(), AppError> {
+        match self.media_repository.find_user_avatar(user_id).await? {
+            Some(avatar) => self.delete(avatar).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn user_avatar(&self, user_id: Uuid) -> Result<Option<MediaImage>, AppError> {
+        let avatar = self.media_repository.find_user_avatar(user_id).await?;
+        Ok(avatar.map(|avatar| self.to_image(&avatar)))
+    }
+
+    async fn upload_logo(&self, user_id: Uuid, company_id: Uuid, bytes: Vec<u8>) -> Result<MediaImage, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.store(MediaKind::Logo, user_id, company_id, bytes).await
+    }
+
+    async fn delete_logo(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        self.require_manager(user_id, company_id).await?;
+        let media = self.media_repository.list_company_media(company_id).await?;
+        match media.into_iter().find(|asset| asset.kind == MediaKind::Logo) {
+            Some(logo) => self.delete(logo).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn add_gallery_image(&self, user_id: Uuid, company_id: Uuid, bytes: Vec<u8>) -> Result<MediaImage, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        let media = self.media_repository.list_company_media(company_id).await?;
+        if media.iter().filter(|asset| asset.kind == MediaKind::Gallery).count() >= MAX_GALLERY_IMAGES {
+            return Err(AppError::Conflict(format!(
+                "В галерее не может быть больше {} снимков",
+                MAX_GALLERY_IMAGES
+            )));
+        }
+        self.store(MediaKind::Gallery, user_id, company_id, bytes).await
+    }
+
+    async fn delete_gallery_image(&self, user_id: Uuid, company_id: Uuid, media_id: Uuid) -> Result<(), AppError> {
+        self.require_manager(user_id, company_id).await?;
+        let media = self.media_repository.list_company_media(company_id).await?;
+        match media
+            .into_iter()
+            .find(|asset| asset.id == media_id && asset.kind == MediaKind::Gallery)
+        {
+            Some(image) => self.delete(image).await,
+            None => Err(AppError::NotFound("Снимок не найден".to_string())),
+        }
+    }
+
+    async fn company_media(&self, company_id: Uuid) -> Result<CompanyMedia, AppError> {
+        let mut media = CompanyMedia::default();
+        for asset in self.media_repository.list_company_media(company_id).await? {
+            match asset.kind {
+                MediaKind::Logo => media.logo = Some(self.to_image(&asset)),
+                MediaKind::Gallery => media.gallery.push(self.to_image(&asset)),
+                MediaKind::Avatar => {}
+            }
+        }
+        Ok(media)
+    }
+
+    async fn read_file(&self, key: &str) -> Result<Option<(&'static str, Vec<u8>)>, AppError> {
+        // Раздаем только то, что сами записали: перекодированные JPEG и PNG
+        let Some(content_type) = content_type_for_key(key) else {
+            return Ok(None);
+        };
+        Ok(self.storage.get(key).await?.map(|bytes| (content_type, bytes)))
+    }
+}
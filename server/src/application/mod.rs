@@ -1 +1,39 @@
+pub mod analytics_service;
+pub mod booking_context;
+pub mod booking_lifecycle;
+pub mod booking_series_service;
+pub mod booking_service;
+pub mod bookmark_service;
+pub mod calendar_service;
+pub mod catalog_service;
+pub mod checkin_service;
+pub mod checkin_token;
+pub mod customer_restriction_service;
+pub mod customer_service;
+pub mod group_session_service;
+pub mod hmac_signature;
+pub mod hold_service;
+pub mod hold_sweeper;
+pub mod icalendar;
+pub mod job_admin_service;
+pub mod media_processing;
+pub mod media_service;
+pub mod notification_dispatcher;
+pub mod notification_templates;
+pub mod payment_service;
+pub mod pricing;
+pub mod promo_code_service;
+pub mod qr_code;
+pub mod queue_service;
+pub mod recurrence;
+pub mod reminder_scheduler;
+pub mod reminder_service;
+pub mod review_service;
+pub mod search_service;
 pub mod services;
+pub mod slot_engine;
+pub mod slot_service;
+pub mod waitlist_service;
+pub mod webhook_service;
+pub mod webhook_signature;
+pub mod webhook_target;
@@ -0,0 +1,141 @@
+use crate::application::notification_templates::render;
+use crate::domain::entities::{NotificationKind, OutboxMessage, OutgoingMessage};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, NotificationOutbox, Notifier};
+use chrono::Duration;
+use std::sync::Arc;
+
+/// Сколько раз пытаться доставить сообщение, прежде чем пометить его failed
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 8;
+
+/// Сообщений за один проход
+const BATCH_SIZE: i64 = 50;
+
+/// Пауза перед повтором: 30 секунд, удваивается с каждой попыткой, но не больше часа
+pub fn retry_delay(attempt: i32) -> Duration {
+    let exponent = (attempt - 1).clamp(0, 7) as u32;
+    Duration::seconds(30 * 2_i64.pow(exponent)).min(Duration::hours(1))
+}
+
+/// Фоновая задача, доставляющая сообщения из outbox во все подключенные каналы
+pub struct NotificationDispatcher {
+    outbox: Arc<dyn NotificationOutbox + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+    channels: Vec<Arc<dyn Notifier + Send + Sync>>,
+    max_attempts: i32,
+    lease: Duration,
+}
+
+impl NotificationDispatcher {
+    pub fn new(outbox: Arc<dyn NotificationOutbox + Send + Sync>, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        Self {
+            outbox,
+            clock,
+            channels: Vec::new(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            lease: Duration::minutes(5),
+        }
+    }
+
+    pub fn with_channel(mut self, channel: Arc<dyn Notifier + Send + Sync>) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: i32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Один проход: захватить готовые сообщения и разослать; возвращает число доставленных
+    pub async fn run_once(&self) -> Result<usize, AppError> {
+        let now = self.clock.now();
+        let messages = self.outbox.claim_due(now, now + self.lease, BATCH_SIZE).await?;
+        let mut sent = 0;
+        for message in messages {
+            if self.deliver(message).await? {
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Разослать сообщение по каналам, в которые оно еще не доставлено.
+    /// Ошибка одного канала не мешает остальным, повтор затронет только неудавшиеся
+    async fn deliver(&self, message: OutboxMessage) -> Result<bool, AppError> {
+        let Some(kind) = NotificationKind::parse(&message.kind) else {
+            let error = format!("Неизвестный вид уведомления: {}", message.kind);
+            self.outbox.mark_failed(message.id, &message.delivered_channels, &error).await?;
+            return Ok(false);
+        };
+        let Some(recipient) = self.outbox.find_recipient(message.user_id).await? else {
+            let error = "Получатель не найден".to_string();
+            self.outbox.mark_failed(message.id, &message.delivered_channels, &error).await?;
+            return Ok(false);
+        };
+
+        let rendered = render(kind, &recipient.locale, &recipient.name, &message.payload);
+        let outgoing = OutgoingMessage {
+            id: message.id,
+            kind,
+            recipient,
+            subject: rendered.subject,
+            body: rendered.body,
+            payload: message.payload,
+        };
+
+        let mut delivered = message.delivered_channels;
+        let mut errors = Vec::new();
+        for channel in &self.channels {
+            let name = channel.channel();
+            if delivered.iter().any(|done| done == name) {
+                continue;
+            }
+            match channel.send(&outgoing).await {
+                Ok(()) => delivered.push(name.to_string()),
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
+        }
+
+        if errors.is_empty() {
+            self.outbox.mark_sent(outgoing.id, &delivered).await?;
+            return Ok(true);
+        }
+
+        let error = errors.join("; ");
+        if message.attempts >= self.max_attempts {
+            self.outbox.mark_failed(outgoing.id, &delivered, &error).await?;
+        } else {
+            let next_attempt_at = self.clock.now() + retry_delay(message.attempts);
+            self.outbox.mark_retry(outgoing.id, &delivered, &error, next_attempt_at).await?;
+        }
+        Ok(false)
+    }
+
+    /// Запустить проходы с заданным интервалом в рантайме actix
+    pub fn spawn(self, every: std::time::Duration) {
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    eprintln!("{}", e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_an_hour() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(2), Duration::seconds(60));
+        assert_eq!(retry_delay(4), Duration::seconds(240));
+        assert_eq!(retry_delay(8), Duration::hours(1));
+        assert_eq!(retry_delay(20), Duration::hours(1));
+    }
+}
@@ -0,0 +1,184 @@
+//! Тексты уведомлений по виду и языку получателя. Шаблоны подставляют значения из
+//! `payload` сообщения outbox: `{service}`, `{company}`, `{staff}`, `{location}`,
+//! `{starts_at}`, `{previous_starts_at}`, `{expires_at}`, `{reason}` и имя получателя `{name}`.
+//! Время выводится в часовом поясе компании из `payload.timezone`.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde_json::Value;
+
+use crate::domain::entities::NotificationKind;
+
+pub const DEFAULT_LOCALE: &str = "ru";
+
+pub const SUPPORTED_LOCALES: [&str; 2] = ["ru", "en"];
+
+pub fn is_supported_locale(locale: &str) -> bool {
+    SUPPORTED_LOCALES.contains(&locale)
+}
+
+/// Тема и текст уведомления
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedNotification {
+    pub subject: String,
+    pub body: String,
+}
+
+struct Template {
+    subject: &'static str,
+    body: &'static str,
+}
+
+fn template(kind: NotificationKind, locale: &str) -> Template {
+    match (kind, locale) {
+        (NotificationKind::BookingConfirmed, "en") => Template {
+            subject: "Booking confirmed: {service}",
+            body: "{name}, your booking at {company} is confirmed.\n\
+                   {service}, {starts_at}\nSpecialist: {staff}\nAddress: {location}",
+        },
+        (NotificationKind::BookingConfirmed, _) => Template {
+            subject: "Запись подтверждена: {service}",
+            body: "{name}, ваша запись в «{company}» подтверждена.\n\
+                   {service}, {starts_at}\nСпециалист: {staff}\nАдрес: {location}",
+        },
+        (NotificationKind::BookingCancelled, "en") => Template {
+            subject: "Booking cancelled: {service}",
+            body: "{name}, your booking at {company} on {starts_at} has been cancelled.{reason}",
+        },
+        (NotificationKind::BookingCancelled, _) => Template {
+            subject: "Запись отменена: {service}",
+            body: "{name}, запись в «{company}» на {starts_at} отменена.{reason}",
+        },
+        (NotificationKind::BookingRescheduled, "en") => Template {
+            subject: "Booking rescheduled: {service}",
+            body: "{name}, your booking at {company} has been moved from {previous_starts_at} to {starts_at}.\n\
+                   Specialist: {staff}\nAddress: {location}",
+        },
+        (NotificationKind::BookingRescheduled, _) => Template {
+            subject: "Запись перенесена: {service}",
+            body: "{name}, запись в «{company}» перенесена с {previous_starts_at} на {starts_at}.\n\
+                   Специалист: {staff}\nАдрес: {location}",
+        },
+        (NotificationKind::BookingReminder, "en") => Template {
+            subject: "Reminder: {service} on {starts_at}",
+            body: "{name}, this is a reminder of your booking at {company}.\n\
+                   {service}, {starts_at}\nSpecialist: {staff}\nAddress: {location}",
+        },
+        (NotificationKind::BookingReminder, _) => Template {
+            subject: "Напоминание: {service} {starts_at}",
+            body: "{name}, напоминаем о записи в «{company}».\n\
+                   {service}, {starts_at}\nСпециалист: {staff}\nАдрес: {location}",
+        },
+        (NotificationKind::WaitlistOffer, "en") => Template {
+            subject: "A slot is available: {service}",
+            body: "{name}, a slot at {company} is available on {starts_at}. \
+                   Confirm the booking before {expires_at}, otherwise it will be offered to the next person in line.",
+        },
+        (NotificationKind::WaitlistOffer, _) => Template {
+            subject: "Освободилось время: {service}",
+            body: "{name}, в «{company}» освободилось время {starts_at}. \
+                   Подтвердите запись до {expires_at}, иначе слот предложат следующему в очереди.",
+        },
+    }
+}
+
+/// Момент из `payload` в местном времени компании в формате языка получателя
+fn format_time(payload: &Value, field: &str, timezone: Tz, locale: &str) -> String {
+    let Some(instant) = payload[field]
+        .as_str()
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+    else {
+        return String::new();
+    };
+    let local = instant.with_timezone(&Utc).with_timezone(&timezone);
+    match locale {
+        "en" => local.format("%b %-d, %Y %H:%M").to_string(),
+        _ => local.format("%d.%m.%Y %H:%M").to_string(),
+    }
+}
+
+/// Текст уведомления; неизвестный язык заменяется языком по умолчанию
+pub fn render(kind: NotificationKind, locale: &str, recipient_name: &str, payload: &Value) -> RenderedNotification {
+    let locale = if is_supported_locale(locale) { locale } else { DEFAULT_LOCALE };
+    let timezone: Tz = payload["timezone"].as_str().and_then(|tz| tz.parse().ok()).unwrap_or(Tz::UTC);
+    let text = |field: &str| payload[field].as_str().unwrap_or_default().to_string();
+    let reason = match payload["reason"].as_str() {
+        Some(reason) if locale == "en" => format!("\nReason: {}", reason),
+        Some(reason) => format!("\nПричина: {}", reason),
+        None => String::new(),
+    };
+
+    let values = [
+        ("{name}", recipient_name.to_string()),
+        ("{service}", text("service")),
+        ("{company}", text("company")),
+        ("{staff}", text("staff")),
+        ("{location}", text("location")),
+        ("{starts_at}", format_time(payload, "starts_at", timezone, locale)),
+        ("{previous_starts_at}", format_time(payload, "previous_starts_at", timezone, locale)),
+        ("{expires_at}", format_time(payload, "expires_at", timezone, locale)),
+        ("{reason}", reason),
+    ];
+    let fill = |template: &str| {
+        values
+            .iter()
+            .fold(template.to_string(), |text, (key, value)| text.replace(key, value))
+    };
+
+    let template = template(kind, locale);
+    RenderedNotification {
+        subject: fill(template.subject),
+        body: fill(template.body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn payload() -> Value {
+        json!({
+            "company": "Клиника",
+            "service": "Консультация",
+            "staff": "Доктор",
+            "location": "Главный корпус, Тверская 1",
+            "starts_at": "2025-06-02T06:00:00+00:00",
+            "previous_starts_at": "2025-06-01T07:30:00+00:00",
+            "timezone": "Europe/Moscow",
+        })
+    }
+
+    #[test]
+    fn booking_confirmed_is_rendered_in_company_time() {
+        let rendered = render(NotificationKind::BookingConfirmed, "ru", "Анна", &payload());
+
+        assert_eq!(rendered.subject, "Запись подтверждена: Консультация");
+        assert_eq!(
+            rendered.body,
+            "Анна, ваша запись в «Клиника» подтверждена.\nКонсультация, 02.06.2025 09:00\n\
+             Специалист: Доктор\nАдрес: Главный корпус, Тверская 1"
+        );
+    }
+
+    #[test]
+    fn english_template_and_fallback_for_unknown_locale() {
+        let rendered = render(NotificationKind::BookingRescheduled, "en", "Anna", &payload());
+        assert_eq!(rendered.subject, "Booking rescheduled: Консультация");
+        assert!(rendered.body.contains("moved from Jun 1, 2025 10:30 to Jun 2, 2025 09:00"));
+
+        let fallback = render(NotificationKind::BookingRescheduled, "de", "Anna", &payload());
+        assert_eq!(fallback.subject, "Запись перенесена: Консультация");
+    }
+
+    #[test]
+    fn cancellation_reason_is_optional() {
+        let mut data = payload();
+        let without = render(NotificationKind::BookingCancelled, "ru", "Анна", &data);
+        assert!(without.body.ends_with("отменена."));
+
+        data["reason"] = json!("Врач заболел");
+        let with = render(NotificationKind::BookingCancelled, "ru", "Анна", &data);
+        assert!(with.body.ends_with("отменена.\nПричина: Врач заболел"));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::application::booking_context::BookingContext;
+use crate::application::booking_lifecycle::deposit_refund;
+use crate::application::waitlist_service::release_to_waitlist;
+use crate::domain::entities::{
+    Booking, BookingStatus, CustomerRestriction, Payment, PaymentOutcome, PaymentStatus, ProviderPaymentRequest,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{
+    BookingRepository, Clock, CompanyRepository, PaymentProvider, PaymentRepository, PaymentService, WaitlistService,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Сколько минут запись на услугу с депозитом ждет оплаты
+pub const DEFAULT_DEPOSIT_TTL_MINUTES: i64 = 30;
+
+/// Рассчитаться по депозиту отмененной записи: неоплаченный закрыть, оплаченный вернуть
+/// по правилам компании. Ошибка не отменяет саму отмену, поэтому только пишется в журнал
+pub(crate) async fn settle_deposit(
+    payment_repository: &(dyn PaymentRepository + Send + Sync),
+    booking_repository: &(dyn BookingRepository + Send + Sync),
+    booking: &Booking,
+    now: DateTime<Utc>,
+) {
+    if let Err(e) = try_settle_deposit(payment_repository, booking_repository, booking, now).await {
+        eprintln!("Ошибка расчета по депозиту записи {}: {}", booking.id, e);
+    }
+}
+
+async fn try_settle_deposit(
+    repository: &(dyn PaymentRepository + Send + Sync),
+    booking_repository: &(dyn BookingRepository + Send + Sync),
+    booking: &Booking,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let Some(payment) = repository.find_booking_payment(booking.id).await? else {
+        return Ok(());
+    };
+
+    match payment.status {
+        PaymentStatus::Pending | PaymentStatus::Failed => {
+            repository
+                .update_status(
+                    payment.id,
+                    &[PaymentStatus::Pending, PaymentStatus::Failed],
+                    PaymentStatus::Cancelled,
+                    now,
+                )
+                .await?;
+        }
+        PaymentStatus::Succeeded => {
+            let policy = booking_repository.get_policy(booking.company_id).await?;
+            let amount = deposit_refund(&policy, booking.status, now, booking.starts_at, payment.amount);
+            if amount > 0 {
+                repository.request_refund(payment.id, amount).await?;
+            }
+        }
+        PaymentStatus::Cancelled | PaymentStatus::RefundPending | PaymentStatus::Refunded => {}
+    }
+    Ok(())
+}
+
+pub struct PaymentServiceImpl {
+    context: BookingContext,
+    payment_repository: Arc<dyn PaymentRepository + Send + Sync>,
+    payment_provider: Arc<dyn PaymentProvider + Send + Sync>,
+    /// Время записи, отмененной без оплаты, предлагается листу ожидания
+    waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+    deposit_ttl: Duration,
+}
+
+impl PaymentServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        payment_repository: Arc<dyn PaymentRepository + Send + Sync>,
+        payment_provider: Arc<dyn PaymentProvider + Send + Sync>,
+        waitlist_service: Arc<dyn WaitlistService + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            context: BookingContext::new(company_repository, booking_repository, clock),
+            payment_repository,
+            payment_provider,
+            waitlist_service,
+            deposit_ttl: Duration::minutes(DEFAULT_DEPOSIT_TTL_MINUTES),
+        }
+    }
+
+    pub fn with_deposit_ttl(mut self, deposit_ttl: Duration) -> Self {
+        self.deposit_ttl = deposit_ttl;
+        self
+    }
+
+    /// Депозит оплачен: подтвердить ждущую оплаты запись или вернуть деньги,
+    /// если запись успели отменить
+    async fn apply_paid_deposit(&self, payment: &Payment) -> Result<(), AppError> {
+        let booking = self.context
+            .booking_repository
+            .find_booking(payment.booking_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Запись не найдена".to_string()))?;
+
+        if booking.status == BookingStatus::Pending {
+            // Клиента с ограничением после оплаты по-прежнему подтверждает компания
+            let (restriction, _) = self.context.load_restriction(booking.company_id, booking.customer_id).await?;
+            if restriction.restriction == CustomerRestriction::None {
+                self.context
+                    .booking_repository
+                    .change_status(
+                        booking.id,
+                        BookingStatus::Pending,
+                        BookingStatus::Confirmed,
+                        booking.customer_id,
+                        Some("Депозит оплачен"),
+                    )
+                    .await?;
+            }
+        } else if !booking.status.is_active() {
+            self.payment_repository.request_refund(payment.id, payment.amount).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PaymentService for PaymentServiceImpl {
+    async fn get_payment(&self, user_id: Uuid, booking_id: Uuid) -> Result<Payment, AppError> {
+        let (booking, _) = self.context.load_visible_booking(user_id, booking_id).await?;
+        let mut payment = self
+            .payment_repository
+            .find_booking_payment(booking.id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("У записи нет депозита".to_string()))?;
+
+        if booking.customer_id != user_id {
+            payment.client_secret = None;
+        }
+        Ok(payment)
+    }
+
+    async fn start_payment(&self, customer_id: Uuid, booking_id: Uuid) -> Result<Payment, AppError> {
+        let booking = match self.context.booking_repository.find_booking(booking_id).await? {
+            Some(booking) if booking.customer_id == customer_id => booking,
+            _ => return Err(AppError::NotFound("Запись не найдена".to_string())),
+        };
+        let provider = &self.payment_provider;
+        let payment = self
+            .payment_repository
+            .find_booking_payment(booking.id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("У записи нет депозита".to_string()))?;
+
+        match payment.status {
+            // Повторный запрос возвращает уже начатую оплату
+            PaymentStatus::Pending
+                if payment.provider_payment_id.is_some() && payment.provider.as_deref() == Some(provider.name()) =>
+            {
+                return Ok(payment);
+            }
+            PaymentStatus::Pending | PaymentStatus::Failed => {}
+            PaymentStatus::Succeeded | PaymentStatus::RefundPending | PaymentStatus::Refunded => {
+                return Err(AppError::Conflict("Депозит уже оплачен".to_string()));
+            }
+            PaymentStatus::Cancelled => return Err(AppError::Conflict("Запись отменена".to_string())),
+        }
+        if booking.status != BookingStatus::Pending {
+            return Err(AppError::Conflict("Запись не ждет оплаты".to_string()));
+        }
+
+        let service = self.context.require_service(booking.company_id, booking.service_id).await?;
+        // Ключ меняется с каждой попыткой, одновременные запросы одной попытки получают один платеж
+        let attempt = provider
+            .create_payment(&ProviderPaymentRequest {
+                payment_id: payment.id,
+                booking_id: booking.id,
+                amount: payment.amount,
+                currency: payment.currency.clone(),
+                description: format!("Депозит: {}", service.name),
+                idempotency_key: format!("{}-{}", payment.id, payment.updated_at.timestamp_micros()),
+            })
+            .await?;
+
+        self.payment_repository
+            .start_attempt(payment.id, provider.name(), &attempt)
+            .await?
+            .ok_or_else(|| AppError::Conflict("Статус депозита уже изменился".to_string()))
+    }
+
+    async fn handle_webhook(&self, provider: &str, headers: &HashMap<String, String>, body: &str) -> Result<(), AppError> {
+        let repository = &self.payment_repository;
+        let payment_provider = &self.payment_provider;
+        if payment_provider.name() != provider {
+            return Err(AppError::NotFound("Платежная система не найдена".to_string()));
+        }
+        let now = self.context.clock.now();
+        let Some(event) = payment_provider.parse_webhook(headers, body, now)? else {
+            return Ok(());
+        };
+        // Уведомления о прежних попытках и чужих платежах пропускаем
+        let Some(payment) = repository
+            .find_provider_payment(provider, &event.provider_payment_id)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        match event.outcome {
+            PaymentOutcome::Succeeded => {
+                // Оплата могла прийти уже после отмены записи - тогда ниже деньги вернутся
+                let paid = repository
+                    .update_status(
+                        payment.id,
+                        &[PaymentStatus::Pending, PaymentStatus::Failed, PaymentStatus::Cancelled],
+                        PaymentStatus::Succeeded,
+                        now,
+                    )
+                    .await?;
+                // Повтор уведомления доводит до конца обработку, прерванную ошибкой
+                let paid = match paid {
+                    Some(paid) => paid,
+                    None if payment.status == PaymentStatus::Succeeded => payment,
+                    None => return Ok(()),
+                };
+                self.apply_paid_deposit(&paid).await
+            }
+            PaymentOutcome::Failed => {
+                repository
+                    .update_status(payment.id, &[PaymentStatus::Pending], PaymentStatus::Failed, now)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn expire_unpaid_deposits(&self) -> Result<usize, AppError> {
+        let mut expired = 0;
+        for payment in self.payment_repository.list_unpaid(self.context.clock.now() - self.deposit_ttl).await? {
+            let cancelled = match self.context
+                .booking_repository
+                .change_status(
+                    payment.booking_id,
+                    BookingStatus::Pending,
+                    BookingStatus::CancelledByCustomer,
+                    payment.customer_id,
+                    Some("Депозит не оплачен вовремя"),
+                )
+                .await
+            {
+                Ok(cancelled) => cancelled,
+                // Запись подтвердили или отменили после выборки; неоплаченный депозит ей больше
+                // не нужен, иначе он так и остался бы ждать оплаты
+                Err(AppError::Conflict(_)) => {
+                    self.payment_repository
+                        .update_status(
+                            payment.id,
+                            &[PaymentStatus::Pending, PaymentStatus::Failed],
+                            PaymentStatus::Cancelled,
+                            self.context.clock.now(),
+                        )
+                        .await?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            settle_deposit(
+                self.payment_repository.as_ref(),
+                self.context.booking_repository.as_ref(),
+                &cancelled,
+                self.context.clock.now(),
+            )
+            .await;
+            release_to_waitlist(self.waitlist_service.as_ref(), &cancelled).await;
+            expired += 1;
+        }
+        Ok(expired)
+    }
+}
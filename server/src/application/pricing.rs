@@ -0,0 +1,174 @@
+//! Цена записи и промокоды: нормализация кода, применимость промокода к записи и размер скидки.
+//! Функции чистые; лимиты использований проверяются отдельно, окончательно - в транзакции записи.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::entities::{DiscountType, PromoCode, Service};
+use crate::domain::errors::AppError;
+
+const MIN_CODE_LENGTH: usize = 3;
+const MAX_CODE_LENGTH: usize = 32;
+
+/// Код в верхнем регистре; допустимы латинские буквы, цифры, `-` и `_`
+pub fn normalize_code(code: &str) -> Result<String, AppError> {
+    let code = code.trim().to_ascii_uppercase();
+    let valid_chars = code
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid_chars || !(MIN_CODE_LENGTH..=MAX_CODE_LENGTH).contains(&code.len()) {
+        return Err(AppError::Validation(format!(
+            "Промокод должен состоять из {}–{} латинских букв, цифр, `-` или `_`",
+            MIN_CODE_LENGTH, MAX_CODE_LENGTH
+        )));
+    }
+    Ok(code)
+}
+
+/// Проверить, что промокод действует в момент `now` для услуги и филиала записи.
+/// Без филиала промокод с ограничением по филиалам не применить
+pub fn check_promo_applies(
+    promo: &PromoCode,
+    service: &Service,
+    location_id: Option<Uuid>,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    if !promo.is_active || promo.valid_until.is_some_and(|until| until <= now) {
+        return Err(AppError::Validation("Промокод больше не действует".to_string()));
+    }
+    if promo.valid_from.is_some_and(|from| from > now) {
+        return Err(AppError::Validation("Промокод еще не действует".to_string()));
+    }
+    if !promo.service_ids.is_empty() && !promo.service_ids.contains(&service.id) {
+        return Err(AppError::Validation("Промокод не действует для этой услуги".to_string()));
+    }
+    if !promo.location_ids.is_empty() {
+        match location_id {
+            Some(location_id) if promo.location_ids.contains(&location_id) => {}
+            Some(_) => return Err(AppError::Validation("Промокод не действует в этом филиале".to_string())),
+            None => {
+                return Err(AppError::Validation(
+                    "Промокод действует только в отдельных филиалах, укажите филиал".to_string(),
+                ))
+            }
+        }
+    }
+    if promo.currency.as_ref().is_some_and(|currency| *currency != service.currency) {
+        return Err(AppError::Validation("Валюта промокода не совпадает с валютой услуги".to_string()));
+    }
+    if service.price_amount <= 0 {
+        return Err(AppError::Validation("У услуги нет цены, промокод не применить".to_string()));
+    }
+    Ok(())
+}
+
+/// Скидка с цены `price`. Процент округляется вниз; депозит остается к оплате,
+/// поэтому скидка не опускает итог ниже депозита
+pub fn discount_amount(promo: &PromoCode, price: i64, deposit: Option<i64>) -> i64 {
+    let discount = match promo.discount_type {
+        DiscountType::Percent => price * promo.discount_value / 100,
+        DiscountType::Fixed => promo.discount_value,
+    };
+    discount.min(price - deposit.unwrap_or(0)).max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn service(price_amount: i64) -> Service {
+        Service {
+            id: Uuid::new_v4(),
+            company_id: Uuid::new_v4(),
+            name: "Консультация".to_string(),
+            description: None,
+            duration_minutes: 60,
+            buffer_before_minutes: 0,
+            buffer_after_minutes: 0,
+            slot_step_minutes: 30,
+            price_amount,
+            currency: "RUB".to_string(),
+            deposit_amount: None,
+            capacity: 1,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn promo(discount_type: DiscountType, discount_value: i64) -> PromoCode {
+        PromoCode {
+            id: Uuid::new_v4(),
+            company_id: Uuid::new_v4(),
+            code: "SPRING".to_string(),
+            discount_type,
+            discount_value,
+            currency: (discount_type == DiscountType::Fixed).then(|| "RUB".to_string()),
+            valid_from: None,
+            valid_until: None,
+            max_redemptions: None,
+            max_per_customer: None,
+            service_ids: Vec::new(),
+            location_ids: Vec::new(),
+            is_active: true,
+            redemption_count: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn codes_are_case_insensitive() {
+        assert_eq!(normalize_code("  spring-25 ").unwrap(), "SPRING-25");
+        assert!(normalize_code("ab").is_err());
+        assert!(normalize_code("весна").is_err());
+        assert!(normalize_code("SPRING 25").is_err());
+        assert!(normalize_code(&"A".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn discount_is_capped_by_price_and_deposit() {
+        assert_eq!(discount_amount(&promo(DiscountType::Percent, 15), 99_999, None), 14_999);
+        assert_eq!(discount_amount(&promo(DiscountType::Percent, 100), 50_000, None), 50_000);
+        assert_eq!(discount_amount(&promo(DiscountType::Fixed, 70_000), 50_000, None), 50_000);
+        assert_eq!(discount_amount(&promo(DiscountType::Fixed, 10_000), 50_000, Some(30_000)), 10_000);
+        assert_eq!(discount_amount(&promo(DiscountType::Percent, 50), 50_000, Some(30_000)), 20_000);
+    }
+
+    #[test]
+    fn promo_applies_within_window_service_and_location() {
+        let now = Utc::now();
+        let service = service(100_000);
+        let location_id = Uuid::new_v4();
+        let mut code = promo(DiscountType::Percent, 10);
+        assert!(check_promo_applies(&code, &service, None, now).is_ok());
+
+        code.valid_from = Some(now + Duration::hours(1));
+        assert!(check_promo_applies(&code, &service, None, now).is_err());
+        code.valid_from = Some(now - Duration::days(1));
+        code.valid_until = Some(now);
+        assert!(check_promo_applies(&code, &service, None, now).is_err());
+        code.valid_until = Some(now + Duration::days(1));
+        assert!(check_promo_applies(&code, &service, None, now).is_ok());
+
+        code.service_ids = vec![Uuid::new_v4()];
+        assert!(check_promo_applies(&code, &service, None, now).is_err());
+        code.service_ids.push(service.id);
+        code.location_ids = vec![location_id];
+        assert!(check_promo_applies(&code, &service, None, now).is_err());
+        assert!(check_promo_applies(&code, &service, Some(Uuid::new_v4()), now).is_err());
+        assert!(check_promo_applies(&code, &service, Some(location_id), now).is_ok());
+
+        code.is_active = false;
+        assert!(check_promo_applies(&code, &service, Some(location_id), now).is_err());
+    }
+
+    #[test]
+    fn fixed_discount_needs_matching_currency_and_price() {
+        let now = Utc::now();
+        let mut usd = promo(DiscountType::Fixed, 500);
+        usd.currency = Some("USD".to_string());
+        assert!(check_promo_applies(&usd, &service(100_000), None, now).is_err());
+        assert!(check_promo_applies(&promo(DiscountType::Fixed, 500), &service(100_000), None, now).is_ok());
+        assert!(check_promo_applies(&promo(DiscountType::Percent, 10), &service(0), None, now).is_err());
+    }
+}
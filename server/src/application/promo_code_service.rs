@@ -0,0 +1,248 @@
+use crate::application::pricing::{check_promo_applies, discount_amount, normalize_code};
+use crate::domain::entities::{
+    BookingQuote, BookingQuoteRequest, CreatePromoCodeRequest, DiscountType, NewPromoCode, NewPromoRedemption,
+    PromoCode, Service, UpdatePromoCodeRequest,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, CompanyRepository, PromoCodeRepository, PromoCodeService};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Цена записи на услугу и использование промокода, если он задан и действует.
+/// Лимиты проверяются здесь для понятного ответа, окончательно - при создании записи
+pub(crate) async fn price_booking(
+    promo_repository: &(dyn PromoCodeRepository + Send + Sync),
+    customer_id: Uuid,
+    service: &Service,
+    location_id: Option<Uuid>,
+    promo_code: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<(BookingQuote, Option<NewPromoRedemption>), AppError> {
+    let mut quote = BookingQuote {
+        service_id: service.id,
+        currency: service.currency.clone(),
+        price_amount: service.price_amount,
+        discount_amount: 0,
+        total_amount: service.price_amount,
+        deposit_amount: service.deposit_amount,
+        promo_code: None,
+    };
+    let Some(promo_code) = promo_code else {
+        return Ok((quote, None));
+    };
+
+    let promo = promo_repository
+        .find_promo_code_by_code(service.company_id, &normalize_code(promo_code)?)
+        .await?
+        .ok_or_else(|| AppError::Validation("Промокод не найден".to_string()))?;
+    check_promo_applies(&promo, service, location_id, now)?;
+    if promo.max_redemptions.is_some_and(|max| promo.redemption_count >= max) {
+        return Err(AppError::Conflict("Промокод больше недоступен".to_string()));
+    }
+    if let Some(max_per_customer) = promo.max_per_customer
+        && promo_repository.count_customer_redemptions(promo.id, customer_id).await? >= max_per_customer as i64
+    {
+        return Err(AppError::Conflict("Вы уже использовали этот промокод".to_string()));
+    }
+
+    let discount = discount_amount(&promo, service.price_amount, service.deposit_amount);
+    quote.discount_amount = discount;
+    quote.total_amount = service.price_amount - discount;
+    quote.promo_code = Some(promo.code);
+    let redemption = NewPromoRedemption {
+        promo_code_id: promo.id,
+        price_amount: service.price_amount,
+        discount_amount: discount,
+        currency: service.currency.clone(),
+        redeemed_at: now,
+    };
+    Ok((quote, Some(redemption)))
+}
+
+fn validate_limit(limit: Option<i32>) -> Result<(), AppError> {
+    if limit.is_some_and(|limit| limit < 1) {
+        return Err(AppError::Validation("Лимит использований должен быть не меньше 1".to_string()));
+    }
+    Ok(())
+}
+
+pub struct PromoCodeServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    promo_repository: Arc<dyn PromoCodeRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+impl PromoCodeServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        promo_repository: Arc<dyn PromoCodeRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            promo_repository,
+            clock,
+        }
+    }
+
+    /// Кампании настраивают только руководители компании
+    async fn require_manager(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => Ok(()),
+            _ => Err(AppError::Forbidden(
+                "Недостаточно прав для управления компанией".to_string(),
+            )),
+        }
+    }
+
+    /// Услуги и филиалы ограничений без повторов; все должны принадлежать компании
+    async fn validate_scope(&self, company_id: Uuid, data: &CreatePromoCodeRequest) -> Result<(Vec<Uuid>, Vec<Uuid>), AppError> {
+        let mut service_ids = data.service_ids.clone();
+        service_ids.sort();
+        service_ids.dedup();
+        for service_id in &service_ids {
+            if self.company_repository.find_service(company_id, *service_id).await?.is_none() {
+                return Err(AppError::Validation(format!("Услуга {} не найдена", service_id)));
+            }
+        }
+        let mut location_ids = data.location_ids.clone();
+        location_ids.sort();
+        location_ids.dedup();
+        for location_id in &location_ids {
+            if self.company_repository.find_location(company_id, *location_id).await?.is_none() {
+                return Err(AppError::Validation(format!("Филиал {} не найден", location_id)));
+            }
+        }
+        Ok((service_ids, location_ids))
+    }
+}
+
+#[async_trait]
+impl PromoCodeService for PromoCodeServiceImpl {
+    async fn create_promo_code(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreatePromoCodeRequest,
+    ) -> Result<PromoCode, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        let code = normalize_code(&data.code)?;
+        let currency = match data.discount_type {
+            DiscountType::Percent => {
+                if !(1..=100).contains(&data.discount_value) {
+                    return Err(AppError::Validation("Скидка в процентах должна быть от 1 до 100".to_string()));
+                }
+                if data.currency.is_some() {
+                    return Err(AppError::Validation("Валюта указывается только для фиксированной скидки".to_string()));
+                }
+                None
+            }
+            DiscountType::Fixed => {
+                if data.discount_value < 1 {
+                    return Err(AppError::Validation("Сумма скидки должна быть больше нуля".to_string()));
+                }
+                match data.currency.as_deref().map(str::trim) {
+                    Some(currency) if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic()) => {
+                        Some(currency.to_ascii_uppercase())
+                    }
+                    _ => {
+                        return Err(AppError::Validation(
+                            "Для фиксированной скидки укажите валюту из трех букв".to_string(),
+                        ))
+                    }
+                }
+            }
+        };
+        if let (Some(from), Some(until)) = (data.valid_from, data.valid_until)
+            && from >= until
+        {
+            return Err(AppError::Validation("Начало действия должно быть раньше окончания".to_string()));
+        }
+        validate_limit(data.max_redemptions)?;
+        validate_limit(data.max_per_customer)?;
+        let (service_ids, location_ids) = self.validate_scope(company_id, &data).await?;
+
+        self.promo_repository
+            .create_promo_code(&NewPromoCode {
+                company_id,
+                code,
+                discount_type: data.discount_type,
+                discount_value: data.discount_value,
+                currency,
+                valid_from: data.valid_from,
+                valid_until: data.valid_until,
+                max_redemptions: data.max_redemptions,
+                max_per_customer: data.max_per_customer,
+                service_ids,
+                location_ids,
+            })
+            .await
+    }
+
+    async fn list_promo_codes(&self, user_id: Uuid, company_id: Uuid) -> Result<Vec<PromoCode>, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.promo_repository.list_promo_codes(company_id).await
+    }
+
+    async fn update_promo_code(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        promo_code_id: Uuid,
+        data: UpdatePromoCodeRequest,
+    ) -> Result<PromoCode, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        let promo = self
+            .promo_repository
+            .find_promo_code(company_id, promo_code_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Промокод не найден".to_string()))?;
+        if let (Some(from), Some(until)) = (promo.valid_from, data.valid_until)
+            && from >= until
+        {
+            return Err(AppError::Validation("Начало действия должно быть раньше окончания".to_string()));
+        }
+        validate_limit(data.max_redemptions)?;
+        validate_limit(data.max_per_customer)?;
+        if data.max_redemptions.is_some_and(|max| max < promo.redemption_count) {
+            return Err(AppError::Validation(format!(
+                "Промокод уже использован {} раз, лимит не может быть меньше",
+                promo.redemption_count
+            )));
+        }
+
+        self.promo_repository
+            .update_promo_code(promo.id, &data)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Промокод не найден".to_string()))
+    }
+
+    async fn quote_booking(&self, customer_id: Uuid, data: BookingQuoteRequest) -> Result<BookingQuote, AppError> {
+        let service = self
+            .company_repository
+            .find_service(data.company_id, data.service_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Услуга не найдена".to_string()))?;
+        if let Some(location_id) = data.location_id
+            && self.company_repository.find_location(service.company_id, location_id).await?.is_none()
+        {
+            return Err(AppError::NotFound("Филиал не найден".to_string()));
+        }
+
+        let (quote, _) = price_booking(
+            self.promo_repository.as_ref(),
+            customer_id,
+            &service,
+            data.location_id,
+            data.promo_code.as_deref(),
+            self.clock.now(),
+        )
+        .await?;
+        Ok(quote)
+    }
+}
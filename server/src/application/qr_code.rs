@@ -0,0 +1,60 @@
+//! QR-коды картинкой: PNG в оттенках серого или SVG. Коррекция ошибок уровня M
+//! выдерживает блики и трещины на экране телефона.
+
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+use std::io::Cursor;
+
+use crate::domain::entities::{QrImage, QrImageFormat};
+use crate::domain::errors::AppError;
+
+/// Минимальная сторона картинки в пикселях, чтобы код уверенно читался сканером
+const MIN_SIZE: u32 = 256;
+
+pub fn render(data: &str, format: QrImageFormat) -> Result<QrImage, AppError> {
+    let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M)
+        .map_err(|e| AppError::Internal(format!("Ошибка построения QR-кода: {}", e)))?;
+    match format {
+        QrImageFormat::Png => {
+            let image = code.render::<Luma<u8>>().min_dimensions(MIN_SIZE, MIN_SIZE).build();
+            let mut body = Vec::new();
+            DynamicImage::ImageLuma8(image)
+                .write_to(&mut Cursor::new(&mut body), ImageFormat::Png)
+                .map_err(|e| AppError::Internal(format!("Ошибка кодирования QR-кода: {}", e)))?;
+            Ok(QrImage {
+                content_type: "image/png",
+                body,
+            })
+        }
+        QrImageFormat::Svg => {
+            let image = code.render::<svg::Color>().min_dimensions(MIN_SIZE, MIN_SIZE).build();
+            Ok(QrImage {
+                content_type: "image/svg+xml",
+                body: image.into_bytes(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_is_square_and_large_enough() {
+        let png = render("booking-token", QrImageFormat::Png).unwrap();
+        assert_eq!(png.content_type, "image/png");
+        let image = image::load_from_memory_with_format(&png.body, ImageFormat::Png).unwrap();
+        assert_eq!(image.width(), image.height());
+        assert!(image.width() >= MIN_SIZE);
+    }
+
+    #[test]
+    fn svg_is_an_svg_document() {
+        let svg = render("booking-token", QrImageFormat::Svg).unwrap();
+        assert_eq!(svg.content_type, "image/svg+xml");
+        let text = String::from_utf8(svg.body).unwrap();
+        assert!(text.contains("<svg"));
+    }
+}
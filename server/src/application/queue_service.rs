@@ -0,0 +1,324 @@
+use crate::application::slot_service::company_timezone;
+use crate::domain::entities::{
+    CallNextRequest, ChangeQueueTicketStatusRequest, CompanyRole, JoinQueueRequest, LocationQueue, LocationQueueQuery,
+    NewQueueTicket, QueueDayStats, QueueStatsQuery, QueueTicket, QueueTicketStatus, QueueTicketView,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, CompanyRepository, QueueEventBus, QueueRepository, QueueService};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures_util::future::ready;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// За сколько дней истории филиала считается средняя длительность обслуживания
+const HISTORY_DAYS: i64 = 30;
+
+/// Длительность обслуживания, пока у филиала нет истории
+const DEFAULT_SERVICE_MINUTES: f64 = 15.0;
+
+/// Максимальная длина имени клиента на талоне киоска
+const MAX_CUSTOMER_NAME_LENGTH: usize = 100;
+
+/// Максимальный период статистики в днях
+const MAX_STATS_DAYS: i64 = 366;
+
+/// Ожидание при `ahead` ожидающих впереди: каждый занимает среднее время обслуживания
+pub fn estimated_wait_minutes(ahead: i64, average_service_minutes: f64) -> i64 {
+    (ahead as f64 * average_service_minutes).ceil() as i64
+}
+
+async fn average_service_minutes(
+    queue_repository: &(dyn QueueRepository + Send + Sync),
+    location_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<f64, AppError> {
+    Ok(queue_repository
+        .average_service_minutes(location_id, now - Duration::days(HISTORY_DAYS))
+        .await?
+        .unwrap_or(DEFAULT_SERVICE_MINUTES))
+}
+
+/// Состояние талона: место и ожидание считаются только для ожидающих вызова
+async fn ticket_view(
+    queue_repository: &(dyn QueueRepository + Send + Sync),
+    ticket: QueueTicket,
+    now: DateTime<Utc>,
+) -> Result<QueueTicketView, AppError> {
+    let (position, estimated_wait_minutes) = if ticket.status == QueueTicketStatus::Waiting {
+        let ahead = queue_repository
+            .count_waiting_before(ticket.location_id, ticket.queue_date, ticket.number)
+            .await?;
+        let average = average_service_minutes(queue_repository, ticket.location_id, now).await?;
+        (Some(ahead + 1), Some(estimated_wait_minutes(ahead, average)))
+    } else {
+        (None, None)
+    };
+    Ok(QueueTicketView {
+        id: ticket.id,
+        location_id: ticket.location_id,
+        queue_date: ticket.queue_date,
+        number: ticket.number,
+        status: ticket.status,
+        position,
+        estimated_wait_minutes,
+        called_at: ticket.called_at,
+    })
+}
+
+pub struct QueueServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    queue_repository: Arc<dyn QueueRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+    event_bus: Option<Arc<dyn QueueEventBus + Send + Sync>>,
+}
+
+impl QueueServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        queue_repository: Arc<dyn QueueRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            queue_repository,
+            clock,
+            event_bus: None,
+        }
+    }
+
+    pub fn with_events(mut self, event_bus: Arc<dyn QueueEventBus + Send + Sync>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Сегодняшняя дата в часовом поясе компании: очередь каждого дня начинается заново
+    async fn today(&self, company_id: Uuid) -> Result<NaiveDate, AppError> {
+        let company = self
+            .company_repository
+            .find_company(company_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Компания не найдена".to_string()))?;
+        let timezone = company_timezone(&company)?;
+        Ok(self.clock.now().with_timezone(&timezone).date_naive())
+    }
+
+    /// Очередью управляют все участники компании: вызывают клиентов сотрудники на приеме
+    async fn require_member(&self, user_id: Uuid, company_id: Uuid) -> Result<CompanyRole, AppError> {
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+        self.company_repository
+            .get_member_role(company_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::Forbidden("Доступно только сотрудникам компании".to_string()))
+    }
+
+    async fn require_location(&self, company_id: Uuid, location_id: Uuid) -> Result<(), AppError> {
+        if self.company_repository.find_location(company_id, location_id).await?.is_none() {
+            return Err(AppError::NotFound("Филиал не найден".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn find_ticket(&self, ticket_id: Uuid) -> Result<QueueTicket, AppError> {
+        self.queue_repository
+            .find_ticket(ticket_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Талон не найден".to_string()))
+    }
+}
+
+#[async_trait]
+impl QueueService for QueueServiceImpl {
+    async fn join_queue(&self, user_id: Uuid, company_id: Uuid, data: JoinQueueRequest) -> Result<QueueTicketView, AppError> {
+        let queue_date = self.today(company_id).await?;
+        self.require_location(company_id, data.location_id).await?;
+        if let Some(service_id) = data.service_id
+            && self.company_repository.find_service(company_id, service_id).await?.is_none()
+        {
+            return Err(AppError::NotFound("Услуга не найдена".to_string()));
+        }
+
+        // Киоск работает под аккаунтом участника компании и выдает талоны по имени
+        let (customer_id, customer_name) = match data.customer_name.as_deref().map(str::trim) {
+            Some(name) => {
+                if name.is_empty() || name.chars().count() > MAX_CUSTOMER_NAME_LENGTH {
+                    return Err(AppError::Validation(format!(
+                        "Имя клиента должно быть от 1 до {} символов",
+                        MAX_CUSTOMER_NAME_LENGTH
+                    )));
+                }
+                if self.company_repository.get_member_role(company_id, user_id).await?.is_none() {
+                    return Err(AppError::Forbidden(
+                        "Талоны по имени выдают только сотрудники компании".to_string(),
+                    ));
+                }
+                (None, Some(name.to_string()))
+            }
+            None => (Some(user_id), None),
+        };
+
+        let now = self.clock.now();
+        let ticket = self
+            .queue_repository
+            .create_ticket(&NewQueueTicket {
+                company_id,
+                location_id: data.location_id,
+                service_id: data.service_id,
+                customer_id,
+                customer_name,
+                queue_date,
+                joined_at: now,
+            })
+            .await?;
+        ticket_view(self.queue_repository.as_ref(), ticket, now).await
+    }
+
+    async fn get_ticket(&self, ticket_id: Uuid) -> Result<QueueTicketView, AppError> {
+        let ticket = self.find_ticket(ticket_id).await?;
+        ticket_view(self.queue_repository.as_ref(), ticket, self.clock.now()).await
+    }
+
+    async fn ticket_updates(&self, ticket_id: Uuid) -> Result<BoxStream<'static, QueueTicketView>, AppError> {
+        let Some(event_bus) = &self.event_bus else {
+            return Err(AppError::Internal("Поток событий очереди не настроен".to_string()));
+        };
+        let ticket = self.find_ticket(ticket_id).await?;
+        // Подписка до чтения состояния, чтобы не потерять изменения между ними
+        let events = event_bus.subscribe(ticket.location_id);
+        let queue_date = ticket.queue_date;
+        let current = ticket_view(self.queue_repository.as_ref(), ticket, self.clock.now()).await?;
+
+        let queue_repository = self.queue_repository.clone();
+        let clock = self.clock.clone();
+        let updates = events
+            .filter(move |event| ready(event.queue_date == queue_date))
+            .then(move |_| {
+                let queue_repository = queue_repository.clone();
+                let now = clock.now();
+                async move {
+                    match queue_repository.find_ticket(ticket_id).await {
+                        Ok(Some(ticket)) => ticket_view(queue_repository.as_ref(), ticket, now).await.ok(),
+                        _ => None,
+                    }
+                }
+            })
+            .filter_map(ready);
+
+        // Клиенту уходят только изменения. Поток закрывается сразу после выхода талона из очереди,
+        // не дожидаясь следующего события
+        let views = stream::once(ready(current)).chain(updates).boxed();
+        let views = stream::unfold((views, None::<QueueTicketView>, false), |(mut views, mut last, finished)| async move {
+            if finished {
+                return None;
+            }
+            loop {
+                let view = views.next().await?;
+                if last.as_ref() == Some(&view) {
+                    continue;
+                }
+                last = Some(view.clone());
+                let finished = !view.status.is_active();
+                return Some((view, (views, last, finished)));
+            }
+        });
+        Ok(views.boxed())
+    }
+
+    async fn list_queue(&self, user_id: Uuid, company_id: Uuid, query: LocationQueueQuery) -> Result<LocationQueue, AppError> {
+        self.require_member(user_id, company_id).await?;
+        self.require_location(company_id, query.location).await?;
+        let queue_date = match query.date {
+            Some(date) => date,
+            None => self.today(company_id).await?,
+        };
+
+        let tickets = self.queue_repository.list_tickets(query.location, queue_date).await?;
+        let average_service_minutes =
+            average_service_minutes(self.queue_repository.as_ref(), query.location, self.clock.now()).await?;
+        Ok(LocationQueue {
+            location_id: query.location,
+            queue_date,
+            average_service_minutes,
+            tickets,
+        })
+    }
+
+    async fn call_next(&self, user_id: Uuid, company_id: Uuid, data: CallNextRequest) -> Result<QueueTicket, AppError> {
+        self.require_member(user_id, company_id).await?;
+        self.require_location(company_id, data.location_id).await?;
+        let queue_date = self.today(company_id).await?;
+
+        self.queue_repository
+            .call_next(data.location_id, queue_date, user_id, self.clock.now())
+            .await?
+            .ok_or_else(|| AppError::NotFound("В очереди никого нет".to_string()))
+    }
+
+    async fn change_ticket_status(
+        &self,
+        user_id: Uuid,
+        ticket_id: Uuid,
+        data: ChangeQueueTicketStatusRequest,
+    ) -> Result<QueueTicket, AppError> {
+        let ticket = self.find_ticket(ticket_id).await?;
+        let is_member = self
+            .company_repository
+            .get_member_role(ticket.company_id, user_id)
+            .await?
+            .is_some();
+        if !is_member && ticket.customer_id != Some(user_id) {
+            return Err(AppError::Forbidden("Нет доступа к талону".to_string()));
+        }
+
+        let from: &[QueueTicketStatus] = match data.status {
+            QueueTicketStatus::Cancelled => &[QueueTicketStatus::Waiting, QueueTicketStatus::Called],
+            QueueTicketStatus::Served | QueueTicketStatus::NoShow if is_member => &[QueueTicketStatus::Called],
+            QueueTicketStatus::Served | QueueTicketStatus::NoShow => {
+                return Err(AppError::Forbidden(
+                    "Завершить обслуживание могут только сотрудники компании".to_string(),
+                ));
+            }
+            _ => return Err(AppError::Validation("Недопустимый статус талона".to_string())),
+        };
+        self.queue_repository
+            .update_ticket_status(ticket.id, from, data.status, self.clock.now())
+            .await?
+            .ok_or_else(|| {
+                AppError::Conflict(format!(
+                    "Нельзя перевести талон из статуса {} в {}",
+                    ticket.status.as_str(),
+                    data.status.as_str()
+                ))
+            })
+    }
+
+    async fn queue_stats(&self, user_id: Uuid, company_id: Uuid, query: QueueStatsQuery) -> Result<Vec<QueueDayStats>, AppError> {
+        self.require_member(user_id, company_id).await?;
+        self.require_location(company_id, query.location).await?;
+        if query.from > query.to {
+            return Err(AppError::Validation("Начало периода должно быть не позже конца".to_string()));
+        }
+        if (query.to - query.from).num_days() >= MAX_STATS_DAYS {
+            return Err(AppError::Validation(format!(
+                "Период статистики не может быть длиннее {} дней",
+                MAX_STATS_DAYS
+            )));
+        }
+        self.queue_repository.daily_stats(query.location, query.from, query.to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_grows_with_people_ahead() {
+        assert_eq!(estimated_wait_minutes(0, 12.5), 0);
+        assert_eq!(estimated_wait_minutes(1, 12.5), 13);
+        assert_eq!(estimated_wait_minutes(4, 12.5), 50);
+    }
+}
@@ -0,0 +1,336 @@
+//! Подмножество RRULE из RFC 5545 для серий записей: FREQ (DAILY, WEEKLY, MONTHLY),
+//! INTERVAL, COUNT, UNTIL и BYDAY без порядковых номеров. Вхождения считаются в местном
+//! времени компании, поэтому "каждый вторник в 10:00" остается 10:00 и после перехода на
+//! летнее/зимнее время.
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::domain::errors::AppError;
+
+/// Максимальное число вхождений в одной серии
+pub const MAX_OCCURRENCES: usize = 100;
+
+/// Защита от бесконечного перебора, если правило почти не дает вхождений
+const MAX_ITERATIONS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    /// Последняя дата включительно (в местном времени)
+    Date(NaiveDate),
+    /// Последний момент включительно
+    DateTime(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    pub by_day: Vec<Weekday>,
+}
+
+fn invalid(message: impl Into<String>) -> AppError {
+    AppError::Validation(message.into())
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, AppError> {
+    match value {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        _ => Err(invalid(format!("Неподдерживаемое значение BYDAY: {}", value))),
+    }
+}
+
+fn parse_until(value: &str) -> Result<Until, AppError> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok(Until::Date(date));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .map(|moment| Until::DateTime(moment.and_utc()))
+        .map_err(|_| invalid(format!("Неверный формат UNTIL: {}", value)))
+}
+
+/// Местное время в UTC: неоднозначное время берем раньшее, несуществующее
+/// сдвигаем на час вперед, как при переводе часов
+pub fn local_to_utc(timezone: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| timezone.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .map(|moment| moment.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+impl RecurrenceRule {
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        let value = value.trim();
+        let value = value.strip_prefix("RRULE:").unwrap_or(value);
+
+        let mut freq = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = Vec::new();
+
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (key, val) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("Неверная часть RRULE: {}", part)))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match val.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        other => return Err(invalid(format!("Неподдерживаемая частота: {}", other))),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = val
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| *interval >= 1)
+                        .ok_or_else(|| invalid("INTERVAL должен быть положительным числом"))?
+                }
+                "COUNT" => {
+                    count = Some(
+                        val.parse::<u32>()
+                            .ok()
+                            .filter(|count| *count >= 1)
+                            .ok_or_else(|| invalid("COUNT должен быть положительным числом"))?,
+                    )
+                }
+                "UNTIL" => until = Some(parse_until(val)?),
+                "BYDAY" => {
+                    by_day = val
+                        .split(',')
+                        .map(|day| parse_weekday(&day.to_ascii_uppercase()))
+                        .collect::<Result<Vec<_>, _>>()?
+                }
+                other => return Err(invalid(format!("Неподдерживаемая часть RRULE: {}", other))),
+            }
+        }
+
+        let freq = freq.ok_or_else(|| invalid("В RRULE нужен FREQ"))?;
+        if count.is_some() && until.is_some() {
+            return Err(invalid("COUNT и UNTIL нельзя указывать вместе"));
+        }
+        if count.is_none() && until.is_none() {
+            return Err(invalid("Серия должна быть ограничена COUNT или UNTIL"));
+        }
+        if count.is_some_and(|count| count as usize > MAX_OCCURRENCES) {
+            return Err(invalid(format!(
+                "В серии не может быть больше {} повторений",
+                MAX_OCCURRENCES
+            )));
+        }
+        if freq == Frequency::Monthly && !by_day.is_empty() {
+            return Err(invalid("BYDAY для FREQ=MONTHLY не поддерживается"));
+        }
+
+        by_day.sort_by_key(|day| day.num_days_from_monday());
+        by_day.dedup();
+
+        Ok(Self {
+            freq,
+            interval,
+            count,
+            until,
+            by_day,
+        })
+    }
+
+    /// Начала вхождений начиная с `start` (местное время компании), в UTC
+    pub fn occurrences(&self, start: NaiveDateTime, timezone: Tz) -> Result<Vec<DateTime<Utc>>, AppError> {
+        let time = start.time();
+        let first_date = start.date();
+        let mut result = Vec::new();
+
+        // Перебираем периоды (день, неделю, месяц) и даты-кандидаты внутри периода
+        for period in 0..MAX_ITERATIONS {
+            let step = period as u32 * self.interval;
+            let candidates: Vec<NaiveDate> = match self.freq {
+                Frequency::Daily => {
+                    let date = first_date + Duration::days(step as i64);
+                    if self.by_day.is_empty() || self.by_day.contains(&date.weekday()) {
+                        vec![date]
+                    } else {
+                        vec![]
+                    }
+                }
+                Frequency::Weekly => {
+                    let week_start = first_date - Duration::days(first_date.weekday().num_days_from_monday() as i64)
+                        + Duration::weeks(step as i64);
+                    let days = if self.by_day.is_empty() {
+                        vec![first_date.weekday()]
+                    } else {
+                        self.by_day.clone()
+                    };
+                    days.iter()
+                        .map(|day| week_start + Duration::days(day.num_days_from_monday() as i64))
+                        .filter(|date| *date >= first_date)
+                        .collect()
+                }
+                Frequency::Monthly => {
+                    // Месяцы без нужного числа (31-е, 29 февраля) пропускаются, как в RFC 5545
+                    let month = first_date.with_day(1).and_then(|date| date.checked_add_months(Months::new(step)));
+                    match month {
+                        Some(month) => month.with_day(first_date.day()).into_iter().collect(),
+                        None => break,
+                    }
+                }
+            };
+
+            for date in candidates {
+                let local = date.and_time(time);
+                let starts_at = local_to_utc(timezone, local);
+                let past_until = match self.until {
+                    Some(Until::Date(until)) => date > until,
+                    Some(Until::DateTime(until)) => starts_at > until,
+                    None => false,
+                };
+                if past_until {
+                    return Ok(result);
+                }
+
+                result.push(starts_at);
+                if self.count.is_some_and(|count| result.len() >= count as usize) {
+                    return Ok(result);
+                }
+                if result.len() > MAX_OCCURRENCES {
+                    return Err(invalid(format!(
+                        "В серии не может быть больше {} повторений",
+                        MAX_OCCURRENCES
+                    )));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn weekly_count_keeps_local_time_across_dst() {
+        let rule = RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=TU;COUNT=3").unwrap();
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+
+        // 2025-03-30 Берлин переходит на летнее время
+        let starts = rule.occurrences(local("2025-03-18 10:00"), berlin).unwrap();
+        assert_eq!(
+            starts,
+            vec![
+                utc("2025-03-18T09:00:00Z"),
+                utc("2025-03-25T09:00:00Z"),
+                utc("2025-04-01T08:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_by_several_days_with_interval() {
+        let rule = RecurrenceRule::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=FR,MO;COUNT=4").unwrap();
+        let starts = rule.occurrences(local("2025-06-04 09:00"), Tz::UTC).unwrap();
+
+        // Первая неделя: только пятница (понедельник уже прошел), потом через неделю
+        assert_eq!(
+            starts,
+            vec![
+                utc("2025-06-06T09:00:00Z"),
+                utc("2025-06-16T09:00:00Z"),
+                utc("2025-06-20T09:00:00Z"),
+                utc("2025-06-30T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn daily_until_date_is_inclusive_and_filters_by_day() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY;BYDAY=MO,WE;UNTIL=20250611").unwrap();
+        let starts = rule.occurrences(local("2025-06-02 12:00"), Tz::UTC).unwrap();
+
+        assert_eq!(
+            starts,
+            vec![
+                utc("2025-06-02T12:00:00Z"),
+                utc("2025-06-04T12:00:00Z"),
+                utc("2025-06-09T12:00:00Z"),
+                utc("2025-06-11T12:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn until_datetime_is_compared_in_utc() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY;UNTIL=20250603T120000Z").unwrap();
+        let starts = rule.occurrences(local("2025-06-01 12:00"), Tz::UTC).unwrap();
+        assert_eq!(starts.len(), 3);
+    }
+
+    #[test]
+    fn monthly_skips_months_without_the_day() {
+        let rule = RecurrenceRule::parse("FREQ=MONTHLY;COUNT=3").unwrap();
+        let starts = rule.occurrences(local("2025-01-31 10:00"), Tz::UTC).unwrap();
+
+        assert_eq!(
+            starts,
+            vec![
+                utc("2025-01-31T10:00:00Z"),
+                utc("2025-03-31T10:00:00Z"),
+                utc("2025-05-31T10:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_or_unbounded_rules() {
+        for rule in [
+            "FREQ=WEEKLY",
+            "FREQ=YEARLY;COUNT=2",
+            "FREQ=WEEKLY;COUNT=2;UNTIL=20250101",
+            "FREQ=WEEKLY;BYDAY=1MO;COUNT=2",
+            "FREQ=MONTHLY;BYDAY=MO;COUNT=2",
+            "FREQ=DAILY;BYHOUR=10;COUNT=2",
+            "FREQ=DAILY;COUNT=101",
+            "FREQ=DAILY;INTERVAL=0;COUNT=2",
+        ] {
+            assert!(
+                matches!(RecurrenceRule::parse(rule), Err(AppError::Validation(_))),
+                "{}",
+                rule
+            );
+        }
+    }
+
+    #[test]
+    fn too_many_occurrences_until_is_rejected() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY;UNTIL=20300101").unwrap();
+        assert!(rule.occurrences(local("2025-06-01 10:00"), Tz::UTC).is_err());
+    }
+}
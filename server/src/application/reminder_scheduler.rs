@@ -0,0 +1,47 @@
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, ReminderRepository};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Напоминаний за один проход
+const BATCH_SIZE: i64 = 100;
+
+/// Фоновая задача, отправляющая наступившие напоминания о записях.
+/// Можно запускать в каждом экземпляре сервера: строки захватываются через SKIP LOCKED
+pub struct ReminderScheduler {
+    reminder_repository: Arc<dyn ReminderRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+impl ReminderScheduler {
+    pub fn new(
+        reminder_repository: Arc<dyn ReminderRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            reminder_repository,
+            clock,
+        }
+    }
+
+    /// Один проход: поставить в outbox напоминания, время которых наступило;
+    /// не поместившиеся в пачку уйдут следующим проходом
+    pub async fn run_once(&self) -> Result<u64, AppError> {
+        self.reminder_repository
+            .send_due_reminders(self.clock.now(), BATCH_SIZE)
+            .await
+    }
+
+    /// Запустить проходы с заданным интервалом в рантайме actix
+    pub fn spawn(self, every: Duration) {
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    eprintln!("{}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,112 @@
+use crate::domain::entities::{BookingReminder, ReminderRule, UpdateReminderRulesRequest};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, CompanyRepository, ReminderRepository, ReminderService};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Сколько правил напоминаний может быть у компании
+pub const MAX_REMINDER_RULES: usize = 5;
+
+/// Самое раннее напоминание - за неделю до начала
+pub const MAX_REMINDER_OFFSET_MINUTES: i32 = 7 * 24 * 60;
+
+pub struct ReminderServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+    reminder_repository: Arc<dyn ReminderRepository + Send + Sync>,
+}
+
+impl ReminderServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        reminder_repository: Arc<dyn ReminderRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            booking_repository,
+            reminder_repository,
+        }
+    }
+
+    async fn require_company(&self, company_id: Uuid) -> Result<(), AppError> {
+        match self.company_repository.find_company(company_id).await? {
+            Some(_) => Ok(()),
+            None => Err(AppError::NotFound("Компания не найдена".to_string())),
+        }
+    }
+}
+
+fn validate_offsets(data: &UpdateReminderRulesRequest) -> Result<Vec<i32>, AppError> {
+    if data.rules.len() > MAX_REMINDER_RULES {
+        return Err(AppError::Validation(format!(
+            "Правил напоминаний может быть не больше {}",
+            MAX_REMINDER_RULES
+        )));
+    }
+
+    let mut offsets = Vec::with_capacity(data.rules.len());
+    for rule in &data.rules {
+        if !(1..=MAX_REMINDER_OFFSET_MINUTES).contains(&rule.offset_minutes) {
+            return Err(AppError::Validation(format!(
+                "Напоминание можно отправить не раньше чем за {} минут и не позже чем за минуту до начала",
+                MAX_REMINDER_OFFSET_MINUTES
+            )));
+        }
+        if offsets.contains(&rule.offset_minutes) {
+            return Err(AppError::Validation(format!(
+                "Правило за {} минут указано дважды",
+                rule.offset_minutes
+            )));
+        }
+        offsets.push(rule.offset_minutes);
+    }
+    Ok(offsets)
+}
+
+#[async_trait]
+impl ReminderService for ReminderServiceImpl {
+    async fn get_rules(&self, user_id: Uuid, company_id: Uuid) -> Result<Vec<ReminderRule>, AppError> {
+        self.require_company(company_id).await?;
+        if self.company_repository.get_member_role(company_id, user_id).await?.is_none() {
+            return Err(AppError::Forbidden("Вы не состоите в компании".to_string()));
+        }
+        self.reminder_repository.list_rules(company_id).await
+    }
+
+    async fn update_rules(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: UpdateReminderRulesRequest,
+    ) -> Result<Vec<ReminderRule>, AppError> {
+        self.require_company(company_id).await?;
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => {}
+            _ => {
+                return Err(AppError::Forbidden(
+                    "Недостаточно прав для управления компанией".to_string(),
+                ))
+            }
+        }
+
+        let offsets = validate_offsets(&data)?;
+        self.reminder_repository.replace_rules(company_id, &offsets).await
+    }
+
+    async fn booking_reminders(&self, user_id: Uuid, booking_id: Uuid) -> Result<Vec<BookingReminder>, AppError> {
+        let not_found = || AppError::NotFound("Запись не найдена".to_string());
+        let booking = self.booking_repository.find_booking(booking_id).await?.ok_or_else(not_found)?;
+        if booking.customer_id != user_id
+            && self
+                .company_repository
+                .get_member_role(booking.company_id, user_id)
+                .await?
+                .is_none()
+        {
+            return Err(not_found());
+        }
+        self.reminder_repository.list_booking_reminders(booking.id).await
+    }
+}
@@ -0,0 +1,161 @@
+use crate::domain::entities::{
+    BookingStatus, CreateReviewRequest, Review, ReviewReplyRequest, ReviewVisibilityRequest, ReviewsQuery,
+    ReviewsResponse,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, CompanyRepository, ReviewRepository, ReviewService, UserAuthRepository};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Размер страницы отзывов по умолчанию и максимальный
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Максимальная длина текста отзыва и ответа компании в символах
+const MAX_TEXT_LENGTH: usize = 2000;
+
+pub struct ReviewServiceImpl {
+    user_repository: Arc<dyn UserAuthRepository + Send + Sync>,
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+    review_repository: Arc<dyn ReviewRepository + Send + Sync>,
+}
+
+/// Текст без пробелов по краям; пустой текст означает отзыв только с оценкой
+fn normalize_text(text: Option<String>) -> Result<Option<String>, AppError> {
+    let Some(text) = text.map(|text| text.trim().to_string()).filter(|text| !text.is_empty()) else {
+        return Ok(None);
+    };
+    if text.chars().count() > MAX_TEXT_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Текст не может быть длиннее {} символов",
+            MAX_TEXT_LENGTH
+        )));
+    }
+    Ok(Some(text))
+}
+
+impl ReviewServiceImpl {
+    pub fn new(
+        user_repository: Arc<dyn UserAuthRepository + Send + Sync>,
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        review_repository: Arc<dyn ReviewRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            user_repository,
+            company_repository,
+            booking_repository,
+            review_repository,
+        }
+    }
+
+    /// Отвечать на отзывы от имени компании могут только ее руководители
+    async fn require_manager(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => Ok(()),
+            _ => Err(AppError::Forbidden(
+                "Недостаточно прав для управления компанией".to_string(),
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl ReviewService for ReviewServiceImpl {
+    async fn create_review(&self, user_id: Uuid, booking_id: Uuid, data: CreateReviewRequest) -> Result<Review, AppError> {
+        if !(1..=5).contains(&data.rating) {
+            return Err(AppError::Validation("Оценка должна быть от 1 до 5".to_string()));
+        }
+        let text = normalize_text(data.text)?;
+
+        // Чужая запись для автора выглядит как несуществующая
+        let booking = match self.booking_repository.find_booking(booking_id).await? {
+            Some(booking) if booking.customer_id == user_id => booking,
+            _ => return Err(AppError::NotFound("Запись не найдена".to_string())),
+        };
+        if booking.status != BookingStatus::Completed {
+            return Err(AppError::Conflict(
+                "Отзыв можно оставить только после завершенного визита".to_string(),
+            ));
+        }
+
+        self.review_repository
+            .create_review(&booking, &CreateReviewRequest { rating: data.rating, text })
+            .await
+    }
+
+    async fn list_reviews(&self, company_id: Uuid, query: ReviewsQuery) -> Result<ReviewsResponse, AppError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(AppError::Validation(format!(
+                "limit должен быть от 1 до {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        if offset < 0 {
+            return Err(AppError::Validation("offset не может быть отрицательным".to_string()));
+        }
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+
+        let (reviews, rating_avg, rating_count) = self
+            .review_repository
+            .list_reviews(company_id, query.staff, limit, offset)
+            .await?;
+
+        Ok(ReviewsResponse {
+            rating_avg,
+            rating_count,
+            reviews,
+            limit,
+            offset,
+        })
+    }
+
+    async fn reply_to_review(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        review_id: Uuid,
+        data: ReviewReplyRequest,
+    ) -> Result<Review, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        let Some(reply) = normalize_text(Some(data.reply))? else {
+            return Err(AppError::Validation("Ответ не может быть пустым".to_string()));
+        };
+        match self.review_repository.find_review(review_id).await? {
+            Some(review) if review.company_id == company_id => {}
+            _ => return Err(AppError::NotFound("Отзыв не найден".to_string())),
+        }
+
+        self.review_repository
+            .set_reply(review_id, &reply)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Отзыв не найден".to_string()))
+    }
+
+    async fn set_visibility(
+        &self,
+        user_id: Uuid,
+        review_id: Uuid,
+        data: ReviewVisibilityRequest,
+    ) -> Result<Review, AppError> {
+        match self.user_repository.find_by_id(user_id).await? {
+            Some(user) if user.is_moderator() => {}
+            _ => return Err(AppError::Forbidden("Доступно только модераторам".to_string())),
+        }
+        let reason = normalize_text(data.reason)?;
+
+        self.review_repository
+            .set_visibility(review_id, data.is_hidden, reason.as_deref(), user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Отзыв не найден".to_string()))
+    }
+}
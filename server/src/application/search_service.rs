@@ -0,0 +1,189 @@
+use crate::domain::entities::{CompanySearchFilter, CompanySearchQuery, CompanySearchResponse, SearchSort};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, SearchRepository, SearchService};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Размер страницы поиска по умолчанию и максимальный
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 50;
+
+/// Максимальная длина поискового запроса и радиус поиска
+const MAX_QUERY_LENGTH: usize = 200;
+const MAX_RADIUS_KM: f64 = 500.0;
+
+pub struct SearchServiceImpl {
+    search_repository: Arc<dyn SearchRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+/// Курсор страницы: ключ сортировки и id последней компании в hex, чтобы клиент
+/// передавал его как есть и не собирал сам
+pub fn encode_cursor(sort_key: f64, id: Uuid) -> String {
+    hex::encode(format!("{}|{}", sort_key, id))
+}
+
+pub fn decode_cursor(cursor: &str) -> Option<(f64, Uuid)> {
+    let raw = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+    let (sort_key, id) = raw.split_once('|')?;
+    let sort_key: f64 = sort_key.parse().ok()?;
+    if sort_key.is_nan() {
+        return None;
+    }
+    Some((sort_key, id.parse().ok()?))
+}
+
+/// Пустые строки в параметрах запроса означают отсутствие фильтра
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+impl SearchServiceImpl {
+    pub fn new(search_repository: Arc<dyn SearchRepository + Send + Sync>, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        Self {
+            search_repository,
+            clock,
+        }
+    }
+
+    fn build_filter(&self, query: CompanySearchQuery) -> Result<CompanySearchFilter, AppError> {
+        let text = non_empty(query.q);
+        if text.as_ref().is_some_and(|text| text.chars().count() > MAX_QUERY_LENGTH) {
+            return Err(AppError::Validation(format!(
+                "Запрос не может быть длиннее {} символов",
+                MAX_QUERY_LENGTH
+            )));
+        }
+
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(AppError::Validation(format!(
+                "limit должен быть от 1 до {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        if query.min_rating.is_some_and(|rating| !(1.0..=5.0).contains(&rating)) {
+            return Err(AppError::Validation("min_rating должен быть от 1 до 5".to_string()));
+        }
+        if query.price_min.is_some_and(|price| price < 0) || query.price_max.is_some_and(|price| price < 0) {
+            return Err(AppError::Validation("Цена не может быть отрицательной".to_string()));
+        }
+        if let (Some(min), Some(max)) = (query.price_min, query.price_max)
+            && min > max
+        {
+            return Err(AppError::Validation("price_min больше price_max".to_string()));
+        }
+
+        let origin = match (query.lat, query.lon) {
+            (None, None) => None,
+            (Some(lat), Some(lon)) if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) => {
+                Some((lat, lon))
+            }
+            _ => {
+                return Err(AppError::Validation(
+                    "lat и lon задаются вместе: широта от -90 до 90, долгота от -180 до 180".to_string(),
+                ));
+            }
+        };
+        if let Some(radius) = query.radius_km {
+            if origin.is_none() {
+                return Err(AppError::Validation("Для radius_km нужны lat и lon".to_string()));
+            }
+            if !(radius > 0.0 && radius <= MAX_RADIUS_KM) {
+                return Err(AppError::Validation(format!(
+                    "radius_km должен быть больше 0 и не больше {}",
+                    MAX_RADIUS_KM
+                )));
+            }
+        }
+
+        // По умолчанию: по релевантности, если есть текст, иначе по рейтингу
+        let sort = query.sort.unwrap_or(if text.is_some() {
+            SearchSort::Relevance
+        } else {
+            SearchSort::Rating
+        });
+        if sort == SearchSort::Distance && origin.is_none() {
+            return Err(AppError::Validation("Для сортировки по расстоянию нужны lat и lon".to_string()));
+        }
+        if sort == SearchSort::Relevance && text.is_none() {
+            return Err(AppError::Validation("Для сортировки по релевантности нужен q".to_string()));
+        }
+
+        let after = match non_empty(query.cursor) {
+            Some(cursor) => Some(
+                decode_cursor(&cursor).ok_or_else(|| AppError::Validation("Некорректный курсор".to_string()))?,
+            ),
+            None => None,
+        };
+
+        Ok(CompanySearchFilter {
+            text,
+            city: non_empty(query.city),
+            category: non_empty(query.category).map(|category| category.to_lowercase()),
+            min_rating: query.min_rating,
+            price_min: query.price_min,
+            price_max: query.price_max,
+            open_at: query.open_now.unwrap_or(false).then(|| self.clock.now()),
+            available_on: query.available_on,
+            origin,
+            radius_km: query.radius_km,
+            sort,
+            after,
+            limit,
+        })
+    }
+}
+
+#[async_trait]
+impl SearchService for SearchServiceImpl {
+    async fn search_companies(&self, query: CompanySearchQuery) -> Result<CompanySearchResponse, AppError> {
+        let filter = self.build_filter(query)?;
+
+        // Лишняя запись показывает, что за страницей есть продолжение
+        let page_filter = CompanySearchFilter {
+            limit: filter.limit + 1,
+            ..filter.clone()
+        };
+        let (mut companies, (total, facets)) = futures_util::try_join!(
+            self.search_repository.search_companies(&page_filter),
+            self.search_repository.search_facets(&filter),
+        )?;
+
+        let next_cursor = if companies.len() as i64 > filter.limit {
+            companies.truncate(filter.limit as usize);
+            companies.last().map(|last| encode_cursor(last.sort_key, last.id))
+        } else {
+            None
+        };
+
+        Ok(CompanySearchResponse {
+            companies,
+            total,
+            facets,
+            next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_sort_key_exactly() {
+        let id = Uuid::new_v4();
+        for key in [0.0, 0.0607927, -12.345678901234567, 49_000_012.0, f64::NEG_INFINITY] {
+            assert_eq!(decode_cursor(&encode_cursor(key, id)), Some((key, id)));
+        }
+    }
+
+    #[test]
+    fn malformed_cursor_is_rejected() {
+        assert_eq!(decode_cursor("not-hex"), None);
+        assert_eq!(decode_cursor(&hex::encode("1.5")), None);
+        assert_eq!(decode_cursor(&hex::encode("NaN|00000000-0000-0000-0000-000000000000")), None);
+        assert_eq!(decode_cursor(&hex::encode("1.5|nope")), None);
+    }
+}
@@ -0,0 +1,867 @@
+//! Расчет свободных слотов для записи.
+//!
+//! Модуль не обращается к БД: на вход подаются расписания сотрудников,
+//! исключения, занятые интервалы и занятость ресурсов, на выходе - список времени начала.
+//! Все вычисления над локальным временем компании выполняются через
+//! `chrono_tz`, поэтому переходы на летнее/зимнее время учитываются корректно.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::domain::entities::{BusyInterval, GroupSession, ResourceBusyInterval, ScheduleException, StaffSchedule};
+
+/// Параметры услуги, влияющие на расчет слотов
+#[derive(Debug, Clone, Copy)]
+pub struct SlotRules {
+    pub duration: Duration,
+    pub buffer_before: Duration,
+    pub buffer_after: Duration,
+    pub step: Duration,
+    /// Мест на занятии; 1 - индивидуальная услуга
+    pub capacity: i32,
+}
+
+/// Входные данные по одному сотруднику
+#[derive(Debug, Clone)]
+pub struct StaffAvailability {
+    pub staff_id: Uuid,
+    pub weekly: Vec<StaffSchedule>,
+    pub exceptions: Vec<ScheduleException>,
+    /// Занятые интервалы (уже с учетом буферов существующих записей)
+    pub busy: Vec<BusyInterval>,
+    /// Уже созданные занятия рассчитываемой групповой услуги
+    pub sessions: Vec<GroupSession>,
+}
+
+/// Вариант ресурса для требования услуги и его занятость
+#[derive(Debug, Clone)]
+pub struct ResourceAvailability {
+    pub resource_id: Uuid,
+    pub location_id: Uuid,
+    pub busy: Vec<ResourceBusyInterval>,
+}
+
+/// Запрос на расчет: диапазон дат включительно, в часовом поясе компании
+#[derive(Debug, Clone)]
+pub struct SlotRequest {
+    pub timezone: Tz,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub now: DateTime<Utc>,
+    pub rules: SlotRules,
+    /// Требования услуги к ресурсам: по каждому нужен один свободный вариант в филиале слота
+    pub resources: Vec<Vec<ResourceAvailability>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailableSlot {
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Существующее занятие в это время, к которому присоединится запись
+    pub session_id: Option<Uuid>,
+    pub capacity: i32,
+    pub seats_left: i32,
+    /// Выбранные ресурсы, по одному на требование услуги
+    pub resource_ids: Vec<Uuid>,
+}
+
+/// Интервал, который займет запись с началом в `start`, включая буферы
+pub fn blocked_interval(start: DateTime<Utc>, rules: &SlotRules) -> (DateTime<Utc>, DateTime<Utc>) {
+    (
+        start - rules.buffer_before,
+        start + rules.duration + rules.buffer_after,
+    )
+}
+
+/// Рассчитать все доступные времена начала для всех переданных сотрудников.
+///
+/// Сама услуга должна целиком помещаться в рабочее время, буферы проверяются
+/// только на пересечение с занятыми интервалами. Кандидаты берутся по сетке
+/// `step` от начала рабочего интервала, плюс сразу после каждого занятого
+/// интервала сотрудника или ресурса, чтобы запись "встык" была возможна даже вне сетки.
+///
+/// Для групповой услуги время уже созданного занятия доступно, пока на нем есть
+/// места: записи этого занятия не считаются занятым временем.
+///
+/// Если услуге нужны ресурсы, слот доступен только при свободном ресурсе по каждому
+/// требованию в том же филиале.
+pub fn compute_available_slots(
+    request: &SlotRequest,
+    staff: &[StaffAvailability],
+) -> Vec<AvailableSlot> {
+    let mut slots = Vec::new();
+    if request.rules.duration <= Duration::zero() || request.rules.step <= Duration::zero() {
+        return slots;
+    }
+
+    for member in staff {
+        let mut seen = BTreeSet::new();
+        let mut date = request.from;
+        while date <= request.to {
+            for (start, end, location_id) in working_intervals(request.timezone, date, member) {
+                for candidate in candidates(start, end, member, request) {
+                    if candidate < request.now || !seen.insert(candidate) {
+                        continue;
+                    }
+                    let session = member.sessions.iter().find(|session| session.starts_at == candidate);
+                    let session_id = session.map(|session| session.id);
+                    let (blocked_start, blocked_end) = blocked_interval(candidate, &request.rules);
+                    let overlaps = member.busy.iter().any(|busy| {
+                        busy.start < blocked_end
+                            && blocked_start < busy.end
+                            && (session_id.is_none() || busy.session_id != session_id)
+                    });
+                    let (capacity, seats_left) = match session {
+                        Some(session) => (session.capacity, session.capacity - session.seats_taken),
+                        None => (request.rules.capacity, request.rules.capacity),
+                    };
+                    if overlaps || seats_left <= 0 {
+                        continue;
+                    }
+                    let Some(resource_ids) = pick_resources(
+                        &request.resources,
+                        location_id,
+                        (blocked_start, blocked_end),
+                        session_id,
+                    ) else {
+                        continue;
+                    };
+                    slots.push(AvailableSlot {
+                        staff_id: member.staff_id,
+                        location_id,
+                        start: candidate,
+                        end: candidate + request.rules.duration,
+                        session_id,
+                        capacity,
+                        seats_left,
+                        resource_ids,
+                    });
+                }
+            }
+            date = match date.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    }
+
+    slots.sort_by(|a, b| a.start.cmp(&b.start).then(a.staff_id.cmp(&b.staff_id)));
+    slots
+}
+
+/// Выбрать по одному ресурсу на требование услуги. Ресурсы, уже занятые этим групповым
+/// занятием, предпочитаются: участники одного занятия делят кабинет и оборудование
+fn pick_resources(
+    requirements: &[Vec<ResourceAvailability>],
+    location_id: Uuid,
+    (blocked_start, blocked_end): (DateTime<Utc>, DateTime<Utc>),
+    session_id: Option<Uuid>,
+) -> Option<Vec<Uuid>> {
+    let same_session = |busy: &ResourceBusyInterval| session_id.is_some() && busy.session_id == session_id;
+    let is_free = |option: &&ResourceAvailability| {
+        !option
+            .busy
+            .iter()
+            .any(|busy| busy.start < blocked_end && blocked_start < busy.end && !same_session(busy))
+    };
+
+    requirements
+        .iter()
+        .map(|options| {
+            let free: Vec<&ResourceAvailability> = options
+                .iter()
+                .filter(|option| option.location_id == location_id)
+                .filter(is_free)
+                .collect();
+            free.iter()
+                .find(|option| option.busy.iter().any(same_session))
+                .or(free.first())
+                .map(|option| option.resource_id)
+        })
+        .collect()
+}
+
+/// Рабочее время сотрудника за дату в минутах: расписание с исключениями, пересечения
+/// интервалов считаются один раз, а переход на летнее/зимнее время сокращает или удлиняет день
+pub fn working_minutes(tz: Tz, date: NaiveDate, member: &StaffAvailability) -> i64 {
+    let mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)> = working_intervals(tz, date, member)
+        .into_iter()
+        .map(|(start, end, _)| (start, end))
+        .collect();
+    intervals.sort();
+
+    let mut total = Duration::zero();
+    let mut covered_until: Option<DateTime<Utc>> = None;
+    for (start, end) in intervals {
+        let start = covered_until.map_or(start, |until| start.max(until));
+        if start < end {
+            total += end - start;
+            covered_until = Some(end);
+        }
+    }
+    total.num_minutes()
+}
+
+/// Рабочие интервалы сотрудника на дату в UTC с учетом исключений
+fn working_intervals(
+    tz: Tz,
+    date: NaiveDate,
+    member: &StaffAvailability,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>, Uuid)> {
+    let weekday = date.weekday().number_from_monday() as i16;
+    let day_exceptions: Vec<&ScheduleException> = member
+        .exceptions
+        .iter()
+        .filter(|e| e.exception_date == date)
+        .collect();
+
+    let day_off = day_exceptions
+        .iter()
+        .any(|e| !e.is_available && e.start_time.is_none());
+
+    let mut local: Vec<(NaiveTime, NaiveTime, Uuid)> = Vec::new();
+    if !day_off {
+        local = member
+            .weekly
+            .iter()
+            .filter(|s| s.weekday == weekday)
+            .map(|s| (s.start_time, s.end_time, s.location_id))
+            .collect();
+
+        for exception in day_exceptions.iter().filter(|e| !e.is_available) {
+            if let (Some(off_start), Some(off_end)) = (exception.start_time, exception.end_time) {
+                local = local
+                    .into_iter()
+                    .flat_map(|(start, end, location_id)| {
+                        let mut parts = Vec::new();
+                        if start < off_start {
+                            parts.push((start, end.min(off_start), location_id));
+                        }
+                        if off_end < end {
+                            parts.push((start.max(off_end), end, location_id));
+                        }
+                        parts
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    for exception in day_exceptions.iter().filter(|e| e.is_available) {
+        if let (Some(start), Some(end), Some(location_id)) =
+            (exception.start_time, exception.end_time, exception.location_id)
+        {
+            local.push((start, end, location_id));
+        }
+    }
+
+    local
+        .into_iter()
+        .filter(|(start, end, _)| start < end)
+        .map(|(start, end, location_id)| {
+            (
+                resolve_local(tz, date.and_time(start), false),
+                resolve_local(tz, date.and_time(end), true),
+                location_id,
+            )
+        })
+        .filter(|(start, end, _)| start < end)
+        .collect()
+}
+
+/// Кандидаты на время начала внутри рабочего интервала
+fn candidates(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    member: &StaffAvailability,
+    request: &SlotRequest,
+) -> BTreeSet<DateTime<Utc>> {
+    let rules = &request.rules;
+    let mut result = BTreeSet::new();
+
+    let mut candidate = start;
+    while candidate + rules.duration <= end {
+        result.insert(candidate);
+        candidate += rules.step;
+    }
+
+    // Занятие могло начаться вне сетки, например после переноса
+    let after_busy = member.busy.iter().map(|interval| interval.end + rules.buffer_before);
+    let after_resources = request
+        .resources
+        .iter()
+        .flatten()
+        .flat_map(|option| option.busy.iter().map(|interval| interval.end + rules.buffer_before));
+    let sessions = member.sessions.iter().map(|session| session.starts_at);
+    for candidate in after_busy.chain(after_resources).chain(sessions) {
+        if candidate >= start && candidate + rules.duration <= end {
+            result.insert(candidate);
+        }
+    }
+
+    result
+}
+
+/// Перевести локальное время компании в UTC.
+///
+/// Для неоднозначного времени (перевод часов назад) начало интервала берет
+/// более раннее значение, конец - более позднее, чтобы интервал покрывал все
+/// показанное на часах время. Несуществующее время (перевод вперед) сдвигается
+/// на первый момент после перехода.
+fn resolve_local(tz: Tz, naive: NaiveDateTime, prefer_latest: bool) -> DateTime<Utc> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, latest) => {
+            if prefer_latest {
+                latest.with_timezone(&Utc)
+            } else {
+                earliest.with_timezone(&Utc)
+            }
+        }
+        LocalResult::None => {
+            let mut probe = naive;
+            for _ in 0..(24 * 60) {
+                probe += Duration::minutes(1);
+                if let Some(dt) = tz.from_local_datetime(&probe).earliest() {
+                    return dt.with_timezone(&Utc);
+                }
+            }
+            Utc.from_utc_datetime(&naive)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location() -> Uuid {
+        Uuid::from_u128(100)
+    }
+
+    fn staff_id() -> Uuid {
+        Uuid::from_u128(1)
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn weekly(weekday: i16, start: NaiveTime, end: NaiveTime) -> StaffSchedule {
+        StaffSchedule {
+            staff_id: staff_id(),
+            location_id: location(),
+            weekday,
+            start_time: start,
+            end_time: end,
+        }
+    }
+
+    fn rules(duration: i64, step: i64) -> SlotRules {
+        SlotRules {
+            duration: Duration::minutes(duration),
+            buffer_before: Duration::zero(),
+            buffer_after: Duration::zero(),
+            step: Duration::minutes(step),
+            capacity: 1,
+        }
+    }
+
+    fn request(tz: Tz, day: NaiveDate, rules: SlotRules) -> SlotRequest {
+        SlotRequest {
+            timezone: tz,
+            from: day,
+            to: day,
+            now: utc("2000-01-01T00:00:00Z"),
+            rules,
+            resources: Vec::new(),
+        }
+    }
+
+    fn member(weekly: Vec<StaffSchedule>) -> StaffAvailability {
+        StaffAvailability {
+            staff_id: staff_id(),
+            weekly,
+            exceptions: Vec::new(),
+            busy: Vec::new(),
+            sessions: Vec::new(),
+        }
+    }
+
+    fn busy(start: &str, end: &str) -> BusyInterval {
+        BusyInterval {
+            booking_id: Uuid::new_v4(),
+            staff_id: staff_id(),
+            start: utc(start),
+            end: utc(end),
+            session_id: None,
+        }
+    }
+
+    fn session(id: u128, start: &str, end: &str, seats_taken: i32) -> GroupSession {
+        GroupSession {
+            id: Uuid::from_u128(id),
+            company_id: Uuid::from_u128(10),
+            service_id: Uuid::from_u128(20),
+            staff_id: staff_id(),
+            location_id: location(),
+            starts_at: utc(start),
+            ends_at: utc(end),
+            capacity: 3,
+            seats_taken,
+            created_at: utc("2025-06-01T00:00:00Z"),
+        }
+    }
+
+    fn resource(id: u128, location_id: Uuid, busy: &[(&str, &str)]) -> ResourceAvailability {
+        ResourceAvailability {
+            resource_id: Uuid::from_u128(id),
+            location_id,
+            busy: busy
+                .iter()
+                .map(|(start, end)| ResourceBusyInterval {
+                    booking_id: Uuid::new_v4(),
+                    resource_id: Uuid::from_u128(id),
+                    start: utc(start),
+                    end: utc(end),
+                    session_id: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn starts(slots: &[AvailableSlot]) -> Vec<String> {
+        slots.iter().map(|s| s.start.to_rfc3339()).collect()
+    }
+
+    // 2025-06-02 - понедельник
+    const MONDAY: (i32, u32, u32) = (2025, 6, 2);
+
+    #[test]
+    fn generates_grid_inside_working_hours() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let slots = compute_available_slots(
+            &request(chrono_tz::UTC, day, rules(60, 30)),
+            &[member(vec![weekly(1, time(9, 0), time(11, 0))])],
+        );
+
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-06-02T09:00:00+00:00",
+                "2025-06-02T09:30:00+00:00",
+                "2025-06-02T10:00:00+00:00",
+            ]
+        );
+        assert_eq!(slots[0].end, utc("2025-06-02T10:00:00Z"));
+        assert_eq!(slots[0].location_id, location());
+    }
+
+    #[test]
+    fn ignores_other_weekdays() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let slots = compute_available_slots(
+            &request(chrono_tz::UTC, day, rules(60, 30)),
+            &[member(vec![weekly(2, time(9, 0), time(11, 0))])],
+        );
+        assert!(slots.is_empty());
+    }
+
+    #[test]
+    fn converts_local_time_using_company_timezone() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let slots = compute_available_slots(
+            &request(chrono_tz::Europe::Moscow, day, rules(60, 60)),
+            &[member(vec![weekly(1, time(9, 0), time(10, 0))])],
+        );
+        assert_eq!(starts(&slots), vec!["2025-06-02T06:00:00+00:00"]);
+    }
+
+    #[test]
+    fn day_off_exception_removes_whole_day() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(12, 0))]);
+        staff.exceptions.push(ScheduleException {
+            staff_id: staff_id(),
+            exception_date: day,
+            start_time: None,
+            end_time: None,
+            is_available: false,
+            location_id: None,
+        });
+
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules(60, 60)), &[staff]);
+        assert!(slots.is_empty());
+    }
+
+    #[test]
+    fn partial_exception_splits_working_interval() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(13, 0))]);
+        staff.exceptions.push(ScheduleException {
+            staff_id: staff_id(),
+            exception_date: day,
+            start_time: Some(time(10, 0)),
+            end_time: Some(time(11, 0)),
+            is_available: false,
+            location_id: None,
+        });
+
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules(60, 60)), &[staff]);
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-06-02T09:00:00+00:00",
+                "2025-06-02T11:00:00+00:00",
+                "2025-06-02T12:00:00+00:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn extra_working_hours_are_added() {
+        // Воскресенье без расписания, но с дополнительной сменой
+        let day = date(2025, 6, 8);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(13, 0))]);
+        staff.exceptions.push(ScheduleException {
+            staff_id: staff_id(),
+            exception_date: day,
+            start_time: Some(time(15, 0)),
+            end_time: Some(time(16, 0)),
+            is_available: true,
+            location_id: Some(location()),
+        });
+
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules(30, 30)), &[staff]);
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-06-08T15:00:00+00:00", "2025-06-08T15:30:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn busy_interval_hides_overlapping_slots() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(12, 0))]);
+        staff.busy.push(busy("2025-06-02T10:00:00Z", "2025-06-02T11:00:00Z"));
+
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules(60, 30)), &[staff]);
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-06-02T09:00:00+00:00", "2025-06-02T11:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn back_to_back_bookings_are_allowed() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(12, 0))]);
+        staff.busy.push(busy("2025-06-02T09:00:00Z", "2025-06-02T10:00:00Z"));
+        staff.busy.push(busy("2025-06-02T11:00:00Z", "2025-06-02T12:00:00Z"));
+
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules(60, 60)), &[staff]);
+        assert_eq!(starts(&slots), vec!["2025-06-02T10:00:00+00:00"]);
+    }
+
+    #[test]
+    fn slot_right_after_off_grid_booking_is_offered() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(11, 0))]);
+        staff.busy.push(busy("2025-06-02T09:00:00Z", "2025-06-02T09:20:00Z"));
+
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules(30, 30)), &[staff]);
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-06-02T09:20:00+00:00",
+                "2025-06-02T09:30:00+00:00",
+                "2025-06-02T10:00:00+00:00",
+                "2025-06-02T10:30:00+00:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn buffers_keep_distance_from_busy_intervals() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(12, 0))]);
+        staff.busy.push(busy("2025-06-02T10:00:00Z", "2025-06-02T10:30:00Z"));
+
+        let rules = SlotRules {
+            buffer_before: Duration::minutes(15),
+            buffer_after: Duration::minutes(15),
+            ..rules(30, 30)
+        };
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules), &[staff]);
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-06-02T09:00:00+00:00",
+                "2025-06-02T10:45:00+00:00",
+                "2025-06-02T11:00:00+00:00",
+                "2025-06-02T11:30:00+00:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn group_session_with_free_seats_stays_available() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(11, 0))]);
+        staff.sessions.push(session(7, "2025-06-02T09:00:00Z", "2025-06-02T10:00:00Z", 2));
+        staff.busy.push(BusyInterval {
+            session_id: Some(Uuid::from_u128(7)),
+            ..busy("2025-06-02T09:00:00Z", "2025-06-02T10:00:00Z")
+        });
+
+        let group = SlotRules { capacity: 3, ..rules(60, 30) };
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, group), &[staff]);
+
+        // К занятию в 09:00 можно присоединиться, пересекающиеся с ним времена заняты
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-06-02T09:00:00+00:00", "2025-06-02T10:00:00+00:00"]
+        );
+        assert_eq!(slots[0].session_id, Some(Uuid::from_u128(7)));
+        assert_eq!(slots[0].seats_left, 1);
+        assert_eq!((slots[1].session_id, slots[1].seats_left), (None, 3));
+    }
+
+    #[test]
+    fn full_group_session_is_hidden() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(10, 0))]);
+        staff.sessions.push(session(7, "2025-06-02T09:00:00Z", "2025-06-02T10:00:00Z", 3));
+        staff.busy.push(BusyInterval {
+            session_id: Some(Uuid::from_u128(7)),
+            ..busy("2025-06-02T09:00:00Z", "2025-06-02T10:00:00Z")
+        });
+
+        let group = SlotRules { capacity: 3, ..rules(60, 60) };
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, group), &[staff]);
+        assert!(slots.is_empty());
+    }
+
+    #[test]
+    fn any_free_resource_of_a_requirement_will_do() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut req = request(chrono_tz::UTC, day, rules(60, 60));
+        req.resources = vec![vec![
+            resource(1, location(), &[("2025-06-02T09:00:00Z", "2025-06-02T11:00:00Z")]),
+            resource(2, location(), &[("2025-06-02T10:00:00Z", "2025-06-02T11:00:00Z")]),
+            resource(3, Uuid::from_u128(101), &[]),
+        ]];
+
+        let slots = compute_available_slots(&req, &[member(vec![weekly(1, time(9, 0), time(12, 0))])]);
+
+        // В 10:00 заняты оба кабинета филиала; свободный кабинет другого филиала не подходит
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-06-02T09:00:00+00:00", "2025-06-02T11:00:00+00:00"]
+        );
+        assert_eq!(slots[0].resource_ids, vec![Uuid::from_u128(2)]);
+        assert_eq!(slots[1].resource_ids, vec![Uuid::from_u128(1)]);
+    }
+
+    #[test]
+    fn every_resource_requirement_must_be_met() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut req = request(chrono_tz::UTC, day, rules(60, 60));
+        req.resources = vec![
+            vec![resource(1, location(), &[])],
+            vec![resource(2, location(), &[("2025-06-02T09:30:00Z", "2025-06-02T10:30:00Z")])],
+        ];
+
+        let slots = compute_available_slots(&req, &[member(vec![weekly(1, time(9, 0), time(12, 0))])]);
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-06-02T10:30:00+00:00", "2025-06-02T11:00:00+00:00"]
+        );
+        assert_eq!(slots[0].resource_ids, vec![Uuid::from_u128(1), Uuid::from_u128(2)]);
+    }
+
+    #[test]
+    fn slots_in_the_past_are_skipped() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut req = request(chrono_tz::UTC, day, rules(60, 60));
+        req.now = utc("2025-06-02T10:30:00Z");
+
+        let slots = compute_available_slots(&req, &[member(vec![weekly(1, time(9, 0), time(13, 0))])]);
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-06-02T11:00:00+00:00", "2025-06-02T12:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn same_wall_clock_time_shifts_utc_across_dst() {
+        let tz = chrono_tz::Europe::Berlin;
+        let mut req = request(tz, date(2025, 3, 29), rules(60, 60));
+        req.to = date(2025, 3, 31);
+        let slots = compute_available_slots(
+            &req,
+            &[member(vec![
+                weekly(6, time(9, 0), time(10, 0)),
+                weekly(1, time(9, 0), time(10, 0)),
+            ])],
+        );
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-03-29T08:00:00+00:00", "2025-03-31T07:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn spring_forward_day_is_one_hour_shorter() {
+        // 2025-03-30 в Берлине: 02:00 CET -> 03:00 CEST
+        let tz = chrono_tz::Europe::Berlin;
+        let slots = compute_available_slots(
+            &request(tz, date(2025, 3, 30), rules(60, 60)),
+            &[member(vec![weekly(7, time(1, 0), time(5, 0))])],
+        );
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-03-30T00:00:00+00:00",
+                "2025-03-30T01:00:00+00:00",
+                "2025-03-30T02:00:00+00:00",
+            ]
+        );
+        let local: Vec<String> = slots
+            .iter()
+            .map(|s| s.start.with_timezone(&tz).format("%H:%M").to_string())
+            .collect();
+        assert_eq!(local, vec!["01:00", "03:00", "04:00"]);
+    }
+
+    #[test]
+    fn working_minutes_follow_breaks_overlaps_and_dst() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let mut staff = member(vec![weekly(1, time(9, 0), time(13, 0)), weekly(1, time(12, 0), time(14, 0))]);
+        staff.exceptions.push(ScheduleException {
+            staff_id: staff_id(),
+            exception_date: day,
+            start_time: Some(time(10, 0)),
+            end_time: Some(time(11, 0)),
+            is_available: false,
+            location_id: None,
+        });
+        assert_eq!(working_minutes(chrono_tz::UTC, day, &staff), 240);
+
+        let berlin = member(vec![weekly(7, time(1, 0), time(5, 0))]);
+        assert_eq!(working_minutes(chrono_tz::Europe::Berlin, date(2025, 3, 30), &berlin), 180);
+        assert_eq!(working_minutes(chrono_tz::Europe::Berlin, date(2025, 3, 31), &berlin), 0);
+    }
+
+    #[test]
+    fn schedule_starting_inside_dst_gap_starts_after_transition() {
+        let tz = chrono_tz::Europe::Berlin;
+        let slots = compute_available_slots(
+            &request(tz, date(2025, 3, 30), rules(60, 60)),
+            &[member(vec![weekly(7, time(2, 30), time(5, 0))])],
+        );
+        assert_eq!(
+            starts(&slots),
+            vec!["2025-03-30T01:00:00+00:00", "2025-03-30T02:00:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn fall_back_day_is_one_hour_longer() {
+        // 2025-10-26 в Берлине: 03:00 CEST -> 02:00 CET
+        let tz = chrono_tz::Europe::Berlin;
+        let slots = compute_available_slots(
+            &request(tz, date(2025, 10, 26), rules(60, 60)),
+            &[member(vec![weekly(7, time(1, 0), time(4, 0))])],
+        );
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-10-25T23:00:00+00:00",
+                "2025-10-26T00:00:00+00:00",
+                "2025-10-26T01:00:00+00:00",
+                "2025-10-26T02:00:00+00:00",
+            ]
+        );
+        let local: Vec<String> = slots
+            .iter()
+            .map(|s| s.start.with_timezone(&tz).format("%H:%M%:z").to_string())
+            .collect();
+        assert_eq!(local, vec!["01:00+02:00", "02:00+02:00", "02:00+01:00", "03:00+01:00"]);
+    }
+
+    #[test]
+    fn ambiguous_end_time_uses_later_instant() {
+        let tz = chrono_tz::Europe::Berlin;
+        let slots = compute_available_slots(
+            &request(tz, date(2025, 10, 26), rules(30, 30)),
+            &[member(vec![weekly(7, time(1, 30), time(2, 30))])],
+        );
+        // 01:30 CEST .. 02:30 CET - два часа реального времени
+        assert_eq!(slots.len(), 4);
+        assert_eq!(slots.last().unwrap().end, utc("2025-10-26T01:30:00Z"));
+    }
+
+    #[test]
+    fn busy_interval_across_dst_transition() {
+        let tz = chrono_tz::Europe::Berlin;
+        let mut staff = member(vec![weekly(7, time(1, 0), time(4, 0))]);
+        // Занято с 02:00 CEST до 02:00 CET
+        staff.busy.push(busy("2025-10-26T00:00:00Z", "2025-10-26T01:00:00Z"));
+
+        let slots = compute_available_slots(&request(tz, date(2025, 10, 26), rules(60, 60)), &[staff]);
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-10-25T23:00:00+00:00",
+                "2025-10-26T01:00:00+00:00",
+                "2025-10-26T02:00:00+00:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn overlapping_schedule_entries_do_not_duplicate_slots() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let slots = compute_available_slots(
+            &request(chrono_tz::UTC, day, rules(60, 60)),
+            &[member(vec![
+                weekly(1, time(9, 0), time(11, 0)),
+                weekly(1, time(10, 0), time(12, 0)),
+            ])],
+        );
+        assert_eq!(
+            starts(&slots),
+            vec![
+                "2025-06-02T09:00:00+00:00",
+                "2025-06-02T10:00:00+00:00",
+                "2025-06-02T11:00:00+00:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn slots_of_several_staff_are_merged_by_time() {
+        let day = date(MONDAY.0, MONDAY.1, MONDAY.2);
+        let first = member(vec![weekly(1, time(10, 0), time(11, 0))]);
+        let mut second = member(vec![weekly(1, time(9, 0), time(10, 0))]);
+        second.staff_id = Uuid::from_u128(2);
+
+        let slots = compute_available_slots(&request(chrono_tz::UTC, day, rules(60, 60)), &[first, second]);
+        let staff: Vec<Uuid> = slots.iter().map(|s| s.staff_id).collect();
+        assert_eq!(staff, vec![Uuid::from_u128(2), Uuid::from_u128(1)]);
+    }
+}
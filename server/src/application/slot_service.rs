@@ -0,0 +1,245 @@
+use crate::application::slot_engine::{
+    compute_available_slots, ResourceAvailability, SlotRequest, SlotRules, StaffAvailability,
+};
+use crate::domain::entities::{Company, Service, SlotEvent, SlotInfo, SlotStreamQuery, SlotsQuery, SlotsResponse};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, CompanyRepository, SlotEventBus, SlotService};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use futures_util::stream::{BoxStream, StreamExt};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Максимальная длина запрашиваемого периода в днях
+const MAX_RANGE_DAYS: i64 = 31;
+
+pub struct SlotServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+    event_bus: Option<Arc<dyn SlotEventBus + Send + Sync>>,
+}
+
+impl SlotServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            booking_repository,
+            event_bus: None,
+        }
+    }
+
+    pub fn with_events(mut self, event_bus: Arc<dyn SlotEventBus + Send + Sync>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+}
+
+pub(crate) fn company_timezone(company: &Company) -> Result<Tz, AppError> {
+    company
+        .timezone
+        .parse()
+        .map_err(|_| AppError::Internal(format!("Неизвестный часовой пояс компании: {}", company.timezone)))
+}
+
+pub(crate) fn slot_rules(service: &Service) -> SlotRules {
+    SlotRules {
+        duration: Duration::minutes(service.duration_minutes as i64),
+        buffer_before: Duration::minutes(service.buffer_before_minutes as i64),
+        buffer_after: Duration::minutes(service.buffer_after_minutes as i64),
+        step: Duration::minutes(service.slot_step_minutes as i64),
+        capacity: service.capacity,
+    }
+}
+
+/// Период выборки занятости для дат `from..=to`: берем с запасом в сутки,
+/// так как границы дат зависят от часового пояса
+fn busy_range(from: NaiveDate, to: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    (
+        from.and_time(NaiveTime::MIN).and_utc() - Duration::days(1),
+        to.and_time(NaiveTime::MIN).and_utc() + Duration::days(2),
+    )
+}
+
+/// Собрать расписания, исключения, занятое время сотрудников и занятия групповой услуги за период
+pub(crate) async fn load_staff_availability(
+    company_repository: &(dyn CompanyRepository + Send + Sync),
+    booking_repository: &(dyn BookingRepository + Send + Sync),
+    service: &Service,
+    staff_ids: &[Uuid],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<StaffAvailability>, AppError> {
+    let weekly = company_repository.list_weekly_schedules(staff_ids).await?;
+    let exceptions = company_repository
+        .list_schedule_exceptions(staff_ids, from, to)
+        .await?;
+
+    let (busy_from, busy_to) = busy_range(from, to);
+    let busy = booking_repository
+        .list_busy_intervals(staff_ids, busy_from, busy_to)
+        .await?;
+    let sessions = if service.capacity > 1 {
+        booking_repository
+            .list_group_sessions(service.id, staff_ids, busy_from, busy_to)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(staff_ids
+        .iter()
+        .map(|staff_id| StaffAvailability {
+            staff_id: *staff_id,
+            weekly: weekly.iter().filter(|s| s.staff_id == *staff_id).cloned().collect(),
+            exceptions: exceptions.iter().filter(|e| e.staff_id == *staff_id).cloned().collect(),
+            busy: busy.iter().filter(|b| b.staff_id == *staff_id).cloned().collect(),
+            sessions: sessions.iter().filter(|s| s.staff_id == *staff_id).cloned().collect(),
+        })
+        .collect())
+}
+
+/// Варианты ресурсов по каждому требованию услуги с их занятостью за период
+pub(crate) async fn load_resource_availability(
+    company_repository: &(dyn CompanyRepository + Send + Sync),
+    booking_repository: &(dyn BookingRepository + Send + Sync),
+    service: &Service,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Vec<ResourceAvailability>>, AppError> {
+    let requirements = company_repository.list_service_requirements(service.id).await?;
+    if requirements.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let resource_ids: Vec<Uuid> = requirements
+        .iter()
+        .flat_map(|requirement| requirement.resources.iter().map(|resource| resource.id))
+        .collect();
+    let (busy_from, busy_to) = busy_range(from, to);
+    let busy = booking_repository
+        .list_resource_busy(&resource_ids, busy_from, busy_to)
+        .await?;
+
+    Ok(requirements
+        .iter()
+        .map(|requirement| {
+            requirement
+                .resources
+                .iter()
+                .map(|resource| ResourceAvailability {
+                    resource_id: resource.id,
+                    location_id: resource.location_id,
+                    busy: busy.iter().filter(|b| b.resource_id == resource.id).cloned().collect(),
+                })
+                .collect()
+        })
+        .collect())
+}
+
+#[async_trait]
+impl SlotService for SlotServiceImpl {
+    async fn available_slots(&self, company_id: Uuid, query: SlotsQuery) -> Result<SlotsResponse, AppError> {
+        if query.to < query.from {
+            return Err(AppError::Validation("Дата окончания раньше даты начала".to_string()));
+        }
+        if (query.to - query.from).num_days() >= MAX_RANGE_DAYS {
+            return Err(AppError::Validation(format!(
+                "Период не может быть больше {} дней",
+                MAX_RANGE_DAYS
+            )));
+        }
+
+        let company = self
+            .company_repository
+            .find_company(company_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Компания не найдена".to_string()))?;
+        let timezone = company_timezone(&company)?;
+
+        let service = self
+            .company_repository
+            .find_service(company_id, query.service)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Услуга не найдена".to_string()))?;
+
+        let mut staff = self.company_repository.list_staff_for_service(service.id).await?;
+        if let Some(staff_id) = query.staff {
+            staff.retain(|member| member.id == staff_id);
+            if staff.is_empty() {
+                return Err(AppError::NotFound("Сотрудник не оказывает эту услугу".to_string()));
+            }
+        }
+
+        let staff_ids: Vec<Uuid> = staff.iter().map(|member| member.id).collect();
+        let availability = load_staff_availability(
+            self.company_repository.as_ref(),
+            self.booking_repository.as_ref(),
+            &service,
+            &staff_ids,
+            query.from,
+            query.to,
+        )
+        .await?;
+        let resources = load_resource_availability(
+            self.company_repository.as_ref(),
+            self.booking_repository.as_ref(),
+            &service,
+            query.from,
+            query.to,
+        )
+        .await?;
+
+        let request = SlotRequest {
+            timezone,
+            from: query.from,
+            to: query.to,
+            now: Utc::now(),
+            rules: slot_rules(&service),
+            resources,
+        };
+
+        let slots = compute_available_slots(&request, &availability)
+            .into_iter()
+            .map(|slot| SlotInfo {
+                staff_id: slot.staff_id,
+                location_id: slot.location_id,
+                starts_at: slot.start.with_timezone(&timezone).fixed_offset(),
+                ends_at: slot.end.with_timezone(&timezone).fixed_offset(),
+                capacity: slot.capacity,
+                seats_left: slot.seats_left,
+            })
+            .collect();
+
+        Ok(SlotsResponse {
+            company_id,
+            service_id: service.id,
+            timezone: company.timezone,
+            slots,
+        })
+    }
+
+    async fn slot_events(
+        &self,
+        company_id: Uuid,
+        query: SlotStreamQuery,
+    ) -> Result<BoxStream<'static, SlotEvent>, AppError> {
+        let Some(event_bus) = &self.event_bus else {
+            return Err(AppError::Internal("Поток событий слотов не настроен".to_string()));
+        };
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+
+        // Подписка оформляется до ответа, чтобы не потерять события между запросом и чтением потока
+        let events = event_bus.subscribe(company_id).filter(move |event| {
+            let matches = query.staff.is_none_or(|staff_id| staff_id == event.staff_id)
+                && query.location.is_none_or(|location_id| location_id == event.location_id);
+            futures_util::future::ready(matches)
+        });
+        Ok(events.boxed())
+    }
+}
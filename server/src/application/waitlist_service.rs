@@ -0,0 +1,261 @@
+use crate::application::booking_context::BookingContext;
+use crate::application::slot_engine::{compute_available_slots, SlotRequest};
+use crate::application::slot_service::{
+    company_timezone, load_resource_availability, load_staff_availability, slot_rules,
+};
+use crate::domain::entities::{
+    Booking, BookingStatus, FreedSlot, JoinWaitlistRequest, NewBooking, NewWaitlistEntry, WaitlistEntry,
+    WaitlistStatus,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{BookingRepository, Clock, CompanyRepository, WaitlistRepository, WaitlistService};
+use async_trait::async_trait;
+use chrono::Duration;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Сколько минут клиент из листа ожидания может подтвердить предложенный слот
+pub const DEFAULT_WAITLIST_OFFER_TTL_MINUTES: i64 = 30;
+
+/// Предложить время отмененной записи листу ожидания. Ошибка не отменяет саму отмену,
+/// поэтому только пишется в журнал
+pub(crate) async fn release_to_waitlist(waitlist_service: &(dyn WaitlistService + Send + Sync), booking: &Booking) {
+    let slot = FreedSlot {
+        company_id: booking.company_id,
+        staff_id: booking.staff_id,
+        starts_at: booking.starts_at,
+    };
+    if let Err(e) = waitlist_service.offer_freed_slot(slot).await {
+        eprintln!("Ошибка предложения слота из листа ожидания: {}", e);
+    }
+}
+
+pub struct WaitlistServiceImpl {
+    context: BookingContext,
+    waitlist_repository: Arc<dyn WaitlistRepository + Send + Sync>,
+    offer_ttl: Duration,
+}
+
+impl WaitlistServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        booking_repository: Arc<dyn BookingRepository + Send + Sync>,
+        waitlist_repository: Arc<dyn WaitlistRepository + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            context: BookingContext::new(company_repository, booking_repository, clock),
+            waitlist_repository,
+            offer_ttl: Duration::minutes(DEFAULT_WAITLIST_OFFER_TTL_MINUTES),
+        }
+    }
+
+    pub fn with_offer_ttl(mut self, offer_ttl: Duration) -> Self {
+        self.offer_ttl = offer_ttl;
+        self
+    }
+
+    async fn load_customer_entry(&self, customer_id: Uuid, entry_id: Uuid) -> Result<WaitlistEntry, AppError> {
+        self.waitlist_repository
+            .find_entry(entry_id)
+            .await?
+            .filter(|entry| entry.customer_id == customer_id)
+            .ok_or_else(|| AppError::NotFound("Запись в листе ожидания не найдена".to_string()))
+    }
+
+    /// Слот, от которого отказались или не успели подтвердить, уходит следующему в очереди
+    async fn pass_offer_on(&self, entry: &WaitlistEntry) {
+        let (Some(staff_id), Some(starts_at)) = (entry.offered_staff_id, entry.offered_starts_at) else {
+            return;
+        };
+        let slot = FreedSlot {
+            company_id: entry.company_id,
+            staff_id,
+            starts_at,
+        };
+        if let Err(e) = self.offer_freed_slot(slot).await {
+            eprintln!("Ошибка предложения слота из листа ожидания: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl WaitlistService for WaitlistServiceImpl {
+    async fn join_waitlist(&self, customer_id: Uuid, data: JoinWaitlistRequest) -> Result<WaitlistEntry, AppError> {
+        let waitlist = &self.waitlist_repository;
+        let company = self.context.require_company(data.company_id).await?;
+        let service = self.context.require_service(company.id, data.service_id).await?;
+        let timezone = company_timezone(&company)?;
+        self.context.new_booking_status(company.id, customer_id).await?;
+
+        if data.date < self.context.clock.now().with_timezone(&timezone).date_naive() {
+            return Err(AppError::Validation("Нельзя встать в очередь на прошедший день".to_string()));
+        }
+        if let Some(location_id) = data.location_id
+            && self.context.company_repository.find_location(company.id, location_id).await?.is_none()
+        {
+            return Err(AppError::NotFound("Филиал не найден".to_string()));
+        }
+
+        let staff = match data.staff_id {
+            Some(staff_id) => vec![self.context.require_staff_for(company.id, staff_id, &service).await?],
+            None => self.context.company_repository.list_staff_for_service(service.id).await?,
+        };
+
+        // Очередь нужна только тогда, когда записаться напрямую уже некуда
+        let staff_ids: Vec<Uuid> = staff.iter().map(|member| member.id).collect();
+        let availability = load_staff_availability(
+            self.context.company_repository.as_ref(),
+            self.context.booking_repository.as_ref(),
+            &service,
+            &staff_ids,
+            data.date,
+            data.date,
+        )
+        .await?;
+        let resources = load_resource_availability(
+            self.context.company_repository.as_ref(),
+            self.context.booking_repository.as_ref(),
+            &service,
+            data.date,
+            data.date,
+        )
+        .await?;
+        let request = SlotRequest {
+            timezone,
+            from: data.date,
+            to: data.date,
+            now: self.context.clock.now(),
+            rules: slot_rules(&service),
+            resources,
+        };
+        let has_free_slots = compute_available_slots(&request, &availability)
+            .iter()
+            .any(|slot| data.location_id.is_none_or(|location_id| location_id == slot.location_id));
+        if has_free_slots {
+            return Err(AppError::Conflict("На этот день есть свободное время, запишитесь напрямую".to_string()));
+        }
+
+        waitlist
+            .create_entry(&NewWaitlistEntry {
+                customer_id,
+                company_id: company.id,
+                service_id: service.id,
+                staff_id: data.staff_id,
+                location_id: data.location_id,
+                desired_date: data.date,
+            })
+            .await
+    }
+
+    async fn list_waitlist(&self, customer_id: Uuid) -> Result<Vec<WaitlistEntry>, AppError> {
+        self.waitlist_repository.list_customer_entries(customer_id).await
+    }
+
+    async fn leave_waitlist(&self, customer_id: Uuid, entry_id: Uuid) -> Result<(), AppError> {
+        let closed = self
+            .waitlist_repository
+            .close_entry(entry_id, customer_id, WaitlistStatus::Cancelled)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Запись в листе ожидания не найдена".to_string()))?;
+
+        self.pass_offer_on(&closed).await;
+        Ok(())
+    }
+
+    async fn accept_offer(&self, customer_id: Uuid, entry_id: Uuid) -> Result<Booking, AppError> {
+        let entry = self.load_customer_entry(customer_id, entry_id).await?;
+        let hold_id = match (entry.status, entry.hold_id) {
+            (WaitlistStatus::Offered, Some(hold_id)) => hold_id,
+            _ => return Err(AppError::Conflict("Нет действующего предложения".to_string())),
+        };
+
+        let service = self.context.require_service(entry.company_id, entry.service_id).await?;
+        let status = self.context.initial_booking_status(entry.company_id, customer_id, &service).await?;
+
+        self.waitlist_repository
+            .accept_offer(entry.id, hold_id, customer_id, status, self.context.clock.now())
+            .await
+    }
+
+    async fn decline_offer(&self, customer_id: Uuid, entry_id: Uuid) -> Result<(), AppError> {
+        let entry = self.load_customer_entry(customer_id, entry_id).await?;
+        if entry.status != WaitlistStatus::Offered {
+            return Err(AppError::Conflict("Нет действующего предложения".to_string()));
+        }
+
+        let closed = self
+            .waitlist_repository
+            .close_entry(entry.id, customer_id, WaitlistStatus::Declined)
+            .await?
+            .ok_or_else(|| AppError::Conflict("Нет действующего предложения".to_string()))?;
+
+        self.pass_offer_on(&closed).await;
+        Ok(())
+    }
+
+    async fn offer_freed_slot(&self, slot: FreedSlot) -> Result<Option<WaitlistEntry>, AppError> {
+        let waitlist = &self.waitlist_repository;
+        let company = self.context.require_company(slot.company_id).await?;
+        let timezone = company_timezone(&company)?;
+        let Some(staff) = self.context.company_repository.find_staff(company.id, slot.staff_id).await? else {
+            return Ok(None);
+        };
+
+        let now = self.context.clock.now();
+        let date = slot.starts_at.with_timezone(&timezone).date_naive();
+        for entry in waitlist.list_waiting(company.id, date, staff.id).await? {
+            if !staff.service_ids.contains(&entry.service_id) {
+                continue;
+            }
+            let Some(service) = self.context.company_repository.find_service(company.id, entry.service_id).await? else {
+                continue;
+            };
+
+            // Услуга из очереди может не поместиться в освободившееся время
+            let resolved = match self.context
+                .resolve_slot(&company, &service, &staff, slot.starts_at, entry.location_id, &[])
+                .await
+            {
+                Ok(resolved) => resolved,
+                Err(AppError::Validation(_)) | Err(AppError::Conflict(_)) => continue,
+                Err(e) => return Err(e),
+            };
+
+            let hold = NewBooking {
+                customer_id: entry.customer_id,
+                company_id: company.id,
+                service_id: service.id,
+                staff_id: staff.id,
+                location_id: resolved.slot.location_id,
+                starts_at: resolved.slot.start,
+                ends_at: resolved.slot.end,
+                blocked_from: resolved.blocked_from,
+                blocked_to: resolved.blocked_to,
+                status: BookingStatus::Held,
+                capacity: service.capacity,
+                resource_ids: resolved.slot.resource_ids.clone(),
+                promo: None,
+            };
+            // Предложение не переживает начало приема
+            let expires_at = (now + self.offer_ttl).min(resolved.slot.start);
+            match waitlist.make_offer(entry.id, &hold, expires_at).await {
+                // Уведомление о предложении ставится в outbox в той же транзакции
+                Ok(Some(offered)) => return Ok(Some(offered)),
+                Ok(None) => continue,
+                Err(AppError::Conflict(_)) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn process_expired_offers(&self) -> Result<usize, AppError> {
+        let expired = self.waitlist_repository.expire_offers(self.context.clock.now()).await?;
+        for entry in &expired {
+            self.pass_offer_on(entry).await;
+        }
+        Ok(expired.len())
+    }
+}
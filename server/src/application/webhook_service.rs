@@ -0,0 +1,179 @@
+use crate::application::webhook_target::check_public_url;
+use crate::domain::entities::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookDeliveriesQuery, WebhookDelivery, WebhookEvent,
+    WebhookSubscription, WebhookSubscriptionResponse,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{CompanyRepository, WebhookRepository, WebhookService};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Сколько вебхуков может быть у компании
+pub const MAX_WEBHOOKS_PER_COMPANY: usize = 10;
+
+const MAX_URL_LENGTH: usize = 2048;
+
+pub struct WebhookServiceImpl {
+    company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+    webhook_repository: Arc<dyn WebhookRepository + Send + Sync>,
+    allow_private_urls: bool,
+}
+
+/// Секрет подписи: 244 случайных бита в hex с узнаваемым префиксом
+fn generate_secret() -> String {
+    format!("whsec_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn validate_url(url: &str) -> Result<(), AppError> {
+    let valid_scheme = url.starts_with("https://") || url.starts_with("http://");
+    if !valid_scheme || url.len() > MAX_URL_LENGTH || url.chars().any(char::is_whitespace) {
+        return Err(AppError::Validation(
+            "Адрес вебхука должен быть ссылкой http:// или https://".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// События подписки без повторов; проверочное событие отправляется только по запросу
+fn validate_events(events: &[WebhookEvent]) -> Result<Vec<WebhookEvent>, AppError> {
+    if events.is_empty() {
+        return Err(AppError::Validation("Укажите хотя бы одно событие".to_string()));
+    }
+    let mut unique = Vec::with_capacity(events.len());
+    for event in events {
+        if *event == WebhookEvent::Test {
+            return Err(AppError::Validation(
+                "На проверочное событие нельзя подписаться".to_string(),
+            ));
+        }
+        if !unique.contains(event) {
+            unique.push(*event);
+        }
+    }
+    Ok(unique)
+}
+
+impl WebhookServiceImpl {
+    pub fn new(
+        company_repository: Arc<dyn CompanyRepository + Send + Sync>,
+        webhook_repository: Arc<dyn WebhookRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            company_repository,
+            webhook_repository,
+            allow_private_urls: false,
+        }
+    }
+
+    /// Разрешить адреса внутренней сети и loopback - для разработки с локальным получателем
+    pub fn with_private_urls(mut self, allowed: bool) -> Self {
+        self.allow_private_urls = allowed;
+        self
+    }
+
+    async fn check_url(&self, url: &str) -> Result<(), AppError> {
+        validate_url(url)?;
+        if !self.allow_private_urls {
+            check_public_url(url).await?;
+        }
+        Ok(())
+    }
+
+    /// Интеграции настраивают только руководители компании
+    async fn require_manager(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError> {
+        if self.company_repository.find_company(company_id).await?.is_none() {
+            return Err(AppError::NotFound("Компания не найдена".to_string()));
+        }
+        match self.company_repository.get_member_role(company_id, user_id).await? {
+            Some(role) if role.can_manage() => Ok(()),
+            _ => Err(AppError::Forbidden(
+                "Недостаточно прав для управления компанией".to_string(),
+            )),
+        }
+    }
+
+    async fn find_webhook(&self, company_id: Uuid, webhook_id: Uuid) -> Result<WebhookSubscription, AppError> {
+        match self.webhook_repository.find_subscription(webhook_id).await? {
+            Some(webhook) if webhook.company_id == company_id => Ok(webhook),
+            _ => Err(AppError::NotFound("Вебхук не найден".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl WebhookService for WebhookServiceImpl {
+    async fn create_webhook(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateWebhookRequest,
+    ) -> Result<WebhookSubscriptionResponse, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.check_url(&data.url).await?;
+        let events = validate_events(&data.events)?;
+        if self.webhook_repository.list_subscriptions(company_id).await?.len() >= MAX_WEBHOOKS_PER_COMPANY {
+            return Err(AppError::Conflict(format!(
+                "У компании может быть не больше {} вебхуков",
+                MAX_WEBHOOKS_PER_COMPANY
+            )));
+        }
+
+        let secret = generate_secret();
+        let subscription = self
+            .webhook_repository
+            .create_subscription(company_id, &data.url, &events, &secret)
+            .await?;
+        Ok(WebhookSubscriptionResponse { subscription, secret })
+    }
+
+    async fn list_webhooks(&self, user_id: Uuid, company_id: Uuid) -> Result<Vec<WebhookSubscription>, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.webhook_repository.list_subscriptions(company_id).await
+    }
+
+    async fn update_webhook(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        webhook_id: Uuid,
+        data: UpdateWebhookRequest,
+    ) -> Result<WebhookSubscription, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.find_webhook(company_id, webhook_id).await?;
+        if let Some(url) = &data.url {
+            self.check_url(url).await?;
+        }
+        let events = data.events.as_deref().map(validate_events).transpose()?;
+
+        self.webhook_repository
+            .update_subscription(webhook_id, data.url.as_deref(), events.as_deref(), data.is_active)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Вебхук не найден".to_string()))
+    }
+
+    async fn delete_webhook(&self, user_id: Uuid, company_id: Uuid, webhook_id: Uuid) -> Result<(), AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.find_webhook(company_id, webhook_id).await?;
+        self.webhook_repository.delete_subscription(webhook_id).await?;
+        Ok(())
+    }
+
+    async fn list_deliveries(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        webhook_id: Uuid,
+        query: WebhookDeliveriesQuery,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.find_webhook(company_id, webhook_id).await?;
+        self.webhook_repository.list_deliveries(webhook_id, &query).await
+    }
+
+    async fn send_test_event(&self, user_id: Uuid, company_id: Uuid, webhook_id: Uuid) -> Result<WebhookDelivery, AppError> {
+        self.require_manager(user_id, company_id).await?;
+        self.find_webhook(company_id, webhook_id).await?;
+        self.webhook_repository.enqueue_test_delivery(webhook_id).await
+    }
+}
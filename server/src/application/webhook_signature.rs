@@ -0,0 +1,52 @@
+//! Подпись запросов вебхуков. Получатель считает HMAC-SHA256 от строки
+//! `{timestamp}.{тело запроса}` секретом подписки и сравнивает с заголовком
+//! `X-LocationX-Signature: sha256=<hex>`; метка времени из `X-LocationX-Timestamp`
+//! (секунды Unix) защищает от повторной отправки перехваченного запроса.
+
+use crate::application::hmac_signature;
+
+pub const SIGNATURE_HEADER: &str = "X-LocationX-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-LocationX-Timestamp";
+pub const EVENT_HEADER: &str = "X-LocationX-Event";
+pub const DELIVERY_HEADER: &str = "X-LocationX-Delivery";
+
+/// Значение заголовка подписи для тела запроса
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let timestamp = timestamp.to_string();
+    let signature = hmac_signature::sign_hex(secret.as_bytes(), &[timestamp.as_bytes(), b".", body.as_bytes()]);
+    format!("sha256={}", signature)
+}
+
+/// Проверить подпись за постоянное время
+pub fn verify(secret: &str, timestamp: i64, body: &str, signature: &str) -> bool {
+    let Some(signature) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    let timestamp = timestamp.to_string();
+    hmac_signature::verify_hex(secret.as_bytes(), &[timestamp.as_bytes(), b".", body.as_bytes()], signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_is_hmac_of_timestamp_and_body() {
+        let signature = sign("whsec_test", 1717315200, r#"{"type":"webhook.test"}"#);
+        assert_eq!(
+            signature,
+            "sha256=56ea50f7c866ccee72d9326f2f68d4b2eb7e93176a1e758286973a93bde6d9ce"
+        );
+    }
+
+    #[test]
+    fn verify_rejects_changed_body_timestamp_or_secret() {
+        let body = r#"{"type":"booking.created"}"#;
+        let signature = sign("secret", 100, body);
+        assert!(verify("secret", 100, body, &signature));
+        assert!(!verify("secret", 101, body, &signature));
+        assert!(!verify("other", 100, body, &signature));
+        assert!(!verify("secret", 100, r#"{"type":"booking.cancelled"}"#, &signature));
+        assert!(!verify("secret", 100, body, "sha256=zz"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Проверка адреса вебхука. Запросы уходят с сервера, поэтому адрес, который указывает
+//! на сам сервер или во внутреннюю сеть (loopback, частные диапазоны, link-local с
+//! метаданными облака), открыл бы компании доступ к внутренним сервисам.
+
+use crate::domain::errors::AppError;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // 100.64.0.0/10 - адреса провайдерского NAT
+    let shared = a == 100 && (64..128).contains(&b);
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || shared)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(mapped) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(mapped);
+    }
+    !(ip.is_loopback() || ip.is_unspecified() || ip.is_unique_local() || ip.is_unicast_link_local() || ip.is_multicast())
+}
+
+/// Адрес из публичной сети, куда можно отправлять вебхуки
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+/// Разрешить имя хоста из адреса и убедиться, что все его адреса публичные
+pub async fn check_public_url(url: &str) -> Result<(), AppError> {
+    let invalid = || AppError::Validation("Адрес вебхука должен быть ссылкой http:// или https://".to_string());
+    let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    let port = parsed.port_or_known_default().ok_or_else(invalid)?;
+    let host = parsed.host_str().ok_or_else(invalid)?;
+    // IPv6 в адресе записывается в квадратных скобках
+    let addresses: Vec<IpAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse() {
+        Ok(ip) => vec![ip],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| AppError::Validation(format!("Не удалось найти адрес сервера {}", host)))?
+            .map(|address| address.ip())
+            .collect(),
+    };
+
+    if addresses.is_empty() || !addresses.into_iter().all(is_public_ip) {
+        return Err(AppError::Validation(
+            "Адрес вебхука указывает на внутреннюю сеть".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["8.8.8.8", "100.128.0.1", "2001:4860:4860::8888", "::ffff:8.8.8.8"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
 
 #[derive(Serialize, Debug, Clone)]
 pub struct DbStatus {
@@ -50,6 +50,8 @@ pub struct User {
     pub first_name: Option<String>,
     pub last_name: Option<String>, 
     pub email: Option<String>,
+    /// Язык уведомлений: ru или en
+    pub locale: String,
     pub user_type_id: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -57,6 +59,22 @@ pub struct User {
     // password_hash не включаем в сериализацию из соображений безопасности
 }
 
+/// Типы пользователей из таблицы user_types
+pub const USER_TYPE_MODERATOR: i32 = 2;
+pub const USER_TYPE_ADMIN: i32 = 3;
+
+impl User {
+    /// Модератор контента; администраторы тоже могут модерировать
+    pub fn is_moderator(&self) -> bool {
+        self.user_type_id >= USER_TYPE_MODERATOR
+    }
+
+    /// Администратор сервиса: admin, owner и god
+    pub fn is_admin(&self) -> bool {
+        self.user_type_id >= USER_TYPE_ADMIN
+    }
+}
+
 // Структуры для регистрации пользователя
 #[derive(Deserialize, Debug)]
 pub struct RegisterUserRequest {
@@ -95,7 +113,9 @@ pub struct UserInfo {
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub email: Option<String>,
+    pub locale: String,
     pub user_type_id: i32,
+    pub avatar: Option<MediaImage>,
 }
 
 // Структуры для обновления токена
@@ -115,6 +135,7 @@ pub struct UpdateUserRequest {
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub email: Option<String>,
+    pub locale: Option<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -160,3 +181,2047 @@ pub struct UsersListResponse {
     pub users: Vec<User>,
     pub total: usize,
 }
+
+// Структуры для компаний
+#[derive(Serialize, Debug, Clone)]
+pub struct Company {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub timezone: String,
+    /// Рубрики каталога в нижнем регистре: "стоматология", "dentistry"
+    pub categories: Vec<String>,
+    /// Средняя оценка по видимым отзывам; пусто, пока отзывов нет
+    pub rating_avg: Option<f64>,
+    pub rating_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateCompanyRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub timezone: String,
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+/// Роль пользователя внутри компании
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompanyRole {
+    Owner,
+    Manager,
+    Staff,
+}
+
+impl CompanyRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompanyRole::Owner => "owner",
+            CompanyRole::Manager => "manager",
+            CompanyRole::Staff => "staff",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "owner" => Some(CompanyRole::Owner),
+            "manager" => Some(CompanyRole::Manager),
+            "staff" => Some(CompanyRole::Staff),
+            _ => None,
+        }
+    }
+
+    /// Может ли роль управлять услугами, сотрудниками и расписанием
+    pub fn can_manage(&self) -> bool {
+        matches!(self, CompanyRole::Owner | CompanyRole::Manager)
+    }
+}
+
+/// Карточка компании для текущего пользователя
+#[derive(Serialize, Debug)]
+pub struct CompanyResponse {
+    #[serde(flatten)]
+    pub company: Company,
+    pub bookmarked: bool,
+    #[serde(flatten)]
+    pub media: CompanyMedia,
+}
+
+/// Краткие сведения о компании для списков
+#[derive(Serialize, Debug, Clone)]
+pub struct CompanySummary {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub timezone: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Bookmark {
+    pub company: CompanySummary,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BookmarksQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BookmarksResponse {
+    pub bookmarks: Vec<Bookmark>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Location {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub name: String,
+    pub address: Option<String>,
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateLocationRequest {
+    pub name: String,
+    pub address: Option<String>,
+    pub city: Option<String>,
+    /// Координаты для поиска по радиусу, задаются вместе
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+// Услуга компании (длительность и буферы в минутах)
+#[derive(Serialize, Debug, Clone)]
+pub struct Service {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub duration_minutes: i32,
+    pub buffer_before_minutes: i32,
+    pub buffer_after_minutes: i32,
+    pub slot_step_minutes: i32,
+    pub price_amount: i64,
+    pub currency: String,
+    /// Депозит при записи в минимальных единицах валюты услуги; `None` - без предоплаты
+    pub deposit_amount: Option<i64>,
+    /// Мест на занятии; больше 1 - групповая услуга
+    pub capacity: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateServiceRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub duration_minutes: i32,
+    pub buffer_before_minutes: Option<i32>,
+    pub buffer_after_minutes: Option<i32>,
+    pub slot_step_minutes: Option<i32>,
+    pub price_amount: Option<i64>,
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub deposit_amount: Option<i64>,
+    pub capacity: Option<i32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StaffMember {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub display_name: String,
+    pub service_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateStaffRequest {
+    pub display_name: String,
+    pub user_id: Option<Uuid>,
+    #[serde(default)]
+    pub service_ids: Vec<Uuid>,
+}
+
+// Ресурс филиала: кабинет или оборудование, которое услуга занимает вместе с сотрудником
+#[derive(Serialize, Debug, Clone)]
+pub struct Resource {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub location_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateResourceRequest {
+    pub location_id: Uuid,
+    pub name: String,
+}
+
+/// Требование услуги к ресурсам: нужен один любой ресурс из `resources`
+#[derive(Serialize, Debug, Clone)]
+pub struct ServiceResourceRequirement {
+    pub resources: Vec<Resource>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResourceRequirementRequest {
+    pub resource_ids: Vec<Uuid>,
+}
+
+/// Полностью заменяет требования услуги к ресурсам
+#[derive(Deserialize, Debug)]
+pub struct UpdateServiceResourcesRequest {
+    pub requirements: Vec<ResourceRequirementRequest>,
+}
+
+// Структуры для расписания сотрудников
+// weekday: 1 - понедельник ... 7 - воскресенье (ISO 8601)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaffSchedule {
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub weekday: i16,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ScheduleEntryRequest {
+    pub location_id: Uuid,
+    pub weekday: i16,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateScheduleRequest {
+    pub entries: Vec<ScheduleEntryRequest>,
+}
+
+/// Исключение из расписания на конкретную дату.
+/// `is_available = false` без времени - выходной на весь день,
+/// со временем - перерыв; `is_available = true` - дополнительные часы.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleException {
+    pub staff_id: Uuid,
+    pub exception_date: NaiveDate,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub is_available: bool,
+    pub location_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateScheduleExceptionRequest {
+    pub exception_date: NaiveDate,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub is_available: bool,
+    pub location_id: Option<Uuid>,
+}
+
+/// Занятый интервал сотрудника в UTC, включая буферы
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusyInterval {
+    pub booking_id: Uuid,
+    pub staff_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Групповое занятие, к которому относится запись
+    pub session_id: Option<Uuid>,
+}
+
+/// Занятость ресурса активной записью (с учетом буферов)
+#[derive(Debug, Clone)]
+pub struct ResourceBusyInterval {
+    pub booking_id: Uuid,
+    pub resource_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub session_id: Option<Uuid>,
+}
+
+/// Занятие групповой услуги: записи на него делят время сотрудника
+#[derive(Serialize, Debug, Clone)]
+pub struct GroupSession {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub capacity: i32,
+    pub seats_taken: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Участник занятия для списка у сотрудников компании
+#[derive(Serialize, Debug, Clone)]
+pub struct SessionAttendee {
+    pub booking_id: Uuid,
+    pub customer_id: Uuid,
+    pub username: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub status: BookingStatus,
+    pub booked_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GroupSessionDetails {
+    #[serde(flatten)]
+    pub session: GroupSession,
+    pub attendees: Vec<SessionAttendee>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SessionsQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub service: Option<Uuid>,
+}
+
+// Структуры для свободных слотов
+#[derive(Deserialize, Debug)]
+pub struct SlotsQuery {
+    pub service: Uuid,
+    pub staff: Option<Uuid>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SlotInfo {
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub starts_at: DateTime<FixedOffset>,
+    pub ends_at: DateTime<FixedOffset>,
+    pub capacity: i32,
+    pub seats_left: i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SlotsResponse {
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub timezone: String,
+    pub slots: Vec<SlotInfo>,
+}
+
+/// Что произошло со временем сотрудника: запись или бронь заняла его либо освободила
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotEventKind {
+    Taken,
+    Freed,
+}
+
+/// Событие потока слотов; поток публичный, поэтому без данных клиента и записи
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SlotEvent {
+    pub kind: SlotEventKind,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Фильтры потока: без них приходят события всей компании
+#[derive(Deserialize, Debug, Default)]
+pub struct SlotStreamQuery {
+    pub staff: Option<Uuid>,
+    pub location: Option<Uuid>,
+}
+
+// Структуры для записей
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingStatus {
+    /// Временная бронь слота на время оформления, см. `SlotHold`
+    Held,
+    Pending,
+    Confirmed,
+    CheckedIn,
+    Completed,
+    CancelledByCustomer,
+    CancelledByCompany,
+    NoShow,
+}
+
+impl BookingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookingStatus::Held => "held",
+            BookingStatus::Pending => "pending",
+            BookingStatus::Confirmed => "confirmed",
+            BookingStatus::CheckedIn => "checked_in",
+            BookingStatus::Completed => "completed",
+            BookingStatus::CancelledByCustomer => "cancelled_by_customer",
+            BookingStatus::CancelledByCompany => "cancelled_by_company",
+            BookingStatus::NoShow => "no_show",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "held" => Some(BookingStatus::Held),
+            "pending" => Some(BookingStatus::Pending),
+            "confirmed" => Some(BookingStatus::Confirmed),
+            "checked_in" => Some(BookingStatus::CheckedIn),
+            "completed" => Some(BookingStatus::Completed),
+            "cancelled_by_customer" => Some(BookingStatus::CancelledByCustomer),
+            "cancelled_by_company" => Some(BookingStatus::CancelledByCompany),
+            "no_show" => Some(BookingStatus::NoShow),
+            _ => None,
+        }
+    }
+
+    /// Занимает ли запись время сотрудника
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            BookingStatus::Pending | BookingStatus::Confirmed | BookingStatus::CheckedIn
+        )
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(
+            self,
+            BookingStatus::CancelledByCustomer | BookingStatus::CancelledByCompany
+        )
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Booking {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub status: BookingStatus,
+    /// Серия, к которой относится запись
+    pub series_id: Option<Uuid>,
+    /// Групповое занятие, место на котором занимает запись
+    pub session_id: Option<Uuid>,
+    /// Ресурсы, занятые записью (по одному на требование услуги)
+    pub resource_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateBookingRequest {
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    #[serde(default)]
+    pub promo_code: Option<String>,
+}
+
+/// Данные для вставки записи, интервалы уже рассчитаны
+#[derive(Debug, Clone)]
+pub struct NewBooking {
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub blocked_from: DateTime<Utc>,
+    pub blocked_to: DateTime<Utc>,
+    pub status: BookingStatus,
+    /// Вместимость услуги; при значении больше 1 запись занимает место на общем занятии
+    pub capacity: i32,
+    pub resource_ids: Vec<Uuid>,
+    /// Промокод, использование которого записывается вместе с записью
+    pub promo: Option<NewPromoRedemption>,
+}
+
+/// Временная бронь слота: держит время сотрудника до `expires_at`
+#[derive(Serialize, Debug, Clone)]
+pub struct SlotHold {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateHoldRequest {
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ChangeBookingStatusRequest {
+    pub status: BookingStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RescheduleBookingRequest {
+    pub starts_at: DateTime<Utc>,
+    pub staff_id: Option<Uuid>,
+    pub location_id: Option<Uuid>,
+}
+
+/// Новое время записи при переносе, интервалы уже рассчитаны
+#[derive(Debug, Clone)]
+pub struct BookingReschedule {
+    pub staff_id: Uuid,
+    pub location_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub blocked_from: DateTime<Utc>,
+    pub blocked_to: DateTime<Utc>,
+    /// Вместимость услуги, см. `NewBooking::capacity`
+    pub capacity: i32,
+    pub resource_ids: Vec<Uuid>,
+}
+
+/// Запись в истории изменений: кто и когда создал, перевел статус или перенес запись
+#[derive(Serialize, Debug, Clone)]
+pub struct BookingEvent {
+    pub id: i64,
+    pub booking_id: Uuid,
+    pub event_type: String,
+    pub from_status: Option<BookingStatus>,
+    pub to_status: BookingStatus,
+    pub previous_starts_at: Option<DateTime<Utc>>,
+    pub new_starts_at: Option<DateTime<Utc>>,
+    pub actor_id: Uuid,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Окно подсчета неявок по умолчанию, дней
+pub const DEFAULT_NO_SHOW_WINDOW_DAYS: i32 = 90;
+
+/// За сколько часов до начала отмена записи возвращает депозит, если компания не задала иное
+pub const DEFAULT_DEPOSIT_REFUND_MIN_HOURS: i32 = 24;
+
+/// Политика компании для клиентов: за сколько часов до начала можно отменить или перенести запись
+/// и что происходит с новыми записями клиента после повторных неявок
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BookingPolicy {
+    pub company_id: Uuid,
+    pub customer_cancel_min_hours: i32,
+    pub customer_reschedule_min_hours: i32,
+    /// Сколько неявок за окно включают ограничение; `None` - правило выключено
+    pub no_show_limit: Option<i32>,
+    pub no_show_window_days: i32,
+    pub no_show_action: CustomerRestriction,
+    /// Не позднее скольких часов до начала клиент должен отменить запись, чтобы вернуть депозит
+    pub deposit_refund_min_hours: i32,
+}
+
+impl BookingPolicy {
+    pub fn default_for(company_id: Uuid) -> Self {
+        Self {
+            company_id,
+            customer_cancel_min_hours: 0,
+            customer_reschedule_min_hours: 0,
+            no_show_limit: None,
+            no_show_window_days: DEFAULT_NO_SHOW_WINDOW_DAYS,
+            no_show_action: CustomerRestriction::RequireConfirmation,
+            deposit_refund_min_hours: DEFAULT_DEPOSIT_REFUND_MIN_HOURS,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateBookingPolicyRequest {
+    pub customer_cancel_min_hours: i32,
+    pub customer_reschedule_min_hours: i32,
+    #[serde(default)]
+    pub no_show_limit: Option<i32>,
+    #[serde(default)]
+    pub no_show_window_days: Option<i32>,
+    #[serde(default)]
+    pub no_show_action: Option<CustomerRestriction>,
+    #[serde(default)]
+    pub deposit_refund_min_hours: Option<i32>,
+}
+
+/// Ограничение онлайн-записи клиента в компании
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomerRestriction {
+    None,
+    /// Новые записи создаются в статусе pending и ждут подтверждения компании
+    RequireConfirmation,
+    /// Онлайн-запись закрыта, записать клиента может только компания
+    Blocked,
+}
+
+impl CustomerRestriction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomerRestriction::None => "none",
+            CustomerRestriction::RequireConfirmation => "require_confirmation",
+            CustomerRestriction::Blocked => "blocked",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(CustomerRestriction::None),
+            "require_confirmation" => Some(CustomerRestriction::RequireConfirmation),
+            "blocked" => Some(CustomerRestriction::Blocked),
+            _ => None,
+        }
+    }
+}
+
+/// Ограничение, заданное руководителем компании вручную вместо правила о неявках
+#[derive(Serialize, Debug, Clone)]
+pub struct RestrictionOverride {
+    pub company_id: Uuid,
+    pub customer_id: Uuid,
+    pub restriction: CustomerRestriction,
+    pub reason: Option<String>,
+    pub set_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SetRestrictionOverrideRequest {
+    pub restriction: CustomerRestriction,
+    pub reason: Option<String>,
+}
+
+/// Действующее ограничение клиента в компании и из чего оно следует
+#[derive(Serialize, Debug, Clone)]
+pub struct CustomerRestrictionStatus {
+    pub company_id: Uuid,
+    pub customer_id: Uuid,
+    pub restriction: CustomerRestriction,
+    /// Неявки за окно правила
+    pub no_show_count: i64,
+    pub no_show_limit: Option<i32>,
+    pub no_show_window_days: i32,
+    /// Ограничение задано вручную и правило о неявках не применяется
+    pub overridden: bool,
+}
+
+/// Ограничение клиента для сотрудников компании: вместе с ручной настройкой и ее причиной
+#[derive(Serialize, Debug)]
+pub struct CustomerRestrictionDetails {
+    #[serde(flatten)]
+    pub status: CustomerRestrictionStatus,
+    #[serde(rename = "override")]
+    pub restriction_override: Option<RestrictionOverride>,
+}
+
+/// Серия повторяющихся записей; вхождения хранятся как обычные записи
+#[derive(Serialize, Debug, Clone)]
+pub struct BookingSeries {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Option<Uuid>,
+    pub rrule: String,
+    pub starts_at: DateTime<Utc>,
+    pub timezone: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateBookingSeriesRequest {
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Option<Uuid>,
+    /// Начало первого вхождения
+    pub starts_at: DateTime<Utc>,
+    /// Например `FREQ=WEEKLY;BYDAY=TU;COUNT=8`
+    pub rrule: String,
+    /// Создать серию без занятых вхождений вместо отказа
+    #[serde(default)]
+    pub skip_conflicts: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewBookingSeries {
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    pub location_id: Option<Uuid>,
+    pub rrule: String,
+    pub starts_at: DateTime<Utc>,
+    pub timezone: String,
+}
+
+/// Вхождение серии, которое нельзя записать
+#[derive(Serialize, Debug, Clone)]
+pub struct SeriesConflict {
+    pub starts_at: DateTime<Utc>,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BookingSeriesResponse {
+    /// `None`, если серия не создана из-за конфликтов
+    pub series: Option<BookingSeries>,
+    pub bookings: Vec<Booking>,
+    pub conflicts: Vec<SeriesConflict>,
+}
+
+/// Отмена вхождения и всех следующих
+#[derive(Deserialize, Debug)]
+pub struct CancelSeriesRequest {
+    pub from_booking_id: Uuid,
+    pub reason: Option<String>,
+}
+
+/// Перенос вхождения и всех следующих на тот же сдвиг в местном времени
+#[derive(Deserialize, Debug)]
+pub struct RescheduleSeriesRequest {
+    pub from_booking_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub staff_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SeriesChangeResponse {
+    pub bookings: Vec<Booking>,
+    pub conflicts: Vec<SeriesConflict>,
+}
+
+// Лист ожидания
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitlistStatus {
+    Waiting,
+    /// Клиенту предложен освободившийся слот, он держится бронью до `offer_expires_at`
+    Offered,
+    Booked,
+    Declined,
+    Expired,
+    Cancelled,
+}
+
+impl WaitlistStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WaitlistStatus::Waiting => "waiting",
+            WaitlistStatus::Offered => "offered",
+            WaitlistStatus::Booked => "booked",
+            WaitlistStatus::Declined => "declined",
+            WaitlistStatus::Expired => "expired",
+            WaitlistStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "waiting" => Some(WaitlistStatus::Waiting),
+            "offered" => Some(WaitlistStatus::Offered),
+            "booked" => Some(WaitlistStatus::Booked),
+            "declined" => Some(WaitlistStatus::Declined),
+            "expired" => Some(WaitlistStatus::Expired),
+            "cancelled" => Some(WaitlistStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Option<Uuid>,
+    pub location_id: Option<Uuid>,
+    pub desired_date: NaiveDate,
+    pub status: WaitlistStatus,
+    /// Бронь предложенного слота, подтверждается через `POST /v1/waitlist/{id}/accept`
+    pub hold_id: Option<Uuid>,
+    pub offered_staff_id: Option<Uuid>,
+    pub offered_starts_at: Option<DateTime<Utc>>,
+    pub offer_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JoinWaitlistRequest {
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Option<Uuid>,
+    pub location_id: Option<Uuid>,
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWaitlistEntry {
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Option<Uuid>,
+    pub location_id: Option<Uuid>,
+    pub desired_date: NaiveDate,
+}
+
+/// Освободившийся слот сотрудника, который можно предложить очереди
+#[derive(Debug, Clone, Copy)]
+pub struct FreedSlot {
+    pub company_id: Uuid,
+    pub staff_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+}
+
+// Уведомления
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    WaitlistOffer,
+    BookingConfirmed,
+    BookingCancelled,
+    BookingRescheduled,
+    BookingReminder,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::WaitlistOffer => "waitlist_offer",
+            NotificationKind::BookingConfirmed => "booking_confirmed",
+            NotificationKind::BookingCancelled => "booking_cancelled",
+            NotificationKind::BookingRescheduled => "booking_rescheduled",
+            NotificationKind::BookingReminder => "booking_reminder",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "waitlist_offer" => Some(NotificationKind::WaitlistOffer),
+            "booking_confirmed" => Some(NotificationKind::BookingConfirmed),
+            "booking_cancelled" => Some(NotificationKind::BookingCancelled),
+            "booking_rescheduled" => Some(NotificationKind::BookingRescheduled),
+            "booking_reminder" => Some(NotificationKind::BookingReminder),
+            _ => None,
+        }
+    }
+}
+
+/// Уведомление пользователю; `data` - параметры для текста конкретного вида
+#[derive(Serialize, Debug, Clone)]
+pub struct Notification {
+    pub user_id: Uuid,
+    pub kind: NotificationKind,
+    pub data: serde_json::Value,
+}
+
+/// Сообщение из outbox, захваченное диспетчером для доставки
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Вид уведомления как он сохранен в таблице, см. `NotificationKind::parse`
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// Номер текущей попытки, начиная с 1
+    pub attempts: i32,
+    pub delivered_channels: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Recipient {
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub locale: String,
+}
+
+/// Уведомление, готовое к отправке в канал: текст по шаблону вида и языка получателя
+#[derive(Serialize, Debug, Clone)]
+pub struct OutgoingMessage {
+    pub id: Uuid,
+    pub kind: NotificationKind,
+    pub recipient: Recipient,
+    pub subject: String,
+    pub body: String,
+    pub payload: serde_json::Value,
+}
+
+// Напоминания
+/// Правило компании: напомнить клиенту за `offset_minutes` минут до начала записи
+#[derive(Serialize, Debug, Clone)]
+pub struct ReminderRule {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub offset_minutes: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReminderRuleRequest {
+    pub offset_minutes: i32,
+}
+
+/// Полный набор правил компании; отсутствующие в списке правила удаляются
+#[derive(Deserialize, Debug)]
+pub struct UpdateReminderRulesRequest {
+    pub rules: Vec<ReminderRuleRequest>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderStatus {
+    Scheduled,
+    Sent,
+    /// Запись отменили или перенесли, либо правило удалено
+    Cancelled,
+    /// Время напоминания пропущено, а прием уже начался
+    Missed,
+}
+
+impl ReminderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderStatus::Scheduled => "scheduled",
+            ReminderStatus::Sent => "sent",
+            ReminderStatus::Cancelled => "cancelled",
+            ReminderStatus::Missed => "missed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "scheduled" => Some(ReminderStatus::Scheduled),
+            "sent" => Some(ReminderStatus::Sent),
+            "cancelled" => Some(ReminderStatus::Cancelled),
+            "missed" => Some(ReminderStatus::Missed),
+            _ => None,
+        }
+    }
+}
+
+/// Напоминание о конкретной записи; отправленные остаются в истории и после переноса
+#[derive(Serialize, Debug, Clone)]
+pub struct BookingReminder {
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    /// Пусто, если правило с тех пор удалено
+    pub rule_id: Option<Uuid>,
+    pub offset_minutes: i32,
+    pub remind_at: DateTime<Utc>,
+    pub status: ReminderStatus,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Вебхуки
+/// Событие, на которое подписывается интеграция компании
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    #[serde(rename = "booking.created")]
+    BookingCreated,
+    #[serde(rename = "booking.cancelled")]
+    BookingCancelled,
+    #[serde(rename = "booking.rescheduled")]
+    BookingRescheduled,
+    /// Прочие смены статуса: подтверждение, приход, завершение, неявка
+    #[serde(rename = "booking.status_changed")]
+    BookingStatusChanged,
+    /// Проверочное событие; на него не подписываются, оно отправляется по запросу
+    #[serde(rename = "webhook.test")]
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::BookingCreated => "booking.created",
+            WebhookEvent::BookingCancelled => "booking.cancelled",
+            WebhookEvent::BookingRescheduled => "booking.rescheduled",
+            WebhookEvent::BookingStatusChanged => "booking.status_changed",
+            WebhookEvent::Test => "webhook.test",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "booking.created" => Some(WebhookEvent::BookingCreated),
+            "booking.cancelled" => Some(WebhookEvent::BookingCancelled),
+            "booking.rescheduled" => Some(WebhookEvent::BookingRescheduled),
+            "booking.status_changed" => Some(WebhookEvent::BookingStatusChanged),
+            "webhook.test" => Some(WebhookEvent::Test),
+            _ => None,
+        }
+    }
+}
+
+/// Подписка компании на события; секрет показывается только при создании
+#[derive(Serialize, Debug, Clone)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WebhookSubscriptionResponse {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    /// Ключ HMAC-SHA256 для проверки подписи запросов
+    pub secret: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    /// Попытки исчерпаны
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(WebhookDeliveryStatus::Pending),
+            "delivered" => Some(WebhookDeliveryStatus::Delivered),
+            "failed" => Some(WebhookDeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Доставка события в подписку; `payload` - тело запроса
+#[derive(Serialize, Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Доставка вместе с адресом и секретом подписки для отправки
+#[derive(Debug, Clone)]
+pub struct WebhookDeliveryTarget {
+    pub delivery: WebhookDelivery,
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct WebhookDeliveriesQuery {
+    pub status: Option<WebhookDeliveryStatus>,
+    pub limit: Option<i64>,
+}
+
+// Календари
+/// Запись для выгрузки в iCalendar вместе с названиями, которые увидит пользователь
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub booking_id: Uuid,
+    pub customer_id: Uuid,
+    pub company_id: Uuid,
+    pub staff_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub status: BookingStatus,
+    /// Сколько раз запись переносили или меняли ее статус
+    pub sequence: i32,
+    pub updated_at: DateTime<Utc>,
+    pub company_name: String,
+    pub timezone: String,
+    pub service_name: String,
+    pub staff_name: String,
+    pub customer_name: String,
+    pub location_name: String,
+    pub location_address: Option<String>,
+}
+
+/// Подписка на календарь; `staff_id` задан у ленты сотрудника
+#[derive(Serialize, Debug, Clone)]
+pub struct CalendarFeed {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub company_id: Option<Uuid>,
+    pub staff_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Новая ссылка на ленту; токен показывается только один раз
+#[derive(Serialize, Debug)]
+pub struct CalendarFeedResponse {
+    #[serde(flatten)]
+    pub feed: CalendarFeed,
+    pub token: String,
+    pub url: String,
+}
+
+// Фоновые задачи
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    /// Попытки исчерпаны, задача ждет ручного повтора
+    Dead,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Dead => "dead",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(JobStatus::Pending),
+            "running" => Some(JobStatus::Running),
+            "completed" => Some(JobStatus::Completed),
+            "dead" => Some(JobStatus::Dead),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct JobRecord {
+    pub id: Uuid,
+    /// Вид задачи, по которому воркер выбирает обработчик
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    /// Сколько раз задачу брали в работу
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewJob {
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// Не раньше этого момента; по умолчанию сразу
+    pub run_at: Option<DateTime<Utc>>,
+    pub max_attempts: i32,
+    /// Пока в очереди ждет задача с таким ключом, вторая не ставится
+    pub dedupe_key: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct JobsQuery {
+    /// По умолчанию - задачи в статусе dead
+    pub status: Option<JobStatus>,
+    pub kind: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// Структуры для отзывов
+#[derive(Serialize, Debug, Clone)]
+pub struct Review {
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+    /// Имя автора для подписи; идентификатор клиента наружу не отдается
+    pub author_name: Option<String>,
+    pub rating: i32,
+    pub text: Option<String>,
+    pub reply: Option<String>,
+    pub replied_at: Option<DateTime<Utc>>,
+    pub is_hidden: bool,
+    pub hidden_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateReviewRequest {
+    pub rating: i32,
+    pub text: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewReplyRequest {
+    pub reply: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewVisibilityRequest {
+    pub is_hidden: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewsQuery {
+    pub staff: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Страница видимых отзывов и рейтинг по тем же условиям (компания или сотрудник)
+#[derive(Serialize, Debug)]
+pub struct ReviewsResponse {
+    pub rating_avg: Option<f64>,
+    pub rating_count: i64,
+    pub reviews: Vec<Review>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+// Структуры для поиска компаний
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    Relevance,
+    Rating,
+    Distance,
+}
+
+impl SearchSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchSort::Relevance => "relevance",
+            SearchSort::Rating => "rating",
+            SearchSort::Distance => "distance",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CompanySearchQuery {
+    pub q: Option<String>,
+    pub city: Option<String>,
+    pub category: Option<String>,
+    pub min_rating: Option<f64>,
+    /// Диапазон цены услуг в минимальных единицах валюты
+    pub price_min: Option<i64>,
+    pub price_max: Option<i64>,
+    pub open_now: Option<bool>,
+    /// Есть свободное рабочее время в этот день (по часовому поясу компании)
+    pub available_on: Option<NaiveDate>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub radius_km: Option<f64>,
+    pub sort: Option<SearchSort>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Проверенные условия поиска для репозитория
+#[derive(Debug, Clone)]
+pub struct CompanySearchFilter {
+    pub text: Option<String>,
+    pub city: Option<String>,
+    pub category: Option<String>,
+    pub min_rating: Option<f64>,
+    pub price_min: Option<i64>,
+    pub price_max: Option<i64>,
+    /// Момент, в который компания должна работать
+    pub open_at: Option<DateTime<Utc>>,
+    pub available_on: Option<NaiveDate>,
+    /// Точка поиска: широта и долгота
+    pub origin: Option<(f64, f64)>,
+    pub radius_km: Option<f64>,
+    pub sort: SearchSort,
+    /// Позиция, после которой начинается страница: ключ сортировки и id компании
+    pub after: Option<(f64, Uuid)>,
+    pub limit: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CompanySearchHit {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub categories: Vec<String>,
+    pub rating_avg: Option<f64>,
+    pub rating_count: i32,
+    pub cities: Vec<String>,
+    /// Самая низкая цена услуги в минимальных единицах валюты
+    pub price_from: Option<i64>,
+    /// До ближайшего филиала, если в запросе есть координаты
+    pub distance_km: Option<f64>,
+    /// Ключ сортировки для курсора следующей страницы
+    #[serde(skip)]
+    pub sort_key: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// Сколько найденных компаний приходится на каждую рубрику и город
+#[derive(Serialize, Debug, Default)]
+pub struct SearchFacets {
+    pub categories: Vec<FacetCount>,
+    pub cities: Vec<FacetCount>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompanySearchResponse {
+    pub companies: Vec<CompanySearchHit>,
+    pub total: i64,
+    pub facets: SearchFacets,
+    /// Передается в `cursor` за следующей страницей; пусто на последней
+    pub next_cursor: Option<String>,
+}
+
+// Изображения
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    Avatar,
+    Logo,
+    Gallery,
+}
+
+impl MediaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaKind::Avatar => "avatar",
+            MediaKind::Logo => "logo",
+            MediaKind::Gallery => "gallery",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "avatar" => Some(MediaKind::Avatar),
+            "logo" => Some(MediaKind::Logo),
+            "gallery" => Some(MediaKind::Gallery),
+            _ => None,
+        }
+    }
+}
+
+/// Изображение, уже записанное в хранилище; id выбирается заранее, он входит в ключи файлов
+#[derive(Debug, Clone)]
+pub struct NewMedia {
+    pub id: Uuid,
+    pub kind: MediaKind,
+    pub user_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+    pub uploaded_by: Uuid,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MediaAsset {
+    pub id: Uuid,
+    pub kind: MediaKind,
+    pub user_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Изображение в ответах API; адреса могут быть подписанными и со временем истекать
+#[derive(Serialize, Debug, Clone)]
+pub struct MediaImage {
+    pub id: Uuid,
+    pub url: String,
+    pub thumbnail_url: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct CompanyMedia {
+    pub logo: Option<MediaImage>,
+    pub gallery: Vec<MediaImage>,
+}
+
+// Клиенты компании: сводка по записям, закрытые заметки и метки
+#[derive(Serialize, Debug, Clone)]
+pub struct CustomerSummary {
+    pub id: Uuid,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub tags: Vec<String>,
+    /// Все записи в компании, кроме временных броней
+    pub booking_count: i64,
+    /// Записи, на которые клиент пришел (checked_in и completed)
+    pub visit_count: i64,
+    pub no_show_count: i64,
+    pub last_visit_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CustomerNote {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Карточка клиента для сотрудников компании: записи в этой компании, новые первыми, и заметки
+#[derive(Serialize, Debug)]
+pub struct CustomerProfile {
+    #[serde(flatten)]
+    pub customer: CustomerSummary,
+    pub bookings: Vec<Booking>,
+    pub notes: Vec<CustomerNote>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CustomersQuery {
+    /// Подстрока имени, фамилии, логина или email
+    pub q: Option<String>,
+    pub tag: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CustomersResponse {
+    pub customers: Vec<CustomerSummary>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CustomerNoteRequest {
+    pub body: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateCustomerTagsRequest {
+    pub tags: Vec<String>,
+}
+
+// Платежи: депозиты за записи. Суммы - в минимальных единицах валюты (копейки, центы)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    /// Ждет оплаты
+    Pending,
+    Succeeded,
+    /// Попытка оплаты не удалась, клиент может начать оплату заново
+    Failed,
+    /// Запись отменили до оплаты
+    Cancelled,
+    /// Возврат поставлен в очередь
+    RefundPending,
+    Refunded,
+}
+
+impl PaymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentStatus::Pending => "pending",
+            PaymentStatus::Succeeded => "succeeded",
+            PaymentStatus::Failed => "failed",
+            PaymentStatus::Cancelled => "cancelled",
+            PaymentStatus::RefundPending => "refund_pending",
+            PaymentStatus::Refunded => "refunded",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(PaymentStatus::Pending),
+            "succeeded" => Some(PaymentStatus::Succeeded),
+            "failed" => Some(PaymentStatus::Failed),
+            "cancelled" => Some(PaymentStatus::Cancelled),
+            "refund_pending" => Some(PaymentStatus::RefundPending),
+            "refunded" => Some(PaymentStatus::Refunded),
+            _ => None,
+        }
+    }
+}
+
+/// Депозит за запись
+#[derive(Serialize, Debug, Clone)]
+pub struct Payment {
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    pub company_id: Uuid,
+    pub customer_id: Uuid,
+    pub amount: i64,
+    pub currency: String,
+    pub status: PaymentStatus,
+    pub provider: Option<String>,
+    pub provider_payment_id: Option<String>,
+    /// Нужен клиенту для оплаты, сотрудникам компании не показывается
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    pub refund_amount: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub refunded_at: Option<DateTime<Utc>>,
+}
+
+/// Запрос на создание платежа в платежной системе
+#[derive(Debug, Clone)]
+pub struct ProviderPaymentRequest {
+    pub payment_id: Uuid,
+    pub booking_id: Uuid,
+    pub amount: i64,
+    pub currency: String,
+    pub description: String,
+    /// Повтор запроса с тем же ключом не создает второй платеж
+    pub idempotency_key: String,
+}
+
+/// Платеж, созданный в платежной системе
+#[derive(Debug, Clone)]
+pub struct ProviderPayment {
+    pub provider_payment_id: String,
+    pub client_secret: Option<String>,
+}
+
+/// Чем закончилась попытка оплаты по уведомлению платежной системы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentOutcome {
+    Succeeded,
+    Failed,
+}
+
+/// Проверенное уведомление платежной системы о платеже
+#[derive(Debug, Clone)]
+pub struct PaymentWebhookEvent {
+    pub event_id: String,
+    pub provider_payment_id: String,
+    pub outcome: PaymentOutcome,
+}
+
+// Промокоды компаний. Суммы - в минимальных единицах валюты
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscountType {
+    /// Процент от цены услуги, от 1 до 100
+    Percent,
+    /// Фиксированная сумма в валюте промокода
+    Fixed,
+}
+
+impl DiscountType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscountType::Percent => "percent",
+            DiscountType::Fixed => "fixed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "percent" => Some(DiscountType::Percent),
+            "fixed" => Some(DiscountType::Fixed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PromoCode {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub code: String,
+    pub discount_type: DiscountType,
+    pub discount_value: i64,
+    /// Валюта фиксированной скидки
+    pub currency: Option<String>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_redemptions: Option<i32>,
+    pub max_per_customer: Option<i32>,
+    /// Пустой список - промокод действует на все услуги
+    pub service_ids: Vec<Uuid>,
+    /// Пустой список - промокод действует во всех филиалах
+    pub location_ids: Vec<Uuid>,
+    pub is_active: bool,
+    pub redemption_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreatePromoCodeRequest {
+    pub code: String,
+    pub discount_type: DiscountType,
+    pub discount_value: i64,
+    pub currency: Option<String>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_redemptions: Option<i32>,
+    pub max_per_customer: Option<i32>,
+    #[serde(default)]
+    pub service_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub location_ids: Vec<Uuid>,
+}
+
+/// Проверенный промокод для сохранения: код в верхнем регистре, валюта только у фиксированной скидки
+#[derive(Debug, Clone)]
+pub struct NewPromoCode {
+    pub company_id: Uuid,
+    pub code: String,
+    pub discount_type: DiscountType,
+    pub discount_value: i64,
+    pub currency: Option<String>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_redemptions: Option<i32>,
+    pub max_per_customer: Option<i32>,
+    pub service_ids: Vec<Uuid>,
+    pub location_ids: Vec<Uuid>,
+}
+
+/// Изменение кампании: незаданные поля не меняются
+#[derive(Deserialize, Debug)]
+pub struct UpdatePromoCodeRequest {
+    pub is_active: Option<bool>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_redemptions: Option<i32>,
+    pub max_per_customer: Option<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BookingQuoteRequest {
+    pub company_id: Uuid,
+    pub service_id: Uuid,
+    /// Нужен, если промокод действует только в отдельных филиалах
+    pub location_id: Option<Uuid>,
+    pub promo_code: Option<String>,
+}
+
+/// Расчет цены записи: `total_amount = price_amount - discount_amount`
+#[derive(Serialize, Debug, Clone)]
+pub struct BookingQuote {
+    pub service_id: Uuid,
+    pub currency: String,
+    pub price_amount: i64,
+    pub discount_amount: i64,
+    pub total_amount: i64,
+    /// Депозит, который нужно будет внести при записи
+    pub deposit_amount: Option<i64>,
+    pub promo_code: Option<String>,
+}
+
+/// Использование промокода; в транзакции создания записи заново проверяются срок и лимиты
+#[derive(Debug, Clone)]
+pub struct NewPromoRedemption {
+    pub promo_code_id: Uuid,
+    pub price_amount: i64,
+    pub discount_amount: i64,
+    pub currency: String,
+    pub redeemed_at: DateTime<Utc>,
+}
+
+// Электронная очередь филиала
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueTicketStatus {
+    Waiting,
+    /// Сотрудник пригласил клиента и обслуживает его
+    Called,
+    Served,
+    NoShow,
+    Cancelled,
+    /// Талон не обслужили до конца дня, очередь которого он занимал
+    Expired,
+}
+
+impl QueueTicketStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueTicketStatus::Waiting => "waiting",
+            QueueTicketStatus::Called => "called",
+            QueueTicketStatus::Served => "served",
+            QueueTicketStatus::NoShow => "no_show",
+            QueueTicketStatus::Cancelled => "cancelled",
+            QueueTicketStatus::Expired => "expired",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "waiting" => Some(QueueTicketStatus::Waiting),
+            "called" => Some(QueueTicketStatus::Called),
+            "served" => Some(QueueTicketStatus::Served),
+            "no_show" => Some(QueueTicketStatus::NoShow),
+            "cancelled" => Some(QueueTicketStatus::Cancelled),
+            "expired" => Some(QueueTicketStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Талон еще в очереди: ждет вызова или обслуживается
+    pub fn is_active(&self) -> bool {
+        matches!(self, QueueTicketStatus::Waiting | QueueTicketStatus::Called)
+    }
+}
+
+/// Талон очереди; виден сотрудникам компании и клиенту, вставшему в очередь
+#[derive(Serialize, Debug, Clone)]
+pub struct QueueTicket {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub location_id: Uuid,
+    pub service_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+    pub customer_name: Option<String>,
+    pub queue_date: NaiveDate,
+    pub number: i32,
+    pub status: QueueTicketStatus,
+    pub called_by: Option<Uuid>,
+    pub joined_at: DateTime<Utc>,
+    pub called_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Встать в очередь: из приложения - для себя, с киоска (аккаунт участника компании) - по имени клиента
+#[derive(Deserialize, Debug)]
+pub struct JoinQueueRequest {
+    pub location_id: Uuid,
+    pub service_id: Option<Uuid>,
+    pub customer_name: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewQueueTicket {
+    pub company_id: Uuid,
+    pub location_id: Uuid,
+    pub service_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+    pub customer_name: Option<String>,
+    pub queue_date: NaiveDate,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Состояние талона для табло и клиента, без данных клиента: доступно по id талона
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct QueueTicketView {
+    pub id: Uuid,
+    pub location_id: Uuid,
+    pub queue_date: NaiveDate,
+    pub number: i32,
+    pub status: QueueTicketStatus,
+    /// Место среди ожидающих, начиная с 1; только у ожидающих талонов
+    pub position: Option<i64>,
+    pub estimated_wait_minutes: Option<i64>,
+    pub called_at: Option<DateTime<Utc>>,
+}
+
+/// Очередь филиала за день для сотрудников
+#[derive(Serialize, Debug, Clone)]
+pub struct LocationQueue {
+    pub location_id: Uuid,
+    pub queue_date: NaiveDate,
+    /// Средняя длительность обслуживания, по которой считается ожидание
+    pub average_service_minutes: f64,
+    pub tickets: Vec<QueueTicket>,
+}
+
+/// Без `date` - сегодняшняя очередь в часовом поясе компании
+#[derive(Deserialize, Debug)]
+pub struct LocationQueueQuery {
+    pub location: Uuid,
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CallNextRequest {
+    pub location_id: Uuid,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ChangeQueueTicketStatusRequest {
+    pub status: QueueTicketStatus,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QueueStatsQuery {
+    pub location: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Итоги очереди филиала за день
+#[derive(Serialize, Debug, Clone)]
+pub struct QueueDayStats {
+    pub queue_date: NaiveDate,
+    pub joined: i64,
+    pub served: i64,
+    pub no_show: i64,
+    pub cancelled: i64,
+    pub expired: i64,
+    /// От постановки в очередь до вызова
+    pub average_wait_minutes: Option<f64>,
+    /// От вызова до окончания обслуживания
+    pub average_service_minutes: Option<f64>,
+}
+
+/// Событие очереди: изменились талоны филиала за день. Подписчики сами пересчитывают свое место
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueueEvent {
+    pub company_id: Uuid,
+    pub location_id: Uuid,
+    pub queue_date: NaiveDate,
+}
+
+// Отметка о приходе по QR-коду
+/// Токен для QR-кода записи; действует до `expires_at` и только в филиале записи
+#[derive(Serialize, Debug, Clone)]
+pub struct CheckinToken {
+    pub booking_id: Uuid,
+    pub location_id: Uuid,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QrImageFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+/// Картинка QR-кода с типом содержимого для ответа
+#[derive(Debug, Clone)]
+pub struct QrImage {
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CheckinQrQuery {
+    #[serde(default)]
+    pub format: QrImageFormat,
+}
+
+/// Отсканированный на стойке QR-код; `location_id` - филиал, где стоит сотрудник
+#[derive(Deserialize, Debug)]
+pub struct CheckinRequest {
+    pub location_id: Uuid,
+    pub token: String,
+}
+
+// Аналитика компании
+/// Отчет аналитики; в пути запроса - `/analytics/{report}`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsReportKind {
+    Bookings,
+    Utilization,
+    Cancellations,
+    Revenue,
+    Customers,
+}
+
+impl AnalyticsReportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnalyticsReportKind::Bookings => "bookings",
+            AnalyticsReportKind::Utilization => "utilization",
+            AnalyticsReportKind::Cancellations => "cancellations",
+            AnalyticsReportKind::Revenue => "revenue",
+            AnalyticsReportKind::Customers => "customers",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Период отчета включительно, даты в часовом поясе компании
+#[derive(Deserialize, Debug, Clone)]
+pub struct AnalyticsQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+/// Записи за день по услуге и сотруднику
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BookingsReportRow {
+    pub date: NaiveDate,
+    pub service_id: Uuid,
+    pub service_name: String,
+    pub staff_id: Uuid,
+    pub staff_name: String,
+    pub bookings: i64,
+    pub completed: i64,
+    pub cancelled: i64,
+    pub no_show: i64,
+}
+
+/// Загрузка сотрудника за день: занятое записями время к рабочему по расписанию
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct UtilizationReportRow {
+    pub date: NaiveDate,
+    pub staff_id: Uuid,
+    pub staff_name: String,
+    pub available_minutes: i64,
+    pub booked_minutes: i64,
+    /// Доля от 0 до 1 (больше 1 при записях вне расписания); пусто в нерабочий день
+    pub utilization: Option<f64>,
+}
+
+/// Отмены и неявки за день, доли от всех записей дня
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CancellationsReportRow {
+    pub date: NaiveDate,
+    pub bookings: i64,
+    pub cancelled_by_customer: i64,
+    pub cancelled_by_company: i64,
+    pub no_show: i64,
+    pub cancellation_rate: f64,
+    pub no_show_rate: f64,
+}
+
+/// Выручка за день по оказанным услугам в минимальных единицах валюты
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RevenueReportRow {
+    pub date: NaiveDate,
+    pub currency: String,
+    pub visits: i64,
+    pub revenue: i64,
+    pub discount: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CustomersReportRow {
+    pub date: NaiveDate,
+    pub new_customers: i64,
+    pub returning_customers: i64,
+}
+
+/// Занятое записями время сотрудника за день
+#[derive(Debug, Clone)]
+pub struct StaffBookedMinutes {
+    pub date: NaiveDate,
+    pub staff_id: Uuid,
+    pub booked_minutes: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum AnalyticsRows {
+    Bookings(Vec<BookingsReportRow>),
+    Utilization(Vec<UtilizationReportRow>),
+    Cancellations(Vec<CancellationsReportRow>),
+    Revenue(Vec<RevenueReportRow>),
+    Customers(Vec<CustomersReportRow>),
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AnalyticsReport {
+    pub report: AnalyticsReportKind,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: String,
+    /// Когда данные отчета последний раз пересчитывались
+    pub refreshed_at: Option<DateTime<Utc>>,
+    pub rows: AnalyticsRows,
+}
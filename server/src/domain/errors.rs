@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Ошибка прикладного уровня, по которой presentation выбирает HTTP статус
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    Validation(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    /// Загружаемый файл больше допустимого
+    PayloadTooLarge(String),
+    /// Содержимое файла не того типа, что принимает эндпоинт
+    UnsupportedMediaType(String),
+    Internal(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Validation(msg)
+            | AppError::Forbidden(msg)
+            | AppError::NotFound(msg)
+            | AppError::Conflict(msg)
+            | AppError::PayloadTooLarge(msg)
+            | AppError::UnsupportedMediaType(msg)
+            | AppError::Internal(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+// Ошибки репозиториев пока строковые - считаем их внутренними
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
@@ -1,2 +1,3 @@
 pub mod entities;
+pub mod errors;
 pub mod traits;
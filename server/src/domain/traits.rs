@@ -1,7 +1,50 @@
-use crate::domain::entities::{DbStatus, User, CreateUserRequest, CreateUserResponse, UsersListResponse};
+use crate::domain::entities::{
+    FreedSlot, JoinWaitlistRequest, NewWaitlistEntry, Notification, OutboxMessage, OutgoingMessage, Recipient,
+    WaitlistEntry, WaitlistStatus,
+    BookingSeries, BookingSeriesResponse, CancelSeriesRequest, CreateBookingSeriesRequest, NewBookingSeries,
+    RescheduleSeriesRequest, SeriesChangeResponse,
+    Bookmark, BookmarksQuery, BookmarksResponse,
+    CalendarEvent, CalendarFeed, CalendarFeedResponse,
+    BookingReminder, ReminderRule, UpdateReminderRulesRequest,
+    JobRecord, JobsQuery, NewJob,
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookDeliveriesQuery, WebhookDelivery, WebhookDeliveryTarget,
+    WebhookEvent, WebhookSubscription, WebhookSubscriptionResponse,
+    CompanyMedia, MediaAsset, MediaImage, NewMedia,
+    CustomerNote, CustomerNoteRequest, CustomerProfile, CustomerSummary, CustomersQuery, CustomersResponse,
+    UpdateCustomerTagsRequest,
+    CustomerRestriction, CustomerRestrictionDetails, CustomerRestrictionStatus, RestrictionOverride,
+    SetRestrictionOverrideRequest,
+    Payment, PaymentStatus, PaymentWebhookEvent, ProviderPayment, ProviderPaymentRequest,
+    BookingQuote, BookingQuoteRequest, CreatePromoCodeRequest, NewPromoCode, PromoCode, UpdatePromoCodeRequest,
+    CallNextRequest, ChangeQueueTicketStatusRequest, JoinQueueRequest, LocationQueue, LocationQueueQuery, NewQueueTicket,
+    QueueDayStats, QueueEvent, QueueStatsQuery, QueueTicket, QueueTicketStatus, QueueTicketView,
+    CheckinRequest, CheckinToken, QrImage, QrImageFormat,
+    AnalyticsQuery, AnalyticsReport, AnalyticsReportKind, BookingsReportRow, CancellationsReportRow, CustomersReportRow,
+    RevenueReportRow, StaffBookedMinutes,
+    CompanySearchFilter, CompanySearchHit, CompanySearchQuery, CompanySearchResponse, SearchFacets,
+    CreateReviewRequest, Review, ReviewReplyRequest, ReviewVisibilityRequest, ReviewsQuery, ReviewsResponse,
+    Booking, BookingEvent, BookingPolicy, BookingReschedule, BookingStatus, BusyInterval,
+    GroupSession, GroupSessionDetails, SessionAttendee, SessionsQuery,
+    CreateResourceRequest, Resource, ResourceBusyInterval, ResourceRequirementRequest, ServiceResourceRequirement,
+    UpdateServiceResourcesRequest,
+    ChangeBookingStatusRequest, Company, CreateBookingRequest, CreateHoldRequest, SlotHold, NewBooking, RescheduleBookingRequest,
+    UpdateBookingPolicyRequest, CompanyRole, CreateCompanyRequest, CreateLocationRequest, CreateScheduleExceptionRequest,
+    CreateServiceRequest, CreateStaffRequest, CreateUserRequest, CreateUserResponse, DbStatus, Location,
+    ScheduleEntryRequest, ScheduleException, Service, SlotEvent, SlotStreamQuery, SlotsQuery, SlotsResponse, StaffMember,
+    StaffSchedule, UpdateScheduleRequest, User, UsersListResponse,
+};
+use crate::domain::errors::AppError;
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::stream::BoxStream;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Источник текущего времени, в тестах подменяется управляемыми часами
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
 #[async_trait]
 pub trait DatabaseHealthChecker {
     async fn check_health(&self) -> DbStatus;
@@ -42,6 +85,7 @@ pub trait UserAuthRepository {
         first_name: Option<&str>,
         last_name: Option<&str>,
         email: Option<&str>,
+        locale: Option<&str>,
     ) -> Result<Option<User>, String>;
 }
 
@@ -52,3 +96,979 @@ pub trait UserService {
     async fn get_all_users(&self) -> Result<UsersListResponse, String>;
     async fn delete_user(&self, id: Uuid) -> Result<String, String>;
 }
+
+// Репозиторий компаний, их филиалов, услуг, сотрудников и расписаний
+#[async_trait]
+pub trait CompanyRepository {
+    async fn create_company(&self, owner_id: Uuid, data: &CreateCompanyRequest) -> Result<Company, String>;
+    async fn find_company(&self, id: Uuid) -> Result<Option<Company>, String>;
+    async fn get_member_role(&self, company_id: Uuid, user_id: Uuid) -> Result<Option<CompanyRole>, String>;
+    async fn create_location(&self, company_id: Uuid, data: &CreateLocationRequest) -> Result<Location, String>;
+    async fn find_location(&self, company_id: Uuid, location_id: Uuid) -> Result<Option<Location>, String>;
+    async fn create_service(&self, company_id: Uuid, data: &CreateServiceRequest) -> Result<Service, String>;
+    async fn find_service(&self, company_id: Uuid, service_id: Uuid) -> Result<Option<Service>, String>;
+    async fn create_staff(&self, company_id: Uuid, data: &CreateStaffRequest) -> Result<StaffMember, String>;
+    async fn find_staff(&self, company_id: Uuid, staff_id: Uuid) -> Result<Option<StaffMember>, String>;
+    async fn list_staff_for_service(&self, service_id: Uuid) -> Result<Vec<StaffMember>, String>;
+    async fn list_staff(&self, company_id: Uuid) -> Result<Vec<StaffMember>, String>;
+    async fn replace_weekly_schedule(
+        &self,
+        staff_id: Uuid,
+        entries: &[ScheduleEntryRequest],
+    ) -> Result<Vec<StaffSchedule>, String>;
+    async fn list_weekly_schedules(&self, staff_ids: &[Uuid]) -> Result<Vec<StaffSchedule>, String>;
+    async fn add_schedule_exception(
+        &self,
+        staff_id: Uuid,
+        data: &CreateScheduleExceptionRequest,
+    ) -> Result<ScheduleException, String>;
+    async fn list_schedule_exceptions(
+        &self,
+        staff_ids: &[Uuid],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ScheduleException>, String>;
+    async fn create_resource(&self, company_id: Uuid, data: &CreateResourceRequest) -> Result<Resource, String>;
+    async fn list_resources(&self, company_id: Uuid) -> Result<Vec<Resource>, String>;
+    /// Требования услуги к ресурсам в порядке задания
+    async fn list_service_requirements(&self, service_id: Uuid) -> Result<Vec<ServiceResourceRequirement>, String>;
+    async fn replace_service_requirements(
+        &self,
+        service_id: Uuid,
+        requirements: &[ResourceRequirementRequest],
+    ) -> Result<Vec<ServiceResourceRequirement>, String>;
+}
+
+#[async_trait]
+pub trait CatalogService {
+    async fn create_company(&self, user_id: Uuid, data: CreateCompanyRequest) -> Result<Company, AppError>;
+    async fn get_company(&self, company_id: Uuid) -> Result<Company, AppError>;
+    async fn create_location(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateLocationRequest,
+    ) -> Result<Location, AppError>;
+    async fn create_service(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateServiceRequest,
+    ) -> Result<Service, AppError>;
+    async fn create_staff(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateStaffRequest,
+    ) -> Result<StaffMember, AppError>;
+    async fn update_schedule(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        staff_id: Uuid,
+        data: UpdateScheduleRequest,
+    ) -> Result<Vec<StaffSchedule>, AppError>;
+    async fn add_schedule_exception(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        staff_id: Uuid,
+        data: CreateScheduleExceptionRequest,
+    ) -> Result<ScheduleException, AppError>;
+    async fn create_resource(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateResourceRequest,
+    ) -> Result<Resource, AppError>;
+    async fn list_resources(&self, company_id: Uuid) -> Result<Vec<Resource>, AppError>;
+    async fn get_service_resources(
+        &self,
+        company_id: Uuid,
+        service_id: Uuid,
+    ) -> Result<Vec<ServiceResourceRequirement>, AppError>;
+    async fn update_service_resources(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        service_id: Uuid,
+        data: UpdateServiceResourcesRequest,
+    ) -> Result<Vec<ServiceResourceRequirement>, AppError>;
+}
+
+#[async_trait]
+pub trait SlotService {
+    async fn available_slots(&self, company_id: Uuid, query: SlotsQuery) -> Result<SlotsResponse, AppError>;
+    /// Поток событий о занятых и освободившихся слотах компании
+    async fn slot_events(
+        &self,
+        company_id: Uuid,
+        query: SlotStreamQuery,
+    ) -> Result<BoxStream<'static, SlotEvent>, AppError>;
+}
+
+/// Рассылка событий слотов подписчикам; события приходят от всех экземпляров сервера
+pub trait SlotEventBus {
+    fn subscribe(&self, company_id: Uuid) -> BoxStream<'static, SlotEvent>;
+}
+
+#[async_trait]
+pub trait BookingRepository {
+    /// Нарушение ограничения на пересечение записей возвращается как `AppError::Conflict`
+    async fn create_booking(&self, booking: &NewBooking) -> Result<Booking, AppError>;
+    async fn find_booking(&self, id: Uuid) -> Result<Option<Booking>, AppError>;
+    /// Активные записи сотрудников, пересекающиеся с периодом (интервалы с буферами)
+    async fn list_busy_intervals(
+        &self,
+        staff_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BusyInterval>, AppError>;
+    /// Занятость ресурсов активными записями, пересекающаяся с периодом (с буферами)
+    async fn list_resource_busy(
+        &self,
+        resource_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ResourceBusyInterval>, AppError>;
+    /// Сменить статус, только если текущий статус равен `from`; иначе `AppError::Conflict`
+    async fn change_status(
+        &self,
+        booking_id: Uuid,
+        from: BookingStatus,
+        to: BookingStatus,
+        actor_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<Booking, AppError>;
+    /// Перенести активную запись одним обновлением: старый слот освобождается атомарно
+    async fn reschedule_booking(
+        &self,
+        booking_id: Uuid,
+        change: &BookingReschedule,
+        actor_id: Uuid,
+    ) -> Result<Booking, AppError>;
+    async fn list_booking_events(&self, booking_id: Uuid) -> Result<Vec<BookingEvent>, AppError>;
+    /// Политика компании; если не настроена, возвращается политика по умолчанию
+    async fn get_policy(&self, company_id: Uuid) -> Result<BookingPolicy, AppError>;
+    async fn save_policy(&self, policy: &BookingPolicy) -> Result<BookingPolicy, AppError>;
+    /// Неявки клиента в компании на записи, начинавшиеся не раньше `since`
+    async fn count_no_shows(&self, company_id: Uuid, customer_id: Uuid, since: DateTime<Utc>) -> Result<i64, AppError>;
+    async fn find_restriction_override(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<Option<RestrictionOverride>, AppError>;
+    async fn save_restriction_override(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+        restriction: CustomerRestriction,
+        reason: Option<&str>,
+        set_by: Uuid,
+    ) -> Result<RestrictionOverride, AppError>;
+    async fn delete_restriction_override(&self, company_id: Uuid, customer_id: Uuid) -> Result<bool, AppError>;
+    /// Создать бронь слота; прежние брони клиента в этой компании снимаются
+    async fn create_hold(&self, hold: &NewBooking, expires_at: DateTime<Utc>) -> Result<SlotHold, AppError>;
+    /// Превратить действующую бронь владельца в подтвержденную запись
+    async fn convert_hold(
+        &self,
+        hold_id: Uuid,
+        customer_id: Uuid,
+        status: BookingStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Booking, AppError>;
+    /// Снять бронь владельца; `false`, если брони нет
+    async fn release_hold(&self, hold_id: Uuid, customer_id: Uuid) -> Result<bool, AppError>;
+    /// Снять все брони, истекшие к моменту `now`; возвращает количество снятых
+    async fn release_expired_holds(&self, now: DateTime<Utc>) -> Result<u64, AppError>;
+    /// Создать серию вместе со всеми вхождениями в одной транзакции
+    async fn create_series(
+        &self,
+        series: &NewBookingSeries,
+        occurrences: &[NewBooking],
+    ) -> Result<(BookingSeries, Vec<Booking>), AppError>;
+    async fn find_series(&self, id: Uuid) -> Result<Option<BookingSeries>, AppError>;
+    /// Вхождения серии по порядку исходного времени
+    async fn list_series_bookings(&self, series_id: Uuid) -> Result<Vec<Booking>, AppError>;
+    /// Сменить статус нескольких записей атомарно (каждая - из указанного статуса)
+    async fn change_status_many(
+        &self,
+        bookings: &[(Uuid, BookingStatus)],
+        to: BookingStatus,
+        actor_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<Vec<Booking>, AppError>;
+    /// Перенести несколько записей атомарно; пересечения проверяются по итогу
+    async fn reschedule_many(
+        &self,
+        changes: &[(Uuid, BookingReschedule)],
+        actor_id: Uuid,
+    ) -> Result<Vec<Booking>, AppError>;
+    /// Занятия групповой услуги у сотрудников, начинающиеся в периоде
+    async fn list_group_sessions(
+        &self,
+        service_id: Uuid,
+        staff_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<GroupSession>, AppError>;
+    /// Занятия компании с участниками, по времени начала
+    async fn list_company_sessions(
+        &self,
+        company_id: Uuid,
+        service_id: Option<Uuid>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<GroupSession>, AppError>;
+    async fn find_group_session(&self, id: Uuid) -> Result<Option<GroupSession>, AppError>;
+    /// Записи на занятие (кроме броней) в порядке записи
+    async fn list_session_attendees(&self, session_id: Uuid) -> Result<Vec<SessionAttendee>, AppError>;
+}
+
+#[async_trait]
+pub trait GroupSessionService {
+    /// Занятия компании за период в датах ее часового пояса; только для сотрудников компании
+    async fn list_sessions(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        query: SessionsQuery,
+    ) -> Result<Vec<GroupSession>, AppError>;
+    async fn get_session(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<GroupSessionDetails, AppError>;
+}
+
+#[async_trait]
+pub trait HoldService {
+    async fn create_hold(&self, customer_id: Uuid, data: CreateHoldRequest) -> Result<SlotHold, AppError>;
+    async fn confirm_hold(&self, customer_id: Uuid, hold_id: Uuid) -> Result<Booking, AppError>;
+    async fn release_hold(&self, customer_id: Uuid, hold_id: Uuid) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait BookingSeriesService {
+    /// Без `skip_conflicts` при конфликтах серия не создается, `series` в ответе пустая
+    async fn create_series(
+        &self,
+        customer_id: Uuid,
+        data: CreateBookingSeriesRequest,
+    ) -> Result<BookingSeriesResponse, AppError>;
+    async fn get_series(&self, user_id: Uuid, series_id: Uuid) -> Result<BookingSeriesResponse, AppError>;
+    async fn cancel_following(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        data: CancelSeriesRequest,
+    ) -> Result<SeriesChangeResponse, AppError>;
+    /// При конфликтах ничего не переносится, конфликты возвращаются в ответе
+    async fn reschedule_following(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        data: RescheduleSeriesRequest,
+    ) -> Result<SeriesChangeResponse, AppError>;
+}
+
+#[async_trait]
+pub trait BookingService {
+    async fn create_booking(&self, customer_id: Uuid, data: CreateBookingRequest) -> Result<Booking, AppError>;
+    async fn get_booking(&self, user_id: Uuid, booking_id: Uuid) -> Result<Booking, AppError>;
+    async fn change_status(
+        &self,
+        user_id: Uuid,
+        booking_id: Uuid,
+        data: ChangeBookingStatusRequest,
+    ) -> Result<Booking, AppError>;
+    async fn reschedule_booking(
+        &self,
+        user_id: Uuid,
+        booking_id: Uuid,
+        data: RescheduleBookingRequest,
+    ) -> Result<Booking, AppError>;
+    async fn booking_history(&self, user_id: Uuid, booking_id: Uuid) -> Result<Vec<BookingEvent>, AppError>;
+    async fn get_policy(&self, company_id: Uuid) -> Result<BookingPolicy, AppError>;
+    /// Заменить политику целиком; опущенные необязательные поля получают значения по умолчанию
+    async fn update_policy(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: UpdateBookingPolicyRequest,
+    ) -> Result<BookingPolicy, AppError>;
+}
+
+/// Ограничения онлайн-записи для клиентов с неявками
+#[async_trait]
+pub trait CustomerRestrictionService {
+    /// Ограничение текущего пользователя как клиента компании
+    async fn my_restriction(&self, customer_id: Uuid, company_id: Uuid) -> Result<CustomerRestrictionStatus, AppError>;
+    /// Ограничение клиента для сотрудников компании
+    async fn customer_restriction(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<CustomerRestrictionDetails, AppError>;
+    /// Задать ограничение вручную вместо правила; только руководители компании
+    async fn set_override(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        data: SetRestrictionOverrideRequest,
+    ) -> Result<CustomerRestrictionDetails, AppError>;
+    /// Вернуть клиента под действие правила
+    async fn clear_override(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<CustomerRestrictionDetails, AppError>;
+}
+
+#[async_trait]
+pub trait BookmarkRepository {
+    /// Добавить закладку; `false`, если она уже была
+    async fn add_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError>;
+    /// Удалить закладку; `false`, если ее не было
+    async fn remove_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError>;
+    async fn is_bookmarked(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError>;
+    /// Страница закладок (новые первыми) и их общее количество
+    async fn list_bookmarks(&self, user_id: Uuid, limit: i64, offset: i64) -> Result<(Vec<Bookmark>, i64), AppError>;
+}
+
+#[async_trait]
+pub trait BookmarkService {
+    async fn add_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError>;
+    async fn remove_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError>;
+    async fn is_bookmarked(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError>;
+    async fn list_bookmarks(&self, user_id: Uuid, query: BookmarksQuery) -> Result<BookmarksResponse, AppError>;
+}
+
+#[async_trait]
+pub trait WaitlistRepository {
+    /// Повторная запись в очередь на ту же услугу и день возвращается как `AppError::Conflict`
+    async fn create_entry(&self, entry: &NewWaitlistEntry) -> Result<WaitlistEntry, AppError>;
+    async fn find_entry(&self, id: Uuid) -> Result<Option<WaitlistEntry>, AppError>;
+    /// Записи клиента, ближайшие дни первыми
+    async fn list_customer_entries(&self, customer_id: Uuid) -> Result<Vec<WaitlistEntry>, AppError>;
+    /// Ожидающие на день компании в порядке очереди, которым подходит сотрудник
+    async fn list_waiting(
+        &self,
+        company_id: Uuid,
+        date: NaiveDate,
+        staff_id: Uuid,
+    ) -> Result<Vec<WaitlistEntry>, AppError>;
+    /// Предложить слот: бронь и статус записи меняются в одной транзакции.
+    /// `None`, если запись уже не ожидает
+    async fn make_offer(
+        &self,
+        entry_id: Uuid,
+        hold: &NewBooking,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<WaitlistEntry>, AppError>;
+    /// Принять предложение: бронь становится записью, запись очереди закрывается
+    /// в одной транзакции. Conflict, если предложение уже не действует
+    async fn accept_offer(
+        &self,
+        entry_id: Uuid,
+        hold_id: Uuid,
+        customer_id: Uuid,
+        status: BookingStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Booking, AppError>;
+    /// Выйти из очереди или отказаться от предложения; бронь предложения снимается.
+    /// `None`, если ожидающей записи клиента нет
+    async fn close_entry(
+        &self,
+        entry_id: Uuid,
+        customer_id: Uuid,
+        status: WaitlistStatus,
+    ) -> Result<Option<WaitlistEntry>, AppError>;
+    /// Просрочить предложения, истекшие к `now`, и снять их брони.
+    /// Предложения, которые клиент успел подтвердить, отмечаются как записанные
+    async fn expire_offers(&self, now: DateTime<Utc>) -> Result<Vec<WaitlistEntry>, AppError>;
+}
+
+#[async_trait]
+pub trait WaitlistService {
+    /// Встать в очередь можно только на день, где для услуги нет свободного времени
+    async fn join_waitlist(&self, customer_id: Uuid, data: JoinWaitlistRequest) -> Result<WaitlistEntry, AppError>;
+    async fn list_waitlist(&self, customer_id: Uuid) -> Result<Vec<WaitlistEntry>, AppError>;
+    async fn leave_waitlist(&self, customer_id: Uuid, entry_id: Uuid) -> Result<(), AppError>;
+    async fn accept_offer(&self, customer_id: Uuid, entry_id: Uuid) -> Result<Booking, AppError>;
+    async fn decline_offer(&self, customer_id: Uuid, entry_id: Uuid) -> Result<(), AppError>;
+    /// Предложить освободившийся слот первому подходящему в очереди
+    async fn offer_freed_slot(&self, slot: FreedSlot) -> Result<Option<WaitlistEntry>, AppError>;
+    /// Передать просроченные предложения следующим в очереди; возвращает их количество
+    async fn process_expired_offers(&self) -> Result<usize, AppError>;
+}
+
+/// Канал доставки уведомлений: почта, вебхук, журнал
+#[async_trait]
+pub trait Notifier {
+    /// Имя канала, под которым отмечается доставка в outbox
+    fn channel(&self) -> &'static str;
+    async fn send(&self, message: &OutgoingMessage) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait NotificationOutbox {
+    /// Поставить уведомление в очередь отдельно от изменения данных.
+    /// Уведомления об изменениях записей ставятся в той же транзакции репозиториями
+    async fn enqueue(&self, notification: &Notification) -> Result<Uuid, AppError>;
+    /// Захватить готовые к отправке сообщения до `lease_until`: строки выбираются через
+    /// `FOR UPDATE SKIP LOCKED`, поэтому несколько экземпляров сервера не отправят одно дважды
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<OutboxMessage>, AppError>;
+    async fn find_recipient(&self, user_id: Uuid) -> Result<Option<Recipient>, AppError>;
+    async fn mark_sent(&self, id: Uuid, delivered_channels: &[String]) -> Result<(), AppError>;
+    async fn mark_retry(
+        &self,
+        id: Uuid,
+        delivered_channels: &[String],
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), AppError>;
+    /// Попытки исчерпаны: сообщение остается в таблице со статусом failed
+    async fn mark_failed(&self, id: Uuid, delivered_channels: &[String], error: &str) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait ReminderRepository {
+    async fn list_rules(&self, company_id: Uuid) -> Result<Vec<ReminderRule>, AppError>;
+    /// Заменить набор правил компании. Напоминания удаленных правил отменяются,
+    /// по новым правилам планируются напоминания для уже созданных будущих записей
+    async fn replace_rules(&self, company_id: Uuid, offsets_minutes: &[i32]) -> Result<Vec<ReminderRule>, AppError>;
+    async fn list_booking_reminders(&self, booking_id: Uuid) -> Result<Vec<BookingReminder>, AppError>;
+    /// Отправить наступившие напоминания: строки выбираются через `FOR UPDATE SKIP LOCKED`
+    /// и в той же транзакции ставятся в outbox уведомлений, поэтому несколько экземпляров
+    /// сервера не напомнят дважды. Возвращает число отправленных
+    async fn send_due_reminders(&self, now: DateTime<Utc>, limit: i64) -> Result<u64, AppError>;
+}
+
+#[async_trait]
+pub trait ReminderService {
+    async fn get_rules(&self, user_id: Uuid, company_id: Uuid) -> Result<Vec<ReminderRule>, AppError>;
+    async fn update_rules(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: UpdateReminderRulesRequest,
+    ) -> Result<Vec<ReminderRule>, AppError>;
+    /// Напоминания записи; доступны клиенту и сотрудникам компании
+    async fn booking_reminders(&self, user_id: Uuid, booking_id: Uuid) -> Result<Vec<BookingReminder>, AppError>;
+}
+
+#[async_trait]
+pub trait CalendarRepository {
+    /// Выпустить ссылку на ленту; прежняя ссылка на ту же ленту отзывается
+    async fn create_feed(
+        &self,
+        user_id: Uuid,
+        staff: Option<(Uuid, Uuid)>,
+        token: &str,
+    ) -> Result<CalendarFeed, AppError>;
+    /// Отозвать действующую ссылку; `false`, если ее не было
+    async fn revoke_feed(&self, user_id: Uuid, staff_id: Option<Uuid>) -> Result<bool, AppError>;
+    /// Действующая лента по токену из ссылки
+    async fn find_feed(&self, token: &str) -> Result<Option<CalendarFeed>, AppError>;
+    async fn find_event(&self, booking_id: Uuid) -> Result<Option<CalendarEvent>, AppError>;
+    /// Активные записи пользователя как клиента и как сотрудника, заканчивающиеся после `from`
+    async fn list_user_events(&self, user_id: Uuid, from: DateTime<Utc>) -> Result<Vec<CalendarEvent>, AppError>;
+    /// Активные записи к сотруднику, заканчивающиеся после `from`
+    async fn list_staff_events(&self, staff_id: Uuid, from: DateTime<Utc>) -> Result<Vec<CalendarEvent>, AppError>;
+}
+
+#[async_trait]
+pub trait CalendarService {
+    /// Одна запись в формате iCalendar; доступна клиенту и сотрудникам компании
+    async fn booking_calendar(&self, user_id: Uuid, booking_id: Uuid) -> Result<String, AppError>;
+    async fn create_user_feed(&self, user_id: Uuid) -> Result<CalendarFeedResponse, AppError>;
+    async fn revoke_user_feed(&self, user_id: Uuid) -> Result<(), AppError>;
+    /// Лента записей сотрудника; выдается только руководителям компании
+    async fn create_staff_feed(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        staff_id: Uuid,
+    ) -> Result<CalendarFeedResponse, AppError>;
+    async fn revoke_staff_feed(&self, user_id: Uuid, company_id: Uuid, staff_id: Uuid) -> Result<(), AppError>;
+    /// Содержимое ленты по секретному токену
+    async fn feed_calendar(&self, token: &str) -> Result<String, AppError>;
+}
+
+#[async_trait]
+pub trait JobRepository {
+    /// Поставить задачу в очередь; `None`, если задача с тем же `dedupe_key` уже ждет
+    async fn enqueue(&self, job: &NewJob) -> Result<Option<JobRecord>, AppError>;
+    /// Захватить готовые задачи перечисленных видов до `lease_until` и засчитать попытку.
+    /// Строки выбираются через `FOR UPDATE SKIP LOCKED`; задача воркера, не успевшего
+    /// за время аренды, снова становится доступной, а на последней попытке переходит в dead
+    async fn claim(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        kinds: &[String],
+        limit: i64,
+    ) -> Result<Vec<JobRecord>, AppError>;
+    /// Завершение, повтор и перевод в dead меняют задачу, только пока за воркером
+    /// сохраняется аренда `lease_until` из `claim`; `false` - аренда потеряна,
+    /// задачу уже забрал другой воркер
+    async fn complete(&self, id: Uuid, lease_until: DateTime<Utc>) -> Result<bool, AppError>;
+    /// Вернуть задачу в очередь после ошибки. Если такая же задача (по `dedupe_key`)
+    /// уже ждет в очереди, повтор не нужен и задача закрывается как выполненная
+    async fn retry_later(
+        &self,
+        id: Uuid,
+        lease_until: DateTime<Utc>,
+        error: &str,
+        run_at: DateTime<Utc>,
+    ) -> Result<bool, AppError>;
+    /// Попытки исчерпаны: задача переходит в dead
+    async fn bury(&self, id: Uuid, lease_until: DateTime<Utc>, error: &str) -> Result<bool, AppError>;
+    async fn list_jobs(&self, query: &JobsQuery) -> Result<Vec<JobRecord>, AppError>;
+    /// Повторить задачу из dead с новым счетчиком попыток; `None`, если такой задачи в dead нет
+    async fn retry_dead(&self, id: Uuid) -> Result<Option<JobRecord>, AppError>;
+}
+
+#[async_trait]
+pub trait JobAdminService {
+    async fn list_jobs(&self, user_id: Uuid, query: JobsQuery) -> Result<Vec<JobRecord>, AppError>;
+    async fn retry_job(&self, user_id: Uuid, job_id: Uuid) -> Result<JobRecord, AppError>;
+}
+
+#[async_trait]
+pub trait WebhookRepository {
+    async fn create_subscription(
+        &self,
+        company_id: Uuid,
+        url: &str,
+        events: &[WebhookEvent],
+        secret: &str,
+    ) -> Result<WebhookSubscription, AppError>;
+    async fn list_subscriptions(&self, company_id: Uuid) -> Result<Vec<WebhookSubscription>, AppError>;
+    async fn find_subscription(&self, id: Uuid) -> Result<Option<WebhookSubscription>, AppError>;
+    /// Изменить заданные поля; `None`, если подписки нет
+    async fn update_subscription(
+        &self,
+        id: Uuid,
+        url: Option<&str>,
+        events: Option<&[WebhookEvent]>,
+        is_active: Option<bool>,
+    ) -> Result<Option<WebhookSubscription>, AppError>;
+    async fn delete_subscription(&self, id: Uuid) -> Result<bool, AppError>;
+    async fn list_deliveries(
+        &self,
+        subscription_id: Uuid,
+        query: &WebhookDeliveriesQuery,
+    ) -> Result<Vec<WebhookDelivery>, AppError>;
+    /// Поставить проверочное событие в очередь доставки подписки
+    async fn enqueue_test_delivery(&self, subscription_id: Uuid) -> Result<WebhookDelivery, AppError>;
+    /// Недоставленная доставка с адресом и секретом; `None`, если доставлять уже нечего
+    async fn find_pending_delivery(&self, id: Uuid) -> Result<Option<WebhookDeliveryTarget>, AppError>;
+    /// Записать результат попытки: без ошибки доставка завершена, иначе после `max_attempts`
+    /// попыток получает статус failed
+    async fn record_attempt(
+        &self,
+        id: Uuid,
+        response_status: Option<i32>,
+        error: Option<&str>,
+        max_attempts: i32,
+    ) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait WebhookService {
+    async fn create_webhook(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreateWebhookRequest,
+    ) -> Result<WebhookSubscriptionResponse, AppError>;
+    async fn list_webhooks(&self, user_id: Uuid, company_id: Uuid) -> Result<Vec<WebhookSubscription>, AppError>;
+    async fn update_webhook(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        webhook_id: Uuid,
+        data: UpdateWebhookRequest,
+    ) -> Result<WebhookSubscription, AppError>;
+    async fn delete_webhook(&self, user_id: Uuid, company_id: Uuid, webhook_id: Uuid) -> Result<(), AppError>;
+    async fn list_deliveries(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        webhook_id: Uuid,
+        query: WebhookDeliveriesQuery,
+    ) -> Result<Vec<WebhookDelivery>, AppError>;
+    async fn send_test_event(&self, user_id: Uuid, company_id: Uuid, webhook_id: Uuid) -> Result<WebhookDelivery, AppError>;
+}
+
+#[async_trait]
+pub trait ReviewRepository {
+    /// Повторный отзыв на ту же запись возвращается как `AppError::Conflict`
+    async fn create_review(&self, booking: &Booking, data: &CreateReviewRequest) -> Result<Review, AppError>;
+    async fn find_review(&self, id: Uuid) -> Result<Option<Review>, AppError>;
+    /// Видимые отзывы компании (или ее сотрудника), новые первыми, со средней оценкой и количеством
+    async fn list_reviews(
+        &self,
+        company_id: Uuid,
+        staff_id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Review>, Option<f64>, i64), AppError>;
+    async fn set_reply(&self, id: Uuid, reply: &str) -> Result<Option<Review>, AppError>;
+    async fn set_visibility(
+        &self,
+        id: Uuid,
+        is_hidden: bool,
+        reason: Option<&str>,
+        moderator_id: Uuid,
+    ) -> Result<Option<Review>, AppError>;
+}
+
+#[async_trait]
+pub trait ReviewService {
+    async fn create_review(&self, user_id: Uuid, booking_id: Uuid, data: CreateReviewRequest) -> Result<Review, AppError>;
+    async fn list_reviews(&self, company_id: Uuid, query: ReviewsQuery) -> Result<ReviewsResponse, AppError>;
+    async fn reply_to_review(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        review_id: Uuid,
+        data: ReviewReplyRequest,
+    ) -> Result<Review, AppError>;
+    async fn set_visibility(
+        &self,
+        user_id: Uuid,
+        review_id: Uuid,
+        data: ReviewVisibilityRequest,
+    ) -> Result<Review, AppError>;
+}
+
+#[async_trait]
+pub trait SearchRepository {
+    /// До `filter.limit` компаний по порядку сортировки, начиная после `filter.after`
+    async fn search_companies(&self, filter: &CompanySearchFilter) -> Result<Vec<CompanySearchHit>, AppError>;
+    /// Общее число найденных компаний и фасеты без учета страницы
+    async fn search_facets(&self, filter: &CompanySearchFilter) -> Result<(i64, SearchFacets), AppError>;
+}
+
+#[async_trait]
+pub trait SearchService {
+    async fn search_companies(&self, query: CompanySearchQuery) -> Result<CompanySearchResponse, AppError>;
+}
+
+/// Хранилище файлов изображений: локальный каталог или S3-совместимый бакет
+#[async_trait]
+pub trait MediaStorage {
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), AppError>;
+    /// Содержимое файла; `None`, если файла нет
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AppError>;
+    /// Удаление отсутствующего файла не ошибка
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+    /// Адрес для клиента: постоянный публичный или подписанный с ограниченным сроком
+    fn url(&self, key: &str) -> String;
+}
+
+#[async_trait]
+pub trait MediaRepository {
+    /// Сохранить изображение. Новый аватар или логотип заменяет прежний: замененные записи
+    /// возвращаются, чтобы удалить их файлы
+    async fn save_media(&self, media: &NewMedia) -> Result<(MediaAsset, Vec<MediaAsset>), AppError>;
+    async fn find_user_avatar(&self, user_id: Uuid) -> Result<Option<MediaAsset>, AppError>;
+    /// Логотип и галерея компании, снимки галереи в порядке загрузки
+    async fn list_company_media(&self, company_id: Uuid) -> Result<Vec<MediaAsset>, AppError>;
+    async fn delete_media(&self, id: Uuid) -> Result<Option<MediaAsset>, AppError>;
+}
+
+#[async_trait]
+pub trait MediaService {
+    /// Наибольший размер загружаемого файла в байтах
+    fn max_upload_bytes(&self) -> usize;
+    async fn upload_avatar(&self, user_id: Uuid, bytes: Vec<u8>) -> Result<MediaImage, AppError>;
+    async fn delete_avatar(&self, user_id: Uuid) -> Result<(), AppError>;
+    async fn user_avatar(&self, user_id: Uuid) -> Result<Option<MediaImage>, AppError>;
+    async fn upload_logo(&self, user_id: Uuid, company_id: Uuid, bytes: Vec<u8>) -> Result<MediaImage, AppError>;
+    async fn delete_logo(&self, user_id: Uuid, company_id: Uuid) -> Result<(), AppError>;
+    async fn add_gallery_image(&self, user_id: Uuid, company_id: Uuid, bytes: Vec<u8>) -> Result<MediaImage, AppError>;
+    async fn delete_gallery_image(&self, user_id: Uuid, company_id: Uuid, media_id: Uuid) -> Result<(), AppError>;
+    async fn company_media(&self, company_id: Uuid) -> Result<CompanyMedia, AppError>;
+    /// Файл из хранилища с типом содержимого, для раздачи сервером
+    async fn read_file(&self, key: &str) -> Result<Option<(&'static str, Vec<u8>)>, AppError>;
+}
+
+#[async_trait]
+pub trait CustomerRepository {
+    /// Клиенты компании (пользователи с записями в ней), недавние первыми, и их общее число
+    async fn list_customers(
+        &self,
+        company_id: Uuid,
+        search: Option<&str>,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<CustomerSummary>, i64), AppError>;
+    /// Сводка по клиенту; `None`, если у пользователя нет записей в компании
+    async fn find_customer(&self, company_id: Uuid, customer_id: Uuid) -> Result<Option<CustomerSummary>, AppError>;
+    async fn list_customer_bookings(&self, company_id: Uuid, customer_id: Uuid) -> Result<Vec<Booking>, AppError>;
+    async fn list_notes(&self, company_id: Uuid, customer_id: Uuid) -> Result<Vec<CustomerNote>, AppError>;
+    async fn find_note(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+        note_id: Uuid,
+    ) -> Result<Option<CustomerNote>, AppError>;
+    async fn create_note(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+        author_id: Uuid,
+        body: &str,
+    ) -> Result<CustomerNote, AppError>;
+    async fn update_note(&self, note_id: Uuid, body: &str) -> Result<Option<CustomerNote>, AppError>;
+    async fn delete_note(&self, note_id: Uuid) -> Result<bool, AppError>;
+    /// Заменить метки клиента; возвращает новый набор по алфавиту
+    async fn replace_tags(&self, company_id: Uuid, customer_id: Uuid, tags: &[String]) -> Result<Vec<String>, AppError>;
+}
+
+/// Клиенты компании для ее сотрудников. Заметки и метки закрытые: клиент их не видит
+#[async_trait]
+pub trait CustomerService {
+    async fn list_customers(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        query: CustomersQuery,
+    ) -> Result<CustomersResponse, AppError>;
+    async fn get_customer(&self, user_id: Uuid, company_id: Uuid, customer_id: Uuid) -> Result<CustomerProfile, AppError>;
+    async fn add_note(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        data: CustomerNoteRequest,
+    ) -> Result<CustomerNote, AppError>;
+    /// Изменять и удалять заметку может ее автор или руководитель компании
+    async fn update_note(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        note_id: Uuid,
+        data: CustomerNoteRequest,
+    ) -> Result<CustomerNote, AppError>;
+    async fn delete_note(&self, user_id: Uuid, company_id: Uuid, customer_id: Uuid, note_id: Uuid) -> Result<(), AppError>;
+    async fn update_tags(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        customer_id: Uuid,
+        data: UpdateCustomerTagsRequest,
+    ) -> Result<Vec<String>, AppError>;
+}
+
+/// Платежная система: создание платежа, возврат и проверка уведомлений
+#[async_trait]
+pub trait PaymentProvider {
+    /// Имя в адресе вебхука и в записи платежа
+    fn name(&self) -> &'static str;
+    async fn create_payment(&self, request: &ProviderPaymentRequest) -> Result<ProviderPayment, AppError>;
+    /// Вернуть `amount` по оплаченному платежу; повтор с тем же ключом не возвращает деньги дважды
+    async fn refund(
+        &self,
+        provider_payment_id: &str,
+        amount: i64,
+        currency: &str,
+        idempotency_key: &str,
+    ) -> Result<(), AppError>;
+    /// Проверить подпись уведомления и разобрать его. Заголовки - с именами в нижнем регистре;
+    /// `None` - событие, не влияющее на платежи
+    fn parse_webhook(
+        &self,
+        headers: &HashMap<String, String>,
+        body: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<PaymentWebhookEvent>, AppError>;
+}
+
+#[async_trait]
+pub trait PaymentRepository {
+    async fn find_payment(&self, id: Uuid) -> Result<Option<Payment>, AppError>;
+    async fn find_booking_payment(&self, booking_id: Uuid) -> Result<Option<Payment>, AppError>;
+    async fn find_provider_payment(&self, provider: &str, provider_payment_id: &str) -> Result<Option<Payment>, AppError>;
+    /// Привязать новую попытку оплаты к депозиту, который ждет оплаты или не был оплачен
+    async fn start_attempt(
+        &self,
+        payment_id: Uuid,
+        provider: &str,
+        attempt: &ProviderPayment,
+    ) -> Result<Option<Payment>, AppError>;
+    /// Сменить статус, если он один из `from`; `None` - статус уже другой
+    async fn update_status(
+        &self,
+        payment_id: Uuid,
+        from: &[PaymentStatus],
+        to: PaymentStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Payment>, AppError>;
+    /// Перевести оплаченный депозит в возврат и поставить задачу возврата в очередь
+    async fn request_refund(&self, payment_id: Uuid, amount: i64) -> Result<Option<Payment>, AppError>;
+    /// Неоплаченные депозиты записей, все еще ждущих оплаты, созданные до `created_before`
+    async fn list_unpaid(&self, created_before: DateTime<Utc>) -> Result<Vec<Payment>, AppError>;
+}
+
+/// Депозиты за записи на услуги с предоплатой
+#[async_trait]
+pub trait PaymentService {
+    /// Депозит записи для клиента и сотрудников компании
+    async fn get_payment(&self, user_id: Uuid, booking_id: Uuid) -> Result<Payment, AppError>;
+    /// Начать оплату: создать платеж в платежной системе или вернуть уже начатый
+    async fn start_payment(&self, customer_id: Uuid, booking_id: Uuid) -> Result<Payment, AppError>;
+    /// Уведомление платежной системы `provider`
+    async fn handle_webhook(&self, provider: &str, headers: &HashMap<String, String>, body: &str) -> Result<(), AppError>;
+    /// Отменить записи, депозит по которым не оплачен вовремя; возвращает их количество
+    async fn expire_unpaid_deposits(&self) -> Result<usize, AppError>;
+}
+
+/// Отметка о приходе по QR-коду: клиент показывает код, сотрудник на стойке его сканирует
+#[async_trait]
+pub trait CheckinService {
+    /// Свежий токен для QR-кода подтвержденной записи клиента
+    async fn checkin_token(&self, customer_id: Uuid, booking_id: Uuid) -> Result<CheckinToken, AppError>;
+    /// Тот же токен картинкой QR-кода
+    async fn checkin_qr(&self, customer_id: Uuid, booking_id: Uuid, format: QrImageFormat) -> Result<QrImage, AppError>;
+    /// Проверить токен и отметить приход; сотрудник должен быть в филиале записи
+    async fn check_in(&self, user_id: Uuid, company_id: Uuid, data: CheckinRequest) -> Result<Booking, AppError>;
+}
+
+#[async_trait]
+pub trait PromoCodeRepository {
+    async fn create_promo_code(&self, promo_code: &NewPromoCode) -> Result<PromoCode, AppError>;
+    async fn list_promo_codes(&self, company_id: Uuid) -> Result<Vec<PromoCode>, AppError>;
+    async fn find_promo_code(&self, company_id: Uuid, id: Uuid) -> Result<Option<PromoCode>, AppError>;
+    /// Поиск по коду в верхнем регистре
+    async fn find_promo_code_by_code(&self, company_id: Uuid, code: &str) -> Result<Option<PromoCode>, AppError>;
+    async fn update_promo_code(&self, id: Uuid, data: &UpdatePromoCodeRequest) -> Result<Option<PromoCode>, AppError>;
+    async fn count_customer_redemptions(&self, promo_code_id: Uuid, customer_id: Uuid) -> Result<i64, AppError>;
+}
+
+/// Промокоды: кампании компании и расчет цены записи для клиента
+#[async_trait]
+pub trait PromoCodeService {
+    async fn create_promo_code(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        data: CreatePromoCodeRequest,
+    ) -> Result<PromoCode, AppError>;
+    async fn list_promo_codes(&self, user_id: Uuid, company_id: Uuid) -> Result<Vec<PromoCode>, AppError>;
+    async fn update_promo_code(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        promo_code_id: Uuid,
+        data: UpdatePromoCodeRequest,
+    ) -> Result<PromoCode, AppError>;
+    /// Цена записи с учетом промокода до ее создания
+    async fn quote_booking(&self, customer_id: Uuid, data: BookingQuoteRequest) -> Result<BookingQuote, AppError>;
+}
+
+#[async_trait]
+pub trait QueueRepository {
+    /// Выдать талон со следующим номером дня; второй активный талон клиента - `AppError::Conflict`
+    async fn create_ticket(&self, ticket: &NewQueueTicket) -> Result<QueueTicket, AppError>;
+    async fn find_ticket(&self, id: Uuid) -> Result<Option<QueueTicket>, AppError>;
+    async fn list_tickets(&self, location_id: Uuid, queue_date: NaiveDate) -> Result<Vec<QueueTicket>, AppError>;
+    /// Вызвать первого ожидающего; одновременные вызовы не достанутся одному талону
+    async fn call_next(
+        &self,
+        location_id: Uuid,
+        queue_date: NaiveDate,
+        called_by: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<QueueTicket>, AppError>;
+    /// Сменить статус, если талон в одном из статусов `from`; иначе `None`
+    async fn update_ticket_status(
+        &self,
+        id: Uuid,
+        from: &[QueueTicketStatus],
+        status: QueueTicketStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<QueueTicket>, AppError>;
+    /// Сколько ожидающих талонов того же дня стоит перед номером `number`
+    async fn count_waiting_before(&self, location_id: Uuid, queue_date: NaiveDate, number: i32) -> Result<i64, AppError>;
+    /// Средняя длительность обслуживания в филиале по талонам, обслуженным после `since`
+    async fn average_service_minutes(&self, location_id: Uuid, since: DateTime<Utc>) -> Result<Option<f64>, AppError>;
+    async fn daily_stats(&self, location_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<QueueDayStats>, AppError>;
+}
+
+/// Рассылка событий очередей подписчикам; события приходят от всех экземпляров сервера
+pub trait QueueEventBus {
+    fn subscribe(&self, location_id: Uuid) -> BoxStream<'static, QueueEvent>;
+}
+
+/// Электронная очередь филиала для клиентов без записи
+#[async_trait]
+pub trait QueueService {
+    async fn join_queue(&self, user_id: Uuid, company_id: Uuid, data: JoinQueueRequest) -> Result<QueueTicketView, AppError>;
+    /// Место в очереди и ожидание; талон открыт любому, кто знает его id
+    async fn get_ticket(&self, ticket_id: Uuid) -> Result<QueueTicketView, AppError>;
+    /// Текущее состояние талона, затем каждое его изменение; поток закрывается, когда талон покидает очередь
+    async fn ticket_updates(&self, ticket_id: Uuid) -> Result<BoxStream<'static, QueueTicketView>, AppError>;
+    async fn list_queue(&self, user_id: Uuid, company_id: Uuid, query: LocationQueueQuery) -> Result<LocationQueue, AppError>;
+    async fn call_next(&self, user_id: Uuid, company_id: Uuid, data: CallNextRequest) -> Result<QueueTicket, AppError>;
+    /// Клиент может только отменить свой талон, сотрудники - завершить обслуживание
+    async fn change_ticket_status(
+        &self,
+        user_id: Uuid,
+        ticket_id: Uuid,
+        data: ChangeQueueTicketStatusRequest,
+    ) -> Result<QueueTicket, AppError>;
+    async fn queue_stats(&self, user_id: Uuid, company_id: Uuid, query: QueueStatsQuery) -> Result<Vec<QueueDayStats>, AppError>;
+}
+
+/// Агрегаты для отчетов из материализованных представлений; даты - дни в часовом поясе компании
+#[async_trait]
+pub trait AnalyticsRepository {
+    /// Время самого давнего обновления представлений
+    async fn refreshed_at(&self) -> Result<Option<DateTime<Utc>>, AppError>;
+    async fn bookings_report(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<BookingsReportRow>, AppError>;
+    async fn staff_booked_minutes(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<StaffBookedMinutes>, AppError>;
+    async fn cancellations_report(
+        &self,
+        company_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<CancellationsReportRow>, AppError>;
+    async fn revenue_report(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<RevenueReportRow>, AppError>;
+    async fn customers_report(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<CustomersReportRow>, AppError>;
+}
+
+/// Отчеты для владельцев и менеджеров компании
+#[async_trait]
+pub trait AnalyticsService {
+    async fn report(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        kind: AnalyticsReportKind,
+        query: &AnalyticsQuery,
+    ) -> Result<AnalyticsReport, AppError>;
+    /// Строки того же отчета в CSV с заголовком
+    async fn report_csv(
+        &self,
+        user_id: Uuid,
+        company_id: Uuid,
+        kind: AnalyticsReportKind,
+        query: &AnalyticsQuery,
+    ) -> Result<String, AppError>;
+}
@@ -0,0 +1,12 @@
+use crate::domain::traits::Clock;
+use chrono::{DateTime, Utc};
+
+/// Системные часы
+#[derive(Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
@@ -0,0 +1,140 @@
+//! Платежная система для разработки и тестов. Платежи создаются без внешнего сервиса, а об
+//! исходе оплаты сообщает уведомление `{"id", "type": "payment.succeeded" | "payment.failed",
+//! "payment_id"}` с заголовком `X-Fake-Payments-Signature: sha256=<hex>` - HMAC-SHA256 тела
+//! общим секретом. Возвраты только запоминаются.
+
+use crate::application::hmac_signature;
+use crate::domain::entities::{PaymentOutcome, PaymentWebhookEvent, ProviderPayment, ProviderPaymentRequest};
+use crate::domain::errors::AppError;
+use crate::domain::traits::PaymentProvider;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+pub const FAKE_SIGNATURE_HEADER: &str = "x-fake-payments-signature";
+
+/// Возврат, принятый тестовой платежной системой
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeRefund {
+    pub provider_payment_id: String,
+    pub amount: i64,
+    pub currency: String,
+}
+
+#[derive(Deserialize)]
+struct FakeEvent {
+    id: String,
+    #[serde(rename = "type")]
+    event_type: String,
+    payment_id: String,
+}
+
+pub struct FakePaymentProvider {
+    secret: String,
+    /// Возвраты по ключам идемпотентности
+    refunds: Mutex<Vec<(String, FakeRefund)>>,
+}
+
+impl FakePaymentProvider {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            secret: secret.to_string(),
+            refunds: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Значение заголовка подписи для тела уведомления
+    pub fn sign(&self, body: &str) -> String {
+        format!("sha256={}", hmac_signature::sign_hex(self.secret.as_bytes(), &[body.as_bytes()]))
+    }
+
+    /// Тело уведомления об исходе платежа
+    pub fn event_body(provider_payment_id: &str, outcome: PaymentOutcome) -> String {
+        let event_type = match outcome {
+            PaymentOutcome::Succeeded => "payment.succeeded",
+            PaymentOutcome::Failed => "payment.failed",
+        };
+        serde_json::json!({
+            "id": format!("evt_{}", Uuid::new_v4().simple()),
+            "type": event_type,
+            "payment_id": provider_payment_id,
+        })
+        .to_string()
+    }
+
+    pub fn refunds(&self) -> Vec<FakeRefund> {
+        self.refunds
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, refund)| refund.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PaymentProvider for FakePaymentProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn create_payment(&self, _request: &ProviderPaymentRequest) -> Result<ProviderPayment, AppError> {
+        let id = Uuid::new_v4().simple();
+        Ok(ProviderPayment {
+            provider_payment_id: format!("fake_pi_{}", id),
+            client_secret: Some(format!("fake_pi_{}_secret", id)),
+        })
+    }
+
+    async fn refund(
+        &self,
+        provider_payment_id: &str,
+        amount: i64,
+        currency: &str,
+        idempotency_key: &str,
+    ) -> Result<(), AppError> {
+        let mut refunds = self.refunds.lock().unwrap();
+        if !refunds.iter().any(|(key, _)| key == idempotency_key) {
+            refunds.push((
+                idempotency_key.to_string(),
+                FakeRefund {
+                    provider_payment_id: provider_payment_id.to_string(),
+                    amount,
+                    currency: currency.to_string(),
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_webhook(
+        &self,
+        headers: &HashMap<String, String>,
+        body: &str,
+        _now: DateTime<Utc>,
+    ) -> Result<Option<PaymentWebhookEvent>, AppError> {
+        let signature = headers
+            .get(FAKE_SIGNATURE_HEADER)
+            .and_then(|signature| signature.strip_prefix("sha256="))
+            .ok_or_else(|| AppError::Validation("Нет подписи уведомления".to_string()))?;
+        if !hmac_signature::verify_hex(self.secret.as_bytes(), &[body.as_bytes()], signature) {
+            return Err(AppError::Validation("Неверная подпись уведомления".to_string()));
+        }
+
+        let event: FakeEvent = serde_json::from_str(body)
+            .map_err(|e| AppError::Validation(format!("Некорректное уведомление: {}", e)))?;
+        let outcome = match event.event_type.as_str() {
+            "payment.succeeded" => PaymentOutcome::Succeeded,
+            "payment.failed" => PaymentOutcome::Failed,
+            _ => return Ok(None),
+        };
+        Ok(Some(PaymentWebhookEvent {
+            event_id: event.id,
+            provider_payment_id: event.payment_id,
+            outcome,
+        }))
+    }
+}
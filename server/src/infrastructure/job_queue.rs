@@ -0,0 +1,279 @@
+//! Очередь фоновых задач поверх таблицы `jobs`. Задача - сериализуемая структура с видом
+//! `Job::KIND`; воркер захватывает готовые задачи через `FOR UPDATE SKIP LOCKED`, поэтому
+//! воркеры можно запускать в каждом экземпляре сервера. Упавшая задача повторяется с
+//! экспоненциальной паузой, а исчерпавшая попытки переходит в dead до ручного повтора.
+
+use crate::domain::entities::{JobRecord, NewJob};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, JobRepository};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use futures_util::future::{BoxFuture, Either, select};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Сколько раз выполнять задачу, прежде чем перевести ее в dead
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+
+/// Канал LISTEN/NOTIFY, в который триггер таблицы jobs сообщает о новых задачах
+const JOBS_CHANNEL: &str = "jobs";
+
+/// Пауза перед повторным чтением уведомлений после ошибки соединения
+const LISTEN_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Задач за один проход
+const BATCH_SIZE: i64 = 20;
+
+/// Пауза перед повтором: 10 секунд, удваивается с каждой попыткой, но не больше часа
+pub fn job_retry_delay(attempt: i32) -> Duration {
+    let exponent = (attempt - 1).clamp(0, 9) as u32;
+    Duration::seconds(10 * 2_i64.pow(exponent)).min(Duration::hours(1))
+}
+
+/// Данные задачи, хранящиеся в `jobs.payload`
+pub trait Job: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Вид задачи в таблице; по нему воркер выбирает обработчик
+    const KIND: &'static str;
+    const MAX_ATTEMPTS: i32 = DEFAULT_MAX_ATTEMPTS;
+}
+
+#[async_trait]
+pub trait JobHandler<J: Job>: Send + Sync {
+    /// Ошибка означает повтор задачи позже
+    async fn handle(&self, job: J) -> Result<(), AppError>;
+}
+
+enum JobError {
+    /// Данные не разбираются - повтор не поможет
+    BadPayload(String),
+    Failed(AppError),
+}
+
+type ErasedHandler = Arc<dyn Fn(Value) -> BoxFuture<'static, Result<(), JobError>> + Send + Sync>;
+
+/// Постановка задач в очередь
+#[derive(Clone)]
+pub struct JobQueue {
+    repository: Arc<dyn JobRepository + Send + Sync>,
+}
+
+impl JobQueue {
+    pub fn new(repository: Arc<dyn JobRepository + Send + Sync>) -> Self {
+        Self { repository }
+    }
+
+    /// Выполнить задачу как можно скорее
+    pub async fn enqueue<J: Job>(&self, job: &J) -> Result<Option<JobRecord>, AppError> {
+        self.enqueue_at(job, None, None).await
+    }
+
+    /// Выполнить задачу не раньше `run_at`. Пока в очереди ждет задача с тем же
+    /// `dedupe_key`, новая не ставится и возвращается `None`
+    pub async fn enqueue_at<J: Job>(
+        &self,
+        job: &J,
+        run_at: Option<DateTime<Utc>>,
+        dedupe_key: Option<String>,
+    ) -> Result<Option<JobRecord>, AppError> {
+        let payload = serde_json::to_value(job)
+            .map_err(|e| AppError::Internal(format!("Ошибка сериализации задачи {}: {}", J::KIND, e)))?;
+        self.repository
+            .enqueue(&NewJob {
+                kind: J::KIND.to_string(),
+                payload,
+                run_at,
+                max_attempts: J::MAX_ATTEMPTS,
+                dedupe_key,
+            })
+            .await
+    }
+}
+
+/// Фоновый обработчик задач зарегистрированных видов
+pub struct JobWorker {
+    repository: Arc<dyn JobRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+    handlers: HashMap<String, ErasedHandler>,
+    lease: Duration,
+    notifications: Option<PgPool>,
+}
+
+impl JobWorker {
+    pub fn new(repository: Arc<dyn JobRepository + Send + Sync>, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        Self {
+            repository,
+            clock,
+            handlers: HashMap::new(),
+            lease: Duration::minutes(5),
+            notifications: None,
+        }
+    }
+
+    pub fn register<J: Job>(mut self, handler: impl JobHandler<J> + 'static) -> Self {
+        let handler = Arc::new(handler);
+        let erased: ErasedHandler = Arc::new(move |payload| {
+            let handler = handler.clone();
+            Box::pin(async move {
+                let job: J = serde_json::from_value(payload).map_err(|e| JobError::BadPayload(e.to_string()))?;
+                handler.handle(job).await.map_err(JobError::Failed)
+            })
+        });
+        self.handlers.insert(J::KIND.to_string(), erased);
+        self
+    }
+
+    /// Сколько задача может выполняться, прежде чем ее заберет другой воркер
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Просыпаться по NOTIFY о новой задаче, не дожидаясь очередного опроса
+    pub fn with_notifications(mut self, pool: PgPool) -> Self {
+        self.notifications = Some(pool);
+        self
+    }
+
+    /// Один проход: выполнить готовые задачи; возвращает число успешно выполненных
+    pub async fn run_once(&self) -> Result<usize, AppError> {
+        Ok(self.process_batch().await?.1)
+    }
+
+    /// Захваченные и выполненные задачи за проход
+    async fn process_batch(&self) -> Result<(usize, usize), AppError> {
+        let kinds: Vec<String> = self.handlers.keys().cloned().collect();
+        if kinds.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let now = self.clock.now();
+        // Аренда сверяется с базой на равенство, а там время хранится с точностью до микросекунд
+        let lease_until = (now + self.lease).trunc_subsecs(6);
+        let jobs = self.repository.claim(now, lease_until, &kinds, BATCH_SIZE).await?;
+        let claimed = jobs.len();
+        let mut completed = 0;
+        for job in jobs {
+            if self.execute(job, lease_until).await? {
+                completed += 1;
+            }
+        }
+        Ok((claimed, completed))
+    }
+
+    async fn execute(&self, job: JobRecord, lease_until: DateTime<Utc>) -> Result<bool, AppError> {
+        let Some(handler) = self.handlers.get(&job.kind) else {
+            let error = format!("Неизвестный вид задачи: {}", job.kind);
+            self.keep_lease(&job, self.repository.bury(job.id, lease_until, &error).await?);
+            return Ok(false);
+        };
+
+        match handler(job.payload.clone()).await {
+            Ok(()) => Ok(self.keep_lease(&job, self.repository.complete(job.id, lease_until).await?)),
+            Err(JobError::BadPayload(e)) => {
+                let error = format!("Некорректные данные задачи: {}", e);
+                self.keep_lease(&job, self.repository.bury(job.id, lease_until, &error).await?);
+                Ok(false)
+            }
+            Err(JobError::Failed(e)) => {
+                let error = e.to_string();
+                let kept = if job.attempts >= job.max_attempts {
+                    self.repository.bury(job.id, lease_until, &error).await?
+                } else {
+                    let run_at = self.clock.now() + job_retry_delay(job.attempts);
+                    self.repository.retry_later(job.id, lease_until, &error, run_at).await?
+                };
+                self.keep_lease(&job, kept);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Задача выполнялась дольше аренды и ее забрал другой воркер: результат этого прохода
+    /// не записывается, итог задачи определит следующий исполнитель
+    fn keep_lease(&self, job: &JobRecord, kept: bool) -> bool {
+        if !kept {
+            eprintln!("Аренда задачи {} ({}) истекла до окончания выполнения", job.id, job.kind);
+        }
+        kept
+    }
+
+    /// Выполнять задачи, пока проходы забирают полную пачку
+    async fn drain(&self) {
+        loop {
+            match self.process_batch().await {
+                Ok((claimed, _)) if claimed as i64 == BATCH_SIZE => continue,
+                Ok(_) => break,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Запустить опрос очереди с заданным интервалом; при подключенных уведомлениях воркер
+    /// просыпается и по NOTIFY
+    pub fn spawn(self, every: std::time::Duration) {
+        // Задача на рантайме tokio, а не в LocalSet actix: при остановке рантайма PgListener
+        // освобождает соединение через spawn, и вне контекста tokio это паника
+        tokio::spawn(async move {
+            let mut listener = None;
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                // Не удалось подписаться при запуске - пробуем снова на каждом проходе,
+                // пока задачи подхватывает опрос по интервалу
+                if listener.is_none()
+                    && let Some(pool) = &self.notifications
+                {
+                    match listen(pool).await {
+                        Ok(connected) => listener = Some(connected),
+                        Err(e) => eprintln!("Уведомления о задачах недоступны: {}", e),
+                    }
+                }
+                match listener.as_mut() {
+                    Some(listener) => {
+                        let tick = interval.tick();
+                        let notification = listener.recv();
+                        futures_util::pin_mut!(tick, notification);
+                        // После обрыва PgListener сам переподключается и повторяет LISTEN
+                        // при следующем recv; пауза не дает крутиться вхолостую, пока база недоступна
+                        if let Either::Right((Err(e), _)) = select(tick, notification).await {
+                            eprintln!("Ошибка получения уведомлений о задачах: {}", e);
+                            actix_web::rt::time::sleep(LISTEN_RETRY_DELAY).await;
+                        }
+                    }
+                    None => {
+                        interval.tick().await;
+                    }
+                }
+                self.drain().await;
+            }
+        });
+    }
+}
+
+async fn listen(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(JOBS_CHANNEL).await?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_retry_delay_doubles_up_to_an_hour() {
+        assert_eq!(job_retry_delay(1), Duration::seconds(10));
+        assert_eq!(job_retry_delay(2), Duration::seconds(20));
+        assert_eq!(job_retry_delay(5), Duration::seconds(160));
+        assert_eq!(job_retry_delay(9), Duration::seconds(2560));
+        assert_eq!(job_retry_delay(10), Duration::hours(1));
+        assert_eq!(job_retry_delay(30), Duration::hours(1));
+    }
+}
@@ -43,3 +43,16 @@ pub async fn from_request(
         }))),
     }
 }
+
+// Для открытых эндпоинтов: без заголовка Authorization пользователь анонимный,
+// но переданный недействительный токен все равно ошибка
+pub async fn optional_from_request(
+    req: &HttpRequest,
+    jwt_service: &JwtService,
+) -> Result<Option<Uuid>, HttpResponse> {
+    if req.headers().get("Authorization").is_none() {
+        return Ok(None);
+    }
+
+    from_request(req, jwt_service).await.map(Some)
+}
@@ -62,3 +62,9 @@ impl JwtService {
         Ok(user_id)
     }
 }
+
+impl Default for JwtService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
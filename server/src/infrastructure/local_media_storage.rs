@@ -0,0 +1,81 @@
+use crate::domain::errors::AppError;
+use crate::domain::traits::MediaStorage;
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Файлы в локальном каталоге; раздает их сам сервер по `GET /v1/media/{key}`.
+/// Подходит для разработки и одного экземпляра, несколько реплик используют S3
+pub struct LocalMediaStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalMediaStorage {
+    /// `public_url` - адрес, под которым клиенты видят каталог, например `/v1/media`
+    /// или `https://cdn.example.com/media`
+    pub fn new(root: impl Into<PathBuf>, public_url: &str) -> Self {
+        Self {
+            root: root.into(),
+            public_url: public_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Путь к файлу внутри каталога. Ключ из запроса не должен выводить за его пределы,
+    /// поэтому допускаются только латиница, цифры, `_`, `-`, `.` и непустые сегменты
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        let valid = !key.is_empty()
+            && key.split('/').all(|segment| {
+                !segment.is_empty()
+                    && !segment.starts_with('.')
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            });
+        valid.then(|| self.root.join(Path::new(key)))
+    }
+
+    fn invalid_key(key: &str) -> AppError {
+        AppError::Internal(format!("Недопустимый ключ файла: {}", key))
+    }
+}
+
+#[async_trait]
+impl MediaStorage for LocalMediaStorage {
+    async fn put(&self, key: &str, _content_type: &str, bytes: Vec<u8>) -> Result<(), AppError> {
+        let path = self.path(key).ok_or_else(|| Self::invalid_key(key))?;
+        let io_error = |e: std::io::Error| AppError::Internal(format!("Ошибка записи файла {}: {}", key, e));
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+        // Запись через временный файл, чтобы читатель не увидел файл наполовину
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, bytes).await.map_err(io_error)?;
+        tokio::fs::rename(&partial, &path).await.map_err(io_error)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AppError> {
+        let Some(path) = self.path(key) else {
+            return Ok(None);
+        };
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AppError::Internal(format!("Ошибка чтения файла {}: {}", key, e))),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let path = self.path(key).ok_or_else(|| Self::invalid_key(key))?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(AppError::Internal(format!("Ошибка удаления файла {}: {}", key, e)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::domain::errors::AppError;
+use crate::domain::traits::Clock;
+use crate::infrastructure::job_queue::{Job, JobHandler, JobQueue};
+use async_trait::async_trait;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Ключ, под которым в очереди стоит не больше одной уборки
+pub const CLEANUP_DEDUPE_KEY: &str = "cleanup";
+
+/// Сколько дней хранить отработавшие строки по умолчанию
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// Ежедневная уборка: удалить доставленные уведомления, выполненные задачи и отозванные
+/// ссылки на календари старше срока хранения, закрыть талоны очередей прошлых дней,
+/// затем запланировать следующую уборку
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CleanupJob {
+    pub retention_days: i64,
+}
+
+impl Job for CleanupJob {
+    const KIND: &'static str = "cleanup";
+}
+
+pub struct CleanupHandler {
+    pool: PgPool,
+    queue: JobQueue,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+impl CleanupHandler {
+    pub fn new(pool: PgPool, queue: JobQueue, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        Self { pool, queue, clock }
+    }
+}
+
+#[async_trait]
+impl JobHandler<CleanupJob> for CleanupHandler {
+    async fn handle(&self, job: CleanupJob) -> Result<(), AppError> {
+        let now = self.clock.now();
+        let cutoff = now - Duration::days(job.retention_days);
+        let statements = [
+            "DELETE FROM notification_outbox WHERE status = 'sent' AND sent_at < $1",
+            "DELETE FROM jobs WHERE status = 'completed' AND completed_at < $1",
+            "DELETE FROM calendar_feeds WHERE revoked_at < $1",
+        ];
+        for statement in statements {
+            sqlx::query(statement)
+                .bind(cutoff)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(format!("Ошибка уборки устаревших данных: {}", e)))?;
+        }
+        // Очередь каждого дня начинается заново; день считается в часовом поясе компании
+        sqlx::query(
+            r#"
+            UPDATE queue_tickets t
+            SET status = 'expired', finished_at = $1
+            FROM companies c
+            WHERE c.id = t.company_id AND t.status IN ('waiting', 'called')
+              AND t.queue_date < ($1 AT TIME ZONE c.timezone)::date
+            "#,
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка закрытия талонов очереди: {}", e)))?;
+
+        self.queue
+            .enqueue_at(&job, Some(now + Duration::days(1)), Some(CLEANUP_DEDUPE_KEY.to_string()))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Ключ, под которым в очереди стоит не больше одного обновления аналитики
+pub const ANALYTICS_REFRESH_DEDUPE_KEY: &str = "analytics-refresh";
+
+/// Как часто пересчитывать отчеты по умолчанию
+pub const DEFAULT_ANALYTICS_REFRESH_MINUTES: i64 = 15;
+
+/// Представления аналитики в порядке обновления
+const ANALYTICS_VIEWS: [&str; 2] = ["analytics_booking_daily", "analytics_customer_days"];
+
+/// Пересчитать материализованные представления отчетов и запланировать следующий пересчет.
+/// Обновление идет CONCURRENTLY, поэтому отчеты читаются и во время него
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnalyticsRefreshJob {
+    pub every_minutes: i64,
+}
+
+impl Job for AnalyticsRefreshJob {
+    const KIND: &'static str = "analytics_refresh";
+}
+
+pub struct AnalyticsRefreshHandler {
+    pool: PgPool,
+    queue: JobQueue,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+impl AnalyticsRefreshHandler {
+    pub fn new(pool: PgPool, queue: JobQueue, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        Self { pool, queue, clock }
+    }
+}
+
+#[async_trait]
+impl JobHandler<AnalyticsRefreshJob> for AnalyticsRefreshHandler {
+    async fn handle(&self, job: AnalyticsRefreshJob) -> Result<(), AppError> {
+        for view in ANALYTICS_VIEWS {
+            sqlx::query(&format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {}", view))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(format!("Ошибка обновления {}: {}", view, e)))?;
+            sqlx::query(
+                r#"
+                INSERT INTO analytics_refreshes (view_name, refreshed_at) VALUES ($1, $2)
+                ON CONFLICT (view_name) DO UPDATE SET refreshed_at = EXCLUDED.refreshed_at
+                "#,
+            )
+            .bind(view)
+            .bind(self.clock.now())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка сохранения времени обновления аналитики: {}", e)))?;
+        }
+
+        self.queue
+            .enqueue_at(
+                &job,
+                Some(self.clock.now() + Duration::minutes(job.every_minutes)),
+                Some(ANALYTICS_REFRESH_DEDUPE_KEY.to_string()),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,36 @@
 pub mod database;
 pub mod user_repository;
 pub mod postgres_user_repository;
+pub mod postgres_company_repository;
+pub mod postgres_booking_repository;
+pub mod postgres_bookmark_repository;
+pub mod postgres_calendar_repository;
+pub mod postgres_waitlist_repository;
+pub mod postgres_outbox_repository;
+pub mod postgres_reminder_repository;
+pub mod postgres_job_repository;
+pub mod postgres_webhook_repository;
+pub mod postgres_review_repository;
+pub mod postgres_search_repository;
+pub mod postgres_media_repository;
+pub mod postgres_customer_repository;
+pub mod postgres_payment_repository;
+pub mod postgres_promo_code_repository;
+pub mod postgres_queue_repository;
+pub mod postgres_analytics_repository;
+pub mod job_queue;
+pub mod maintenance_jobs;
+pub mod notifier;
+pub mod smtp_notifier;
+pub mod webhook_notifier;
+pub mod webhook_delivery;
+pub mod payment_refund;
+pub mod fake_payment_provider;
+pub mod stripe_payment_provider;
+pub mod slot_event_bus;
+pub mod queue_event_bus;
+pub mod local_media_storage;
+pub mod s3_media_storage;
+pub mod clock;
 pub mod migrations;
 pub mod jwt;
@@ -0,0 +1,51 @@
+use crate::domain::entities::OutgoingMessage;
+use crate::domain::errors::AppError;
+use crate::domain::traits::Notifier;
+use async_trait::async_trait;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Пишет уведомления в журнал сервера, а при заданном пути - еще и в файл построчно в JSON
+#[derive(Clone, Default)]
+pub struct LogNotifier {
+    path: Option<PathBuf>,
+}
+
+impl LogNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    fn channel(&self) -> &'static str {
+        "log"
+    }
+
+    async fn send(&self, message: &OutgoingMessage) -> Result<(), AppError> {
+        println!(
+            "Уведомление {} для пользователя {}: {}",
+            message.kind.as_str(),
+            message.recipient.user_id,
+            message.subject
+        );
+
+        if let Some(path) = &self.path {
+            let line = serde_json::to_string(message)
+                .map_err(|e| AppError::Internal(format!("Ошибка сериализации уведомления: {}", e)))?;
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", line))
+                .map_err(|e| AppError::Internal(format!("Ошибка записи уведомления в файл: {}", e)))?;
+        }
+        Ok(())
+    }
+}
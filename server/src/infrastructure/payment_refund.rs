@@ -0,0 +1,71 @@
+use crate::domain::entities::PaymentStatus;
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, PaymentProvider, PaymentRepository};
+use crate::infrastructure::job_queue::{Job, JobHandler};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Вернуть депозит через платежную систему
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaymentRefundJob {
+    pub payment_id: Uuid,
+}
+
+impl Job for PaymentRefundJob {
+    const KIND: &'static str = "payment_refund";
+    const MAX_ATTEMPTS: i32 = 10;
+}
+
+/// Отправляет возврат в платежную систему; ключ идемпотентности - id платежа,
+/// поэтому повтор задачи после сбоя не вернет деньги дважды
+pub struct PaymentRefundHandler {
+    repository: Arc<dyn PaymentRepository + Send + Sync>,
+    provider: Arc<dyn PaymentProvider + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+}
+
+impl PaymentRefundHandler {
+    pub fn new(
+        repository: Arc<dyn PaymentRepository + Send + Sync>,
+        provider: Arc<dyn PaymentProvider + Send + Sync>,
+        clock: Arc<dyn Clock + Send + Sync>,
+    ) -> Self {
+        Self {
+            repository,
+            provider,
+            clock,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler<PaymentRefundJob> for PaymentRefundHandler {
+    async fn handle(&self, job: PaymentRefundJob) -> Result<(), AppError> {
+        let Some(payment) = self.repository.find_payment(job.payment_id).await? else {
+            return Ok(());
+        };
+        // Возврат уже проведен
+        if payment.status != PaymentStatus::RefundPending {
+            return Ok(());
+        }
+        let (Some(provider_payment_id), Some(amount)) = (&payment.provider_payment_id, payment.refund_amount) else {
+            return Err(AppError::Internal(format!("По платежу {} нечего возвращать", payment.id)));
+        };
+        if payment.provider.as_deref() != Some(self.provider.name()) {
+            return Err(AppError::Internal(format!(
+                "Платеж {} проведен через другую платежную систему",
+                payment.id
+            )));
+        }
+
+        self.provider
+            .refund(provider_payment_id, amount, &payment.currency, &format!("refund-{}", payment.id))
+            .await?;
+        self.repository
+            .update_status(payment.id, &[PaymentStatus::RefundPending], PaymentStatus::Refunded, self.clock.now())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use crate::domain::entities::{
+    BookingsReportRow, CancellationsReportRow, CustomersReportRow, RevenueReportRow, StaffBookedMinutes,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::AnalyticsRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct PostgreSQLAnalyticsRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLAnalyticsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for PostgreSQLAnalyticsRepository {
+    async fn refreshed_at(&self) -> Result<Option<DateTime<Utc>>, AppError> {
+        sqlx::query_scalar("SELECT MIN(refreshed_at) FROM analytics_refreshes")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка получения времени обновления аналитики: {}", e)))
+    }
+
+    async fn bookings_report(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<BookingsReportRow>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.day, a.service_id, s.name AS service_name, a.staff_id, st.display_name AS staff_name,
+                   SUM(a.bookings)::bigint AS bookings,
+                   SUM(a.completed)::bigint AS completed,
+                   SUM(a.cancelled_by_customer + a.cancelled_by_company)::bigint AS cancelled,
+                   SUM(a.no_show)::bigint AS no_show
+            FROM analytics_booking_daily a
+            JOIN services s ON s.id = a.service_id
+            JOIN staff st ON st.id = a.staff_id
+            WHERE a.company_id = $1 AND a.day BETWEEN $2 AND $3
+            GROUP BY a.day, a.service_id, s.name, a.staff_id, st.display_name
+            ORDER BY a.day, s.name, st.display_name
+            "#,
+        )
+        .bind(company_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения отчета по записям: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| BookingsReportRow {
+                date: row.get("day"),
+                service_id: row.get("service_id"),
+                service_name: row.get("service_name"),
+                staff_id: row.get("staff_id"),
+                staff_name: row.get("staff_name"),
+                bookings: row.get("bookings"),
+                completed: row.get("completed"),
+                cancelled: row.get("cancelled"),
+                no_show: row.get("no_show"),
+            })
+            .collect())
+    }
+
+    async fn staff_booked_minutes(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<StaffBookedMinutes>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day, staff_id, SUM(booked_minutes)::bigint AS booked_minutes
+            FROM analytics_booking_daily
+            WHERE company_id = $1 AND day BETWEEN $2 AND $3
+            GROUP BY day, staff_id
+            HAVING SUM(booked_minutes) > 0
+            "#,
+        )
+        .bind(company_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения занятости сотрудников: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| StaffBookedMinutes {
+                date: row.get("day"),
+                staff_id: row.get("staff_id"),
+                booked_minutes: row.get("booked_minutes"),
+            })
+            .collect())
+    }
+
+    async fn cancellations_report(
+        &self,
+        company_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<CancellationsReportRow>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day, bookings, cancelled_by_customer, cancelled_by_company, no_show,
+                   ROUND((cancelled_by_customer + cancelled_by_company)::numeric / bookings, 4)::float8
+                       AS cancellation_rate,
+                   ROUND(no_show::numeric / bookings, 4)::float8 AS no_show_rate
+            FROM (
+                SELECT day,
+                       SUM(bookings)::bigint AS bookings,
+                       SUM(cancelled_by_customer)::bigint AS cancelled_by_customer,
+                       SUM(cancelled_by_company)::bigint AS cancelled_by_company,
+                       SUM(no_show)::bigint AS no_show
+                FROM analytics_booking_daily
+                WHERE company_id = $1 AND day BETWEEN $2 AND $3
+                GROUP BY day
+            ) daily
+            ORDER BY day
+            "#,
+        )
+        .bind(company_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения отчета по отменам: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| CancellationsReportRow {
+                date: row.get("day"),
+                bookings: row.get("bookings"),
+                cancelled_by_customer: row.get("cancelled_by_customer"),
+                cancelled_by_company: row.get("cancelled_by_company"),
+                no_show: row.get("no_show"),
+                cancellation_rate: row.get("cancellation_rate"),
+                no_show_rate: row.get("no_show_rate"),
+            })
+            .collect())
+    }
+
+    async fn revenue_report(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<RevenueReportRow>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day, currency::text AS currency,
+                   SUM(visits)::bigint AS visits,
+                   SUM(revenue)::bigint AS revenue,
+                   SUM(discount)::bigint AS discount
+            FROM analytics_booking_daily
+            WHERE company_id = $1 AND day BETWEEN $2 AND $3
+            GROUP BY day, currency
+            HAVING SUM(visits) > 0
+            ORDER BY day, currency
+            "#,
+        )
+        .bind(company_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения отчета по выручке: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| RevenueReportRow {
+                date: row.get("day"),
+                currency: row.get("currency"),
+                visits: row.get("visits"),
+                revenue: row.get("revenue"),
+                discount: row.get("discount"),
+            })
+            .collect())
+    }
+
+    async fn customers_report(&self, company_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<CustomersReportRow>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day,
+                   COUNT(*) FILTER (WHERE day = first_day) AS new_customers,
+                   COUNT(*) FILTER (WHERE day > first_day) AS returning_customers
+            FROM analytics_customer_days
+            WHERE company_id = $1 AND day BETWEEN $2 AND $3
+            GROUP BY day
+            ORDER BY day
+            "#,
+        )
+        .bind(company_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения отчета по клиентам: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| CustomersReportRow {
+                date: row.get("day"),
+                new_customers: row.get("new_customers"),
+                returning_customers: row.get("returning_customers"),
+            })
+            .collect())
+    }
+}
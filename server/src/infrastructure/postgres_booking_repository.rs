@@ -0,0 +1,1097 @@
+use crate::domain::entities::{
+    Booking, BookingEvent, BookingPolicy, BookingReschedule, BookingSeries, BookingStatus, BusyInterval,
+    CustomerRestriction, GroupSession, NewBooking, NewBookingSeries, NotificationKind, ResourceBusyInterval, RestrictionOverride, SessionAttendee,
+    SlotHold, WebhookEvent,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::BookingRepository;
+use crate::infrastructure::postgres_outbox_repository::enqueue_booking_notification;
+use crate::infrastructure::postgres_payment_repository::open_deposit;
+use crate::infrastructure::postgres_promo_code_repository::{redeem_promo_code, release_promo_code};
+use crate::infrastructure::postgres_reminder_repository::{cancel_reminders, schedule_reminders};
+use crate::infrastructure::postgres_webhook_repository::enqueue_booking_webhooks;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::postgres::types::PgRange;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+/// SQLSTATE нарушения исключающего ограничения (EXCLUDE USING gist)
+pub const EXCLUSION_VIOLATION: &str = "23P01";
+
+/// SQLSTATE нарушения внешнего ключа
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+/// Ограничения групповых занятий, нарушение которых - ожидаемый конфликт, а не сбой
+const SESSION_FULL_CONSTRAINT: &str = "group_sessions_seats_check";
+const SESSION_CUSTOMER_CONSTRAINT: &str = "idx_bookings_session_customer";
+const RESOURCE_OVERLAP_CONSTRAINT: &str = "booking_resources_no_overlap";
+
+const SESSION_COLUMNS: &str = "id, company_id, service_id, staff_id, location_id, starts_at, ends_at, \
+     capacity, seats_taken, created_at";
+
+/// Статусы действующей записи: ее можно перенести, и она занимает время сотрудника
+pub(crate) const ACTIVE_STATUSES: &str = "('pending', 'confirmed', 'checked_in')";
+
+/// Статусы, занимающие время в расписании, включая брони (совпадает с условием ограничения в БД)
+pub(crate) const BLOCKING_STATUSES: &str = "('held', 'pending', 'confirmed', 'checked_in')";
+
+const SERIES_COLUMNS: &str = "id, customer_id, company_id, service_id, staff_id, location_id, \
+     rrule, starts_at, timezone, created_at";
+
+const HOLD_COLUMNS: &str = "id, customer_id, company_id, service_id, staff_id, location_id, \
+     lower(during) AS starts_at, upper(during) AS ends_at, hold_expires_at, created_at";
+
+const OVERRIDE_COLUMNS: &str = "company_id, customer_id, restriction, reason, set_by, created_at";
+
+pub(crate) const BOOKING_COLUMNS: &str = "id, customer_id, company_id, service_id, staff_id, location_id, \
+     lower(during) AS starts_at, upper(during) AS ends_at, status, series_id, session_id, resource_ids, \
+     created_at, updated_at";
+
+pub struct PostgreSQLBookingRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLBookingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+pub(crate) fn map_booking(row: &PgRow) -> Booking {
+    let status: String = row.get("status");
+    Booking {
+        id: row.get("id"),
+        customer_id: row.get("customer_id"),
+        company_id: row.get("company_id"),
+        service_id: row.get("service_id"),
+        staff_id: row.get("staff_id"),
+        location_id: row.get("location_id"),
+        starts_at: row.get("starts_at"),
+        ends_at: row.get("ends_at"),
+        status: BookingStatus::parse(&status).unwrap_or(BookingStatus::CancelledByCompany),
+        series_id: row.get("series_id"),
+        session_id: row.get("session_id"),
+        resource_ids: row.get("resource_ids"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn map_hold(row: &PgRow) -> SlotHold {
+    SlotHold {
+        id: row.get("id"),
+        customer_id: row.get("customer_id"),
+        company_id: row.get("company_id"),
+        service_id: row.get("service_id"),
+        staff_id: row.get("staff_id"),
+        location_id: row.get("location_id"),
+        starts_at: row.get("starts_at"),
+        ends_at: row.get("ends_at"),
+        expires_at: row.get("hold_expires_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_series(row: &PgRow) -> BookingSeries {
+    BookingSeries {
+        id: row.get("id"),
+        customer_id: row.get("customer_id"),
+        company_id: row.get("company_id"),
+        service_id: row.get("service_id"),
+        staff_id: row.get("staff_id"),
+        location_id: row.get("location_id"),
+        rrule: row.get("rrule"),
+        starts_at: row.get("starts_at"),
+        timezone: row.get("timezone"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_session(row: &PgRow) -> GroupSession {
+    GroupSession {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        service_id: row.get("service_id"),
+        staff_id: row.get("staff_id"),
+        location_id: row.get("location_id"),
+        starts_at: row.get("starts_at"),
+        ends_at: row.get("ends_at"),
+        capacity: row.get("capacity"),
+        seats_taken: row.get("seats_taken"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_event(row: &PgRow) -> BookingEvent {
+    let from_status: Option<String> = row.get("from_status");
+    let to_status: String = row.get("to_status");
+    BookingEvent {
+        id: row.get("id"),
+        booking_id: row.get("booking_id"),
+        event_type: row.get("event_type"),
+        from_status: from_status.as_deref().and_then(BookingStatus::parse),
+        to_status: BookingStatus::parse(&to_status).unwrap_or(BookingStatus::CancelledByCompany),
+        previous_starts_at: row.get("previous_starts_at"),
+        new_starts_at: row.get("new_starts_at"),
+        actor_id: row.get("actor_id"),
+        reason: row.get("reason"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_override(row: &PgRow) -> Result<RestrictionOverride, AppError> {
+    let restriction: String = row.get("restriction");
+    Ok(RestrictionOverride {
+        company_id: row.get("company_id"),
+        customer_id: row.get("customer_id"),
+        restriction: CustomerRestriction::parse(&restriction)
+            .ok_or_else(|| AppError::Internal(format!("Неизвестное ограничение: {}", restriction)))?,
+        reason: row.get("reason"),
+        set_by: row.get("set_by"),
+        created_at: row.get("created_at"),
+    })
+}
+
+/// Перевести ошибку БД в доменную: пересечение записей или ресурсов и переполненное занятие - это конфликт
+pub(crate) fn map_booking_error(error: sqlx::Error, context: &str) -> AppError {
+    if let sqlx::Error::Database(db_error) = &error {
+        if db_error.code().as_deref() == Some(EXCLUSION_VIOLATION)
+            && db_error.constraint() != Some(RESOURCE_OVERLAP_CONSTRAINT)
+        {
+            return AppError::Conflict("Слот уже занят".to_string());
+        }
+        match db_error.constraint() {
+            Some(RESOURCE_OVERLAP_CONSTRAINT) => return AppError::Conflict("Ресурс уже занят".to_string()),
+            Some(SESSION_FULL_CONSTRAINT) => return AppError::Conflict("Свободных мест нет".to_string()),
+            Some(SESSION_CUSTOMER_CONSTRAINT) => {
+                return AppError::Conflict("Вы уже записаны на это занятие".to_string());
+            }
+            _ => {}
+        }
+    }
+    AppError::Internal(format!("{}: {}", context, error))
+}
+
+/// Занятие групповой услуги на это время; создается первой записью.
+/// Строка занятия остается заблокированной до конца транзакции, поэтому параллельные
+/// записи на одно занятие проходят по очереди. Для индивидуальной услуги - `None`
+pub(crate) async fn upsert_group_session(
+    tx: &mut Transaction<'_, Postgres>,
+    booking: &NewBooking,
+) -> Result<Option<Uuid>, AppError> {
+    if booking.capacity <= 1 {
+        return Ok(None);
+    }
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO group_sessions (company_id, service_id, staff_id, location_id, starts_at, ends_at, capacity)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (staff_id, service_id, starts_at) DO UPDATE SET capacity = group_sessions.capacity
+        RETURNING id
+        "#,
+    )
+    .bind(booking.company_id)
+    .bind(booking.service_id)
+    .bind(booking.staff_id)
+    .bind(booking.location_id)
+    .bind(booking.starts_at)
+    .bind(booking.ends_at)
+    .bind(booking.capacity)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка создания занятия: {}", e)))?;
+
+    Ok(Some(row.get("id")))
+}
+
+pub(crate) async fn begin(pool: &PgPool) -> Result<Transaction<'_, Postgres>, AppError> {
+    pool.begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка начала транзакции: {}", e)))
+}
+
+pub(crate) async fn commit(tx: Transaction<'_, Postgres>) -> Result<(), AppError> {
+    tx.commit()
+        .await
+        .map_err(|e| map_booking_error(e, "Ошибка сохранения транзакции"))
+}
+
+/// Вставить запись внутри транзакции вместе с событием создания.
+/// Для вхождения серии исходное время по правилу совпадает с началом записи
+pub(crate) async fn insert_booking(
+    tx: &mut Transaction<'_, Postgres>,
+    booking: &NewBooking,
+    series_id: Option<Uuid>,
+) -> Result<Booking, AppError> {
+    let session_id = upsert_group_session(tx, booking).await?;
+    let row = sqlx::query(&format!(
+        r#"
+        INSERT INTO bookings (customer_id, company_id, service_id, staff_id, location_id,
+                              during, blocked, status, series_id, recurrence_id, session_id, resource_ids,
+                              price_amount, currency)
+        SELECT $1, $2, s.id, $4, $5, tstzrange($6, $7, '[)'), tstzrange($8, $9, '[)'), $10,
+               $11, CASE WHEN $11::uuid IS NULL THEN NULL ELSE $6 END, $12, $13,
+               s.price_amount, s.currency
+        FROM services s WHERE s.id = $3
+        RETURNING {}
+        "#,
+        BOOKING_COLUMNS
+    ))
+    .bind(booking.customer_id)
+    .bind(booking.company_id)
+    .bind(booking.service_id)
+    .bind(booking.staff_id)
+    .bind(booking.location_id)
+    .bind(booking.starts_at)
+    .bind(booking.ends_at)
+    .bind(booking.blocked_from)
+    .bind(booking.blocked_to)
+    .bind(booking.status.as_str())
+    .bind(series_id)
+    .bind(session_id)
+    .bind(&booking.resource_ids)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| map_booking_error(e, "Ошибка создания записи"))?;
+    let created = map_booking(&row);
+    if let Some(promo) = &booking.promo {
+        redeem_promo_code(tx, created.id, booking.customer_id, promo).await?;
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO booking_events (booking_id, event_type, to_status, new_during, actor_id)
+        VALUES ($1, 'created', $2, tstzrange($3, $4, '[)'), $5)
+        "#,
+    )
+    .bind(created.id)
+    .bind(created.status.as_str())
+    .bind(created.starts_at)
+    .bind(created.ends_at)
+    .bind(booking.customer_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка записи истории: {}", e)))?;
+
+    schedule_reminders(tx, created.id).await?;
+    if created.status == BookingStatus::Pending {
+        open_deposit(tx, created.id).await?;
+    }
+    // О вхождениях серии клиент узнает из ответа на создание серии, а не отдельными письмами
+    if series_id.is_none() && created.status == BookingStatus::Confirmed {
+        enqueue_booking_notification(tx, created.id, NotificationKind::BookingConfirmed, json!({})).await?;
+    }
+    enqueue_booking_webhooks(tx, created.id, WebhookEvent::BookingCreated, json!({ "series_id": series_id })).await?;
+
+    Ok(created)
+}
+
+/// Сменить статус внутри транзакции, если он не изменился с момента чтения
+pub(crate) async fn update_booking_status(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: Uuid,
+    from: BookingStatus,
+    to: BookingStatus,
+    actor_id: Uuid,
+    reason: Option<&str>,
+) -> Result<Booking, AppError> {
+    let row = sqlx::query(&format!(
+        "UPDATE bookings SET status = $3 WHERE id = $1 AND status = $2 RETURNING {}",
+        BOOKING_COLUMNS
+    ))
+    .bind(booking_id)
+    .bind(from.as_str())
+    .bind(to.as_str())
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| map_booking_error(e, "Ошибка изменения статуса записи"))?
+    .ok_or_else(|| AppError::Conflict("Статус записи уже изменился".to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO booking_events (booking_id, event_type, from_status, to_status, actor_id, reason)
+        VALUES ($1, 'status_changed', $2, $3, $4, $5)
+        "#,
+    )
+    .bind(booking_id)
+    .bind(from.as_str())
+    .bind(to.as_str())
+    .bind(actor_id)
+    .bind(reason)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка записи истории: {}", e)))?;
+
+    // Напоминать о приеме, который уже начался или не состоится, незачем
+    if !matches!(to, BookingStatus::Pending | BookingStatus::Confirmed) {
+        cancel_reminders(tx, booking_id).await?;
+    }
+    // Промокод отмененной записи можно использовать снова
+    if to.is_cancelled() {
+        release_promo_code(tx, booking_id).await?;
+    }
+    if to == BookingStatus::Confirmed {
+        enqueue_booking_notification(tx, booking_id, NotificationKind::BookingConfirmed, json!({})).await?;
+    } else if to.is_cancelled() {
+        enqueue_booking_notification(tx, booking_id, NotificationKind::BookingCancelled, json!({ "reason": reason }))
+            .await?;
+    }
+    let event = if to.is_cancelled() { WebhookEvent::BookingCancelled } else { WebhookEvent::BookingStatusChanged };
+    enqueue_booking_webhooks(tx, booking_id, event, json!({ "previous_status": from, "reason": reason })).await?;
+
+    Ok(map_booking(&row))
+}
+
+/// Перенести активную запись внутри транзакции и записать событие переноса
+pub(crate) async fn reschedule_in_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: Uuid,
+    change: &BookingReschedule,
+    actor_id: Uuid,
+) -> Result<Booking, AppError> {
+    // Блокируем строку, чтобы параллельная смена статуса не проскочила между чтением и обновлением
+    let current = sqlx::query(&format!(
+        "SELECT customer_id, company_id, service_id, during, lower(during) AS previous_starts_at, status FROM bookings \
+         WHERE id = $1 AND status IN {} FOR UPDATE",
+        ACTIVE_STATUSES
+    ))
+    .bind(booking_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка поиска записи: {}", e)))?
+    .ok_or_else(|| AppError::Conflict("Запись нельзя перенести в текущем статусе".to_string()))?;
+    let status: String = current.get("status");
+    let previous_during: PgRange<DateTime<Utc>> = current.get("during");
+    let previous_starts_at: DateTime<Utc> = current.get("previous_starts_at");
+
+    // Запись групповой услуги переходит на занятие в новое время
+    let session_id = upsert_group_session(
+        tx,
+        &NewBooking {
+            customer_id: current.get("customer_id"),
+            company_id: current.get("company_id"),
+            service_id: current.get("service_id"),
+            staff_id: change.staff_id,
+            location_id: change.location_id,
+            starts_at: change.starts_at,
+            ends_at: change.ends_at,
+            blocked_from: change.blocked_from,
+            blocked_to: change.blocked_to,
+            status: BookingStatus::parse(&status).unwrap_or(BookingStatus::Confirmed),
+            capacity: change.capacity,
+            resource_ids: change.resource_ids.clone(),
+            promo: None,
+        },
+    )
+    .await?;
+
+    // Одно обновление: новый интервал занимается, старый освобождается, ограничение
+    // на пересечение проверяется уже без старого интервала этой записи
+    let row = sqlx::query(&format!(
+        r#"
+        UPDATE bookings
+        SET staff_id = $2, location_id = $3,
+            during = tstzrange($4, $5, '[)'), blocked = tstzrange($6, $7, '[)'),
+            session_id = $8, resource_ids = $9
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        BOOKING_COLUMNS
+    ))
+    .bind(booking_id)
+    .bind(change.staff_id)
+    .bind(change.location_id)
+    .bind(change.starts_at)
+    .bind(change.ends_at)
+    .bind(change.blocked_from)
+    .bind(change.blocked_to)
+    .bind(session_id)
+    .bind(&change.resource_ids)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| map_booking_error(e, "Ошибка переноса записи"))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO booking_events (booking_id, event_type, from_status, to_status,
+                                    previous_during, new_during, actor_id)
+        VALUES ($1, 'rescheduled', $2, $2, $3, tstzrange($4, $5, '[)'), $6)
+        "#,
+    )
+    .bind(booking_id)
+    .bind(&status)
+    .bind(previous_during)
+    .bind(change.starts_at)
+    .bind(change.ends_at)
+    .bind(actor_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка записи истории: {}", e)))?;
+
+    // Услуга при переносе не меняется, а окно действия промокода проверено при записи;
+    // остается филиал: в филиале вне ограничений промокода использование возвращается
+    let out_of_scope: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM promo_redemptions r
+            JOIN promo_codes p ON p.id = r.promo_code_id
+            WHERE r.booking_id = $1 AND cardinality(p.location_ids) > 0 AND NOT ($2 = ANY(p.location_ids))
+        )
+        "#,
+    )
+    .bind(booking_id)
+    .bind(change.location_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка проверки промокода: {}", e)))?;
+    if out_of_scope {
+        release_promo_code(tx, booking_id).await?;
+    }
+
+    cancel_reminders(tx, booking_id).await?;
+    schedule_reminders(tx, booking_id).await?;
+    enqueue_booking_notification(
+        tx,
+        booking_id,
+        NotificationKind::BookingRescheduled,
+        json!({ "previous_starts_at": previous_starts_at }),
+    )
+    .await?;
+    enqueue_booking_webhooks(
+        tx,
+        booking_id,
+        WebhookEvent::BookingRescheduled,
+        json!({ "previous_starts_at": previous_starts_at }),
+    )
+    .await?;
+
+    Ok(map_booking(&row))
+}
+
+/// Превратить бронь клиента в запись внутри транзакции: история, напоминания,
+/// депозит или уведомление о подтверждении
+pub(crate) async fn convert_hold_in_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    hold_id: Uuid,
+    customer_id: Uuid,
+    status: BookingStatus,
+    now: DateTime<Utc>,
+) -> Result<Booking, AppError> {
+    let row = sqlx::query(&format!(
+        r#"
+        UPDATE bookings b
+        SET status = $4, hold_expires_at = NULL, created_at = $3,
+            price_amount = (SELECT s.price_amount FROM services s WHERE s.id = b.service_id),
+            currency = (SELECT s.currency FROM services s WHERE s.id = b.service_id)
+        WHERE b.id = $1 AND b.customer_id = $2 AND b.status = 'held' AND b.hold_expires_at > $3
+        RETURNING {}
+        "#,
+        BOOKING_COLUMNS
+    ))
+    .bind(hold_id)
+    .bind(customer_id)
+    .bind(now)
+    .bind(status.as_str())
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка подтверждения брони: {}", e)))?;
+
+    let Some(row) = row else {
+        // Различаем "нет такой брони" и "бронь истекла, но еще не снята"
+        let expired = sqlx::query(
+            "SELECT 1 FROM bookings WHERE id = $1 AND customer_id = $2 AND status = 'held'",
+        )
+        .bind(hold_id)
+        .bind(customer_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска брони: {}", e)))?;
+        return Err(match expired {
+            Some(_) => AppError::Conflict("Время брони истекло".to_string()),
+            None => AppError::NotFound("Бронь не найдена".to_string()),
+        });
+    };
+    let booking = map_booking(&row);
+
+    sqlx::query(
+        r#"
+        INSERT INTO booking_events (booking_id, event_type, to_status, new_during, actor_id)
+        VALUES ($1, 'created', $2, tstzrange($3, $4, '[)'), $5)
+        "#,
+    )
+    .bind(booking.id)
+    .bind(booking.status.as_str())
+    .bind(booking.starts_at)
+    .bind(booking.ends_at)
+    .bind(customer_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка записи истории: {}", e)))?;
+    schedule_reminders(tx, booking.id).await?;
+    if booking.status == BookingStatus::Pending {
+        open_deposit(tx, booking.id).await?;
+    } else {
+        enqueue_booking_notification(tx, booking.id, NotificationKind::BookingConfirmed, json!({})).await?;
+    }
+    enqueue_booking_webhooks(tx, booking.id, WebhookEvent::BookingCreated, json!({ "series_id": null }))
+        .await?;
+
+    Ok(booking)
+}
+
+#[async_trait]
+impl BookingRepository for PostgreSQLBookingRepository {
+    async fn create_booking(&self, booking: &NewBooking) -> Result<Booking, AppError> {
+        let mut tx = begin(&self.pool).await?;
+        let created = insert_booking(&mut tx, booking, None).await?;
+        commit(tx).await?;
+        Ok(created)
+    }
+
+    async fn find_booking(&self, id: Uuid) -> Result<Option<Booking>, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM bookings WHERE id = $1", BOOKING_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска записи: {}", e)))?;
+
+        Ok(row.as_ref().map(map_booking))
+    }
+
+    async fn list_busy_intervals(
+        &self,
+        staff_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BusyInterval>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT id, staff_id, lower(blocked) AS busy_from, upper(blocked) AS busy_to, session_id
+            FROM bookings
+            WHERE staff_id = ANY($1)
+              AND status IN {}
+              AND blocked && tstzrange($2, $3, '[)')
+            "#,
+            BLOCKING_STATUSES
+        ))
+        .bind(staff_ids)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения занятого времени: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| BusyInterval {
+                booking_id: row.get("id"),
+                staff_id: row.get("staff_id"),
+                start: row.get("busy_from"),
+                end: row.get("busy_to"),
+                session_id: row.get("session_id"),
+            })
+            .collect())
+    }
+
+    async fn list_resource_busy(
+        &self,
+        resource_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ResourceBusyInterval>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT booking_id, resource_id, lower(blocked) AS busy_from, upper(blocked) AS busy_to, session_id
+            FROM booking_resources
+            WHERE resource_id = ANY($1) AND blocked && tstzrange($2, $3, '[)')
+            "#,
+        )
+        .bind(resource_ids)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения занятости ресурсов: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ResourceBusyInterval {
+                booking_id: row.get("booking_id"),
+                resource_id: row.get("resource_id"),
+                start: row.get("busy_from"),
+                end: row.get("busy_to"),
+                session_id: row.get("session_id"),
+            })
+            .collect())
+    }
+
+    async fn change_status(
+        &self,
+        booking_id: Uuid,
+        from: BookingStatus,
+        to: BookingStatus,
+        actor_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<Booking, AppError> {
+        let mut tx = begin(&self.pool).await?;
+        let booking = update_booking_status(&mut tx, booking_id, from, to, actor_id, reason).await?;
+        commit(tx).await?;
+        Ok(booking)
+    }
+
+    async fn reschedule_booking(
+        &self,
+        booking_id: Uuid,
+        change: &BookingReschedule,
+        actor_id: Uuid,
+    ) -> Result<Booking, AppError> {
+        let mut tx = begin(&self.pool).await?;
+        let booking = reschedule_in_tx(&mut tx, booking_id, change, actor_id).await?;
+        commit(tx).await?;
+        Ok(booking)
+    }
+
+    async fn list_booking_events(&self, booking_id: Uuid) -> Result<Vec<BookingEvent>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, booking_id, event_type, from_status, to_status,
+                   lower(previous_during) AS previous_starts_at, lower(new_during) AS new_starts_at,
+                   actor_id, reason, created_at
+            FROM booking_events
+            WHERE booking_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(booking_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения истории записи: {}", e)))?;
+
+        Ok(rows.iter().map(map_event).collect())
+    }
+
+    async fn get_policy(&self, company_id: Uuid) -> Result<BookingPolicy, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT company_id, customer_cancel_min_hours, customer_reschedule_min_hours,
+                   no_show_limit, no_show_window_days, no_show_action, deposit_refund_min_hours
+            FROM booking_policies
+            WHERE company_id = $1
+            "#,
+        )
+        .bind(company_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения политики записи: {}", e)))?;
+
+        let Some(row) = row else {
+            return Ok(BookingPolicy::default_for(company_id));
+        };
+        let action: String = row.get("no_show_action");
+        Ok(BookingPolicy {
+            company_id: row.get("company_id"),
+            customer_cancel_min_hours: row.get("customer_cancel_min_hours"),
+            customer_reschedule_min_hours: row.get("customer_reschedule_min_hours"),
+            no_show_limit: row.get("no_show_limit"),
+            no_show_window_days: row.get("no_show_window_days"),
+            no_show_action: CustomerRestriction::parse(&action)
+                .ok_or_else(|| AppError::Internal(format!("Неизвестное ограничение: {}", action)))?,
+            deposit_refund_min_hours: row.get("deposit_refund_min_hours"),
+        })
+    }
+
+    async fn save_policy(&self, policy: &BookingPolicy) -> Result<BookingPolicy, AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO booking_policies (company_id, customer_cancel_min_hours, customer_reschedule_min_hours,
+                                          no_show_limit, no_show_window_days, no_show_action,
+                                          deposit_refund_min_hours)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (company_id) DO UPDATE
+            SET customer_cancel_min_hours = EXCLUDED.customer_cancel_min_hours,
+                customer_reschedule_min_hours = EXCLUDED.customer_reschedule_min_hours,
+                no_show_limit = EXCLUDED.no_show_limit,
+                no_show_window_days = EXCLUDED.no_show_window_days,
+                no_show_action = EXCLUDED.no_show_action,
+                deposit_refund_min_hours = EXCLUDED.deposit_refund_min_hours
+            "#,
+        )
+        .bind(policy.company_id)
+        .bind(policy.customer_cancel_min_hours)
+        .bind(policy.customer_reschedule_min_hours)
+        .bind(policy.no_show_limit)
+        .bind(policy.no_show_window_days)
+        .bind(policy.no_show_action.as_str())
+        .bind(policy.deposit_refund_min_hours)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка сохранения политики записи: {}", e)))?;
+
+        Ok(policy.clone())
+    }
+
+    async fn count_no_shows(&self, company_id: Uuid, customer_id: Uuid, since: DateTime<Utc>) -> Result<i64, AppError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT count(*)
+            FROM bookings
+            WHERE company_id = $1 AND customer_id = $2 AND status = 'no_show' AND lower(during) >= $3
+            "#,
+        )
+        .bind(company_id)
+        .bind(customer_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка подсчета неявок: {}", e)))
+    }
+
+    async fn find_restriction_override(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<Option<RestrictionOverride>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM customer_restriction_overrides WHERE company_id = $1 AND customer_id = $2",
+            OVERRIDE_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(customer_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения ограничения клиента: {}", e)))?;
+
+        row.as_ref().map(map_override).transpose()
+    }
+
+    async fn save_restriction_override(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+        restriction: CustomerRestriction,
+        reason: Option<&str>,
+        set_by: Uuid,
+    ) -> Result<RestrictionOverride, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO customer_restriction_overrides (company_id, customer_id, restriction, reason, set_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (company_id, customer_id) DO UPDATE
+            SET restriction = EXCLUDED.restriction,
+                reason = EXCLUDED.reason,
+                set_by = EXCLUDED.set_by,
+                created_at = NOW()
+            RETURNING {}
+            "#,
+            OVERRIDE_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(customer_id)
+        .bind(restriction.as_str())
+        .bind(reason)
+        .bind(set_by)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                AppError::NotFound("Клиент не найден".to_string())
+            }
+            e => AppError::Internal(format!("Ошибка сохранения ограничения клиента: {}", e)),
+        })?;
+
+        map_override(&row)
+    }
+
+    async fn delete_restriction_override(&self, company_id: Uuid, customer_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM customer_restriction_overrides WHERE company_id = $1 AND customer_id = $2")
+            .bind(company_id)
+            .bind(customer_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка удаления ограничения клиента: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn create_hold(&self, hold: &NewBooking, expires_at: DateTime<Utc>) -> Result<SlotHold, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        // У клиента одна бронь на компанию: выбор другого слота освобождает прежний.
+        // Брони предложений из листа ожидания живут своим сроком и здесь не снимаются
+        sqlx::query(
+            r#"
+            DELETE FROM bookings b
+            WHERE b.customer_id = $1 AND b.company_id = $2 AND b.status = 'held'
+              AND NOT EXISTS (SELECT 1 FROM waitlist_entries w WHERE w.hold_id = b.id AND w.status = 'offered')
+            "#,
+        )
+            .bind(hold.customer_id)
+            .bind(hold.company_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка снятия брони: {}", e)))?;
+
+        let session_id = upsert_group_session(&mut tx, hold).await?;
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO bookings (customer_id, company_id, service_id, staff_id, location_id,
+                                  during, blocked, status, hold_expires_at, session_id, resource_ids)
+            VALUES ($1, $2, $3, $4, $5, tstzrange($6, $7, '[)'), tstzrange($8, $9, '[)'), 'held', $10, $11, $12)
+            RETURNING {}
+            "#,
+            HOLD_COLUMNS
+        ))
+        .bind(hold.customer_id)
+        .bind(hold.company_id)
+        .bind(hold.service_id)
+        .bind(hold.staff_id)
+        .bind(hold.location_id)
+        .bind(hold.starts_at)
+        .bind(hold.ends_at)
+        .bind(hold.blocked_from)
+        .bind(hold.blocked_to)
+        .bind(expires_at)
+        .bind(session_id)
+        .bind(&hold.resource_ids)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| map_booking_error(e, "Ошибка создания брони"))?;
+
+        commit(tx).await?;
+        Ok(map_hold(&row))
+    }
+
+    async fn convert_hold(
+        &self,
+        hold_id: Uuid,
+        customer_id: Uuid,
+        status: BookingStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Booking, AppError> {
+        let mut tx = begin(&self.pool).await?;
+        let booking = convert_hold_in_tx(&mut tx, hold_id, customer_id, status, now).await?;
+        commit(tx).await?;
+        Ok(booking)
+    }
+
+    async fn release_hold(&self, hold_id: Uuid, customer_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM bookings WHERE id = $1 AND customer_id = $2 AND status = 'held'")
+            .bind(hold_id)
+            .bind(customer_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка снятия брони: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn release_expired_holds(&self, now: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM bookings WHERE status = 'held' AND hold_expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка снятия истекших броней: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn create_series(
+        &self,
+        series: &NewBookingSeries,
+        occurrences: &[NewBooking],
+    ) -> Result<(BookingSeries, Vec<Booking>), AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO booking_series (customer_id, company_id, service_id, staff_id, location_id,
+                                        rrule, starts_at, timezone)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {}
+            "#,
+            SERIES_COLUMNS
+        ))
+        .bind(series.customer_id)
+        .bind(series.company_id)
+        .bind(series.service_id)
+        .bind(series.staff_id)
+        .bind(series.location_id)
+        .bind(&series.rrule)
+        .bind(series.starts_at)
+        .bind(&series.timezone)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка создания серии: {}", e)))?;
+        let created = map_series(&row);
+
+        // Серия создается целиком: если вхождение успели занять, откатываем все
+        let mut bookings = Vec::with_capacity(occurrences.len());
+        for occurrence in occurrences {
+            bookings.push(insert_booking(&mut tx, occurrence, Some(created.id)).await?);
+        }
+
+        commit(tx).await?;
+        Ok((created, bookings))
+    }
+
+    async fn find_series(&self, id: Uuid) -> Result<Option<BookingSeries>, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM booking_series WHERE id = $1", SERIES_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска серии: {}", e)))?;
+
+        Ok(row.as_ref().map(map_series))
+    }
+
+    async fn list_series_bookings(&self, series_id: Uuid) -> Result<Vec<Booking>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM bookings WHERE series_id = $1 ORDER BY recurrence_id",
+            BOOKING_COLUMNS
+        ))
+        .bind(series_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения записей серии: {}", e)))?;
+
+        Ok(rows.iter().map(map_booking).collect())
+    }
+
+    async fn change_status_many(
+        &self,
+        bookings: &[(Uuid, BookingStatus)],
+        to: BookingStatus,
+        actor_id: Uuid,
+        reason: Option<&str>,
+    ) -> Result<Vec<Booking>, AppError> {
+        let mut tx = begin(&self.pool).await?;
+        let mut changed = Vec::with_capacity(bookings.len());
+        for (booking_id, from) in bookings {
+            changed.push(update_booking_status(&mut tx, *booking_id, *from, to, actor_id, reason).await?);
+        }
+        commit(tx).await?;
+        Ok(changed)
+    }
+
+    async fn reschedule_many(
+        &self,
+        changes: &[(Uuid, BookingReschedule)],
+        actor_id: Uuid,
+    ) -> Result<Vec<Booking>, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        // Вхождения могут временно наложиться друг на друга, проверяем итог при коммите
+        sqlx::query("SET CONSTRAINTS bookings_staff_no_overlap, booking_resources_no_overlap DEFERRED")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка переноса записей: {}", e)))?;
+
+        let mut moved = Vec::with_capacity(changes.len());
+        for (booking_id, change) in changes {
+            moved.push(reschedule_in_tx(&mut tx, *booking_id, change, actor_id).await?);
+        }
+
+        commit(tx).await?;
+        Ok(moved)
+    }
+
+    async fn list_group_sessions(
+        &self,
+        service_id: Uuid,
+        staff_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<GroupSession>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM group_sessions
+            WHERE service_id = $1 AND staff_id = ANY($2) AND starts_at >= $3 AND starts_at < $4
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(service_id)
+        .bind(staff_ids)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения занятий: {}", e)))?;
+
+        Ok(rows.iter().map(map_session).collect())
+    }
+
+    async fn list_company_sessions(
+        &self,
+        company_id: Uuid,
+        service_id: Option<Uuid>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<GroupSession>, AppError> {
+        // Занятия, с которых ушли все участники, не показываем
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM group_sessions
+            WHERE company_id = $1 AND ($2::uuid IS NULL OR service_id = $2)
+              AND starts_at >= $3 AND starts_at < $4 AND seats_taken > 0
+            ORDER BY starts_at, staff_id
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(service_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения занятий: {}", e)))?;
+
+        Ok(rows.iter().map(map_session).collect())
+    }
+
+    async fn find_group_session(&self, id: Uuid) -> Result<Option<GroupSession>, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM group_sessions WHERE id = $1", SESSION_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска занятия: {}", e)))?;
+
+        Ok(row.as_ref().map(map_session))
+    }
+
+    async fn list_session_attendees(&self, session_id: Uuid) -> Result<Vec<SessionAttendee>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT b.id AS booking_id, b.customer_id, u.username, u.first_name, u.last_name,
+                   b.status, b.created_at
+            FROM bookings b
+            JOIN users u ON u.id = b.customer_id
+            WHERE b.session_id = $1 AND b.status <> 'held'
+            ORDER BY b.created_at, b.id
+            "#,
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения участников занятия: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let status: String = row.get("status");
+                SessionAttendee {
+                    booking_id: row.get("booking_id"),
+                    customer_id: row.get("customer_id"),
+                    username: row.get("username"),
+                    first_name: row.get("first_name"),
+                    last_name: row.get("last_name"),
+                    status: BookingStatus::parse(&status).unwrap_or(BookingStatus::CancelledByCompany),
+                    booked_at: row.get("created_at"),
+                }
+            })
+            .collect())
+    }
+}
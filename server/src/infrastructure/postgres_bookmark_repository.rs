@@ -0,0 +1,109 @@
+use crate::domain::entities::{Bookmark, CompanySummary};
+use crate::domain::errors::AppError;
+use crate::domain::traits::BookmarkRepository;
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct PostgreSQLBookmarkRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLBookmarkRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BookmarkRepository for PostgreSQLBookmarkRepository {
+    async fn add_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO user_bookmarks (user_id, company_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(company_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка добавления закладки: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_bookmark(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM user_bookmarks WHERE user_id = $1 AND company_id = $2")
+            .bind(user_id)
+            .bind(company_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка удаления закладки: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn is_bookmarked(&self, user_id: Uuid, company_id: Uuid) -> Result<bool, AppError> {
+        let row = sqlx::query("SELECT 1 FROM user_bookmarks WHERE user_id = $1 AND company_id = $2")
+            .bind(user_id)
+            .bind(company_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка проверки закладки: {}", e)))?;
+
+        Ok(row.is_some())
+    }
+
+    async fn list_bookmarks(&self, user_id: Uuid, limit: i64, offset: i64) -> Result<(Vec<Bookmark>, i64), AppError> {
+        // Удаленные компании в закладках не показываем
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id, c.name, c.description, c.website, c.timezone, b.created_at,
+                   COUNT(*) OVER () AS total
+            FROM user_bookmarks b
+            JOIN companies c ON c.id = b.company_id AND c.deleted_at IS NULL
+            WHERE b.user_id = $1
+            ORDER BY b.created_at DESC, c.id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения закладок: {}", e)))?;
+
+        let total = match rows.first() {
+            Some(row) => row.get("total"),
+            // Страница за пределами списка - считаем отдельно
+            None => sqlx::query(
+                r#"
+                SELECT COUNT(*) AS total
+                FROM user_bookmarks b
+                JOIN companies c ON c.id = b.company_id AND c.deleted_at IS NULL
+                WHERE b.user_id = $1
+                "#,
+            )
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка получения закладок: {}", e)))?
+            .get("total"),
+        };
+
+        let bookmarks = rows
+            .iter()
+            .map(|row| Bookmark {
+                company: CompanySummary {
+                    id: row.get("id"),
+                    name: row.get("name"),
+                    description: row.get("description"),
+                    website: row.get("website"),
+                    timezone: row.get("timezone"),
+                },
+                created_at: row.get("created_at"),
+            })
+            .collect();
+
+        Ok((bookmarks, total))
+    }
+}
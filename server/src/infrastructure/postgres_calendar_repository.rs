@@ -0,0 +1,200 @@
+use crate::domain::entities::{BookingStatus, CalendarEvent, CalendarFeed};
+use crate::domain::errors::AppError;
+use crate::domain::traits::CalendarRepository;
+use crate::infrastructure::postgres_booking_repository::{begin, commit, ACTIVE_STATUSES};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+const FEED_COLUMNS: &str = "id, user_id, company_id, staff_id, created_at";
+
+/// Запись вместе с названиями; `sequence` растет с каждым переносом и сменой статуса
+const EVENT_SELECT: &str = r#"
+    SELECT b.id AS booking_id, b.customer_id, b.company_id, b.staff_id, b.session_id,
+           lower(b.during) AS starts_at, upper(b.during) AS ends_at, b.status, b.updated_at,
+           (SELECT COUNT(*) FROM booking_events e
+            WHERE e.booking_id = b.id AND e.event_type <> 'created')::INTEGER AS sequence,
+           c.name AS company_name, c.timezone, s.name AS service_name, st.display_name AS staff_name,
+           COALESCE(NULLIF(TRIM(CONCAT_WS(' ', u.first_name, u.last_name)), ''), u.username) AS customer_name,
+           l.name AS location_name, l.address AS location_address
+    FROM bookings b
+    JOIN companies c ON c.id = b.company_id
+    JOIN services s ON s.id = b.service_id
+    JOIN staff st ON st.id = b.staff_id
+    JOIN locations l ON l.id = b.location_id
+    JOIN users u ON u.id = b.customer_id
+"#;
+
+fn map_feed(row: &PgRow) -> CalendarFeed {
+    CalendarFeed {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        company_id: row.get("company_id"),
+        staff_id: row.get("staff_id"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_event(row: &PgRow) -> CalendarEvent {
+    let status: String = row.get("status");
+    CalendarEvent {
+        booking_id: row.get("booking_id"),
+        customer_id: row.get("customer_id"),
+        company_id: row.get("company_id"),
+        staff_id: row.get("staff_id"),
+        session_id: row.get("session_id"),
+        starts_at: row.get("starts_at"),
+        ends_at: row.get("ends_at"),
+        status: BookingStatus::parse(&status).unwrap_or(BookingStatus::CancelledByCompany),
+        sequence: row.get("sequence"),
+        updated_at: row.get("updated_at"),
+        company_name: row.get("company_name"),
+        timezone: row.get("timezone"),
+        service_name: row.get("service_name"),
+        staff_name: row.get("staff_name"),
+        customer_name: row.get("customer_name"),
+        location_name: row.get("location_name"),
+        location_address: row.get("location_address"),
+    }
+}
+
+pub struct PostgreSQLCalendarRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLCalendarRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CalendarRepository for PostgreSQLCalendarRepository {
+    async fn create_feed(
+        &self,
+        user_id: Uuid,
+        staff: Option<(Uuid, Uuid)>,
+        token: &str,
+    ) -> Result<CalendarFeed, AppError> {
+        let (company_id, staff_id) = staff.unzip();
+        let mut tx = begin(&self.pool).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE calendar_feeds SET revoked_at = NOW()
+            WHERE user_id = $1 AND staff_id IS NOT DISTINCT FROM $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(staff_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка отзыва ссылки на календарь: {}", e)))?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO calendar_feeds (user_id, company_id, staff_id, token_hash)
+            VALUES ($1, $2, $3, sha256(convert_to($4, 'UTF8')))
+            RETURNING {}
+            "#,
+            FEED_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(company_id)
+        .bind(staff_id)
+        .bind(token)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            // Параллельный выпуск ссылки на ту же ленту
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some("23505") => {
+                AppError::Conflict("Ссылка на календарь уже выпускается".to_string())
+            }
+            e => AppError::Internal(format!("Ошибка создания ссылки на календарь: {}", e)),
+        })?;
+
+        commit(tx).await?;
+        Ok(map_feed(&row))
+    }
+
+    async fn revoke_feed(&self, user_id: Uuid, staff_id: Option<Uuid>) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE calendar_feeds SET revoked_at = NOW()
+            WHERE user_id = $1 AND staff_id IS NOT DISTINCT FROM $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(staff_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка отзыва ссылки на календарь: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_feed(&self, token: &str) -> Result<Option<CalendarFeed>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM calendar_feeds
+            WHERE token_hash = sha256(convert_to($1, 'UTF8')) AND revoked_at IS NULL
+            "#,
+            FEED_COLUMNS
+        ))
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения календаря: {}", e)))?;
+
+        Ok(row.as_ref().map(map_feed))
+    }
+
+    async fn find_event(&self, booking_id: Uuid) -> Result<Option<CalendarEvent>, AppError> {
+        let row = sqlx::query(&format!("{} WHERE b.id = $1", EVENT_SELECT))
+            .bind(booking_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка получения записи: {}", e)))?;
+
+        Ok(row.as_ref().map(map_event))
+    }
+
+    async fn list_user_events(&self, user_id: Uuid, from: DateTime<Utc>) -> Result<Vec<CalendarEvent>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            {}
+            WHERE (b.customer_id = $1 OR st.user_id = $1)
+              AND upper(b.during) > $2 AND b.status IN {} AND c.deleted_at IS NULL
+            ORDER BY lower(b.during), b.id
+            "#,
+            EVENT_SELECT, ACTIVE_STATUSES
+        ))
+        .bind(user_id)
+        .bind(from)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения записей: {}", e)))?;
+
+        Ok(rows.iter().map(map_event).collect())
+    }
+
+    async fn list_staff_events(&self, staff_id: Uuid, from: DateTime<Utc>) -> Result<Vec<CalendarEvent>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            {}
+            WHERE b.staff_id = $1 AND upper(b.during) > $2 AND b.status IN {}
+            ORDER BY lower(b.during), b.id
+            "#,
+            EVENT_SELECT, ACTIVE_STATUSES
+        ))
+        .bind(staff_id)
+        .bind(from)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения записей: {}", e)))?;
+
+        Ok(rows.iter().map(map_event).collect())
+    }
+}
@@ -0,0 +1,617 @@
+use crate::domain::entities::{
+    Company, CompanyRole, CreateCompanyRequest, CreateLocationRequest, CreateScheduleExceptionRequest,
+    CreateResourceRequest, CreateServiceRequest, CreateStaffRequest, Location, Resource, ResourceRequirementRequest,
+    ScheduleEntryRequest, ScheduleException, Service, ServiceResourceRequirement, StaffMember, StaffSchedule,
+};
+use crate::domain::traits::CompanyRepository;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+const COMPANY_COLUMNS: &str =
+    "id, name, description, website, email, phone, timezone, categories, rating_avg, rating_count, created_at, updated_at";
+
+const LOCATION_COLUMNS: &str = "id, company_id, name, address, city, latitude, longitude, created_at";
+
+const SERVICE_COLUMNS: &str = "id, company_id, name, description, duration_minutes, \
+     buffer_before_minutes, buffer_after_minutes, slot_step_minutes, price_amount, currency, deposit_amount, capacity, created_at";
+
+const RESOURCE_COLUMNS: &str = "r.id, r.company_id, r.location_id, r.name, r.created_at";
+
+pub struct PostgreSQLCompanyRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLCompanyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+pub(crate) fn map_company(row: &PgRow) -> Company {
+    Company {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        website: row.get("website"),
+        email: row.get("email"),
+        phone: row.get("phone"),
+        timezone: row.get("timezone"),
+        categories: row.get("categories"),
+        rating_avg: row.get("rating_avg"),
+        rating_count: row.get("rating_count"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn map_location(row: &PgRow) -> Location {
+    Location {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        name: row.get("name"),
+        address: row.get("address"),
+        city: row.get("city"),
+        latitude: row.get("latitude"),
+        longitude: row.get("longitude"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub(crate) fn map_service(row: &PgRow) -> Service {
+    Service {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        duration_minutes: row.get("duration_minutes"),
+        buffer_before_minutes: row.get("buffer_before_minutes"),
+        buffer_after_minutes: row.get("buffer_after_minutes"),
+        slot_step_minutes: row.get("slot_step_minutes"),
+        price_amount: row.get("price_amount"),
+        deposit_amount: row.get("deposit_amount"),
+        currency: row.get("currency"),
+        capacity: row.get("capacity"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_resource(row: &PgRow) -> Resource {
+    Resource {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        location_id: row.get("location_id"),
+        name: row.get("name"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_staff(row: &PgRow) -> StaffMember {
+    StaffMember {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        user_id: row.get("user_id"),
+        display_name: row.get("display_name"),
+        service_ids: row.get("service_ids"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_schedule(row: &PgRow) -> StaffSchedule {
+    StaffSchedule {
+        staff_id: row.get("staff_id"),
+        location_id: row.get("location_id"),
+        weekday: row.get("weekday"),
+        start_time: row.get("start_time"),
+        end_time: row.get("end_time"),
+    }
+}
+
+fn map_exception(row: &PgRow) -> ScheduleException {
+    ScheduleException {
+        staff_id: row.get("staff_id"),
+        exception_date: row.get("exception_date"),
+        start_time: row.get("start_time"),
+        end_time: row.get("end_time"),
+        is_available: row.get("is_available"),
+        location_id: row.get("location_id"),
+    }
+}
+
+#[async_trait]
+impl CompanyRepository for PostgreSQLCompanyRepository {
+    async fn create_company(&self, owner_id: Uuid, data: &CreateCompanyRequest) -> Result<Company, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Ошибка начала транзакции: {}", e))?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO companies (name, description, website, email, phone, timezone, categories)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            COMPANY_COLUMNS
+        ))
+        .bind(&data.name)
+        .bind(&data.description)
+        .bind(&data.website)
+        .bind(&data.email)
+        .bind(&data.phone)
+        .bind(&data.timezone)
+        .bind(&data.categories)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| format!("Ошибка создания компании: {}", e))?;
+        let company = map_company(&row);
+
+        sqlx::query("INSERT INTO company_members (company_id, user_id, role) VALUES ($1, $2, 'owner')")
+            .bind(company.id)
+            .bind(owner_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка добавления владельца компании: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Ошибка сохранения компании: {}", e))?;
+
+        Ok(company)
+    }
+
+    async fn find_company(&self, id: Uuid) -> Result<Option<Company>, String> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM companies WHERE id = $1 AND deleted_at IS NULL",
+            COMPANY_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка поиска компании: {}", e))?;
+
+        Ok(row.as_ref().map(map_company))
+    }
+
+    async fn get_member_role(&self, company_id: Uuid, user_id: Uuid) -> Result<Option<CompanyRole>, String> {
+        let row = sqlx::query("SELECT role FROM company_members WHERE company_id = $1 AND user_id = $2")
+            .bind(company_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| format!("Ошибка получения роли в компании: {}", e))?;
+
+        Ok(row.and_then(|row| CompanyRole::parse(row.get::<String, _>("role").as_str())))
+    }
+
+    async fn create_location(&self, company_id: Uuid, data: &CreateLocationRequest) -> Result<Location, String> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO locations (company_id, name, address, city, latitude, longitude)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            LOCATION_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(&data.name)
+        .bind(&data.address)
+        .bind(&data.city)
+        .bind(data.latitude)
+        .bind(data.longitude)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка создания филиала: {}", e))?;
+
+        Ok(map_location(&row))
+    }
+
+    async fn find_location(&self, company_id: Uuid, location_id: Uuid) -> Result<Option<Location>, String> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM locations
+            WHERE id = $1 AND company_id = $2 AND deleted_at IS NULL
+            "#,
+            LOCATION_COLUMNS
+        ))
+        .bind(location_id)
+        .bind(company_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка поиска филиала: {}", e))?;
+
+        Ok(row.as_ref().map(map_location))
+    }
+
+    async fn create_service(&self, company_id: Uuid, data: &CreateServiceRequest) -> Result<Service, String> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO services (company_id, name, description, duration_minutes, buffer_before_minutes,
+                                  buffer_after_minutes, slot_step_minutes, price_amount, currency, capacity,
+                                  deposit_amount)
+            VALUES ($1, $2, $3, $4, COALESCE($5, 0), COALESCE($6, 0), COALESCE($7, 15),
+                    COALESCE($8, 0), COALESCE($9, 'RUB'), COALESCE($10, 1), $11)
+            RETURNING {}
+            "#,
+            SERVICE_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(&data.name)
+        .bind(&data.description)
+        .bind(data.duration_minutes)
+        .bind(data.buffer_before_minutes)
+        .bind(data.buffer_after_minutes)
+        .bind(data.slot_step_minutes)
+        .bind(data.price_amount)
+        .bind(&data.currency)
+        .bind(data.capacity)
+        .bind(data.deposit_amount)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка создания услуги: {}", e))?;
+
+        Ok(map_service(&row))
+    }
+
+    async fn find_service(&self, company_id: Uuid, service_id: Uuid) -> Result<Option<Service>, String> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM services WHERE id = $1 AND company_id = $2 AND deleted_at IS NULL",
+            SERVICE_COLUMNS
+        ))
+        .bind(service_id)
+        .bind(company_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка поиска услуги: {}", e))?;
+
+        Ok(row.as_ref().map(map_service))
+    }
+
+    async fn create_staff(&self, company_id: Uuid, data: &CreateStaffRequest) -> Result<StaffMember, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Ошибка начала транзакции: {}", e))?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO staff (company_id, user_id, display_name)
+            VALUES ($1, $2, $3)
+            RETURNING id, company_id, user_id, display_name, created_at
+            "#,
+        )
+        .bind(company_id)
+        .bind(data.user_id)
+        .bind(&data.display_name)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| format!("Ошибка создания сотрудника: {}", e))?;
+
+        let staff_id: Uuid = row.get("id");
+        for service_id in &data.service_ids {
+            sqlx::query("INSERT INTO staff_services (staff_id, service_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+                .bind(staff_id)
+                .bind(service_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Ошибка привязки услуги к сотруднику: {}", e))?;
+        }
+
+        // Сотрудник с аккаунтом становится участником компании
+        if let Some(user_id) = data.user_id {
+            sqlx::query(
+                "INSERT INTO company_members (company_id, user_id, role) VALUES ($1, $2, 'staff') ON CONFLICT DO NOTHING",
+            )
+            .bind(company_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка добавления сотрудника в компанию: {}", e))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Ошибка сохранения сотрудника: {}", e))?;
+
+        Ok(StaffMember {
+            id: staff_id,
+            company_id: row.get("company_id"),
+            user_id: row.get("user_id"),
+            display_name: row.get("display_name"),
+            service_ids: data.service_ids.clone(),
+            created_at: row.get("created_at"),
+        })
+    }
+
+    async fn find_staff(&self, company_id: Uuid, staff_id: Uuid) -> Result<Option<StaffMember>, String> {
+        let row = sqlx::query(
+            r#"
+            SELECT s.id, s.company_id, s.user_id, s.display_name, s.created_at,
+                   COALESCE(array_agg(ss.service_id) FILTER (WHERE ss.service_id IS NOT NULL), '{}') AS service_ids
+            FROM staff s
+            LEFT JOIN staff_services ss ON ss.staff_id = s.id
+            WHERE s.id = $1 AND s.company_id = $2 AND s.deleted_at IS NULL
+            GROUP BY s.id
+            "#,
+        )
+        .bind(staff_id)
+        .bind(company_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка поиска сотрудника: {}", e))?;
+
+        Ok(row.as_ref().map(map_staff))
+    }
+
+    async fn list_staff_for_service(&self, service_id: Uuid) -> Result<Vec<StaffMember>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT s.id, s.company_id, s.user_id, s.display_name, s.created_at,
+                   array_agg(all_services.service_id) AS service_ids
+            FROM staff s
+            JOIN staff_services target ON target.staff_id = s.id AND target.service_id = $1
+            JOIN staff_services all_services ON all_services.staff_id = s.id
+            WHERE s.deleted_at IS NULL
+            GROUP BY s.id
+            ORDER BY s.created_at
+            "#,
+        )
+        .bind(service_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка получения сотрудников услуги: {}", e))?;
+
+        Ok(rows.iter().map(map_staff).collect())
+    }
+
+    async fn list_staff(&self, company_id: Uuid) -> Result<Vec<StaffMember>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT s.id, s.company_id, s.user_id, s.display_name, s.created_at,
+                   COALESCE(array_agg(ss.service_id) FILTER (WHERE ss.service_id IS NOT NULL), '{}') AS service_ids
+            FROM staff s
+            LEFT JOIN staff_services ss ON ss.staff_id = s.id
+            WHERE s.company_id = $1 AND s.deleted_at IS NULL
+            GROUP BY s.id
+            ORDER BY s.created_at
+            "#,
+        )
+        .bind(company_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка получения сотрудников компании: {}", e))?;
+
+        Ok(rows.iter().map(map_staff).collect())
+    }
+
+    async fn replace_weekly_schedule(
+        &self,
+        staff_id: Uuid,
+        entries: &[ScheduleEntryRequest],
+    ) -> Result<Vec<StaffSchedule>, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Ошибка начала транзакции: {}", e))?;
+
+        sqlx::query("DELETE FROM staff_schedules WHERE staff_id = $1")
+            .bind(staff_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка очистки расписания: {}", e))?;
+
+        let mut schedule = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let row = sqlx::query(
+                r#"
+                INSERT INTO staff_schedules (staff_id, location_id, weekday, start_time, end_time)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING staff_id, location_id, weekday, start_time, end_time
+                "#,
+            )
+            .bind(staff_id)
+            .bind(entry.location_id)
+            .bind(entry.weekday)
+            .bind(entry.start_time)
+            .bind(entry.end_time)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка сохранения расписания: {}", e))?;
+            schedule.push(map_schedule(&row));
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Ошибка сохранения расписания: {}", e))?;
+
+        Ok(schedule)
+    }
+
+    async fn list_weekly_schedules(&self, staff_ids: &[Uuid]) -> Result<Vec<StaffSchedule>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT staff_id, location_id, weekday, start_time, end_time
+            FROM staff_schedules
+            WHERE staff_id = ANY($1)
+            ORDER BY staff_id, weekday, start_time
+            "#,
+        )
+        .bind(staff_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка получения расписания: {}", e))?;
+
+        Ok(rows.iter().map(map_schedule).collect())
+    }
+
+    async fn add_schedule_exception(
+        &self,
+        staff_id: Uuid,
+        data: &CreateScheduleExceptionRequest,
+    ) -> Result<ScheduleException, String> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO staff_schedule_exceptions
+                (staff_id, location_id, exception_date, start_time, end_time, is_available)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING staff_id, location_id, exception_date, start_time, end_time, is_available
+            "#,
+        )
+        .bind(staff_id)
+        .bind(data.location_id)
+        .bind(data.exception_date)
+        .bind(data.start_time)
+        .bind(data.end_time)
+        .bind(data.is_available)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка создания исключения в расписании: {}", e))?;
+
+        Ok(map_exception(&row))
+    }
+
+    async fn list_schedule_exceptions(
+        &self,
+        staff_ids: &[Uuid],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ScheduleException>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT staff_id, location_id, exception_date, start_time, end_time, is_available
+            FROM staff_schedule_exceptions
+            WHERE staff_id = ANY($1) AND exception_date BETWEEN $2 AND $3
+            ORDER BY exception_date, start_time
+            "#,
+        )
+        .bind(staff_ids)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка получения исключений расписания: {}", e))?;
+
+        Ok(rows.iter().map(map_exception).collect())
+    }
+
+    async fn create_resource(&self, company_id: Uuid, data: &CreateResourceRequest) -> Result<Resource, String> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO resources (company_id, location_id, name)
+            VALUES ($1, $2, $3)
+            RETURNING id, company_id, location_id, name, created_at
+            "#,
+        )
+        .bind(company_id)
+        .bind(data.location_id)
+        .bind(data.name.trim())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка создания ресурса: {}", e))?;
+
+        Ok(map_resource(&row))
+    }
+
+    async fn list_resources(&self, company_id: Uuid) -> Result<Vec<Resource>, String> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM resources r WHERE r.company_id = $1 ORDER BY r.location_id, r.name, r.id",
+            RESOURCE_COLUMNS
+        ))
+        .bind(company_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка получения ресурсов: {}", e))?;
+
+        Ok(rows.iter().map(map_resource).collect())
+    }
+
+    async fn list_service_requirements(&self, service_id: Uuid) -> Result<Vec<ServiceResourceRequirement>, String> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT q.id AS requirement_id, {}
+            FROM service_resource_requirements q
+            JOIN service_resource_options o ON o.requirement_id = q.id
+            JOIN resources r ON r.id = o.resource_id
+            WHERE q.service_id = $1
+            ORDER BY q.position, r.name, r.id
+            "#,
+            RESOURCE_COLUMNS
+        ))
+        .bind(service_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Ошибка получения требований услуги к ресурсам: {}", e))?;
+
+        // Строки идут подряд по требованиям
+        let mut requirements: Vec<(Uuid, ServiceResourceRequirement)> = Vec::new();
+        for row in &rows {
+            let requirement_id: Uuid = row.get("requirement_id");
+            match requirements.last_mut() {
+                Some((id, requirement)) if *id == requirement_id => requirement.resources.push(map_resource(row)),
+                _ => requirements.push((
+                    requirement_id,
+                    ServiceResourceRequirement {
+                        resources: vec![map_resource(row)],
+                    },
+                )),
+            }
+        }
+
+        Ok(requirements.into_iter().map(|(_, requirement)| requirement).collect())
+    }
+
+    async fn replace_service_requirements(
+        &self,
+        service_id: Uuid,
+        requirements: &[ResourceRequirementRequest],
+    ) -> Result<Vec<ServiceResourceRequirement>, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Ошибка начала транзакции: {}", e))?;
+
+        sqlx::query("DELETE FROM service_resource_requirements WHERE service_id = $1")
+            .bind(service_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка удаления требований услуги к ресурсам: {}", e))?;
+
+        for (position, requirement) in requirements.iter().enumerate() {
+            let row = sqlx::query(
+                "INSERT INTO service_resource_requirements (service_id, position) VALUES ($1, $2) RETURNING id",
+            )
+            .bind(service_id)
+            .bind(position as i32)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка сохранения требования услуги к ресурсам: {}", e))?;
+            let requirement_id: Uuid = row.get("id");
+
+            sqlx::query(
+                r#"
+                INSERT INTO service_resource_options (requirement_id, resource_id)
+                SELECT $1, resource_id FROM unnest($2::uuid[]) AS resource_id
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(requirement_id)
+            .bind(&requirement.resource_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка сохранения вариантов ресурсов: {}", e))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Ошибка сохранения требований услуги к ресурсам: {}", e))?;
+
+        self.list_service_requirements(service_id).await
+    }
+}
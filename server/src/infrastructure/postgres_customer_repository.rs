@@ -0,0 +1,313 @@
+use crate::domain::entities::{Booking, CustomerNote, CustomerSummary};
+use crate::domain::errors::AppError;
+use crate::domain::traits::CustomerRepository;
+use crate::infrastructure::postgres_booking_repository::{BOOKING_COLUMNS, map_booking};
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// Сводка по клиентам компании $1: счетчики по записям без временных броней и метки.
+/// Дальнейшие условия добавляются к `WHERE TRUE`
+const CUSTOMERS_SELECT: &str = r#"
+    WITH stats AS (
+        SELECT customer_id,
+               count(*) AS booking_count,
+               count(*) FILTER (WHERE status IN ('checked_in', 'completed')) AS visit_count,
+               count(*) FILTER (WHERE status = 'no_show') AS no_show_count,
+               max(lower(during)) FILTER (WHERE status IN ('checked_in', 'completed')) AS last_visit_at,
+               max(lower(during)) AS last_booking_at
+        FROM bookings
+        WHERE company_id = $1 AND status <> 'held'
+        GROUP BY customer_id
+    )
+    SELECT u.id, u.first_name, u.last_name, u.email,
+           COALESCE(t.tags, ARRAY[]::text[]) AS tags,
+           s.booking_count, s.visit_count, s.no_show_count, s.last_visit_at, s.last_booking_at,
+           count(*) OVER () AS total
+    FROM stats s
+    JOIN users u ON u.id = s.customer_id
+    LEFT JOIN LATERAL (
+        SELECT array_agg(ct.tag::text ORDER BY ct.tag) AS tags
+        FROM customer_tags ct
+        WHERE ct.company_id = $1 AND ct.customer_id = s.customer_id
+    ) t ON TRUE
+    WHERE TRUE
+"#;
+
+/// Поиск по подстроке $2 и метке $3 для списка клиентов.
+/// strpos вместо ILIKE: символы `%` и `_` в запросе ищутся как есть
+const CUSTOMERS_FILTER: &str = r#"
+    AND ($2::text IS NULL
+         OR strpos(lower(concat_ws(' ', u.first_name, u.last_name, u.username, u.email)), lower($2)) > 0)
+    AND ($3::text IS NULL OR $3 = ANY(t.tags))
+"#;
+
+/// Колонки заметки с именем автора; `n` - заметки, `u` - пользователи
+const NOTE_COLUMNS: &str = "n.id, n.customer_id, n.author_id, \
+     NULLIF(concat_ws(' ', u.first_name, u.last_name), '') AS author_name, n.body, n.created_at, n.updated_at";
+
+pub struct PostgreSQLCustomerRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLCustomerRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_customer(row: &PgRow) -> CustomerSummary {
+    CustomerSummary {
+        id: row.get("id"),
+        first_name: row.get("first_name"),
+        last_name: row.get("last_name"),
+        email: row.get("email"),
+        tags: row.get("tags"),
+        booking_count: row.get("booking_count"),
+        visit_count: row.get("visit_count"),
+        no_show_count: row.get("no_show_count"),
+        last_visit_at: row.get("last_visit_at"),
+    }
+}
+
+fn map_note(row: &PgRow) -> CustomerNote {
+    CustomerNote {
+        id: row.get("id"),
+        customer_id: row.get("customer_id"),
+        author_id: row.get("author_id"),
+        author_name: row.get("author_name"),
+        body: row.get("body"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+#[async_trait]
+impl CustomerRepository for PostgreSQLCustomerRepository {
+    async fn list_customers(
+        &self,
+        company_id: Uuid,
+        search: Option<&str>,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<CustomerSummary>, i64), AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            {}
+            {}
+            ORDER BY s.last_booking_at DESC, u.id
+            LIMIT $4 OFFSET $5
+            "#,
+            CUSTOMERS_SELECT, CUSTOMERS_FILTER
+        ))
+        .bind(company_id)
+        .bind(search)
+        .bind(tag)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения клиентов компании: {}", e)))?;
+
+        let total = match rows.first() {
+            Some(row) => row.get("total"),
+            // Страница за пределами списка: общее число считается отдельно
+            None if offset > 0 => {
+                sqlx::query_scalar(&format!(
+                    r#"
+                    SELECT count(*) FROM ({}
+                    {}) c
+                    "#,
+                    CUSTOMERS_SELECT, CUSTOMERS_FILTER
+                ))
+                .bind(company_id)
+                .bind(search)
+                .bind(tag)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(format!("Ошибка подсчета клиентов компании: {}", e)))?
+            }
+            None => 0,
+        };
+
+        Ok((rows.iter().map(map_customer).collect(), total))
+    }
+
+    async fn find_customer(&self, company_id: Uuid, customer_id: Uuid) -> Result<Option<CustomerSummary>, AppError> {
+        let row = sqlx::query(&format!("{} AND s.customer_id = $2", CUSTOMERS_SELECT))
+            .bind(company_id)
+            .bind(customer_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска клиента: {}", e)))?;
+
+        Ok(row.as_ref().map(map_customer))
+    }
+
+    async fn list_customer_bookings(&self, company_id: Uuid, customer_id: Uuid) -> Result<Vec<Booking>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM bookings
+            WHERE company_id = $1 AND customer_id = $2 AND status <> 'held'
+            ORDER BY lower(during) DESC
+            "#,
+            BOOKING_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(customer_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения записей клиента: {}", e)))?;
+
+        Ok(rows.iter().map(map_booking).collect())
+    }
+
+    async fn list_notes(&self, company_id: Uuid, customer_id: Uuid) -> Result<Vec<CustomerNote>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM customer_notes n
+            LEFT JOIN users u ON u.id = n.author_id
+            WHERE n.company_id = $1 AND n.customer_id = $2
+            ORDER BY n.created_at DESC, n.id
+            "#,
+            NOTE_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(customer_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения заметок о клиенте: {}", e)))?;
+
+        Ok(rows.iter().map(map_note).collect())
+    }
+
+    async fn find_note(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+        note_id: Uuid,
+    ) -> Result<Option<CustomerNote>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM customer_notes n
+            LEFT JOIN users u ON u.id = n.author_id
+            WHERE n.id = $1 AND n.company_id = $2 AND n.customer_id = $3
+            "#,
+            NOTE_COLUMNS
+        ))
+        .bind(note_id)
+        .bind(company_id)
+        .bind(customer_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска заметки: {}", e)))?;
+
+        Ok(row.as_ref().map(map_note))
+    }
+
+    async fn create_note(
+        &self,
+        company_id: Uuid,
+        customer_id: Uuid,
+        author_id: Uuid,
+        body: &str,
+    ) -> Result<CustomerNote, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            WITH n AS (
+                INSERT INTO customer_notes (company_id, customer_id, author_id, body)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *
+            )
+            SELECT {}
+            FROM n
+            LEFT JOIN users u ON u.id = n.author_id
+            "#,
+            NOTE_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(customer_id)
+        .bind(author_id)
+        .bind(body)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка создания заметки: {}", e)))?;
+
+        Ok(map_note(&row))
+    }
+
+    async fn update_note(&self, note_id: Uuid, body: &str) -> Result<Option<CustomerNote>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            WITH n AS (
+                UPDATE customer_notes SET body = $2 WHERE id = $1
+                RETURNING *
+            )
+            SELECT {}
+            FROM n
+            LEFT JOIN users u ON u.id = n.author_id
+            "#,
+            NOTE_COLUMNS
+        ))
+        .bind(note_id)
+        .bind(body)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка изменения заметки: {}", e)))?;
+
+        Ok(row.as_ref().map(map_note))
+    }
+
+    async fn delete_note(&self, note_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM customer_notes WHERE id = $1")
+            .bind(note_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка удаления заметки: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn replace_tags(&self, company_id: Uuid, customer_id: Uuid, tags: &[String]) -> Result<Vec<String>, AppError> {
+        let db_error = |e: sqlx::Error| AppError::Internal(format!("Ошибка сохранения меток клиента: {}", e));
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+
+        sqlx::query("DELETE FROM customer_tags WHERE company_id = $1 AND customer_id = $2 AND tag <> ALL($3)")
+            .bind(company_id)
+            .bind(customer_id)
+            .bind(tags)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        sqlx::query(
+            r#"
+            INSERT INTO customer_tags (company_id, customer_id, tag)
+            SELECT $1, $2, unnest($3::varchar[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(company_id)
+        .bind(customer_id)
+        .bind(tags)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let tags = sqlx::query_scalar(
+            "SELECT tag::text FROM customer_tags WHERE company_id = $1 AND customer_id = $2 ORDER BY tag",
+        )
+        .bind(company_id)
+        .bind(customer_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        tx.commit().await.map_err(db_error)?;
+        Ok(tags)
+    }
+}
@@ -0,0 +1,245 @@
+use crate::domain::entities::{JobRecord, JobStatus, JobsQuery, NewJob};
+use crate::domain::errors::AppError;
+use crate::domain::traits::JobRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+const UNIQUE_VIOLATION: &str = "23505";
+
+const JOB_COLUMNS: &str = "id, kind, payload, status, attempts, max_attempts, run_at, last_error, \
+     created_at, updated_at, completed_at";
+
+/// Больше задач за раз в админке не показываем
+const MAX_LIST_LIMIT: i64 = 500;
+
+pub struct PostgreSQLJobRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLJobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Закрыть задачу, работу которой выполнит такая же задача из очереди
+    async fn supersede(&self, id: Uuid, lease_until: DateTime<Utc>, error: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs SET status = 'completed', completed_at = NOW(), locked_until = NULL, last_error = $3
+            WHERE id = $1 AND status = 'running' AND locked_until = $2
+            "#,
+        )
+        .bind(id)
+        .bind(lease_until)
+        .bind(format!("{} (повтор уступил такой же задаче из очереди)", error))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка завершения задачи: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn map_job(row: &PgRow) -> JobRecord {
+    let status: String = row.get("status");
+    JobRecord {
+        id: row.get("id"),
+        kind: row.get("kind"),
+        payload: row.get("payload"),
+        status: JobStatus::parse(&status).unwrap_or(JobStatus::Dead),
+        attempts: row.get("attempts"),
+        max_attempts: row.get("max_attempts"),
+        run_at: row.get("run_at"),
+        last_error: row.get("last_error"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        completed_at: row.get("completed_at"),
+    }
+}
+
+#[async_trait]
+impl JobRepository for PostgreSQLJobRepository {
+    async fn enqueue(&self, job: &NewJob) -> Result<Option<JobRecord>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO jobs (kind, payload, run_at, max_attempts, dedupe_key)
+            VALUES ($1, $2, COALESCE($3, NOW()), $4, $5)
+            ON CONFLICT (dedupe_key) WHERE status = 'pending' AND dedupe_key IS NOT NULL DO NOTHING
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(&job.kind)
+        .bind(&job.payload)
+        .bind(job.run_at)
+        .bind(job.max_attempts)
+        .bind(&job.dedupe_key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка постановки задачи в очередь: {}", e)))?;
+
+        Ok(row.as_ref().map(map_job))
+    }
+
+    async fn claim(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        kinds: &[String],
+        limit: i64,
+    ) -> Result<Vec<JobRecord>, AppError> {
+        // Аренда истекла на последней попытке: воркер падал, не успев записать итог, и
+        // без этого задача повторялась бы бесконечно
+        sqlx::query(
+            r#"
+            UPDATE jobs SET status = 'dead', locked_until = NULL,
+                last_error = 'Выполнение прервано: аренда истекла на последней попытке'
+            WHERE kind = ANY($2) AND status = 'running' AND locked_until <= $1 AND attempts >= max_attempts
+            "#,
+        )
+        .bind(now)
+        .bind(kinds)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления задач: {}", e)))?;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE jobs SET status = 'running', attempts = attempts + 1, locked_until = $2
+            WHERE id IN (
+                SELECT id FROM jobs
+                WHERE kind = ANY($3)
+                  AND ((status = 'pending' AND run_at <= $1)
+                    OR (status = 'running' AND locked_until <= $1 AND attempts < max_attempts))
+                ORDER BY run_at
+                LIMIT $4
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(now)
+        .bind(lease_until)
+        .bind(kinds)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка выборки задач: {}", e)))?;
+
+        let mut jobs: Vec<JobRecord> = rows.iter().map(map_job).collect();
+        jobs.sort_by_key(|job| job.run_at);
+        Ok(jobs)
+    }
+
+    async fn complete(&self, id: Uuid, lease_until: DateTime<Utc>) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs SET status = 'completed', completed_at = NOW(), locked_until = NULL, last_error = NULL
+            WHERE id = $1 AND status = 'running' AND locked_until = $2
+            "#,
+        )
+        .bind(id)
+        .bind(lease_until)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка завершения задачи: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn retry_later(
+        &self,
+        id: Uuid,
+        lease_until: DateTime<Utc>,
+        error: &str,
+        run_at: DateTime<Utc>,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs SET status = 'pending', run_at = $3, last_error = $4, locked_until = NULL
+            WHERE id = $1 AND status = 'running' AND locked_until = $2
+            "#,
+        )
+        .bind(id)
+        .bind(lease_until)
+        .bind(run_at)
+        .bind(error)
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            // Пока задача выполнялась, в очередь встала такая же: повтор ей уступает. Это не сбой,
+            // поэтому задача закрывается как выполненная, а не попадает в dead
+            Err(sqlx::Error::Database(db_error)) if db_error.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                self.supersede(id, lease_until, error).await
+            }
+            Err(e) => Err(AppError::Internal(format!("Ошибка переноса задачи: {}", e))),
+        }
+    }
+
+    async fn bury(&self, id: Uuid, lease_until: DateTime<Utc>, error: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs SET status = 'dead', last_error = $3, locked_until = NULL
+            WHERE id = $1 AND status = 'running' AND locked_until = $2
+            "#,
+        )
+        .bind(id)
+        .bind(lease_until)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления задачи: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_jobs(&self, query: &JobsQuery) -> Result<Vec<JobRecord>, AppError> {
+        let status = query.status.unwrap_or(JobStatus::Dead);
+        let limit = query.limit.unwrap_or(100).clamp(1, MAX_LIST_LIMIT);
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM jobs
+            WHERE status = $1 AND ($2::varchar IS NULL OR kind = $2)
+            ORDER BY updated_at DESC
+            LIMIT $3
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(status.as_str())
+        .bind(&query.kind)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения задач: {}", e)))?;
+
+        Ok(rows.iter().map(map_job).collect())
+    }
+
+    async fn retry_dead(&self, id: Uuid) -> Result<Option<JobRecord>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE jobs SET status = 'pending', attempts = 0, run_at = NOW(), locked_until = NULL
+            WHERE id = $1 AND status = 'dead'
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_error) if db_error.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                AppError::Conflict("Такая задача уже ждет в очереди".to_string())
+            }
+            _ => AppError::Internal(format!("Ошибка повтора задачи: {}", e)),
+        })?;
+
+        Ok(row.as_ref().map(map_job))
+    }
+}
@@ -0,0 +1,148 @@
+use crate::domain::entities::{MediaAsset, MediaKind, NewMedia};
+use crate::domain::errors::AppError;
+use crate::domain::traits::MediaRepository;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// SQLSTATE нарушения уникальности
+const UNIQUE_VIOLATION: &str = "23505";
+
+const MEDIA_COLUMNS: &str = "id, kind, user_id, company_id, storage_key, thumbnail_key, content_type, \
+     width, height, size_bytes, created_at";
+
+pub struct PostgreSQLMediaRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLMediaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_media(row: &PgRow) -> Result<MediaAsset, AppError> {
+    let kind: String = row.get("kind");
+    Ok(MediaAsset {
+        id: row.get("id"),
+        kind: MediaKind::parse(&kind)
+            .ok_or_else(|| AppError::Internal(format!("Неизвестный тип изображения: {}", kind)))?,
+        user_id: row.get("user_id"),
+        company_id: row.get("company_id"),
+        storage_key: row.get("storage_key"),
+        thumbnail_key: row.get("thumbnail_key"),
+        content_type: row.get("content_type"),
+        width: row.get("width"),
+        height: row.get("height"),
+        size_bytes: row.get("size_bytes"),
+        created_at: row.get("created_at"),
+    })
+}
+
+#[async_trait]
+impl MediaRepository for PostgreSQLMediaRepository {
+    async fn save_media(&self, media: &NewMedia) -> Result<(MediaAsset, Vec<MediaAsset>), AppError> {
+        let db_error = |e: sqlx::Error| AppError::Internal(format!("Ошибка сохранения изображения: {}", e));
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+
+        // Прежний аватар или логотип удаляется в той же транзакции, что и вставка нового
+        let replaced = match media.kind {
+            MediaKind::Avatar | MediaKind::Logo => sqlx::query(&format!(
+                r#"
+                DELETE FROM media
+                WHERE kind = $1
+                  AND user_id IS NOT DISTINCT FROM $2
+                  AND company_id IS NOT DISTINCT FROM $3
+                RETURNING {}
+                "#,
+                MEDIA_COLUMNS
+            ))
+            .bind(media.kind.as_str())
+            .bind(media.user_id)
+            .bind(media.company_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(map_media)
+            .collect::<Result<Vec<_>, _>>()?,
+            MediaKind::Gallery => Vec::new(),
+        };
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO media (id, kind, user_id, company_id, uploaded_by, storage_key, thumbnail_key,
+                               content_type, width, height, size_bytes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING {}
+            "#,
+            MEDIA_COLUMNS
+        ))
+        .bind(media.id)
+        .bind(media.kind.as_str())
+        .bind(media.user_id)
+        .bind(media.company_id)
+        .bind(media.uploaded_by)
+        .bind(&media.storage_key)
+        .bind(&media.thumbnail_key)
+        .bind(&media.content_type)
+        .bind(media.width)
+        .bind(media.height)
+        .bind(media.size_bytes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            // Параллельная загрузка успела вставить свой аватар или логотип первой
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                AppError::Conflict("Изображение уже заменяется другим запросом".to_string())
+            }
+            e => db_error(e),
+        })?;
+        let asset = map_media(&row)?;
+
+        tx.commit().await.map_err(db_error)?;
+        Ok((asset, replaced))
+    }
+
+    async fn find_user_avatar(&self, user_id: Uuid) -> Result<Option<MediaAsset>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM media WHERE kind = 'avatar' AND user_id = $1",
+            MEDIA_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска аватара: {}", e)))?;
+
+        row.as_ref().map(map_media).transpose()
+    }
+
+    async fn list_company_media(&self, company_id: Uuid) -> Result<Vec<MediaAsset>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM media
+            WHERE company_id = $1
+            ORDER BY created_at, id
+            "#,
+            MEDIA_COLUMNS
+        ))
+        .bind(company_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения изображений компании: {}", e)))?;
+
+        rows.iter().map(map_media).collect()
+    }
+
+    async fn delete_media(&self, id: Uuid) -> Result<Option<MediaAsset>, AppError> {
+        let row = sqlx::query(&format!("DELETE FROM media WHERE id = $1 RETURNING {}", MEDIA_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка удаления изображения: {}", e)))?;
+
+        row.as_ref().map(map_media).transpose()
+    }
+}
@@ -0,0 +1,215 @@
+use crate::domain::entities::{Notification, NotificationKind, OutboxMessage, Recipient};
+use crate::domain::errors::AppError;
+use crate::domain::traits::NotificationOutbox;
+use crate::infrastructure::postgres_booking_repository::{begin, commit};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+const OUTBOX_COLUMNS: &str = "id, user_id, kind, payload, attempts, delivered_channels, created_at";
+
+pub struct PostgreSQLOutboxRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLOutboxRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_message(row: &PgRow) -> OutboxMessage {
+    OutboxMessage {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        kind: row.get("kind"),
+        payload: row.get("payload"),
+        attempts: row.get("attempts"),
+        delivered_channels: row.get("delivered_channels"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Поставить уведомление в outbox внутри транзакции изменения данных
+pub(crate) async fn enqueue_in_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    notification: &Notification,
+) -> Result<Uuid, AppError> {
+    let row = sqlx::query("INSERT INTO notification_outbox (user_id, kind, payload) VALUES ($1, $2, $3) RETURNING id")
+        .bind(notification.user_id)
+        .bind(notification.kind.as_str())
+        .bind(&notification.data)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка постановки уведомления в очередь: {}", e)))?;
+
+    Ok(row.get("id"))
+}
+
+/// Уведомить клиента о записи: названия и время берутся из самой записи на момент транзакции,
+/// `extra` дополняет их параметрами конкретного вида (причина отмены, прежнее время)
+pub(crate) async fn enqueue_booking_notification(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: Uuid,
+    kind: NotificationKind,
+    extra: Value,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO notification_outbox (user_id, kind, payload)
+        SELECT b.customer_id, $2,
+               jsonb_build_object(
+                   'booking_id', b.id,
+                   'company_id', b.company_id,
+                   'company', c.name,
+                   'service', s.name,
+                   'staff', st.display_name,
+                   'location', concat_ws(', ', l.name, l.address),
+                   'starts_at', lower(b.during),
+                   'ends_at', upper(b.during),
+                   'timezone', c.timezone
+               ) || $3::jsonb
+        FROM bookings b
+        JOIN companies c ON c.id = b.company_id
+        JOIN services s ON s.id = b.service_id
+        JOIN staff st ON st.id = b.staff_id
+        JOIN locations l ON l.id = b.location_id
+        WHERE b.id = $1
+        "#,
+    )
+    .bind(booking_id)
+    .bind(kind.as_str())
+    .bind(extra)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка постановки уведомления в очередь: {}", e)))?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl NotificationOutbox for PostgreSQLOutboxRepository {
+    async fn enqueue(&self, notification: &Notification) -> Result<Uuid, AppError> {
+        let mut tx = begin(&self.pool).await?;
+        let id = enqueue_in_tx(&mut tx, notification).await?;
+        commit(tx).await?;
+        Ok(id)
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<OutboxMessage>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE notification_outbox SET attempts = attempts + 1, locked_until = $2
+            WHERE id IN (
+                SELECT id FROM notification_outbox
+                WHERE status = 'pending' AND next_attempt_at <= $1
+                  AND (locked_until IS NULL OR locked_until <= $1)
+                ORDER BY next_attempt_at, created_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            OUTBOX_COLUMNS
+        ))
+        .bind(now)
+        .bind(lease_until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка выборки уведомлений: {}", e)))?;
+
+        let mut messages: Vec<OutboxMessage> = rows.iter().map(map_message).collect();
+        messages.sort_by_key(|message| message.created_at);
+        Ok(messages)
+    }
+
+    async fn find_recipient(&self, user_id: Uuid) -> Result<Option<Recipient>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, email, locale,
+                   COALESCE(NULLIF(TRIM(CONCAT_WS(' ', first_name, last_name)), ''), username) AS name
+            FROM users WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска получателя: {}", e)))?;
+
+        Ok(row.map(|row| Recipient {
+            user_id: row.get("id"),
+            name: row.get("name"),
+            email: row.get("email"),
+            locale: row.get("locale"),
+        }))
+    }
+
+    async fn mark_sent(&self, id: Uuid, delivered_channels: &[String]) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE notification_outbox
+            SET status = 'sent', delivered_channels = $2, locked_until = NULL, last_error = NULL, sent_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(delivered_channels)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления уведомления: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_retry(
+        &self,
+        id: Uuid,
+        delivered_channels: &[String],
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE notification_outbox
+            SET delivered_channels = $2, last_error = $3, next_attempt_at = $4, locked_until = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(delivered_channels)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления уведомления: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: Uuid, delivered_channels: &[String], error: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE notification_outbox
+            SET status = 'failed', delivered_channels = $2, last_error = $3, locked_until = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(delivered_channels)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления уведомления: {}", e)))?;
+
+        Ok(())
+    }
+}
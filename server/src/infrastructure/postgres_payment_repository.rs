@@ -0,0 +1,213 @@
+use crate::domain::entities::{Payment, PaymentStatus, ProviderPayment};
+use crate::domain::errors::AppError;
+use crate::domain::traits::PaymentRepository;
+use crate::infrastructure::job_queue::Job;
+use crate::infrastructure::payment_refund::PaymentRefundJob;
+use crate::infrastructure::postgres_booking_repository::{begin, commit};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+const PAYMENT_COLUMNS: &str = "id, booking_id, company_id, customer_id, amount, currency, status, provider, \
+     provider_payment_id, client_secret, refund_amount, created_at, updated_at, paid_at, refunded_at";
+
+pub struct PostgreSQLPaymentRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLPaymentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Платеж по значению колонки `column` (id или booking_id)
+    async fn find_by(&self, column: &str, value: Uuid) -> Result<Option<Payment>, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM payments WHERE {} = $1", PAYMENT_COLUMNS, column))
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска платежа: {}", e)))?;
+
+        Ok(row.as_ref().map(map_payment))
+    }
+}
+
+fn map_payment(row: &PgRow) -> Payment {
+    let status: String = row.get("status");
+    Payment {
+        id: row.get("id"),
+        booking_id: row.get("booking_id"),
+        company_id: row.get("company_id"),
+        customer_id: row.get("customer_id"),
+        amount: row.get("amount"),
+        currency: row.get("currency"),
+        // Значения ограничены CHECK в БД
+        status: PaymentStatus::parse(&status).unwrap_or(PaymentStatus::Pending),
+        provider: row.get("provider"),
+        provider_payment_id: row.get("provider_payment_id"),
+        client_secret: row.get("client_secret"),
+        refund_amount: row.get("refund_amount"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        paid_at: row.get("paid_at"),
+        refunded_at: row.get("refunded_at"),
+    }
+}
+
+/// Завести депозит для записи на услугу с предоплатой - в транзакции создания записи.
+/// Депозит нужен только записи, ждущей подтверждения: его дает оплата
+pub(crate) async fn open_deposit(tx: &mut Transaction<'_, Postgres>, booking_id: Uuid) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO payments (booking_id, company_id, customer_id, amount, currency, created_at)
+        SELECT b.id, b.company_id, b.customer_id, s.deposit_amount, s.currency, b.created_at
+        FROM bookings b
+        JOIN services s ON s.id = b.service_id
+        WHERE b.id = $1 AND b.status = 'pending' AND s.deposit_amount IS NOT NULL
+        ON CONFLICT (booking_id) DO NOTHING
+        "#,
+    )
+    .bind(booking_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка создания депозита: {}", e)))?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl PaymentRepository for PostgreSQLPaymentRepository {
+    async fn find_payment(&self, id: Uuid) -> Result<Option<Payment>, AppError> {
+        self.find_by("id", id).await
+    }
+
+    async fn find_booking_payment(&self, booking_id: Uuid) -> Result<Option<Payment>, AppError> {
+        self.find_by("booking_id", booking_id).await
+    }
+
+    async fn find_provider_payment(&self, provider: &str, provider_payment_id: &str) -> Result<Option<Payment>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM payments WHERE provider = $1 AND provider_payment_id = $2",
+            PAYMENT_COLUMNS
+        ))
+        .bind(provider)
+        .bind(provider_payment_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска платежа: {}", e)))?;
+
+        Ok(row.as_ref().map(map_payment))
+    }
+
+    async fn start_attempt(
+        &self,
+        payment_id: Uuid,
+        provider: &str,
+        attempt: &ProviderPayment,
+    ) -> Result<Option<Payment>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE payments
+            SET status = 'pending', provider = $2, provider_payment_id = $3, client_secret = $4
+            WHERE id = $1 AND status IN ('pending', 'failed')
+            RETURNING {}
+            "#,
+            PAYMENT_COLUMNS
+        ))
+        .bind(payment_id)
+        .bind(provider)
+        .bind(&attempt.provider_payment_id)
+        .bind(&attempt.client_secret)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка сохранения платежа: {}", e)))?;
+
+        Ok(row.as_ref().map(map_payment))
+    }
+
+    async fn update_status(
+        &self,
+        payment_id: Uuid,
+        from: &[PaymentStatus],
+        to: PaymentStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Payment>, AppError> {
+        let from: Vec<&str> = from.iter().map(PaymentStatus::as_str).collect();
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE payments
+            SET status = $3,
+                paid_at = CASE WHEN $3 = 'succeeded' THEN $4 ELSE paid_at END,
+                refunded_at = CASE WHEN $3 = 'refunded' THEN $4 ELSE refunded_at END
+            WHERE id = $1 AND status = ANY($2)
+            RETURNING {}
+            "#,
+            PAYMENT_COLUMNS
+        ))
+        .bind(payment_id)
+        .bind(&from)
+        .bind(to.as_str())
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка изменения статуса платежа: {}", e)))?;
+
+        Ok(row.as_ref().map(map_payment))
+    }
+
+    async fn request_refund(&self, payment_id: Uuid, amount: i64) -> Result<Option<Payment>, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE payments
+            SET status = 'refund_pending', refund_amount = $2
+            WHERE id = $1 AND status = 'succeeded'
+            RETURNING {}
+            "#,
+            PAYMENT_COLUMNS
+        ))
+        .bind(payment_id)
+        .bind(amount)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка оформления возврата: {}", e)))?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        sqlx::query("INSERT INTO jobs (kind, payload, max_attempts) VALUES ($1, $2, $3)")
+            .bind(PaymentRefundJob::KIND)
+            .bind(json!({ "payment_id": payment_id }))
+            .bind(PaymentRefundJob::MAX_ATTEMPTS)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка постановки возврата в очередь: {}", e)))?;
+
+        commit(tx).await?;
+        Ok(Some(map_payment(&row)))
+    }
+
+    async fn list_unpaid(&self, created_before: DateTime<Utc>) -> Result<Vec<Payment>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM payments p
+            WHERE p.status IN ('pending', 'failed')
+              AND p.created_at < $1
+              AND EXISTS (SELECT 1 FROM bookings b WHERE b.id = p.booking_id AND b.status = 'pending')
+            ORDER BY p.created_at
+            "#,
+            PAYMENT_COLUMNS
+        ))
+        .bind(created_before)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска неоплаченных депозитов: {}", e)))?;
+
+        Ok(rows.iter().map(map_payment).collect())
+    }
+}
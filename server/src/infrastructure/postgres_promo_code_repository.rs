@@ -0,0 +1,242 @@
+use crate::domain::entities::{DiscountType, NewPromoCode, NewPromoRedemption, PromoCode, UpdatePromoCodeRequest};
+use crate::domain::errors::AppError;
+use crate::domain::traits::PromoCodeRepository;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+/// SQLSTATE нарушения уникальности
+const UNIQUE_VIOLATION: &str = "23505";
+
+const PROMO_CODE_COLUMNS: &str = "id, company_id, code, discount_type, discount_value, currency, valid_from, \
+     valid_until, max_redemptions, max_per_customer, service_ids, location_ids, is_active, redemption_count, \
+     created_at, updated_at";
+
+pub struct PostgreSQLPromoCodeRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLPromoCodeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_promo_code(row: &PgRow) -> PromoCode {
+    let discount_type: String = row.get("discount_type");
+    PromoCode {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        code: row.get("code"),
+        // Значения ограничены CHECK в БД
+        discount_type: DiscountType::parse(&discount_type).unwrap_or(DiscountType::Percent),
+        discount_value: row.get("discount_value"),
+        currency: row.get("currency"),
+        valid_from: row.get("valid_from"),
+        valid_until: row.get("valid_until"),
+        max_redemptions: row.get("max_redemptions"),
+        max_per_customer: row.get("max_per_customer"),
+        service_ids: row.get("service_ids"),
+        location_ids: row.get("location_ids"),
+        is_active: row.get("is_active"),
+        redemption_count: row.get("redemption_count"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// Записать использование промокода в транзакции создания записи. Счетчик увеличивается
+/// под блокировкой строки промокода, поэтому одновременные записи не превысят лимиты
+pub(crate) async fn redeem_promo_code(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: Uuid,
+    customer_id: Uuid,
+    redemption: &NewPromoRedemption,
+) -> Result<(), AppError> {
+    let row = sqlx::query(
+        r#"
+        UPDATE promo_codes
+        SET redemption_count = redemption_count + 1
+        WHERE id = $1 AND is_active
+          AND (valid_from IS NULL OR valid_from <= $2)
+          AND (valid_until IS NULL OR valid_until > $2)
+          AND (max_redemptions IS NULL OR redemption_count < max_redemptions)
+        RETURNING max_per_customer
+        "#,
+    )
+    .bind(redemption.promo_code_id)
+    .bind(redemption.redeemed_at)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка применения промокода: {}", e)))?;
+    let Some(row) = row else {
+        return Err(AppError::Conflict("Промокод больше недоступен".to_string()));
+    };
+
+    // Строка промокода уже заблокирована: параллельные использования ждут этой транзакции
+    let max_per_customer: Option<i32> = row.get("max_per_customer");
+    if let Some(max_per_customer) = max_per_customer {
+        let used: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM promo_redemptions WHERE promo_code_id = $1 AND customer_id = $2")
+                .bind(redemption.promo_code_id)
+                .bind(customer_id)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(|e| AppError::Internal(format!("Ошибка применения промокода: {}", e)))?;
+        if used >= max_per_customer as i64 {
+            return Err(AppError::Conflict("Вы уже использовали этот промокод".to_string()));
+        }
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO promo_redemptions (booking_id, promo_code_id, customer_id, price_amount, discount_amount,
+                                       currency, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(booking_id)
+    .bind(redemption.promo_code_id)
+    .bind(customer_id)
+    .bind(redemption.price_amount)
+    .bind(redemption.discount_amount)
+    .bind(&redemption.currency)
+    .bind(redemption.redeemed_at)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка применения промокода: {}", e)))?;
+
+    Ok(())
+}
+
+/// Вернуть использование промокода отмененной записью: счетчик и лимит на клиента
+/// снова учитывают его как свободное
+pub(crate) async fn release_promo_code(tx: &mut Transaction<'_, Postgres>, booking_id: Uuid) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        WITH released AS (
+            DELETE FROM promo_redemptions WHERE booking_id = $1 RETURNING promo_code_id
+        )
+        UPDATE promo_codes SET redemption_count = redemption_count - 1
+        WHERE id IN (SELECT promo_code_id FROM released)
+        "#,
+    )
+    .bind(booking_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка возврата промокода: {}", e)))?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl PromoCodeRepository for PostgreSQLPromoCodeRepository {
+    async fn create_promo_code(&self, promo_code: &NewPromoCode) -> Result<PromoCode, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO promo_codes (company_id, code, discount_type, discount_value, currency, valid_from,
+                                     valid_until, max_redemptions, max_per_customer, service_ids, location_ids)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING {}
+            "#,
+            PROMO_CODE_COLUMNS
+        ))
+        .bind(promo_code.company_id)
+        .bind(&promo_code.code)
+        .bind(promo_code.discount_type.as_str())
+        .bind(promo_code.discount_value)
+        .bind(&promo_code.currency)
+        .bind(promo_code.valid_from)
+        .bind(promo_code.valid_until)
+        .bind(promo_code.max_redemptions)
+        .bind(promo_code.max_per_customer)
+        .bind(&promo_code.service_ids)
+        .bind(&promo_code.location_ids)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                AppError::Conflict("Такой промокод у компании уже есть".to_string())
+            }
+            e => AppError::Internal(format!("Ошибка создания промокода: {}", e)),
+        })?;
+
+        Ok(map_promo_code(&row))
+    }
+
+    async fn list_promo_codes(&self, company_id: Uuid) -> Result<Vec<PromoCode>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM promo_codes WHERE company_id = $1 ORDER BY created_at DESC, id",
+            PROMO_CODE_COLUMNS
+        ))
+        .bind(company_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения промокодов: {}", e)))?;
+
+        Ok(rows.iter().map(map_promo_code).collect())
+    }
+
+    async fn find_promo_code(&self, company_id: Uuid, id: Uuid) -> Result<Option<PromoCode>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM promo_codes WHERE company_id = $1 AND id = $2",
+            PROMO_CODE_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска промокода: {}", e)))?;
+
+        Ok(row.as_ref().map(map_promo_code))
+    }
+
+    async fn find_promo_code_by_code(&self, company_id: Uuid, code: &str) -> Result<Option<PromoCode>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM promo_codes WHERE company_id = $1 AND code = $2",
+            PROMO_CODE_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска промокода: {}", e)))?;
+
+        Ok(row.as_ref().map(map_promo_code))
+    }
+
+    async fn update_promo_code(&self, id: Uuid, data: &UpdatePromoCodeRequest) -> Result<Option<PromoCode>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE promo_codes
+            SET is_active = COALESCE($2, is_active),
+                valid_until = COALESCE($3, valid_until),
+                max_redemptions = COALESCE($4, max_redemptions),
+                max_per_customer = COALESCE($5, max_per_customer)
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            PROMO_CODE_COLUMNS
+        ))
+        .bind(id)
+        .bind(data.is_active)
+        .bind(data.valid_until)
+        .bind(data.max_redemptions)
+        .bind(data.max_per_customer)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка изменения промокода: {}", e)))?;
+
+        Ok(row.as_ref().map(map_promo_code))
+    }
+
+    async fn count_customer_redemptions(&self, promo_code_id: Uuid, customer_id: Uuid) -> Result<i64, AppError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM promo_redemptions WHERE promo_code_id = $1 AND customer_id = $2")
+            .bind(promo_code_id)
+            .bind(customer_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка подсчета использований промокода: {}", e)))
+    }
+}
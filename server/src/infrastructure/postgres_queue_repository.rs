@@ -0,0 +1,257 @@
+use crate::domain::entities::{NewQueueTicket, QueueDayStats, QueueTicket, QueueTicketStatus};
+use crate::domain::errors::AppError;
+use crate::domain::traits::QueueRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// SQLSTATE нарушения уникальности
+const UNIQUE_VIOLATION: &str = "23505";
+
+const TICKET_COLUMNS: &str = "id, company_id, location_id, service_id, customer_id, customer_name, queue_date, \
+     number, status, called_by, joined_at, called_at, finished_at";
+
+pub struct PostgreSQLQueueRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLQueueRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_ticket(row: &PgRow) -> QueueTicket {
+    let status: String = row.get("status");
+    QueueTicket {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        location_id: row.get("location_id"),
+        service_id: row.get("service_id"),
+        customer_id: row.get("customer_id"),
+        customer_name: row.get("customer_name"),
+        queue_date: row.get("queue_date"),
+        number: row.get("number"),
+        // Значения ограничены CHECK в БД
+        status: QueueTicketStatus::parse(&status).unwrap_or(QueueTicketStatus::Expired),
+        called_by: row.get("called_by"),
+        joined_at: row.get("joined_at"),
+        called_at: row.get("called_at"),
+        finished_at: row.get("finished_at"),
+    }
+}
+
+#[async_trait]
+impl QueueRepository for PostgreSQLQueueRepository {
+    async fn create_ticket(&self, ticket: &NewQueueTicket) -> Result<QueueTicket, AppError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка начала транзакции: {}", e)))?;
+
+        // Счетчик дня блокируется до коммита, номера выдаются без пропусков и повторов
+        let number: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO queue_counters (location_id, queue_date, last_number)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (location_id, queue_date) DO UPDATE SET last_number = queue_counters.last_number + 1
+            RETURNING last_number
+            "#,
+        )
+        .bind(ticket.location_id)
+        .bind(ticket.queue_date)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка выдачи номера талона: {}", e)))?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO queue_tickets (company_id, location_id, service_id, customer_id, customer_name, queue_date,
+                                       number, joined_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {}
+            "#,
+            TICKET_COLUMNS
+        ))
+        .bind(ticket.company_id)
+        .bind(ticket.location_id)
+        .bind(ticket.service_id)
+        .bind(ticket.customer_id)
+        .bind(&ticket.customer_name)
+        .bind(ticket.queue_date)
+        .bind(number)
+        .bind(ticket.joined_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                AppError::Conflict("Вы уже стоите в очереди этого филиала".to_string())
+            }
+            e => AppError::Internal(format!("Ошибка постановки в очередь: {}", e)),
+        })?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка фиксации транзакции: {}", e)))?;
+        Ok(map_ticket(&row))
+    }
+
+    async fn find_ticket(&self, id: Uuid) -> Result<Option<QueueTicket>, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM queue_tickets WHERE id = $1", TICKET_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска талона: {}", e)))?;
+
+        Ok(row.as_ref().map(map_ticket))
+    }
+
+    async fn list_tickets(&self, location_id: Uuid, queue_date: NaiveDate) -> Result<Vec<QueueTicket>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM queue_tickets WHERE location_id = $1 AND queue_date = $2 ORDER BY number",
+            TICKET_COLUMNS
+        ))
+        .bind(location_id)
+        .bind(queue_date)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения очереди: {}", e)))?;
+
+        Ok(rows.iter().map(map_ticket).collect())
+    }
+
+    async fn call_next(
+        &self,
+        location_id: Uuid,
+        queue_date: NaiveDate,
+        called_by: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<QueueTicket>, AppError> {
+        // Талон, который уже вызывает другой сотрудник, пропускается
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE queue_tickets
+            SET status = 'called', called_by = $3, called_at = $4
+            WHERE id = (
+                SELECT id FROM queue_tickets
+                WHERE location_id = $1 AND queue_date = $2 AND status = 'waiting'
+                ORDER BY number
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            ) AND status = 'waiting'
+            RETURNING {}
+            "#,
+            TICKET_COLUMNS
+        ))
+        .bind(location_id)
+        .bind(queue_date)
+        .bind(called_by)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка вызова из очереди: {}", e)))?;
+
+        Ok(row.as_ref().map(map_ticket))
+    }
+
+    async fn update_ticket_status(
+        &self,
+        id: Uuid,
+        from: &[QueueTicketStatus],
+        status: QueueTicketStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Option<QueueTicket>, AppError> {
+        let from: Vec<&str> = from.iter().map(|status| status.as_str()).collect();
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE queue_tickets
+            SET status = $3, finished_at = $4
+            WHERE id = $1 AND status = ANY($2)
+            RETURNING {}
+            "#,
+            TICKET_COLUMNS
+        ))
+        .bind(id)
+        .bind(&from)
+        .bind(status.as_str())
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка изменения талона: {}", e)))?;
+
+        Ok(row.as_ref().map(map_ticket))
+    }
+
+    async fn count_waiting_before(&self, location_id: Uuid, queue_date: NaiveDate, number: i32) -> Result<i64, AppError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM queue_tickets
+            WHERE location_id = $1 AND queue_date = $2 AND status = 'waiting' AND number < $3
+            "#,
+        )
+        .bind(location_id)
+        .bind(queue_date)
+        .bind(number)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка расчета места в очереди: {}", e)))
+    }
+
+    async fn average_service_minutes(&self, location_id: Uuid, since: DateTime<Utc>) -> Result<Option<f64>, AppError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT (AVG(EXTRACT(EPOCH FROM finished_at - called_at)) / 60)::float8
+            FROM queue_tickets
+            WHERE location_id = $1 AND status = 'served' AND finished_at >= $2
+            "#,
+        )
+        .bind(location_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка расчета длительности обслуживания: {}", e)))
+    }
+
+    async fn daily_stats(&self, location_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<QueueDayStats>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT queue_date,
+                   COUNT(*) AS joined,
+                   COUNT(*) FILTER (WHERE status = 'served') AS served,
+                   COUNT(*) FILTER (WHERE status = 'no_show') AS no_show,
+                   COUNT(*) FILTER (WHERE status = 'cancelled') AS cancelled,
+                   COUNT(*) FILTER (WHERE status = 'expired') AS expired,
+                   ROUND(AVG(EXTRACT(EPOCH FROM called_at - joined_at)) / 60, 1)::float8 AS average_wait_minutes,
+                   ROUND(AVG(EXTRACT(EPOCH FROM finished_at - called_at)) FILTER (WHERE status = 'served') / 60, 1)
+                       ::float8 AS average_service_minutes
+            FROM queue_tickets
+            WHERE location_id = $1 AND queue_date BETWEEN $2 AND $3
+            GROUP BY queue_date
+            ORDER BY queue_date
+            "#,
+        )
+        .bind(location_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения статистики очереди: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| QueueDayStats {
+                queue_date: row.get("queue_date"),
+                joined: row.get("joined"),
+                served: row.get("served"),
+                no_show: row.get("no_show"),
+                cancelled: row.get("cancelled"),
+                expired: row.get("expired"),
+                average_wait_minutes: row.get("average_wait_minutes"),
+                average_service_minutes: row.get("average_service_minutes"),
+            })
+            .collect())
+    }
+}
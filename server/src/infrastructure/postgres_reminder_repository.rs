@@ -0,0 +1,239 @@
+use crate::domain::entities::{BookingReminder, NotificationKind, ReminderRule, ReminderStatus};
+use crate::domain::errors::AppError;
+use crate::domain::traits::ReminderRepository;
+use crate::infrastructure::postgres_booking_repository::{begin, commit};
+use crate::infrastructure::postgres_outbox_repository::enqueue_booking_notification;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+const RULE_COLUMNS: &str = "id, company_id, offset_minutes, created_at";
+
+const REMINDER_COLUMNS: &str = "id, booking_id, rule_id, offset_minutes, remind_at, status, sent_at, created_at";
+
+/// Статусы, при которых клиенту напоминают о записи
+const REMINDED_STATUSES: &str = "('pending', 'confirmed')";
+
+pub struct PostgreSQLReminderRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLReminderRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_rule(row: &PgRow) -> ReminderRule {
+    ReminderRule {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        offset_minutes: row.get("offset_minutes"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_reminder(row: &PgRow) -> BookingReminder {
+    let status: String = row.get("status");
+    BookingReminder {
+        id: row.get("id"),
+        booking_id: row.get("booking_id"),
+        rule_id: row.get("rule_id"),
+        offset_minutes: row.get("offset_minutes"),
+        remind_at: row.get("remind_at"),
+        status: ReminderStatus::parse(&status).unwrap_or(ReminderStatus::Cancelled),
+        sent_at: row.get("sent_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Запланировать напоминания записи по правилам компании. Напоминания, время которых
+/// уже прошло (запись создана незадолго до начала), не планируются
+pub(crate) async fn schedule_reminders(tx: &mut Transaction<'_, Postgres>, booking_id: Uuid) -> Result<(), AppError> {
+    sqlx::query(&format!(
+        r#"
+        INSERT INTO booking_reminders (booking_id, rule_id, offset_minutes, remind_at)
+        SELECT b.id, r.id, r.offset_minutes, lower(b.during) - make_interval(mins => r.offset_minutes)
+        FROM bookings b
+        JOIN reminder_rules r ON r.company_id = b.company_id
+        WHERE b.id = $1 AND b.status IN {}
+          AND lower(b.during) - make_interval(mins => r.offset_minutes) > NOW()
+        ON CONFLICT (booking_id, rule_id) WHERE status = 'scheduled' DO NOTHING
+        "#,
+        REMINDED_STATUSES
+    ))
+    .bind(booking_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка планирования напоминаний: {}", e)))?;
+
+    Ok(())
+}
+
+/// Отменить запланированные напоминания записи
+pub(crate) async fn cancel_reminders(tx: &mut Transaction<'_, Postgres>, booking_id: Uuid) -> Result<(), AppError> {
+    sqlx::query("UPDATE booking_reminders SET status = 'cancelled' WHERE booking_id = $1 AND status = 'scheduled'")
+        .bind(booking_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка отмены напоминаний: {}", e)))?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl ReminderRepository for PostgreSQLReminderRepository {
+    async fn list_rules(&self, company_id: Uuid) -> Result<Vec<ReminderRule>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM reminder_rules WHERE company_id = $1 ORDER BY offset_minutes DESC",
+            RULE_COLUMNS
+        ))
+        .bind(company_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения правил напоминаний: {}", e)))?;
+
+        Ok(rows.iter().map(map_rule).collect())
+    }
+
+    async fn replace_rules(&self, company_id: Uuid, offsets_minutes: &[i32]) -> Result<Vec<ReminderRule>, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE booking_reminders SET status = 'cancelled'
+            WHERE status = 'scheduled' AND rule_id IN (
+                SELECT id FROM reminder_rules WHERE company_id = $1 AND NOT (offset_minutes = ANY($2))
+            )
+            "#,
+        )
+        .bind(company_id)
+        .bind(offsets_minutes)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка отмены напоминаний: {}", e)))?;
+
+        sqlx::query("DELETE FROM reminder_rules WHERE company_id = $1 AND NOT (offset_minutes = ANY($2))")
+            .bind(company_id)
+            .bind(offsets_minutes)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка удаления правил напоминаний: {}", e)))?;
+
+        let added: Vec<Uuid> = sqlx::query(
+            r#"
+            INSERT INTO reminder_rules (company_id, offset_minutes)
+            SELECT $1, offset_minutes FROM unnest($2::INTEGER[]) AS offset_minutes
+            ON CONFLICT (company_id, offset_minutes) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(company_id)
+        .bind(offsets_minutes)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка сохранения правил напоминаний: {}", e)))?
+        .iter()
+        .map(|row| row.get("id"))
+        .collect();
+
+        // Новые правила действуют и для записей, созданных до их появления
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO booking_reminders (booking_id, rule_id, offset_minutes, remind_at)
+            SELECT b.id, r.id, r.offset_minutes, lower(b.during) - make_interval(mins => r.offset_minutes)
+            FROM reminder_rules r
+            JOIN bookings b ON b.company_id = r.company_id
+            WHERE r.id = ANY($1) AND b.status IN {}
+              AND lower(b.during) - make_interval(mins => r.offset_minutes) > NOW()
+            ON CONFLICT (booking_id, rule_id) WHERE status = 'scheduled' DO NOTHING
+            "#,
+            REMINDED_STATUSES
+        ))
+        .bind(&added)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка планирования напоминаний: {}", e)))?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM reminder_rules WHERE company_id = $1 ORDER BY offset_minutes DESC",
+            RULE_COLUMNS
+        ))
+        .bind(company_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения правил напоминаний: {}", e)))?;
+
+        commit(tx).await?;
+        Ok(rows.iter().map(map_rule).collect())
+    }
+
+    async fn list_booking_reminders(&self, booking_id: Uuid) -> Result<Vec<BookingReminder>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM booking_reminders WHERE booking_id = $1 ORDER BY remind_at, created_at",
+            REMINDER_COLUMNS
+        ))
+        .bind(booking_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения напоминаний: {}", e)))?;
+
+        Ok(rows.iter().map(map_reminder).collect())
+    }
+
+    async fn send_due_reminders(&self, now: DateTime<Utc>, limit: i64) -> Result<u64, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        // Строки, захваченные другим экземпляром, пропускаются, а не ждут его коммита
+        let due = sqlx::query(
+            r#"
+            SELECT r.id, r.booking_id, r.offset_minutes, lower(b.during) > $1 AS upcoming
+            FROM booking_reminders r
+            JOIN bookings b ON b.id = r.booking_id
+            WHERE r.status = 'scheduled' AND r.remind_at <= $1
+            ORDER BY r.remind_at
+            LIMIT $2
+            FOR UPDATE OF r SKIP LOCKED
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка выборки напоминаний: {}", e)))?;
+
+        let mut sent = 0;
+        for row in &due {
+            let reminder_id: Uuid = row.get("id");
+            let upcoming: bool = row.get("upcoming");
+            let status = if upcoming {
+                let offset_minutes: i32 = row.get("offset_minutes");
+                enqueue_booking_notification(
+                    &mut tx,
+                    row.get("booking_id"),
+                    NotificationKind::BookingReminder,
+                    json!({ "reminder_id": reminder_id, "offset_minutes": offset_minutes }),
+                )
+                .await?;
+                sent += 1;
+                ReminderStatus::Sent
+            } else {
+                ReminderStatus::Missed
+            };
+
+            sqlx::query("UPDATE booking_reminders SET status = $2, sent_at = $3 WHERE id = $1")
+                .bind(reminder_id)
+                .bind(status.as_str())
+                .bind(upcoming.then_some(now))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Internal(format!("Ошибка обновления напоминания: {}", e)))?;
+        }
+
+        commit(tx).await?;
+        Ok(sent)
+    }
+}
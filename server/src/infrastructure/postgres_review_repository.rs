@@ -0,0 +1,198 @@
+use crate::domain::entities::{Booking, CreateReviewRequest, Review};
+use crate::domain::errors::AppError;
+use crate::domain::traits::ReviewRepository;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// SQLSTATE нарушения уникальности
+const UNIQUE_VIOLATION: &str = "23505";
+
+/// Колонки отзыва с подписью автора; `r` - отзывы, `u` - пользователи
+const REVIEW_COLUMNS: &str = "r.id, r.booking_id, r.company_id, r.service_id, r.staff_id, \
+     u.first_name AS author_name, r.rating, r.text, r.reply, r.replied_at, r.is_hidden, r.hidden_reason, \
+     r.created_at, r.updated_at";
+
+pub struct PostgreSQLReviewRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLReviewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_review(row: &PgRow) -> Review {
+    Review {
+        id: row.get("id"),
+        booking_id: row.get("booking_id"),
+        company_id: row.get("company_id"),
+        service_id: row.get("service_id"),
+        staff_id: row.get("staff_id"),
+        author_name: row.get("author_name"),
+        rating: row.get("rating"),
+        text: row.get("text"),
+        reply: row.get("reply"),
+        replied_at: row.get("replied_at"),
+        is_hidden: row.get("is_hidden"),
+        hidden_reason: row.get("hidden_reason"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+#[async_trait]
+impl ReviewRepository for PostgreSQLReviewRepository {
+    async fn create_review(&self, booking: &Booking, data: &CreateReviewRequest) -> Result<Review, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            WITH r AS (
+                INSERT INTO reviews (booking_id, company_id, service_id, staff_id, customer_id, rating, text)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING *
+            )
+            SELECT {}
+            FROM r
+            LEFT JOIN users u ON u.id = r.customer_id
+            "#,
+            REVIEW_COLUMNS
+        ))
+        .bind(booking.id)
+        .bind(booking.company_id)
+        .bind(booking.service_id)
+        .bind(booking.staff_id)
+        .bind(booking.customer_id)
+        .bind(data.rating)
+        .bind(&data.text)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                AppError::Conflict("Отзыв на эту запись уже оставлен".to_string())
+            }
+            e => AppError::Internal(format!("Ошибка создания отзыва: {}", e)),
+        })?;
+
+        Ok(map_review(&row))
+    }
+
+    async fn find_review(&self, id: Uuid) -> Result<Option<Review>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM reviews r LEFT JOIN users u ON u.id = r.customer_id WHERE r.id = $1",
+            REVIEW_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска отзыва: {}", e)))?;
+
+        Ok(row.as_ref().map(map_review))
+    }
+
+    async fn list_reviews(
+        &self,
+        company_id: Uuid,
+        staff_id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Review>, Option<f64>, i64), AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM reviews r
+            LEFT JOIN users u ON u.id = r.customer_id
+            WHERE r.company_id = $1 AND ($2::uuid IS NULL OR r.staff_id = $2) AND NOT r.is_hidden
+            ORDER BY r.created_at DESC, r.id
+            LIMIT $3 OFFSET $4
+            "#,
+            REVIEW_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(staff_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения отзывов: {}", e)))?;
+
+        let stats = sqlx::query(
+            r#"
+            SELECT round(avg(rating), 2)::float8 AS rating_avg, count(*) AS rating_count
+            FROM reviews
+            WHERE company_id = $1 AND ($2::uuid IS NULL OR staff_id = $2) AND NOT is_hidden
+            "#,
+        )
+        .bind(company_id)
+        .bind(staff_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка расчета рейтинга: {}", e)))?;
+
+        Ok((
+            rows.iter().map(map_review).collect(),
+            stats.get("rating_avg"),
+            stats.get("rating_count"),
+        ))
+    }
+
+    async fn set_reply(&self, id: Uuid, reply: &str) -> Result<Option<Review>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            WITH r AS (
+                UPDATE reviews SET reply = $2, replied_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {}
+            FROM r
+            LEFT JOIN users u ON u.id = r.customer_id
+            "#,
+            REVIEW_COLUMNS
+        ))
+        .bind(id)
+        .bind(reply)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка сохранения ответа на отзыв: {}", e)))?;
+
+        Ok(row.as_ref().map(map_review))
+    }
+
+    async fn set_visibility(
+        &self,
+        id: Uuid,
+        is_hidden: bool,
+        reason: Option<&str>,
+        moderator_id: Uuid,
+    ) -> Result<Option<Review>, AppError> {
+        // Возвращенный отзыв снова виден, причина и модератор сбрасываются
+        let row = sqlx::query(&format!(
+            r#"
+            WITH r AS (
+                UPDATE reviews
+                SET is_hidden = $2,
+                    hidden_reason = CASE WHEN $2 THEN $3 END,
+                    hidden_by = CASE WHEN $2 THEN $4::uuid END,
+                    hidden_at = CASE WHEN $2 THEN NOW() END
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {}
+            FROM r
+            LEFT JOIN users u ON u.id = r.customer_id
+            "#,
+            REVIEW_COLUMNS
+        ))
+        .bind(id)
+        .bind(is_hidden)
+        .bind(reason)
+        .bind(moderator_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка модерации отзыва: {}", e)))?;
+
+        Ok(row.as_ref().map(map_review))
+    }
+}
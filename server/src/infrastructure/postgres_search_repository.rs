@@ -0,0 +1,269 @@
+use crate::domain::entities::{CompanySearchFilter, CompanySearchHit, FacetCount, SearchFacets};
+use crate::domain::errors::AppError;
+use crate::domain::traits::SearchRepository;
+use async_trait::async_trait;
+use sqlx::postgres::{PgArguments, PgRow};
+use sqlx::query::Query;
+use sqlx::{PgPool, Postgres, Row};
+
+/// Найденные компании с ключом сортировки; параметры $1..$12 задает `bind_filter`.
+/// Порядок всегда `sort_key DESC, id`, поэтому расстояние берется со знаком минус,
+/// а рейтинг сворачивается в одно число: сотые доли оценки, затем число отзывов
+const MATCHED_COMPANIES: &str = r#"
+    WITH search AS (
+        SELECT CASE WHEN $1::text IS NULL THEN NULL
+                    ELSE websearch_to_tsquery('russian', $1) || websearch_to_tsquery('english', $1)
+               END AS tsq
+    ),
+    candidates AS (
+        SELECT c.id, c.name, c.description, c.categories, c.rating_avg, c.rating_count,
+               CASE WHEN search.tsq IS NULL THEN 0 ELSE ts_rank(c.search_vector, search.tsq) END::float8 AS rank,
+               geo.distance_km
+        FROM companies c
+        CROSS JOIN search
+        LEFT JOIN LATERAL (
+            SELECT min(geo_distance_km($9, $10, l.latitude, l.longitude)) AS distance_km
+            FROM locations l
+            WHERE l.company_id = c.id AND l.deleted_at IS NULL AND l.latitude IS NOT NULL
+              AND $9::float8 IS NOT NULL AND $10::float8 IS NOT NULL
+        ) geo ON TRUE
+        WHERE c.deleted_at IS NULL
+          AND (search.tsq IS NULL OR c.search_vector @@ search.tsq)
+          AND ($3::text IS NULL OR $3 = ANY(c.categories))
+          AND ($4::float8 IS NULL OR c.rating_avg >= $4)
+          AND ($11::float8 IS NULL OR geo.distance_km <= $11)
+          AND ($2::text IS NULL OR EXISTS (
+              SELECT 1 FROM locations l
+              WHERE l.company_id = c.id AND l.deleted_at IS NULL AND lower(l.city) = lower($2)
+          ))
+          AND (($5::bigint IS NULL AND $6::bigint IS NULL) OR EXISTS (
+              SELECT 1 FROM services s
+              WHERE s.company_id = c.id AND s.deleted_at IS NULL
+                AND ($5::bigint IS NULL OR s.price_amount >= $5)
+                AND ($6::bigint IS NULL OR s.price_amount <= $6)
+          ))
+          -- Открыто сейчас: кто-то из сотрудников работает в этот момент по местному времени
+          -- (дополнительные часы, иначе недельное расписание без выходного или перерыва)
+          AND ($7::timestamptz IS NULL OR EXISTS (
+              SELECT 1
+              FROM staff st
+              CROSS JOIN LATERAL (SELECT $7::timestamptz AT TIME ZONE c.timezone AS local) now_local
+              WHERE st.company_id = c.id AND st.deleted_at IS NULL
+                AND (
+                    EXISTS (
+                        SELECT 1 FROM staff_schedule_exceptions e
+                        WHERE e.staff_id = st.id AND e.exception_date = now_local.local::date AND e.is_available
+                          AND now_local.local::time >= e.start_time AND now_local.local::time < e.end_time
+                    )
+                    OR (
+                        EXISTS (
+                            SELECT 1 FROM staff_schedules ws
+                            WHERE ws.staff_id = st.id AND ws.weekday = extract(isodow FROM now_local.local)
+                              AND now_local.local::time >= ws.start_time AND now_local.local::time < ws.end_time
+                        )
+                        AND NOT EXISTS (
+                            SELECT 1 FROM staff_schedule_exceptions e
+                            WHERE e.staff_id = st.id AND e.exception_date = now_local.local::date
+                              AND NOT e.is_available
+                              AND (e.start_time IS NULL
+                                   OR (now_local.local::time >= e.start_time AND now_local.local::time < e.end_time))
+                        )
+                    )
+                )
+          ))
+          -- Свободное время в день: у сотрудника есть непрерывный промежуток не короче самой
+          -- короткой его услуги. Рабочие часы считаются по филиалам так же, как в расчете слотов:
+          -- недельное расписание без выходного и перерывов плюс дополнительные часы, пересекающиеся
+          -- интервалы сливаются. Время занятий с местами не занято. Буферы, шаг слотов и ресурсы
+          -- не учитываются, точные слоты дает GET /slots
+          AND ($8::date IS NULL OR EXISTS (
+              SELECT 1
+              FROM staff st
+              CROSS JOIN LATERAL (
+                  SELECT tstzrange($8::date::timestamp AT TIME ZONE c.timezone,
+                                   ($8::date + 1)::timestamp AT TIME ZONE c.timezone) AS day
+              ) bounds
+              CROSS JOIN LATERAL (
+                  SELECT min(s.duration_minutes) * interval '1 minute' AS shortest
+                  FROM staff_services ss
+                  JOIN services s ON s.id = ss.service_id AND s.deleted_at IS NULL
+                  WHERE ss.staff_id = st.id
+              ) offered
+              CROSS JOIN LATERAL (
+                  SELECT
+                      EXISTS (
+                          SELECT 1 FROM staff_schedule_exceptions e
+                          WHERE e.staff_id = st.id AND e.exception_date = $8::date
+                            AND NOT e.is_available AND e.start_time IS NULL
+                      ) AS day_off,
+                      coalesce((
+                          SELECT range_agg(tstzrange(($8::date + e.start_time) AT TIME ZONE c.timezone,
+                                                     ($8::date + e.end_time) AT TIME ZONE c.timezone))
+                          FROM staff_schedule_exceptions e
+                          WHERE e.staff_id = st.id AND e.exception_date = $8::date
+                            AND NOT e.is_available AND e.start_time IS NOT NULL
+                      ), '{}'::tstzmultirange) AS breaks,
+                      coalesce((
+                          SELECT range_agg(b.blocked)
+                          FROM bookings b
+                          LEFT JOIN group_sessions gs ON gs.id = b.session_id
+                          WHERE b.staff_id = st.id AND b.blocked && bounds.day
+                            AND b.status IN ('held', 'pending', 'confirmed', 'checked_in')
+                            AND (gs.id IS NULL OR gs.seats_taken >= gs.capacity)
+                      ), '{}'::tstzmultirange) AS busy
+              ) load
+              CROSS JOIN LATERAL (
+                  SELECT (coalesce(range_agg(hours.period) FILTER (WHERE NOT hours.extra AND NOT load.day_off),
+                                   '{}'::tstzmultirange) - load.breaks)
+                         + coalesce(range_agg(hours.period) FILTER (WHERE hours.extra), '{}'::tstzmultirange)
+                         AS working
+                  FROM (
+                      SELECT ws.location_id, FALSE AS extra,
+                             tstzrange(($8::date + ws.start_time) AT TIME ZONE c.timezone,
+                                       ($8::date + ws.end_time) AT TIME ZONE c.timezone) AS period
+                      FROM staff_schedules ws
+                      WHERE ws.staff_id = st.id AND ws.weekday = extract(isodow FROM $8::date)
+                      UNION ALL
+                      SELECT e.location_id, TRUE,
+                             tstzrange(($8::date + e.start_time) AT TIME ZONE c.timezone,
+                                       ($8::date + e.end_time) AT TIME ZONE c.timezone)
+                      FROM staff_schedule_exceptions e
+                      WHERE e.staff_id = st.id AND e.exception_date = $8::date AND e.is_available
+                  ) hours
+                  GROUP BY hours.location_id
+              ) location_hours
+              CROSS JOIN LATERAL unnest(location_hours.working - load.busy) AS free(period)
+              WHERE st.company_id = c.id AND st.deleted_at IS NULL
+                AND upper(free.period) - lower(free.period) >= offered.shortest
+          ))
+    ),
+    matched AS (
+        SELECT candidates.*,
+               CASE $12::text
+                   WHEN 'relevance' THEN rank
+                   WHEN 'distance' THEN -coalesce(distance_km, 'Infinity'::float8)
+                   ELSE round(coalesce(rating_avg, 0) * 100) * 10000000 + least(rating_count, 9999999)
+               END::float8 AS sort_key
+        FROM candidates
+    )
+"#;
+
+pub struct PostgreSQLSearchRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLSearchRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn bind_filter<'q>(
+    query: Query<'q, Postgres, PgArguments>,
+    filter: &'q CompanySearchFilter,
+) -> Query<'q, Postgres, PgArguments> {
+    query
+        .bind(&filter.text)
+        .bind(&filter.city)
+        .bind(&filter.category)
+        .bind(filter.min_rating)
+        .bind(filter.price_min)
+        .bind(filter.price_max)
+        .bind(filter.open_at)
+        .bind(filter.available_on)
+        .bind(filter.origin.map(|(latitude, _)| latitude))
+        .bind(filter.origin.map(|(_, longitude)| longitude))
+        .bind(filter.radius_km)
+        .bind(filter.sort.as_str())
+}
+
+fn map_hit(row: &PgRow) -> CompanySearchHit {
+    CompanySearchHit {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        categories: row.get("categories"),
+        rating_avg: row.get("rating_avg"),
+        rating_count: row.get("rating_count"),
+        cities: row.get("cities"),
+        price_from: row.get("price_from"),
+        distance_km: row.get("distance_km"),
+        sort_key: row.get("sort_key"),
+    }
+}
+
+#[async_trait]
+impl SearchRepository for PostgreSQLSearchRepository {
+    async fn search_companies(&self, filter: &CompanySearchFilter) -> Result<Vec<CompanySearchHit>, AppError> {
+        let sql = format!(
+            r#"
+            {}
+            SELECT m.id, m.name, m.description, m.categories, m.rating_avg, m.rating_count, m.distance_km,
+                   m.sort_key,
+                   ARRAY(
+                       SELECT DISTINCT l.city FROM locations l
+                       WHERE l.company_id = m.id AND l.deleted_at IS NULL AND l.city IS NOT NULL
+                       ORDER BY l.city
+                   ) AS cities,
+                   (SELECT min(s.price_amount) FROM services s
+                    WHERE s.company_id = m.id AND s.deleted_at IS NULL) AS price_from
+            FROM matched m
+            WHERE $13::float8 IS NULL OR m.sort_key < $13 OR (m.sort_key = $13 AND m.id > $14)
+            ORDER BY m.sort_key DESC, m.id
+            LIMIT $15
+            "#,
+            MATCHED_COMPANIES
+        );
+        let rows = bind_filter(sqlx::query(&sql), filter)
+            .bind(filter.after.map(|(key, _)| key))
+            .bind(filter.after.map(|(_, id)| id))
+            .bind(filter.limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска компаний: {}", e)))?;
+
+        Ok(rows.iter().map(map_hit).collect())
+    }
+
+    async fn search_facets(&self, filter: &CompanySearchFilter) -> Result<(i64, SearchFacets), AppError> {
+        // Одним запросом: строка total и по строке на каждое значение фасета
+        let sql = format!(
+            r#"
+            {}
+            SELECT 'total' AS facet, NULL::text AS value, count(*) AS count FROM matched
+            UNION ALL
+            SELECT 'category', category, count(*)
+            FROM matched, unnest(matched.categories) AS category
+            GROUP BY category
+            UNION ALL
+            SELECT 'city', l.city, count(DISTINCT m.id)
+            FROM matched m
+            JOIN locations l ON l.company_id = m.id AND l.deleted_at IS NULL AND l.city IS NOT NULL
+            GROUP BY l.city
+            "#,
+            MATCHED_COMPANIES
+        );
+        let rows = bind_filter(sqlx::query(&sql), filter)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка подсчета фасетов: {}", e)))?;
+
+        let mut total = 0;
+        let mut facets = SearchFacets::default();
+        for row in &rows {
+            let facet: String = row.get("facet");
+            let count: i64 = row.get("count");
+            let value: Option<String> = row.get("value");
+            match (facet.as_str(), value) {
+                ("total", _) => total = count,
+                ("category", Some(value)) => facets.categories.push(FacetCount { value, count }),
+                ("city", Some(value)) => facets.cities.push(FacetCount { value, count }),
+                _ => {}
+            }
+        }
+        for values in [&mut facets.categories, &mut facets.cities] {
+            values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        }
+        Ok((total, facets))
+    }
+}
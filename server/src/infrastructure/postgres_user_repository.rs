@@ -18,7 +18,7 @@ impl PostgreSQLUserRepository {
     pub async fn find_by_username(&self, username: &str) -> Result<Option<User>, String> {
         let result = sqlx::query(
             r#"
-            SELECT id, username, first_name, last_name, email, user_type_id, 
+            SELECT id, username, first_name, last_name, email, locale, user_type_id, 
                    created_at, updated_at, deleted_at
             FROM users 
             WHERE username = $1 AND deleted_at IS NULL
@@ -36,6 +36,7 @@ impl PostgreSQLUserRepository {
                 first_name: row.get("first_name"),
                 last_name: row.get("last_name"),
                 email: row.get("email"),
+                locale: row.get("locale"),
                 user_type_id: row.get("user_type_id"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -80,7 +81,7 @@ impl PostgreSQLUserRepository {
             r#"
             INSERT INTO users (username, password_hash, first_name, last_name, email, user_type_id)
             VALUES ($1, $2, $3, $4, $5, COALESCE($6, 1))
-            RETURNING id, username, first_name, last_name, email, user_type_id, 
+            RETURNING id, username, first_name, last_name, email, locale, user_type_id, 
                       created_at, updated_at, deleted_at
             "#
         )
@@ -100,6 +101,7 @@ impl PostgreSQLUserRepository {
             first_name: result.get("first_name"),
             last_name: result.get("last_name"),
             email: result.get("email"),
+            locale: result.get("locale"),
             user_type_id: result.get("user_type_id"),
             created_at: result.get("created_at"),
             updated_at: result.get("updated_at"),
@@ -114,6 +116,7 @@ impl PostgreSQLUserRepository {
         first_name: Option<&str>,
         last_name: Option<&str>,
         email: Option<&str>,
+        locale: Option<&str>,
     ) -> Result<Option<User>, String> {
         let result = sqlx::query(
             r#"
@@ -121,9 +124,10 @@ impl PostgreSQLUserRepository {
             SET first_name = COALESCE($2, first_name),
                 last_name = COALESCE($3, last_name),
                 email = COALESCE($4, email),
+                locale = COALESCE($5, locale),
                 updated_at = NOW()
             WHERE id = $1 AND deleted_at IS NULL
-            RETURNING id, username, first_name, last_name, email, user_type_id, 
+            RETURNING id, username, first_name, last_name, email, locale, user_type_id, 
                       created_at, updated_at, deleted_at
             "#
         )
@@ -131,6 +135,7 @@ impl PostgreSQLUserRepository {
         .bind(first_name)
         .bind(last_name)
         .bind(email)
+        .bind(locale)
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| format!("Ошибка обновления пользователя: {}", e))?;
@@ -142,6 +147,7 @@ impl PostgreSQLUserRepository {
                 first_name: row.get("first_name"),
                 last_name: row.get("last_name"),
                 email: row.get("email"),
+                locale: row.get("locale"),
                 user_type_id: row.get("user_type_id"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -159,7 +165,7 @@ impl UserRepository for PostgreSQLUserRepository {
             r#"
             INSERT INTO users (username, password_hash, email, user_type_id)
             VALUES ($1, '', $2, 1)
-            RETURNING id, username, first_name, last_name, email, user_type_id, 
+            RETURNING id, username, first_name, last_name, email, locale, user_type_id, 
                       created_at, updated_at, deleted_at
             "#
         )
@@ -175,6 +181,7 @@ impl UserRepository for PostgreSQLUserRepository {
             first_name: result.get("first_name"),
             last_name: result.get("last_name"),
             email: result.get("email"),
+            locale: result.get("locale"),
             user_type_id: result.get("user_type_id"),
             created_at: result.get("created_at"),
             updated_at: result.get("updated_at"),
@@ -185,7 +192,7 @@ impl UserRepository for PostgreSQLUserRepository {
     async fn get_user_by_id(&self, id: Uuid) -> Result<Option<User>, String> {
         let result = sqlx::query(
             r#"
-            SELECT id, username, first_name, last_name, email, user_type_id, 
+            SELECT id, username, first_name, last_name, email, locale, user_type_id, 
                    created_at, updated_at, deleted_at
             FROM users 
             WHERE id = $1 AND deleted_at IS NULL
@@ -203,6 +210,7 @@ impl UserRepository for PostgreSQLUserRepository {
                 first_name: row.get("first_name"),
                 last_name: row.get("last_name"),
                 email: row.get("email"),
+                locale: row.get("locale"),
                 user_type_id: row.get("user_type_id"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -215,7 +223,7 @@ impl UserRepository for PostgreSQLUserRepository {
     async fn get_all_users(&self) -> Result<Vec<User>, String> {
         let rows = sqlx::query(
             r#"
-            SELECT id, username, first_name, last_name, email, user_type_id, 
+            SELECT id, username, first_name, last_name, email, locale, user_type_id, 
                    created_at, updated_at, deleted_at
             FROM users 
             WHERE deleted_at IS NULL
@@ -233,6 +241,7 @@ impl UserRepository for PostgreSQLUserRepository {
                 first_name: row.get("first_name"),
                 last_name: row.get("last_name"),
                 email: row.get("email"),
+                locale: row.get("locale"),
                 user_type_id: row.get("user_type_id"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
@@ -292,7 +301,8 @@ impl UserAuthRepository for PostgreSQLUserRepository {
         first_name: Option<&str>,
         last_name: Option<&str>,
         email: Option<&str>,
+        locale: Option<&str>,
     ) -> Result<Option<User>, String> {
-        self.update_user_fields(id, first_name, last_name, email).await
+        self.update_user_fields(id, first_name, last_name, email, locale).await
     }
 }
@@ -0,0 +1,306 @@
+use crate::domain::entities::{
+    Booking, BookingStatus, NewBooking, NewWaitlistEntry, NotificationKind, WaitlistEntry, WaitlistStatus,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::WaitlistRepository;
+use crate::infrastructure::postgres_booking_repository::{
+    begin, commit, convert_hold_in_tx, map_booking_error, upsert_group_session,
+};
+use crate::infrastructure::postgres_outbox_repository::enqueue_booking_notification;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::json;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// SQLSTATE нарушения уникальности
+const UNIQUE_VIOLATION: &str = "23505";
+
+const WAITLIST_COLUMNS: &str = "id, customer_id, company_id, service_id, staff_id, location_id, desired_date, \
+     status, hold_id, offered_staff_id, offered_starts_at, offer_expires_at, created_at";
+
+pub struct PostgreSQLWaitlistRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLWaitlistRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_entry(row: &PgRow) -> WaitlistEntry {
+    let status: String = row.get("status");
+    WaitlistEntry {
+        id: row.get("id"),
+        customer_id: row.get("customer_id"),
+        company_id: row.get("company_id"),
+        service_id: row.get("service_id"),
+        staff_id: row.get("staff_id"),
+        location_id: row.get("location_id"),
+        desired_date: row.get("desired_date"),
+        status: WaitlistStatus::parse(&status).unwrap_or(WaitlistStatus::Cancelled),
+        hold_id: row.get("hold_id"),
+        offered_staff_id: row.get("offered_staff_id"),
+        offered_starts_at: row.get("offered_starts_at"),
+        offer_expires_at: row.get("offer_expires_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[async_trait]
+impl WaitlistRepository for PostgreSQLWaitlistRepository {
+    async fn create_entry(&self, entry: &NewWaitlistEntry) -> Result<WaitlistEntry, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO waitlist_entries (customer_id, company_id, service_id, staff_id, location_id, desired_date)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            WAITLIST_COLUMNS
+        ))
+        .bind(entry.customer_id)
+        .bind(entry.company_id)
+        .bind(entry.service_id)
+        .bind(entry.staff_id)
+        .bind(entry.location_id)
+        .bind(entry.desired_date)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_error) if db_error.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                AppError::Conflict("Вы уже в листе ожидания на этот день".to_string())
+            }
+            _ => AppError::Internal(format!("Ошибка записи в лист ожидания: {}", e)),
+        })?;
+
+        Ok(map_entry(&row))
+    }
+
+    async fn find_entry(&self, id: Uuid) -> Result<Option<WaitlistEntry>, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM waitlist_entries WHERE id = $1", WAITLIST_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка поиска в листе ожидания: {}", e)))?;
+
+        Ok(row.as_ref().map(map_entry))
+    }
+
+    async fn list_customer_entries(&self, customer_id: Uuid) -> Result<Vec<WaitlistEntry>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM waitlist_entries WHERE customer_id = $1 ORDER BY desired_date, created_at",
+            WAITLIST_COLUMNS
+        ))
+        .bind(customer_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения листа ожидания: {}", e)))?;
+
+        Ok(rows.iter().map(map_entry).collect())
+    }
+
+    async fn list_waiting(
+        &self,
+        company_id: Uuid,
+        date: NaiveDate,
+        staff_id: Uuid,
+    ) -> Result<Vec<WaitlistEntry>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM waitlist_entries
+            WHERE company_id = $1 AND desired_date = $2 AND status = 'waiting'
+              AND (staff_id IS NULL OR staff_id = $3)
+            ORDER BY created_at, id
+            "#,
+            WAITLIST_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(date)
+        .bind(staff_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения очереди: {}", e)))?;
+
+        Ok(rows.iter().map(map_entry).collect())
+    }
+
+    async fn make_offer(
+        &self,
+        entry_id: Uuid,
+        hold: &NewBooking,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<WaitlistEntry>, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        // Бронь держит слот за клиентом так же, как при обычном оформлении записи
+        let session_id = upsert_group_session(&mut tx, hold).await?;
+        let hold_row = sqlx::query(
+            r#"
+            INSERT INTO bookings (customer_id, company_id, service_id, staff_id, location_id,
+                                  during, blocked, status, hold_expires_at, session_id, resource_ids)
+            VALUES ($1, $2, $3, $4, $5, tstzrange($6, $7, '[)'), tstzrange($8, $9, '[)'), 'held', $10, $11, $12)
+            RETURNING id
+            "#,
+        )
+        .bind(hold.customer_id)
+        .bind(hold.company_id)
+        .bind(hold.service_id)
+        .bind(hold.staff_id)
+        .bind(hold.location_id)
+        .bind(hold.starts_at)
+        .bind(hold.ends_at)
+        .bind(hold.blocked_from)
+        .bind(hold.blocked_to)
+        .bind(expires_at)
+        .bind(session_id)
+        .bind(&hold.resource_ids)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| map_booking_error(e, "Ошибка создания брони"))?;
+        let hold_id: Uuid = hold_row.get("id");
+
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE waitlist_entries
+            SET status = 'offered', hold_id = $2, offered_staff_id = $3, offered_starts_at = $4, offer_expires_at = $5
+            WHERE id = $1 AND status = 'waiting'
+            RETURNING {}
+            "#,
+            WAITLIST_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(hold_id)
+        .bind(hold.staff_id)
+        .bind(hold.starts_at)
+        .bind(expires_at)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка предложения слота: {}", e)))?;
+
+        // Запись успели закрыть или предложить другой слот: бронь откатывается вместе с транзакцией
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let entry = map_entry(&row);
+        enqueue_booking_notification(
+            &mut tx,
+            hold_id,
+            NotificationKind::WaitlistOffer,
+            json!({ "waitlist_entry_id": entry.id, "hold_id": hold_id, "expires_at": expires_at }),
+        )
+        .await?;
+
+        commit(tx).await?;
+        Ok(Some(entry))
+    }
+
+    async fn accept_offer(
+        &self,
+        entry_id: Uuid,
+        hold_id: Uuid,
+        customer_id: Uuid,
+        status: BookingStatus,
+        now: DateTime<Utc>,
+    ) -> Result<Booking, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        // Строка очереди блокируется до конца транзакции: отказ или истечение предложения
+        // не проскочат между закрытием записи и подтверждением брони
+        let result = sqlx::query(
+            "UPDATE waitlist_entries SET status = 'booked' WHERE id = $1 AND hold_id = $2 AND status = 'offered'",
+        )
+        .bind(entry_id)
+        .bind(hold_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления листа ожидания: {}", e)))?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("Нет действующего предложения".to_string()));
+        }
+        let booking = convert_hold_in_tx(&mut tx, hold_id, customer_id, status, now).await?;
+
+        commit(tx).await?;
+        Ok(booking)
+    }
+
+    async fn close_entry(
+        &self,
+        entry_id: Uuid,
+        customer_id: Uuid,
+        status: WaitlistStatus,
+    ) -> Result<Option<WaitlistEntry>, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE waitlist_entries SET status = $3
+            WHERE id = $1 AND customer_id = $2 AND status IN ('waiting', 'offered')
+            RETURNING {}
+            "#,
+            WAITLIST_COLUMNS
+        ))
+        .bind(entry_id)
+        .bind(customer_id)
+        .bind(status.as_str())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления листа ожидания: {}", e)))?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let entry = map_entry(&row);
+
+        if let Some(hold_id) = entry.hold_id {
+            sqlx::query("DELETE FROM bookings WHERE id = $1 AND status = 'held'")
+                .bind(hold_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Internal(format!("Ошибка снятия брони: {}", e)))?;
+        }
+
+        commit(tx).await?;
+        Ok(Some(entry))
+    }
+
+    async fn expire_offers(&self, now: DateTime<Utc>) -> Result<Vec<WaitlistEntry>, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        // Бронь уже подтверждена через /v1/holds - клиент записался
+        sqlx::query(
+            r#"
+            UPDATE waitlist_entries w SET status = 'booked'
+            FROM bookings b
+            WHERE w.status = 'offered' AND w.hold_id = b.id AND b.status <> 'held'
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка обновления листа ожидания: {}", e)))?;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE waitlist_entries SET status = 'expired'
+            WHERE status = 'offered' AND offer_expires_at <= $1
+            RETURNING {}
+            "#,
+            WAITLIST_COLUMNS
+        ))
+        .bind(now)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка просрочки предложений: {}", e)))?;
+        let expired: Vec<WaitlistEntry> = rows.iter().map(map_entry).collect();
+
+        let hold_ids: Vec<Uuid> = expired.iter().filter_map(|entry| entry.hold_id).collect();
+        sqlx::query("DELETE FROM bookings WHERE id = ANY($1) AND status = 'held'")
+            .bind(&hold_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка снятия броней: {}", e)))?;
+
+        commit(tx).await?;
+        Ok(expired)
+    }
+}
@@ -0,0 +1,339 @@
+use crate::domain::entities::{
+    WebhookDeliveriesQuery, WebhookDelivery, WebhookDeliveryStatus, WebhookDeliveryTarget, WebhookEvent,
+    WebhookSubscription,
+};
+use crate::domain::errors::AppError;
+use crate::domain::traits::WebhookRepository;
+use crate::infrastructure::job_queue::Job;
+use crate::infrastructure::postgres_booking_repository::{begin, commit};
+use crate::infrastructure::webhook_delivery::WebhookDeliveryJob;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+const SUBSCRIPTION_COLUMNS: &str = "id, company_id, url, events, is_active, created_at, updated_at";
+
+const DELIVERY_COLUMNS: &str = "id, subscription_id, event_type, payload, status, attempts, response_status, \
+     last_error, created_at, delivered_at";
+
+/// Больше доставок за раз в журнале не показываем
+const MAX_LIST_LIMIT: i64 = 200;
+
+pub struct PostgreSQLWebhookRepository {
+    pool: PgPool,
+}
+
+impl PostgreSQLWebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn event_names(events: &[WebhookEvent]) -> Vec<String> {
+    events.iter().map(|event| event.as_str().to_string()).collect()
+}
+
+fn map_subscription(row: &PgRow) -> WebhookSubscription {
+    let events: Vec<String> = row.get("events");
+    WebhookSubscription {
+        id: row.get("id"),
+        company_id: row.get("company_id"),
+        url: row.get("url"),
+        events: events.iter().filter_map(|event| WebhookEvent::parse(event)).collect(),
+        is_active: row.get("is_active"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn map_delivery(row: &PgRow) -> WebhookDelivery {
+    let status: String = row.get("status");
+    WebhookDelivery {
+        id: row.get("id"),
+        subscription_id: row.get("subscription_id"),
+        event_type: row.get("event_type"),
+        payload: row.get("payload"),
+        status: WebhookDeliveryStatus::parse(&status).unwrap_or(WebhookDeliveryStatus::Failed),
+        attempts: row.get("attempts"),
+        response_status: row.get("response_status"),
+        last_error: row.get("last_error"),
+        created_at: row.get("created_at"),
+        delivered_at: row.get("delivered_at"),
+    }
+}
+
+/// Поставить событие о записи в журнал доставок всех активных подписок компании на это
+/// событие и задачи отправки в очередь - в транзакции изменения записи. Все подписки получают
+/// одно событие с общим `id`, по которому получатель может отсеять повторы
+pub(crate) async fn enqueue_booking_webhooks(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: Uuid,
+    event: WebhookEvent,
+    extra: Value,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        WITH event AS (
+            SELECT b.company_id,
+                   jsonb_build_object(
+                       'id', uuid_generate_v4(),
+                       'type', $2::text,
+                       'created_at', NOW(),
+                       'data', jsonb_build_object(
+                           'booking_id', b.id,
+                           'company_id', b.company_id,
+                           'status', b.status,
+                           'service_id', b.service_id,
+                           'service', s.name,
+                           'staff_id', b.staff_id,
+                           'staff', st.display_name,
+                           'location_id', b.location_id,
+                           'starts_at', lower(b.during),
+                           'ends_at', upper(b.during),
+                           'customer', jsonb_build_object(
+                               'id', u.id,
+                               'name', COALESCE(NULLIF(TRIM(CONCAT_WS(' ', u.first_name, u.last_name)), ''), u.username),
+                               'email', u.email
+                           )
+                       ) || $3::jsonb
+                   ) AS payload
+            FROM bookings b
+            JOIN services s ON s.id = b.service_id
+            JOIN staff st ON st.id = b.staff_id
+            JOIN users u ON u.id = b.customer_id
+            WHERE b.id = $1
+        ), deliveries AS (
+            INSERT INTO webhook_deliveries (subscription_id, event_type, payload)
+            SELECT w.id, $2, e.payload
+            FROM event e
+            JOIN webhook_subscriptions w ON w.company_id = e.company_id
+            WHERE w.is_active AND $2 = ANY(w.events)
+            RETURNING id
+        )
+        INSERT INTO jobs (kind, payload, max_attempts)
+        SELECT $4, jsonb_build_object('delivery_id', id), $5 FROM deliveries
+        "#,
+    )
+    .bind(booking_id)
+    .bind(event.as_str())
+    .bind(extra)
+    .bind(WebhookDeliveryJob::KIND)
+    .bind(WebhookDeliveryJob::MAX_ATTEMPTS)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Ошибка постановки вебхука в очередь: {}", e)))?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl WebhookRepository for PostgreSQLWebhookRepository {
+    async fn create_subscription(
+        &self,
+        company_id: Uuid,
+        url: &str,
+        events: &[WebhookEvent],
+        secret: &str,
+    ) -> Result<WebhookSubscription, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO webhook_subscriptions (company_id, url, events, secret)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(company_id)
+        .bind(url)
+        .bind(event_names(events))
+        .bind(secret)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка создания вебхука: {}", e)))?;
+
+        Ok(map_subscription(&row))
+    }
+
+    async fn list_subscriptions(&self, company_id: Uuid) -> Result<Vec<WebhookSubscription>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE company_id = $1 ORDER BY created_at",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(company_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения вебхуков: {}", e)))?;
+
+        Ok(rows.iter().map(map_subscription).collect())
+    }
+
+    async fn find_subscription(&self, id: Uuid) -> Result<Option<WebhookSubscription>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE id = $1",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска вебхука: {}", e)))?;
+
+        Ok(row.as_ref().map(map_subscription))
+    }
+
+    async fn update_subscription(
+        &self,
+        id: Uuid,
+        url: Option<&str>,
+        events: Option<&[WebhookEvent]>,
+        is_active: Option<bool>,
+    ) -> Result<Option<WebhookSubscription>, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE webhook_subscriptions
+            SET url = COALESCE($2, url), events = COALESCE($3, events), is_active = COALESCE($4, is_active)
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(url)
+        .bind(events.map(event_names))
+        .bind(is_active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка изменения вебхука: {}", e)))?;
+
+        Ok(row.as_ref().map(map_subscription))
+    }
+
+    async fn delete_subscription(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка удаления вебхука: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_deliveries(
+        &self,
+        subscription_id: Uuid,
+        query: &WebhookDeliveriesQuery,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        let limit = query.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM webhook_deliveries
+            WHERE subscription_id = $1 AND ($2::varchar IS NULL OR status = $2)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(query.status.map(|status| status.as_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка получения журнала доставок: {}", e)))?;
+
+        Ok(rows.iter().map(map_delivery).collect())
+    }
+
+    async fn enqueue_test_delivery(&self, subscription_id: Uuid) -> Result<WebhookDelivery, AppError> {
+        let mut tx = begin(&self.pool).await?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO webhook_deliveries (subscription_id, event_type, payload)
+            SELECT w.id, $2, jsonb_build_object(
+                'id', uuid_generate_v4(),
+                'type', $2::text,
+                'created_at', NOW(),
+                'data', $3::jsonb || jsonb_build_object('company_id', w.company_id, 'webhook_id', w.id)
+            )
+            FROM webhook_subscriptions w WHERE w.id = $1
+            RETURNING {}
+            "#,
+            DELIVERY_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(WebhookEvent::Test.as_str())
+        .bind(json!({ "message": "Проверка вебхука" }))
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка постановки вебхука в очередь: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Вебхук не найден".to_string()))?;
+        let delivery = map_delivery(&row);
+
+        sqlx::query("INSERT INTO jobs (kind, payload, max_attempts) VALUES ($1, $2, $3)")
+            .bind(WebhookDeliveryJob::KIND)
+            .bind(json!({ "delivery_id": delivery.id }))
+            .bind(WebhookDeliveryJob::MAX_ATTEMPTS)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка постановки задачи в очередь: {}", e)))?;
+
+        commit(tx).await?;
+        Ok(delivery)
+    }
+
+    async fn find_pending_delivery(&self, id: Uuid) -> Result<Option<WebhookDeliveryTarget>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT d.id, d.subscription_id, d.event_type, d.payload, d.status, d.attempts, d.response_status,
+                   d.last_error, d.created_at, d.delivered_at, w.url, w.secret
+            FROM webhook_deliveries d
+            JOIN webhook_subscriptions w ON w.id = d.subscription_id
+            WHERE d.id = $1 AND d.status <> 'delivered'
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка поиска доставки вебхука: {}", e)))?;
+
+        Ok(row.map(|row| WebhookDeliveryTarget {
+            delivery: map_delivery(&row),
+            url: row.get("url"),
+            secret: row.get("secret"),
+        }))
+    }
+
+    async fn record_attempt(
+        &self,
+        id: Uuid,
+        response_status: Option<i32>,
+        error: Option<&str>,
+        max_attempts: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET attempts = attempts + 1,
+                response_status = $2,
+                last_error = $3,
+                status = CASE
+                    WHEN $3::text IS NULL THEN 'delivered'
+                    WHEN attempts + 1 >= $4 THEN 'failed'
+                    ELSE 'pending'
+                END,
+                delivered_at = CASE WHEN $3::text IS NULL THEN NOW() END
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_status)
+        .bind(error)
+        .bind(max_attempts)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(format!("Ошибка записи попытки доставки: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::domain::entities::QueueEvent;
+use crate::domain::errors::AppError;
+use crate::domain::traits::QueueEventBus;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Канал NOTIFY, в который пишет триггер queue_tickets_notify_change
+pub const QUEUE_EVENTS_CHANNEL: &str = "queue_events";
+
+/// Сколько событий может отстать медленный подписчик, прежде чем пропустит часть из них
+const SUBSCRIBER_BUFFER: usize = 1024;
+
+/// Пауза перед повторным чтением после ошибки соединения
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Слушает канал queue_events и раздает события подписчикам этого экземпляра сервера,
+/// так же как `PostgresSlotEventBus` для слотов
+pub struct PostgresQueueEventBus {
+    sender: broadcast::Sender<QueueEvent>,
+}
+
+impl PostgresQueueEventBus {
+    /// Подписаться на канал и запустить чтение уведомлений в фоне
+    pub async fn listen(pool: &PgPool) -> Result<Arc<Self>, AppError> {
+        let mut listener = PgListener::connect_with(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка подключения к событиям очереди: {}", e)))?;
+        listener
+            .listen(QUEUE_EVENTS_CHANNEL)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка подписки на события очереди: {}", e)))?;
+
+        let (sender, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        let bus = Arc::new(Self { sender: sender.clone() });
+        // Как и у событий слотов, задача на рантайме tokio, а не в LocalSet actix
+        tokio::spawn(async move {
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => match serde_json::from_str::<QueueEvent>(notification.payload()) {
+                        Ok(event) => {
+                            let _ = sender.send(event);
+                        }
+                        Err(e) => eprintln!("Некорректное событие очереди: {}", e),
+                    },
+                    Err(e) => {
+                        eprintln!("Ошибка получения событий очереди: {}", e);
+                        actix_web::rt::time::sleep(RETRY_DELAY).await;
+                    }
+                }
+            }
+        });
+        Ok(bus)
+    }
+}
+
+impl QueueEventBus for PostgresQueueEventBus {
+    fn subscribe(&self, location_id: Uuid) -> BoxStream<'static, QueueEvent> {
+        let receiver = self.sender.subscribe();
+        stream::unfold(receiver, move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.location_id == location_id => return Some((event, receiver)),
+                    Ok(_) => continue,
+                    // Событие лишь повод пересчитать место, поэтому пропуск части из них не страшен
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+}
@@ -0,0 +1,82 @@
+use crate::domain::entities::SlotEvent;
+use crate::domain::errors::AppError;
+use crate::domain::traits::SlotEventBus;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Канал NOTIFY, в который пишет триггер bookings_notify_slot_change
+pub const SLOT_EVENTS_CHANNEL: &str = "slot_events";
+
+/// Сколько событий может отстать медленный подписчик, прежде чем пропустит часть из них
+const SUBSCRIBER_BUFFER: usize = 1024;
+
+/// Пауза перед повторным чтением после ошибки соединения
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Слушает канал slot_events и раздает события подписчикам этого экземпляра сервера.
+/// NOTIFY приходит после коммита из любой реплики, поэтому поток согласован с базой
+pub struct PostgresSlotEventBus {
+    sender: broadcast::Sender<SlotEvent>,
+}
+
+impl PostgresSlotEventBus {
+    /// Подписаться на канал и запустить чтение уведомлений в фоне.
+    /// Возвращается после LISTEN, так что события следующих транзакций не теряются
+    pub async fn listen(pool: &PgPool) -> Result<Arc<Self>, AppError> {
+        let mut listener = PgListener::connect_with(pool)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка подключения к событиям слотов: {}", e)))?;
+        listener
+            .listen(SLOT_EVENTS_CHANNEL)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка подписки на события слотов: {}", e)))?;
+
+        let (sender, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        let bus = Arc::new(Self { sender: sender.clone() });
+        // Задача на рантайме tokio, а не в LocalSet actix: при остановке рантайма PgListener
+        // освобождает соединение через spawn, и вне контекста tokio это паника
+        tokio::spawn(async move {
+            loop {
+                // После обрыва PgListener сам переподключается и повторяет LISTEN
+                match listener.recv().await {
+                    Ok(notification) => match serde_json::from_str::<SlotEvent>(notification.payload()) {
+                        // Ошибка отправки означает лишь, что сейчас никто не подписан
+                        Ok(event) => {
+                            let _ = sender.send(event);
+                        }
+                        Err(e) => eprintln!("Некорректное событие слота: {}", e),
+                    },
+                    Err(e) => {
+                        eprintln!("Ошибка получения событий слотов: {}", e);
+                        actix_web::rt::time::sleep(RETRY_DELAY).await;
+                    }
+                }
+            }
+        });
+        Ok(bus)
+    }
+}
+
+impl SlotEventBus for PostgresSlotEventBus {
+    fn subscribe(&self, company_id: Uuid) -> BoxStream<'static, SlotEvent> {
+        let receiver = self.sender.subscribe();
+        stream::unfold(receiver, move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.company_id == company_id => return Some((event, receiver)),
+                    Ok(_) => continue,
+                    // Отставший подписчик пропускает вытесненные события; клиент сверяется
+                    // с GET /slots при переподключении
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+}
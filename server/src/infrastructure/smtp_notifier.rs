@@ -0,0 +1,92 @@
+use crate::domain::entities::OutgoingMessage;
+use crate::domain::errors::AppError;
+use crate::domain::traits::Notifier;
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::env;
+
+/// Отправляет уведомления письмами через SMTP. Пользователи без почты пропускаются
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpNotifier {
+    /// Сервер без шифрования и авторизации, например локальный перехватчик писем для разработки
+    pub fn unencrypted(host: &str, port: u16, from: &str) -> Result<Self, AppError> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+            .port(port)
+            .build();
+        Ok(Self {
+            transport,
+            from: parse_mailbox(from)?,
+        })
+    }
+
+    /// Настройки из окружения: SMTP_HOST, SMTP_PORT, SMTP_USERNAME, SMTP_PASSWORD, SMTP_FROM
+    /// и SMTP_TLS (none, starttls или tls). Без SMTP_HOST канал не подключается
+    pub fn from_env() -> Result<Option<Self>, AppError> {
+        let Ok(host) = env::var("SMTP_HOST") else {
+            return Ok(None);
+        };
+        let from = env::var("SMTP_FROM").unwrap_or_else(|_| "LocationX <noreply@localhost>".to_string());
+        let tls = env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string());
+        let smtp_error = |e: lettre::transport::smtp::Error| AppError::Internal(format!("Ошибка настройки SMTP: {}", e));
+
+        let mut builder = match tls.as_str() {
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host).map_err(smtp_error)?,
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host).map_err(smtp_error)?,
+            other => return Err(AppError::Internal(format!("Неизвестный режим SMTP_TLS: {}", other))),
+        };
+        if let Some(port) = env::var("SMTP_PORT").ok().and_then(|port| port.parse().ok()) {
+            builder = builder.port(port);
+        }
+        if let (Ok(username), Ok(password)) = (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Some(Self {
+            transport: builder.build(),
+            from: parse_mailbox(&from)?,
+        }))
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, AppError> {
+    address
+        .parse()
+        .map_err(|e| AppError::Internal(format!("Некорректный адрес отправителя {}: {}", address, e)))
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    fn channel(&self) -> &'static str {
+        "email"
+    }
+
+    async fn send(&self, message: &OutgoingMessage) -> Result<(), AppError> {
+        let Some(email) = &message.recipient.email else {
+            return Ok(());
+        };
+        let to = match email.parse::<lettre::Address>() {
+            Ok(address) => Mailbox::new(Some(message.recipient.name.clone()), address),
+            // На некорректный адрес письмо не уйдет и при повторе
+            Err(_) => return Ok(()),
+        };
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject.clone())
+            .body(message.body.clone())
+            .map_err(|e| AppError::Internal(format!("Ошибка формирования письма: {}", e)))?;
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка отправки письма: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+//! Депозиты через Stripe. Платеж - PaymentIntent, клиент подтверждает его через Stripe.js
+//! по `client_secret`. Уведомления подписаны заголовком
+//! `Stripe-Signature: t=<секунды Unix>,v1=<hex>`, где подпись - HMAC-SHA256 строки
+//! `{t}.{тело запроса}` секретом вебхука; уведомления старше пяти минут отклоняются.
+
+use crate::application::hmac_signature;
+use crate::domain::entities::{PaymentOutcome, PaymentWebhookEvent, ProviderPayment, ProviderPaymentRequest};
+use crate::domain::errors::AppError;
+use crate::domain::traits::PaymentProvider;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+const DEFAULT_API_URL: &str = "https://api.stripe.com";
+
+pub const STRIPE_SIGNATURE_HEADER: &str = "stripe-signature";
+
+/// Насколько метка времени уведомления может расходиться с часами сервера
+const SIGNATURE_TOLERANCE_SECONDS: i64 = 300;
+
+#[derive(Deserialize)]
+struct StripeEvent {
+    id: String,
+    #[serde(rename = "type")]
+    event_type: String,
+    data: StripeEventData,
+}
+
+#[derive(Deserialize)]
+struct StripeEventData {
+    object: StripeObject,
+}
+
+#[derive(Deserialize)]
+struct StripeObject {
+    id: String,
+}
+
+/// Проверить заголовок `Stripe-Signature`: хотя бы одна подпись v1 должна совпасть
+/// (при смене секрета Stripe подписывает обоими), а метка времени - быть свежей
+fn verify_signature(secret: &str, header: &str, body: &str, now: DateTime<Utc>) -> bool {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = Some(value),
+            Some(("v1", value)) => signatures.extend(hex::decode(value).ok()),
+            _ => {}
+        }
+    }
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+    let fresh = timestamp
+        .parse::<i64>()
+        .is_ok_and(|seconds| (now.timestamp() - seconds).abs() <= SIGNATURE_TOLERANCE_SECONDS);
+    let signed = [timestamp.as_bytes(), b".", body.as_bytes()];
+    fresh
+        && signatures
+            .iter()
+            .any(|signature| hmac_signature::verify(secret.as_bytes(), &signed, signature))
+}
+
+pub struct StripePaymentProvider {
+    api_url: String,
+    secret_key: String,
+    webhook_secret: String,
+    client: reqwest::Client,
+}
+
+impl StripePaymentProvider {
+    pub fn new(secret_key: &str, webhook_secret: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .unwrap_or_default();
+        Self {
+            api_url: DEFAULT_API_URL.to_string(),
+            secret_key: secret_key.to_string(),
+            webhook_secret: webhook_secret.to_string(),
+            client,
+        }
+    }
+
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Настройки из окружения; без `STRIPE_SECRET_KEY` Stripe не используется
+    pub fn from_env() -> Result<Option<Self>, AppError> {
+        let Ok(secret_key) = env::var("STRIPE_SECRET_KEY") else {
+            return Ok(None);
+        };
+        let webhook_secret = env::var("STRIPE_WEBHOOK_SECRET")
+            .map_err(|_| AppError::Internal("Для Stripe нужна переменная STRIPE_WEBHOOK_SECRET".to_string()))?;
+        let mut provider = Self::new(&secret_key, &webhook_secret);
+        if let Ok(api_url) = env::var("STRIPE_API_URL") {
+            provider = provider.with_api_url(&api_url);
+        }
+        Ok(Some(provider))
+    }
+
+    /// POST в API Stripe с телом application/x-www-form-urlencoded
+    async fn post_form(&self, path: &str, idempotency_key: &str, form: &[(&str, String)]) -> Result<Value, AppError> {
+        let response = self
+            .client
+            .post(format!("{}{}", self.api_url, path))
+            .bearer_auth(&self.secret_key)
+            .header("Idempotency-Key", idempotency_key)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка запроса к Stripe: {}", e)))?;
+
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Некорректный ответ Stripe: {}", e)))?;
+        if !status.is_success() {
+            let message = body["error"]["message"].as_str().unwrap_or_default();
+            return Err(AppError::Internal(format!("Stripe ответил статусом {}: {}", status, message)));
+        }
+        Ok(body)
+    }
+}
+
+#[async_trait]
+impl PaymentProvider for StripePaymentProvider {
+    fn name(&self) -> &'static str {
+        "stripe"
+    }
+
+    async fn create_payment(&self, request: &ProviderPaymentRequest) -> Result<ProviderPayment, AppError> {
+        let intent = self
+            .post_form(
+                "/v1/payment_intents",
+                &request.idempotency_key,
+                &[
+                    ("amount", request.amount.to_string()),
+                    ("currency", request.currency.to_lowercase()),
+                    ("description", request.description.clone()),
+                    ("automatic_payment_methods[enabled]", "true".to_string()),
+                    ("metadata[payment_id]", request.payment_id.to_string()),
+                    ("metadata[booking_id]", request.booking_id.to_string()),
+                ],
+            )
+            .await?;
+
+        let provider_payment_id = intent["id"]
+            .as_str()
+            .ok_or_else(|| AppError::Internal("В ответе Stripe нет id платежа".to_string()))?;
+        Ok(ProviderPayment {
+            provider_payment_id: provider_payment_id.to_string(),
+            client_secret: intent["client_secret"].as_str().map(str::to_string),
+        })
+    }
+
+    async fn refund(
+        &self,
+        provider_payment_id: &str,
+        amount: i64,
+        _currency: &str,
+        idempotency_key: &str,
+    ) -> Result<(), AppError> {
+        self.post_form(
+            "/v1/refunds",
+            idempotency_key,
+            &[
+                ("payment_intent", provider_payment_id.to_string()),
+                ("amount", amount.to_string()),
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn parse_webhook(
+        &self,
+        headers: &HashMap<String, String>,
+        body: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<PaymentWebhookEvent>, AppError> {
+        let header = headers
+            .get(STRIPE_SIGNATURE_HEADER)
+            .ok_or_else(|| AppError::Validation("Нет подписи уведомления".to_string()))?;
+        if !verify_signature(&self.webhook_secret, header, body, now) {
+            return Err(AppError::Validation("Неверная подпись уведомления".to_string()));
+        }
+
+        let event: StripeEvent = serde_json::from_str(body)
+            .map_err(|e| AppError::Validation(format!("Некорректное уведомление: {}", e)))?;
+        // Отмененный PaymentIntent оплатить уже нельзя - для депозита это неудачная попытка
+        let outcome = match event.event_type.as_str() {
+            "payment_intent.succeeded" => PaymentOutcome::Succeeded,
+            "payment_intent.payment_failed" | "payment_intent.canceled" => PaymentOutcome::Failed,
+            _ => return Ok(None),
+        };
+        Ok(Some(PaymentWebhookEvent {
+            event_id: event.id,
+            provider_payment_id: event.data.object.id,
+            outcome,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const BODY: &str =
+        r#"{"id":"evt_1","type":"payment_intent.succeeded","data":{"object":{"id":"pi_123","object":"payment_intent"}}}"#;
+    const SIGNATURE: &str = "fa31b8197dd63829b3be3d9b1dd4cd9eee618292639403a638eae0f0b1988080";
+
+    fn signed_at() -> DateTime<Utc> {
+        Utc.timestamp_opt(1717315200, 0).unwrap()
+    }
+
+    fn headers(value: String) -> HashMap<String, String> {
+        HashMap::from([(STRIPE_SIGNATURE_HEADER.to_string(), value)])
+    }
+
+    #[test]
+    fn signature_is_hmac_of_timestamp_and_body() {
+        let header = format!("t=1717315200,v1={}", SIGNATURE);
+        assert!(verify_signature("whsec_test", &header, BODY, signed_at()));
+        assert!(verify_signature("whsec_test", &header, BODY, signed_at() + chrono::Duration::seconds(300)));
+        // Одна из подписей может быть старым секретом
+        let rotated = format!("t=1717315200,v1={},v1={}", "00".repeat(32), SIGNATURE);
+        assert!(verify_signature("whsec_test", &rotated, BODY, signed_at()));
+    }
+
+    #[test]
+    fn stale_or_forged_signatures_are_rejected() {
+        let header = format!("t=1717315200,v1={}", SIGNATURE);
+        assert!(!verify_signature("whsec_test", &header, BODY, signed_at() + chrono::Duration::seconds(301)));
+        assert!(!verify_signature("whsec_other", &header, BODY, signed_at()));
+        assert!(!verify_signature("whsec_test", &header, &BODY.replace("pi_123", "pi_999"), signed_at()));
+        assert!(!verify_signature("whsec_test", &format!("v1={}", SIGNATURE), BODY, signed_at()));
+        assert!(!verify_signature("whsec_test", &format!("t=1717315201,v1={}", SIGNATURE), BODY, signed_at()));
+    }
+
+    #[test]
+    fn payment_intent_events_are_parsed() {
+        let provider = StripePaymentProvider::new("sk_test", "whsec_test");
+        let event = provider
+            .parse_webhook(&headers(format!("t=1717315200,v1={}", SIGNATURE)), BODY, signed_at())
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_id, "evt_1");
+        assert_eq!(event.provider_payment_id, "pi_123");
+        assert_eq!(event.outcome, PaymentOutcome::Succeeded);
+
+        assert!(matches!(
+            provider.parse_webhook(&HashMap::new(), BODY, signed_at()),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            provider.parse_webhook(&headers("t=1717315200,v1=00".to_string()), BODY, signed_at()),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -18,6 +18,12 @@ impl InMemoryUserRepository {
     }
 }
 
+impl Default for InMemoryUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl UserRepository for InMemoryUserRepository {
     async fn create_user(&self, user_data: CreateUserRequest) -> Result<User, String> {
@@ -32,6 +38,7 @@ impl UserRepository for InMemoryUserRepository {
             first_name: None, // CreateUserRequest не содержит этих полей
             last_name: None,
             email: Some(user_data.email),
+            locale: "ru".to_string(),
             user_type_id: 1, // По умолчанию
             created_at: now,
             updated_at: now,
@@ -0,0 +1,154 @@
+use crate::application::webhook_signature::{DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER, sign};
+use crate::application::webhook_target::{check_public_url, is_public_ip};
+use crate::domain::errors::AppError;
+use crate::domain::traits::{Clock, WebhookRepository};
+use crate::infrastructure::job_queue::{Job, JobHandler};
+use async_trait::async_trait;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Сколько раз пытаться доставить событие; с паузами очереди задач это около двух часов
+pub const WEBHOOK_MAX_ATTEMPTS: i32 = 10;
+
+/// Отправить одну доставку из журнала вебхуков
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookDeliveryJob {
+    pub delivery_id: Uuid,
+}
+
+impl Job for WebhookDeliveryJob {
+    const KIND: &'static str = "webhook_delivery";
+    const MAX_ATTEMPTS: i32 = WEBHOOK_MAX_ATTEMPTS;
+}
+
+/// Разрешает имена только в публичные адреса: имя, которое после проверки стало указывать
+/// во внутреннюю сеть, не уведет запрос туда
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public_ip(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("У {} нет публичных адресов", name.as_str()).into());
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Редиректы не выполняются: иначе ответ получателя мог бы перенаправить запрос во внутреннюю сеть.
+/// Клиент создается при запуске; без него доставку не запускаем, а клиент по умолчанию
+/// обошел бы обе защиты
+fn build_client(allow_private_urls: bool) -> reqwest::Client {
+    let builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(Policy::none());
+    let builder = if allow_private_urls { builder } else { builder.dns_resolver(Arc::new(PublicResolver)) };
+    builder
+        .build()
+        .expect("Не удалось создать HTTP-клиент для доставки вебхуков")
+}
+
+/// Отправляет событие JSON-запросом POST с подписью; ответ не из 2xx означает повтор
+pub struct WebhookDeliveryHandler {
+    repository: Arc<dyn WebhookRepository + Send + Sync>,
+    clock: Arc<dyn Clock + Send + Sync>,
+    client: reqwest::Client,
+    allow_private_urls: bool,
+}
+
+impl WebhookDeliveryHandler {
+    pub fn new(repository: Arc<dyn WebhookRepository + Send + Sync>, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        Self {
+            repository,
+            clock,
+            client: build_client(false),
+            allow_private_urls: false,
+        }
+    }
+
+    /// Разрешить адреса внутренней сети и loopback - для разработки с локальным получателем
+    pub fn with_private_urls(mut self, allowed: bool) -> Self {
+        self.allow_private_urls = allowed;
+        self.client = build_client(allowed);
+        self
+    }
+
+    /// Статус ответа и ошибка попытки
+    async fn send(
+        &self,
+        url: &str,
+        event_type: &str,
+        delivery_id: Uuid,
+        body: String,
+        secret: &str,
+    ) -> (Option<i32>, Option<String>) {
+        // Адрес проверяется и при каждой отправке: имя могло начать указывать в другое место
+        if !self.allow_private_urls
+            && let Err(e) = check_public_url(url).await
+        {
+            return (None, Some(e.to_string()));
+        }
+
+        let timestamp = self.clock.now().timestamp();
+        let result = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event_type)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign(secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await;
+
+        // Тело ответа не сохраняется: журнал доставок видят менеджеры компании
+        match result {
+            Ok(response) if response.status().is_success() => (Some(response.status().as_u16() as i32), None),
+            Ok(response) => {
+                let status = response.status();
+                (Some(status.as_u16() as i32), Some(format!("Получатель ответил статусом {}", status)))
+            }
+            Err(e) => (None, Some(format!("Ошибка отправки вебхука: {}", e))),
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler<WebhookDeliveryJob> for WebhookDeliveryHandler {
+    async fn handle(&self, job: WebhookDeliveryJob) -> Result<(), AppError> {
+        // Подписку удалили или событие уже доставлено
+        let Some(target) = self.repository.find_pending_delivery(job.delivery_id).await? else {
+            return Ok(());
+        };
+
+        let (response_status, error) = self
+            .send(
+                &target.url,
+                &target.delivery.event_type,
+                target.delivery.id,
+                target.delivery.payload.to_string(),
+                &target.secret,
+            )
+            .await;
+
+        self.repository
+            .record_attempt(target.delivery.id, response_status, error.as_deref(), WEBHOOK_MAX_ATTEMPTS)
+            .await?;
+        match error {
+            Some(error) => Err(AppError::Internal(error)),
+            None => Ok(()),
+        }
+    }
+}
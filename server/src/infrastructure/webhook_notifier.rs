@@ -0,0 +1,45 @@
+use crate::domain::entities::OutgoingMessage;
+use crate::domain::errors::AppError;
+use crate::domain::traits::Notifier;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Отправляет уведомление JSON-запросом POST на заданный адрес; ответ не из 2xx считается ошибкой
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { client, url: url.into() }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn channel(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, message: &OutgoingMessage) -> Result<(), AppError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Ошибка отправки вебхука: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(AppError::Internal(format!(
+                "Вебхук ответил статусом {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
@@ -2,14 +2,77 @@ use actix_web::{App, HttpServer, web};
 use std::env;
 use std::sync::Arc;
 
+use server::application::analytics_service::AnalyticsServiceImpl;
+use server::application::booking_series_service::BookingSeriesServiceImpl;
+use server::application::booking_service::BookingServiceImpl;
+use server::application::bookmark_service::BookmarkServiceImpl;
+use server::application::calendar_service::CalendarServiceImpl;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::checkin_service::CheckinServiceImpl;
+use server::application::customer_restriction_service::CustomerRestrictionServiceImpl;
+use server::application::customer_service::CustomerServiceImpl;
+use server::application::group_session_service::GroupSessionServiceImpl;
+use server::application::hold_service::{DEFAULT_HOLD_TTL_MINUTES, HoldServiceImpl};
+use server::application::hold_sweeper::HoldSweeper;
+use server::application::job_admin_service::JobAdminServiceImpl;
+use server::application::media_service::{DEFAULT_MAX_UPLOAD_BYTES, MediaServiceImpl};
+use server::application::notification_dispatcher::NotificationDispatcher;
+use server::application::payment_service::{DEFAULT_DEPOSIT_TTL_MINUTES, PaymentServiceImpl};
+use server::application::reminder_scheduler::ReminderScheduler;
+use server::application::reminder_service::ReminderServiceImpl;
+use server::application::promo_code_service::PromoCodeServiceImpl;
+use server::application::queue_service::QueueServiceImpl;
+use server::application::review_service::ReviewServiceImpl;
+use server::application::search_service::SearchServiceImpl;
 use server::application::services::{HealthServiceImpl, UserServiceImpl};
+use server::application::slot_service::SlotServiceImpl;
+use server::application::waitlist_service::{DEFAULT_WAITLIST_OFFER_TTL_MINUTES, WaitlistServiceImpl};
+use server::application::webhook_service::WebhookServiceImpl;
 use server::infrastructure::{
+    clock::SystemClock,
     database::PostgresHealthChecker, 
+    postgres_booking_repository::PostgreSQLBookingRepository,
+    postgres_bookmark_repository::PostgreSQLBookmarkRepository,
+    postgres_calendar_repository::PostgreSQLCalendarRepository,
+    postgres_waitlist_repository::PostgreSQLWaitlistRepository,
+    notifier::LogNotifier,
+    postgres_outbox_repository::PostgreSQLOutboxRepository,
+    postgres_reminder_repository::PostgreSQLReminderRepository,
+    postgres_job_repository::PostgreSQLJobRepository,
+    postgres_webhook_repository::PostgreSQLWebhookRepository,
+    postgres_review_repository::PostgreSQLReviewRepository,
+    postgres_search_repository::PostgreSQLSearchRepository,
+    postgres_media_repository::PostgreSQLMediaRepository,
+    postgres_customer_repository::PostgreSQLCustomerRepository,
+    postgres_payment_repository::PostgreSQLPaymentRepository,
+    postgres_promo_code_repository::PostgreSQLPromoCodeRepository,
+    postgres_queue_repository::PostgreSQLQueueRepository,
+    postgres_analytics_repository::PostgreSQLAnalyticsRepository,
+    fake_payment_provider::FakePaymentProvider,
+    stripe_payment_provider::StripePaymentProvider,
+    payment_refund::PaymentRefundHandler,
+    local_media_storage::LocalMediaStorage,
+    s3_media_storage::S3MediaStorage,
+    webhook_delivery::WebhookDeliveryHandler,
+    queue_event_bus::PostgresQueueEventBus,
+    slot_event_bus::PostgresSlotEventBus,
+    job_queue::{JobQueue, JobWorker},
+    maintenance_jobs::{
+        ANALYTICS_REFRESH_DEDUPE_KEY, AnalyticsRefreshHandler, AnalyticsRefreshJob, CLEANUP_DEDUPE_KEY,
+        CleanupHandler, CleanupJob, DEFAULT_ANALYTICS_REFRESH_MINUTES, DEFAULT_RETENTION_DAYS,
+    },
+    smtp_notifier::SmtpNotifier,
+    webhook_notifier::WebhookNotifier,
+    postgres_company_repository::PostgreSQLCompanyRepository,
     postgres_user_repository::PostgreSQLUserRepository,
     jwt::jwt_service::JwtService,
     migrations::{run_migrations, ensure_database_exists},
 };
-use server::domain::traits::UserAuthRepository;
+use server::domain::traits::{
+    AnalyticsService, BookingRepository, BookingSeriesService, BookingService, BookmarkRepository, BookmarkService,
+    CalendarRepository, CalendarService, CatalogService, CheckinService, Clock, CompanyRepository, CustomerRestrictionService, CustomerService, GroupSessionService, HoldService, JobAdminService, JobRepository, MediaService, MediaStorage, NotificationOutbox, PaymentProvider, PaymentRepository, PaymentService, PromoCodeRepository, PromoCodeService, QueueService, ReminderRepository, ReminderService, ReviewService, SearchService, SlotService, UserAuthRepository,
+    WaitlistRepository, WaitlistService, WebhookRepository, WebhookService,
+};
 use server::presentation::routes::api_v1_routes;
 
 #[actix_web::main]
@@ -59,6 +122,236 @@ async fn main() -> std::io::Result<()> {
     // Auth repository (тот же PostgreSQL repository, но через другой trait)
     let user_auth_repository: Arc<dyn UserAuthRepository + Send + Sync> = postgres_repository;
 
+    // Компании, услуги, сотрудники и расчет свободных слотов
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(db_pool.clone()));
+    let catalog_service: Arc<dyn CatalogService + Send + Sync> =
+        Arc::new(CatalogServiceImpl::new(company_repository.clone()));
+    let booking_repository: Arc<dyn BookingRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookingRepository::new(db_pool.clone()));
+    // События слотов приходят через LISTEN/NOTIFY, поэтому поток видит записи всех реплик
+    let slot_event_bus = PostgresSlotEventBus::listen(&db_pool)
+        .await
+        .expect("Не удалось подписаться на события слотов");
+    let slot_service: Arc<dyn SlotService + Send + Sync> = Arc::new(
+        SlotServiceImpl::new(company_repository.clone(), booking_repository.clone()).with_events(slot_event_bus),
+    );
+    let clock: Arc<dyn Clock + Send + Sync> = Arc::new(SystemClock);
+
+    // Время удержания брони слота при оформлении записи
+    let hold_ttl_minutes = env::var("HOLD_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_HOLD_TTL_MINUTES);
+    // Сколько держится слот, предложенный клиенту из листа ожидания
+    let offer_ttl_minutes = env::var("WAITLIST_OFFER_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_WAITLIST_OFFER_TTL_MINUTES);
+    let waitlist_repository: Arc<dyn WaitlistRepository + Send + Sync> =
+        Arc::new(PostgreSQLWaitlistRepository::new(db_pool.clone()));
+
+    // Депозиты: Stripe, если задан ключ, иначе тестовая платежная система. Ее секрет обязателен:
+    // случайный менялся бы при перезапуске, и уведомления об оплате переставали бы проходить
+    let payment_provider: Arc<dyn PaymentProvider + Send + Sync> = match StripePaymentProvider::from_env() {
+        Ok(Some(stripe_provider)) => Arc::new(stripe_provider),
+        Ok(None) => Arc::new(FakePaymentProvider::new(
+            &env::var("FAKE_PAYMENTS_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty())
+                .expect("Не задана переменная FAKE_PAYMENTS_SECRET - секрет тестовой платежной системы (или STRIPE_SECRET_KEY)"),
+        )),
+        Err(e) => panic!("Ошибка настройки платежей: {}", e),
+    };
+    let payment_repository: Arc<dyn PaymentRepository + Send + Sync> =
+        Arc::new(PostgreSQLPaymentRepository::new(db_pool.clone()));
+    // Сколько запись на услугу с депозитом ждет оплаты
+    let deposit_ttl_minutes = env::var("DEPOSIT_TTL_MINUTES")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_DEPOSIT_TTL_MINUTES);
+    let promo_repository: Arc<dyn PromoCodeRepository + Send + Sync> =
+        Arc::new(PostgreSQLPromoCodeRepository::new(db_pool.clone()));
+    // QR-коды отметки о приходе подписываются этим секретом. Он обязателен: случайный
+    // секрет менялся бы при перезапуске и различался бы у экземпляров сервера
+    let checkin_secret = env::var("CHECKIN_TOKEN_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty())
+        .expect("Не задана переменная CHECKIN_TOKEN_SECRET - секрет подписи QR-кодов отметки о приходе");
+    let waitlist_service: Arc<dyn WaitlistService + Send + Sync> = Arc::new(
+        WaitlistServiceImpl::new(
+            company_repository.clone(),
+            booking_repository.clone(),
+            waitlist_repository,
+            clock.clone(),
+        )
+        .with_offer_ttl(chrono::Duration::minutes(offer_ttl_minutes)),
+    );
+    let booking_service: Arc<dyn BookingService + Send + Sync> = Arc::new(BookingServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+        payment_repository.clone(),
+        promo_repository.clone(),
+        waitlist_service.clone(),
+        clock.clone(),
+    ));
+    let hold_service: Arc<dyn HoldService + Send + Sync> = Arc::new(
+        HoldServiceImpl::new(company_repository.clone(), booking_repository.clone(), clock.clone())
+            .with_hold_ttl(chrono::Duration::minutes(hold_ttl_minutes)),
+    );
+    let series_service: Arc<dyn BookingSeriesService + Send + Sync> = Arc::new(BookingSeriesServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+        waitlist_service.clone(),
+        clock.clone(),
+    ));
+    let restriction_service: Arc<dyn CustomerRestrictionService + Send + Sync> = Arc::new(
+        CustomerRestrictionServiceImpl::new(
+            company_repository.clone(),
+            booking_repository.clone(),
+            user_auth_repository.clone(),
+            clock.clone(),
+        ),
+    );
+    let payment_service: Arc<dyn PaymentService + Send + Sync> = Arc::new(
+        PaymentServiceImpl::new(
+            company_repository.clone(),
+            booking_repository.clone(),
+            payment_repository.clone(),
+            payment_provider.clone(),
+            waitlist_service.clone(),
+            clock.clone(),
+        )
+        .with_deposit_ttl(chrono::Duration::minutes(deposit_ttl_minutes)),
+    );
+    let checkin_service: Arc<dyn CheckinService + Send + Sync> = Arc::new(CheckinServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+        booking_service.clone(),
+        checkin_secret,
+        clock.clone(),
+    ));
+    let group_session_service: Arc<dyn GroupSessionService + Send + Sync> = Arc::new(GroupSessionServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+    ));
+
+    // Закладки пользователей
+    let bookmark_repository: Arc<dyn BookmarkRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookmarkRepository::new(db_pool.clone()));
+    let bookmark_service: Arc<dyn BookmarkService + Send + Sync> = Arc::new(BookmarkServiceImpl::new(
+        company_repository.clone(),
+        bookmark_repository,
+    ));
+
+    // Выгрузка записей в календари
+    let calendar_repository: Arc<dyn CalendarRepository + Send + Sync> =
+        Arc::new(PostgreSQLCalendarRepository::new(db_pool.clone()));
+    let calendar_service: Arc<dyn CalendarService + Send + Sync> = Arc::new(CalendarServiceImpl::new(
+        company_repository.clone(),
+        calendar_repository,
+        clock.clone(),
+    ));
+
+    // Напоминания о записях по правилам компаний
+    let reminder_repository: Arc<dyn ReminderRepository + Send + Sync> =
+        Arc::new(PostgreSQLReminderRepository::new(db_pool.clone()));
+    let reminder_service: Arc<dyn ReminderService + Send + Sync> = Arc::new(ReminderServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+        reminder_repository.clone(),
+    ));
+
+    // Очередь фоновых задач и ее администрирование
+    let job_repository: Arc<dyn JobRepository + Send + Sync> =
+        Arc::new(PostgreSQLJobRepository::new(db_pool.clone()));
+    let job_queue = JobQueue::new(job_repository.clone());
+    let job_admin_service: Arc<dyn JobAdminService + Send + Sync> = Arc::new(JobAdminServiceImpl::new(
+        user_auth_repository.clone(),
+        job_repository.clone(),
+    ));
+
+    // Вебхуки компаний; доставкой занимается очередь задач. Адреса внутренней сети
+    // запрещены, WEBHOOK_ALLOW_PRIVATE_URLS=true разрешает их для локальной разработки
+    let webhook_repository: Arc<dyn WebhookRepository + Send + Sync> =
+        Arc::new(PostgreSQLWebhookRepository::new(db_pool.clone()));
+    let allow_private_webhook_urls = env::var("WEBHOOK_ALLOW_PRIVATE_URLS")
+        .ok()
+        .and_then(|value| value.parse::<bool>().ok())
+        .unwrap_or(false);
+    let webhook_service: Arc<dyn WebhookService + Send + Sync> = Arc::new(
+        WebhookServiceImpl::new(company_repository.clone(), webhook_repository.clone())
+            .with_private_urls(allow_private_webhook_urls),
+    );
+
+    // Отзывы о завершенных визитах и их модерация
+    let review_service: Arc<dyn ReviewService + Send + Sync> = Arc::new(ReviewServiceImpl::new(
+        user_auth_repository.clone(),
+        company_repository.clone(),
+        booking_repository.clone(),
+        Arc::new(PostgreSQLReviewRepository::new(db_pool.clone())),
+    ));
+
+    // Поиск компаний по тексту, фильтрам и расстоянию
+    let search_service: Arc<dyn SearchService + Send + Sync> = Arc::new(SearchServiceImpl::new(
+        Arc::new(PostgreSQLSearchRepository::new(db_pool.clone())),
+        clock.clone(),
+    ));
+
+    // Изображения: S3-совместимое хранилище, если задан бакет, иначе локальный каталог
+    let media_storage: Arc<dyn MediaStorage + Send + Sync> = match S3MediaStorage::from_env() {
+        Ok(Some(s3_storage)) => Arc::new(s3_storage),
+        Ok(None) => Arc::new(LocalMediaStorage::new(
+            env::var("MEDIA_DIR").unwrap_or_else(|_| "media".to_string()),
+            &env::var("MEDIA_PUBLIC_URL").unwrap_or_else(|_| "/v1/media".to_string()),
+        )),
+        Err(e) => panic!("Ошибка настройки хранилища изображений: {}", e),
+    };
+    let max_upload_bytes = env::var("MEDIA_MAX_UPLOAD_BYTES")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES);
+    let media_service: Arc<dyn MediaService + Send + Sync> = Arc::new(
+        MediaServiceImpl::new(
+            company_repository.clone(),
+            Arc::new(PostgreSQLMediaRepository::new(db_pool.clone())),
+            media_storage,
+        )
+        .with_max_upload_bytes(max_upload_bytes),
+    );
+
+    // Промокоды компаний и расчет цены записи
+    let promo_code_service: Arc<dyn PromoCodeService + Send + Sync> = Arc::new(PromoCodeServiceImpl::new(
+        company_repository.clone(),
+        promo_repository,
+        clock.clone(),
+    ));
+
+    // Живая очередь филиалов; место в очереди обновляется по событиям из всех экземпляров сервера
+    let queue_event_bus = PostgresQueueEventBus::listen(&db_pool)
+        .await
+        .expect("Не удалось подписаться на события очереди");
+    let queue_service: Arc<dyn QueueService + Send + Sync> = Arc::new(
+        QueueServiceImpl::new(
+            company_repository.clone(),
+            Arc::new(PostgreSQLQueueRepository::new(db_pool.clone())),
+            clock.clone(),
+        )
+        .with_events(queue_event_bus),
+    );
+
+    // Отчеты компании по представлениям, которые пересчитывает очередь задач
+    let analytics_service: Arc<dyn AnalyticsService + Send + Sync> = Arc::new(AnalyticsServiceImpl::new(
+        company_repository.clone(),
+        Arc::new(PostgreSQLAnalyticsRepository::new(db_pool.clone())),
+    ));
+
+    // Клиенты компании с закрытыми заметками и метками
+    let customer_service: Arc<dyn CustomerService + Send + Sync> = Arc::new(CustomerServiceImpl::new(
+        company_repository.clone(),
+        Arc::new(PostgreSQLCustomerRepository::new(db_pool.clone())),
+    ));
+
     // Создаем JWT сервис
     let jwt_service = JwtService::new();
 
@@ -76,6 +369,73 @@ async fn main() -> std::io::Result<()> {
         bind_address.0, bind_address.1
     );
 
+    // Снимаем истекшие брони раз в полминуты, просроченные предложения из листа ожидания
+    // передаем следующим в очереди, записи с неоплаченным депозитом отменяем
+    HoldSweeper::new(
+        booking_repository.clone(),
+        waitlist_service.clone(),
+        payment_service.clone(),
+        clock.clone(),
+    )
+    .spawn(std::time::Duration::from_secs(30));
+
+    // Раз в минуту ставим в outbox наступившие напоминания
+    ReminderScheduler::new(reminder_repository, clock.clone()).spawn(std::time::Duration::from_secs(60));
+
+    // Доставляем уведомления из outbox: журнал всегда, почта и вебхук - если настроены
+    let outbox: Arc<dyn NotificationOutbox + Send + Sync> =
+        Arc::new(PostgreSQLOutboxRepository::new(db_pool.clone()));
+    let mut log_notifier = LogNotifier::new();
+    if let Ok(path) = env::var("NOTIFY_LOG_FILE") {
+        log_notifier = log_notifier.with_file(path);
+    }
+    let mut dispatcher = NotificationDispatcher::new(outbox, clock.clone()).with_channel(Arc::new(log_notifier));
+    match SmtpNotifier::from_env() {
+        Ok(Some(smtp_notifier)) => dispatcher = dispatcher.with_channel(Arc::new(smtp_notifier)),
+        Ok(None) => {}
+        Err(e) => eprintln!("Почтовые уведомления отключены: {}", e),
+    }
+    if let Ok(url) = env::var("NOTIFY_WEBHOOK_URL") {
+        dispatcher = dispatcher.with_channel(Arc::new(WebhookNotifier::new(url)));
+    }
+    dispatcher.spawn(std::time::Duration::from_secs(5));
+
+    // Воркер очереди задач: просыпается по NOTIFY, а на случай потери соединения
+    // дополнительно опрашивает очередь раз в полминуты
+    let retention_days = env::var("RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    if let Err(e) = job_queue
+        .enqueue_at(&CleanupJob { retention_days }, None, Some(CLEANUP_DEDUPE_KEY.to_string()))
+        .await
+    {
+        eprintln!("Не удалось запланировать уборку: {}", e);
+    }
+    let analytics_refresh_minutes = env::var("ANALYTICS_REFRESH_MINUTES")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_ANALYTICS_REFRESH_MINUTES);
+    if let Err(e) = job_queue
+        .enqueue_at(
+            &AnalyticsRefreshJob { every_minutes: analytics_refresh_minutes },
+            None,
+            Some(ANALYTICS_REFRESH_DEDUPE_KEY.to_string()),
+        )
+        .await
+    {
+        eprintln!("Не удалось запланировать обновление аналитики: {}", e);
+    }
+    JobWorker::new(job_repository, clock.clone())
+        .register(CleanupHandler::new(db_pool.clone(), job_queue.clone(), clock.clone()))
+        .register(AnalyticsRefreshHandler::new(db_pool.clone(), job_queue.clone(), clock.clone()))
+        .register(
+            WebhookDeliveryHandler::new(webhook_repository, clock.clone()).with_private_urls(allow_private_webhook_urls),
+        )
+        .register(PaymentRefundHandler::new(payment_repository, payment_provider, clock.clone()))
+        .with_notifications(db_pool.clone())
+        .spawn(std::time::Duration::from_secs(30));
+
     // Запускаем HTTP сервер
     HttpServer::new(move || {
         App::new()
@@ -83,6 +443,28 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(user_service.clone()))
             .app_data(web::Data::new(user_auth_repository.clone()))
             .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(catalog_service.clone()))
+            .app_data(web::Data::new(slot_service.clone()))
+            .app_data(web::Data::new(booking_service.clone()))
+            .app_data(web::Data::new(hold_service.clone()))
+            .app_data(web::Data::new(series_service.clone()))
+            .app_data(web::Data::new(restriction_service.clone()))
+            .app_data(web::Data::new(waitlist_service.clone()))
+            .app_data(web::Data::new(group_session_service.clone()))
+            .app_data(web::Data::new(checkin_service.clone()))
+            .app_data(web::Data::new(bookmark_service.clone()))
+            .app_data(web::Data::new(calendar_service.clone()))
+            .app_data(web::Data::new(reminder_service.clone()))
+            .app_data(web::Data::new(job_admin_service.clone()))
+            .app_data(web::Data::new(webhook_service.clone()))
+            .app_data(web::Data::new(review_service.clone()))
+            .app_data(web::Data::new(search_service.clone()))
+            .app_data(web::Data::new(media_service.clone()))
+            .app_data(web::Data::new(customer_service.clone()))
+            .app_data(web::Data::new(payment_service.clone()))
+            .app_data(web::Data::new(promo_code_service.clone()))
+            .app_data(web::Data::new(queue_service.clone()))
+            .app_data(web::Data::new(analytics_service.clone()))
             .service(api_v1_routes())
     })
     .bind(bind_address)?
@@ -0,0 +1,18 @@
+use actix_web::HttpResponse;
+
+use crate::domain::errors::AppError;
+
+impl From<AppError> for HttpResponse {
+    fn from(error: AppError) -> Self {
+        let body = serde_json::json!({ "error": error.to_string() });
+        match error {
+            AppError::Validation(_) => HttpResponse::BadRequest().json(body),
+            AppError::Forbidden(_) => HttpResponse::Forbidden().json(body),
+            AppError::NotFound(_) => HttpResponse::NotFound().json(body),
+            AppError::Conflict(_) => HttpResponse::Conflict().json(body),
+            AppError::PayloadTooLarge(_) => HttpResponse::PayloadTooLarge().json(body),
+            AppError::UnsupportedMediaType(_) => HttpResponse::UnsupportedMediaType().json(body),
+            AppError::Internal(_) => HttpResponse::InternalServerError().json(body),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::{entities::JobsQuery, traits::JobAdminService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/admin/jobs?status=..&kind=..&limit=.. - фоновые задачи, по умолчанию упавшие (администраторы)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    job_admin_service: web::Data<Arc<dyn JobAdminService + Send + Sync>>,
+    query: web::Query<JobsQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match job_admin_service.list_jobs(user_id, query.into_inner()).await {
+        Ok(jobs) => HttpResponse::Ok().json(jobs),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod list_jobs;
+pub mod retry_job;
+pub mod set_review_visibility;
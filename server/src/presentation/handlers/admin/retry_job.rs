@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::JobAdminService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/admin/jobs/{id}/retry - вернуть упавшую задачу в очередь (администраторы)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    job_admin_service: web::Data<Arc<dyn JobAdminService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match job_admin_service.retry_job(user_id, path.into_inner()).await {
+        Ok(job) => HttpResponse::Ok().json(job),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::ReviewVisibilityRequest, traits::ReviewService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// PUT /v1/admin/reviews/{id}/visibility - скрыть отзыв или вернуть его (модераторы)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    review_service: web::Data<Arc<dyn ReviewService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<ReviewVisibilityRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match review_service
+        .set_visibility(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(review) => HttpResponse::Ok().json(review),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::ChangeBookingStatusRequest, traits::BookingService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/bookings/{id}/status - перевести запись в новый статус (подтверждение, отмена, визит)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    booking_service: web::Data<Arc<dyn BookingService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<ChangeBookingStatusRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match booking_service
+        .change_status(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(booking) => HttpResponse::Ok().json(booking),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::{entities::CreateBookingRequest, traits::BookingService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/bookings - записаться на свободный слот, 409 если слот уже занят
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    booking_service: web::Data<Arc<dyn BookingService + Send + Sync>>,
+    request_data: web::Json<CreateBookingRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match booking_service.create_booking(user_id, request_data.into_inner()).await {
+        Ok(booking) => HttpResponse::Created().json(booking),
+        Err(e) => HttpResponse::from(e),
+    }
+}
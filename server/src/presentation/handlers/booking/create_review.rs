@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CreateReviewRequest, traits::ReviewService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/bookings/{id}/review - отзыв клиента о завершенном визите
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    review_service: web::Data<Arc<dyn ReviewService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<CreateReviewRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match review_service
+        .create_review(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(review) => HttpResponse::Created().json(review),
+        Err(e) => HttpResponse::from(e),
+    }
+}
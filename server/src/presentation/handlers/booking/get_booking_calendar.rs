@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, http::header, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::CalendarService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/bookings/{id}.ics - запись файлом iCalendar для добавления в календарь
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    calendar_service: web::Data<Arc<dyn CalendarService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let booking_id = path.into_inner();
+    match calendar_service.booking_calendar(user_id, booking_id).await {
+        Ok(calendar) => HttpResponse::Ok()
+            .content_type("text/calendar; charset=utf-8")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"booking-{}.ics\"", booking_id),
+            ))
+            .body(calendar),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::BookingService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/bookings/{id}/history - история изменений записи
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    booking_service: web::Data<Arc<dyn BookingService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match booking_service.booking_history(user_id, path.into_inner()).await {
+        Ok(events) => HttpResponse::Ok().json(events),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::ReminderService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/bookings/{id}/reminders - запланированные и отправленные напоминания о записи
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    reminder_service: web::Data<Arc<dyn ReminderService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match reminder_service.booking_reminders(user_id, path.into_inner()).await {
+        Ok(reminders) => HttpResponse::Ok().json(reminders),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, http::header, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CheckinQrQuery, traits::CheckinService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/bookings/{id}/checkin-qr?format=png|svg - QR-код для отметки о приходе (клиент записи)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    checkin_service: web::Data<Arc<dyn CheckinService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<CheckinQrQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match checkin_service
+        .checkin_qr(user_id, path.into_inner(), query.format)
+        .await
+    {
+        // Код короткоживущий, кэшировать его нельзя
+        Ok(image) => HttpResponse::Ok()
+            .content_type(image.content_type)
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .body(image.body),
+        Err(e) => HttpResponse::from(e),
+    }
+}
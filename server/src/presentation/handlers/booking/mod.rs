@@ -0,0 +1,12 @@
+pub mod change_booking_status;
+pub mod create_booking;
+pub mod create_review;
+pub mod get_booking;
+pub mod get_booking_calendar;
+pub mod get_booking_history;
+pub mod get_booking_payment;
+pub mod get_booking_reminders;
+pub mod get_checkin_qr;
+pub mod quote_booking;
+pub mod reschedule_booking;
+pub mod start_booking_payment;
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::{entities::BookingQuoteRequest, traits::PromoCodeService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/bookings/quote - цена записи с учетом промокода до ее создания
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    promo_code_service: web::Data<Arc<dyn PromoCodeService + Send + Sync>>,
+    request_data: web::Json<BookingQuoteRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match promo_code_service.quote_booking(user_id, request_data.into_inner()).await {
+        Ok(quote) => HttpResponse::Ok().json(quote),
+        Err(e) => HttpResponse::from(e),
+    }
+}
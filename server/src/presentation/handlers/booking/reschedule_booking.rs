@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::RescheduleBookingRequest, traits::BookingService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/bookings/{id}/reschedule - перенести запись, старый слот освобождается атомарно
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    booking_service: web::Data<Arc<dyn BookingService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<RescheduleBookingRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match booking_service
+        .reschedule_booking(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(booking) => HttpResponse::Ok().json(booking),
+        Err(e) => HttpResponse::from(e),
+    }
+}
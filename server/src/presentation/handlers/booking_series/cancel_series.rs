@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CancelSeriesRequest, traits::BookingSeriesService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/booking-series/{id}/cancel - отменить вхождение и все следующие
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    series_service: web::Data<Arc<dyn BookingSeriesService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<CancelSeriesRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match series_service
+        .cancel_following(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::{entities::CreateBookingSeriesRequest, traits::BookingSeriesService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/booking-series - создать серию записей по правилу RRULE, 409 с отчетом о занятых вхождениях
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    series_service: web::Data<Arc<dyn BookingSeriesService + Send + Sync>>,
+    request_data: web::Json<CreateBookingSeriesRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match series_service.create_series(user_id, request_data.into_inner()).await {
+        Ok(result) if result.series.is_none() => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Часть вхождений серии недоступна",
+            "conflicts": result.conflicts,
+        })),
+        Ok(result) => HttpResponse::Created().json(result),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,4 @@
+pub mod cancel_series;
+pub mod create_series;
+pub mod get_series;
+pub mod reschedule_series;
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::RescheduleSeriesRequest, traits::BookingSeriesService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/booking-series/{id}/reschedule - перенести вхождение и все следующие на тот же сдвиг
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    series_service: web::Data<Arc<dyn BookingSeriesService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<RescheduleSeriesRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match series_service
+        .reschedule_following(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(result) if !result.conflicts.is_empty() => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Часть вхождений нельзя перенести",
+            "conflicts": result.conflicts,
+        })),
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) => HttpResponse::from(e),
+    }
+}
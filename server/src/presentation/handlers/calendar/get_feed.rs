@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+
+use crate::domain::traits::CalendarService;
+
+// GET /v1/calendar/{token}.ics - лента записей по секретной ссылке, без авторизации
+pub async fn handler(
+    calendar_service: web::Data<Arc<dyn CalendarService + Send + Sync>>,
+    path: web::Path<String>,
+) -> impl Responder {
+    match calendar_service.feed_calendar(&path.into_inner()).await {
+        Ok(calendar) => HttpResponse::Ok()
+            .content_type("text/calendar; charset=utf-8")
+            .body(calendar),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CallNextRequest, traits::QueueService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies/{id}/queue/call-next - пригласить следующего по очереди (сотрудники)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    queue_service: web::Data<Arc<dyn QueueService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<CallNextRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match queue_service
+        .call_next(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(ticket) => HttpResponse::Ok().json(ticket),
+        Err(e) => HttpResponse::from(e),
+    }
+}
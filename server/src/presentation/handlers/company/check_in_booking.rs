@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CheckinRequest, traits::CheckinService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies/{id}/checkin - отметить приход по отсканированному QR-коду (сотрудники)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    checkin_service: web::Data<Arc<dyn CheckinService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<CheckinRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match checkin_service
+        .check_in(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(booking) => HttpResponse::Ok().json(booking),
+        Err(e) => HttpResponse::from(e),
+    }
+}
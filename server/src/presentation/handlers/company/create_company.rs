@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::{entities::CreateCompanyRequest, traits::CatalogService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies - создать компанию, текущий пользователь становится владельцем
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    catalog_service: web::Data<Arc<dyn CatalogService + Send + Sync>>,
+    request_data: web::Json<CreateCompanyRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match catalog_service.create_company(user_id, request_data.into_inner()).await {
+        Ok(company) => HttpResponse::Created().json(company),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CreatePromoCodeRequest, traits::PromoCodeService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies/{id}/promo-codes - завести промокод (owner/manager)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    promo_code_service: web::Data<Arc<dyn PromoCodeService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<CreatePromoCodeRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match promo_code_service
+        .create_promo_code(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(promo_code) => HttpResponse::Created().json(promo_code),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CreateResourceRequest, traits::CatalogService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies/{id}/resources - добавить ресурс филиала (кабинет, оборудование)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    catalog_service: web::Data<Arc<dyn CatalogService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<CreateResourceRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match catalog_service
+        .create_resource(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(resource) => HttpResponse::Created().json(resource),
+        Err(e) => HttpResponse::from(e),
+    }
+}
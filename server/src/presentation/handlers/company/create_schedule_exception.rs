@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CreateScheduleExceptionRequest, traits::CatalogService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies/{id}/staff/{staff_id}/exceptions - выходной, перерыв или доп. часы
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    catalog_service: web::Data<Arc<dyn CatalogService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+    request_data: web::Json<CreateScheduleExceptionRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, staff_id) = path.into_inner();
+
+    match catalog_service
+        .add_schedule_exception(user_id, company_id, staff_id, request_data.into_inner())
+        .await
+    {
+        Ok(exception) => HttpResponse::Created().json(exception),
+        Err(e) => HttpResponse::from(e),
+    }
+}
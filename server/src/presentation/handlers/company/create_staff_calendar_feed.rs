@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::CalendarService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies/{id}/staff/{staff_id}/calendar-feed - ссылка на календарь записей сотрудника
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    calendar_service: web::Data<Arc<dyn CalendarService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, staff_id) = path.into_inner();
+
+    match calendar_service.create_staff_feed(user_id, company_id, staff_id).await {
+        Ok(feed) => HttpResponse::Created().json(feed),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CreateWebhookRequest, traits::WebhookService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/companies/{id}/webhooks - подписать интеграцию на события (owner/manager); секрет выдается один раз
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    webhook_service: web::Data<Arc<dyn WebhookService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<CreateWebhookRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match webhook_service
+        .create_webhook(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(webhook) => HttpResponse::Created().json(webhook),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::CustomerService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// DELETE /v1/companies/{id}/customers/{customer_id}/notes/{note_id} - удалить заметку
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    customer_service: web::Data<Arc<dyn CustomerService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, customer_id, note_id) = path.into_inner();
+
+    match customer_service
+        .delete_note(user_id, company_id, customer_id, note_id)
+        .await
+    {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::from(e),
+    }
+}
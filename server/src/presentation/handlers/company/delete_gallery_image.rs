@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::MediaService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// DELETE /v1/companies/{id}/gallery/{media_id} - удалить снимок из галереи
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    media_service: web::Data<Arc<dyn MediaService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, media_id) = path.into_inner();
+
+    match media_service.delete_gallery_image(user_id, company_id, media_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::from(e),
+    }
+}
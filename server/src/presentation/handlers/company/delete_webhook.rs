@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::WebhookService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// DELETE /v1/companies/{id}/webhooks/{webhook_id} - удалить вебхук вместе с журналом доставок
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    webhook_service: web::Data<Arc<dyn WebhookService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, webhook_id) = path.into_inner();
+
+    match webhook_service.delete_webhook(user_id, company_id, webhook_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::from(e),
+    }
+}
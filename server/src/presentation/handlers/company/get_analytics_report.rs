@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, http::header, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::{AnalyticsQuery, AnalyticsReportKind, ReportFormat},
+        traits::AnalyticsService,
+    },
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/analytics/{report}?from=&to=&format=json|csv - отчет компании за период (владелец и менеджеры)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    analytics_service: web::Data<Arc<dyn AnalyticsService + Send + Sync>>,
+    path: web::Path<(Uuid, AnalyticsReportKind)>,
+    query: web::Query<AnalyticsQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let (company_id, kind) = path.into_inner();
+    match query.format {
+        ReportFormat::Json => match analytics_service.report(user_id, company_id, kind, &query).await {
+            Ok(report) => HttpResponse::Ok().json(report),
+            Err(e) => HttpResponse::from(e),
+        },
+        ReportFormat::Csv => match analytics_service.report_csv(user_id, company_id, kind, &query).await {
+            Ok(csv) => HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header((
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"{}-{}-{}.csv\"",
+                        kind.as_str(),
+                        query.from,
+                        query.to
+                    ),
+                ))
+                .body(csv),
+            Err(e) => HttpResponse::from(e),
+        },
+    }
+}
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::domain::traits::BookingService;
+
+// GET /v1/companies/{id}/booking-policy - правила отмены и переноса для клиентов
+pub async fn handler(
+    booking_service: web::Data<Arc<dyn BookingService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    match booking_service.get_policy(path.into_inner()).await {
+        Ok(policy) => HttpResponse::Ok().json(policy),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::CustomerRestrictionService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/booking-restriction - ограничение онлайн-записи текущего пользователя в компании
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    restriction_service: web::Data<Arc<dyn CustomerRestrictionService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match restriction_service.my_restriction(user_id, path.into_inner()).await {
+        Ok(status) => HttpResponse::Ok().json(status),
+        Err(e) => HttpResponse::from(e),
+    }
+}
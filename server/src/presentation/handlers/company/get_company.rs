@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::CompanyResponse,
+        traits::{BookmarkService, CatalogService, MediaService},
+    },
+    infrastructure::jwt::{
+        extract_user_uuid::optional_from_request as extract_optional_user_uuid,
+        jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id} - открытая карточка компании; с токеном в ней есть отметка о закладке
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    catalog_service: web::Data<Arc<dyn CatalogService + Send + Sync>>,
+    bookmark_service: web::Data<Arc<dyn BookmarkService + Send + Sync>>,
+    media_service: web::Data<Arc<dyn MediaService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let viewer_id = match extract_optional_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let company = match catalog_service.get_company(path.into_inner()).await {
+        Ok(company) => company,
+        Err(e) => return HttpResponse::from(e),
+    };
+
+    let bookmarked = match viewer_id {
+        Some(user_id) => match bookmark_service.is_bookmarked(user_id, company.id).await {
+            Ok(bookmarked) => bookmarked,
+            Err(e) => return HttpResponse::from(e),
+        },
+        None => false,
+    };
+
+    let media = match media_service.company_media(company.id).await {
+        Ok(media) => media,
+        Err(e) => return HttpResponse::from(e),
+    };
+
+    HttpResponse::Ok().json(CompanyResponse {
+        company,
+        bookmarked,
+        media,
+    })
+}
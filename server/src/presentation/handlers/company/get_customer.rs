@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::CustomerService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/customers/{customer_id} - карточка клиента: записи в компании и заметки
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    customer_service: web::Data<Arc<dyn CustomerService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, customer_id) = path.into_inner();
+
+    match customer_service.get_customer(user_id, company_id, customer_id).await {
+        Ok(profile) => HttpResponse::Ok().json(profile),
+        Err(e) => HttpResponse::from(e),
+    }
+}
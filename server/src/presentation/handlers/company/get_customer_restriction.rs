@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::CustomerRestrictionService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/customers/{customer_id}/restriction - ограничение клиента и ручная настройка
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    restriction_service: web::Data<Arc<dyn CustomerRestrictionService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, customer_id) = path.into_inner();
+
+    match restriction_service
+        .customer_restriction(user_id, company_id, customer_id)
+        .await
+    {
+        Ok(details) => HttpResponse::Ok().json(details),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::QueueStatsQuery, traits::QueueService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/queue/stats?location=&from=&to= - итоги очереди филиала по дням (сотрудники)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    queue_service: web::Data<Arc<dyn QueueService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<QueueStatsQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match queue_service
+        .queue_stats(user_id, path.into_inner(), query.into_inner())
+        .await
+    {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::domain::traits::CatalogService;
+
+// GET /v1/companies/{id}/services/{service_id}/resources - требования услуги к ресурсам
+pub async fn handler(
+    catalog_service: web::Data<Arc<dyn CatalogService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (company_id, service_id) = path.into_inner();
+    match catalog_service.get_service_resources(company_id, service_id).await {
+        Ok(requirements) => HttpResponse::Ok().json(requirements),
+        Err(e) => HttpResponse::from(e),
+    }
+}
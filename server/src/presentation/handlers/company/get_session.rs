@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::GroupSessionService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/sessions/{session_id} - занятие и список участников (сотрудники)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    session_service: web::Data<Arc<dyn GroupSessionService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let (company_id, session_id) = path.into_inner();
+    match session_service.get_session(user_id, company_id, session_id).await {
+        Ok(details) => HttpResponse::Ok().json(details),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::domain::{entities::SlotsQuery, traits::SlotService};
+
+// GET /v1/companies/{id}/slots?service=&staff=&from=&to= - свободное время для записи
+pub async fn handler(
+    slot_service: web::Data<Arc<dyn SlotService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<SlotsQuery>,
+) -> impl Responder {
+    match slot_service
+        .available_slots(path.into_inner(), query.into_inner())
+        .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CustomersQuery, traits::CustomerService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/customers?q=&tag=&limit=&offset= - клиенты компании со счетчиками визитов
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    customer_service: web::Data<Arc<dyn CustomerService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<CustomersQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match customer_service
+        .list_customers(user_id, path.into_inner(), query.into_inner())
+        .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::PromoCodeService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/promo-codes - промокоды компании со счетчиками использований (owner/manager)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    promo_code_service: web::Data<Arc<dyn PromoCodeService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match promo_code_service.list_promo_codes(user_id, path.into_inner()).await {
+        Ok(promo_codes) => HttpResponse::Ok().json(promo_codes),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::LocationQueueQuery, traits::QueueService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/queue?location=&date= - очередь филиала за день (сотрудники)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    queue_service: web::Data<Arc<dyn QueueService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<LocationQueueQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match queue_service
+        .list_queue(user_id, path.into_inner(), query.into_inner())
+        .await
+    {
+        Ok(queue) => HttpResponse::Ok().json(queue),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::domain::traits::CatalogService;
+
+// GET /v1/companies/{id}/resources - ресурсы всех филиалов компании
+pub async fn handler(
+    catalog_service: web::Data<Arc<dyn CatalogService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    match catalog_service.list_resources(path.into_inner()).await {
+        Ok(resources) => HttpResponse::Ok().json(resources),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::domain::{entities::ReviewsQuery, traits::ReviewService};
+
+// GET /v1/companies/{id}/reviews?staff=&limit=&offset= - отзывы и рейтинг компании или сотрудника
+pub async fn handler(
+    review_service: web::Data<Arc<dyn ReviewService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<ReviewsQuery>,
+) -> impl Responder {
+    match review_service
+        .list_reviews(path.into_inner(), query.into_inner())
+        .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::SessionsQuery, traits::GroupSessionService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/sessions?from=..&to=..&service=.. - групповые занятия с записавшимися (сотрудники)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    session_service: web::Data<Arc<dyn GroupSessionService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<SessionsQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match session_service
+        .list_sessions(user_id, path.into_inner(), query.into_inner())
+        .await
+    {
+        Ok(sessions) => HttpResponse::Ok().json(sessions),
+        Err(e) => HttpResponse::from(e),
+    }
+}
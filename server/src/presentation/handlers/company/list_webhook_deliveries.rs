@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::WebhookDeliveriesQuery, traits::WebhookService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// GET /v1/companies/{id}/webhooks/{webhook_id}/deliveries?status=..&limit=.. - журнал доставок, новые первыми
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    webhook_service: web::Data<Arc<dyn WebhookService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<WebhookDeliveriesQuery>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, webhook_id) = path.into_inner();
+
+    match webhook_service
+        .list_deliveries(user_id, company_id, webhook_id, query.into_inner())
+        .await
+    {
+        Ok(deliveries) => HttpResponse::Ok().json(deliveries),
+        Err(e) => HttpResponse::from(e),
+    }
+}
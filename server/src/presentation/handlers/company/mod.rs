@@ -0,0 +1,52 @@
+pub mod add_gallery_image;
+pub mod call_next_in_queue;
+pub mod check_in_booking;
+pub mod clear_customer_restriction;
+pub mod create_company;
+pub mod create_customer_note;
+pub mod create_location;
+pub mod create_promo_code;
+pub mod create_resource;
+pub mod create_schedule_exception;
+pub mod create_service;
+pub mod create_staff;
+pub mod create_staff_calendar_feed;
+pub mod create_webhook;
+pub mod delete_customer_note;
+pub mod delete_gallery_image;
+pub mod delete_logo;
+pub mod delete_webhook;
+pub mod get_analytics_report;
+pub mod get_booking_policy;
+pub mod get_booking_restriction;
+pub mod get_company;
+pub mod get_customer;
+pub mod get_customer_restriction;
+pub mod get_queue_stats;
+pub mod get_reminder_rules;
+pub mod get_service_resources;
+pub mod get_session;
+pub mod get_slots;
+pub mod join_queue;
+pub mod list_customers;
+pub mod list_promo_codes;
+pub mod list_queue;
+pub mod list_resources;
+pub mod list_reviews;
+pub mod list_sessions;
+pub mod list_webhook_deliveries;
+pub mod list_webhooks;
+pub mod reply_to_review;
+pub mod revoke_staff_calendar_feed;
+pub mod send_test_webhook;
+pub mod set_customer_restriction;
+pub mod stream_slots;
+pub mod update_booking_policy;
+pub mod update_customer_note;
+pub mod update_customer_tags;
+pub mod update_promo_code;
+pub mod update_reminder_rules;
+pub mod update_service_resources;
+pub mod update_staff_schedule;
+pub mod update_webhook;
+pub mod upload_logo;
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::ReviewReplyRequest, traits::ReviewService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// PUT /v1/companies/{id}/reviews/{review_id}/reply - опубликовать или изменить ответ компании
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    review_service: web::Data<Arc<dyn ReviewService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+    request_data: web::Json<ReviewReplyRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, review_id) = path.into_inner();
+
+    match review_service
+        .reply_to_review(user_id, company_id, review_id, request_data.into_inner())
+        .await
+    {
+        Ok(review) => HttpResponse::Ok().json(review),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,53 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::{HttpResponse, Responder, http::header, web};
+use futures_util::stream::{self, StreamExt};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::{SlotEvent, SlotEventKind, SlotStreamQuery},
+    traits::SlotService,
+};
+
+/// Комментарий раз в интервал не дает прокси закрыть молчащее соединение
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Кадр Server-Sent Events: имя события и JSON в одной строке data
+fn sse_frame(event: &SlotEvent) -> web::Bytes {
+    let name = match event.kind {
+        SlotEventKind::Taken => "slot_taken",
+        SlotEventKind::Freed => "slot_freed",
+    };
+    let data = serde_json::to_string(event).unwrap_or_default();
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", name, data))
+}
+
+// GET /v1/companies/{id}/slots/stream?staff=&location= - события о занятых и освободившихся слотах (SSE)
+pub async fn handler(
+    slot_service: web::Data<Arc<dyn SlotService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    query: web::Query<SlotStreamQuery>,
+) -> impl Responder {
+    let events = match slot_service
+        .slot_events(path.into_inner(), query.into_inner())
+        .await
+    {
+        Ok(events) => events,
+        Err(e) => return HttpResponse::from(e),
+    };
+
+    // Первый тик срабатывает сразу, и клиент получает заголовки без ожидания события
+    let keepalive = stream::unfold(actix_web::rt::time::interval(KEEPALIVE_INTERVAL), |mut interval| async move {
+        interval.tick().await;
+        Some((web::Bytes::from_static(b": keepalive\n\n"), interval))
+    });
+    let body = stream::select(events.map(|event| sse_frame(&event)), keepalive).map(Ok::<_, Infallible>);
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(body)
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::UpdateBookingPolicyRequest, traits::BookingService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// PUT /v1/companies/{id}/booking-policy - изменить правила отмены и переноса (owner/manager)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    booking_service: web::Data<Arc<dyn BookingService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<UpdateBookingPolicyRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match booking_service
+        .update_policy(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(policy) => HttpResponse::Ok().json(policy),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::CustomerNoteRequest, traits::CustomerService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// PATCH /v1/companies/{id}/customers/{customer_id}/notes/{note_id} - изменить текст заметки
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    customer_service: web::Data<Arc<dyn CustomerService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+    request_data: web::Json<CustomerNoteRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, customer_id, note_id) = path.into_inner();
+
+    match customer_service
+        .update_note(user_id, company_id, customer_id, note_id, request_data.into_inner())
+        .await
+    {
+        Ok(note) => HttpResponse::Ok().json(note),
+        Err(e) => HttpResponse::from(e),
+    }
+}
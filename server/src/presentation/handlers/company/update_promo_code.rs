@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::UpdatePromoCodeRequest, traits::PromoCodeService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// PATCH /v1/companies/{id}/promo-codes/{promo_code_id} - выключить промокод, продлить срок или изменить лимиты
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    promo_code_service: web::Data<Arc<dyn PromoCodeService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+    request_data: web::Json<UpdatePromoCodeRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (company_id, promo_code_id) = path.into_inner();
+
+    match promo_code_service
+        .update_promo_code(user_id, company_id, promo_code_id, request_data.into_inner())
+        .await
+    {
+        Ok(promo_code) => HttpResponse::Ok().json(promo_code),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::UpdateReminderRulesRequest, traits::ReminderService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// PUT /v1/companies/{id}/reminder-rules - заменить правила напоминаний (owner/manager)
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    reminder_service: web::Data<Arc<dyn ReminderService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    request_data: web::Json<UpdateReminderRulesRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match reminder_service
+        .update_rules(user_id, path.into_inner(), request_data.into_inner())
+        .await
+    {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::UpdateServiceResourcesRequest, traits::CatalogService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// PUT /v1/companies/{id}/services/{service_id}/resources - заменить требования услуги к ресурсам
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    catalog_service: web::Data<Arc<dyn CatalogService + Send + Sync>>,
+    path: web::Path<(Uuid, Uuid)>,
+    request_data: web::Json<UpdateServiceResourcesRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let (company_id, service_id) = path.into_inner();
+    match catalog_service
+        .update_service_resources(user_id, company_id, service_id, request_data.into_inner())
+        .await
+    {
+        Ok(requirements) => HttpResponse::Ok().json(requirements),
+        Err(e) => HttpResponse::from(e),
+    }
+}
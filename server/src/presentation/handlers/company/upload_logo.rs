@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use actix_multipart::Multipart;
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::{
+    domain::traits::MediaService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+    presentation::multipart::read_file_field,
+};
+
+// PUT /v1/companies/{id}/logo - загрузить логотип (multipart/form-data, поле file), прежний заменяется
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    media_service: web::Data<Arc<dyn MediaService + Send + Sync>>,
+    path: web::Path<Uuid>,
+    payload: Multipart,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let bytes = match read_file_field(payload, media_service.max_upload_bytes()).await {
+        Ok(bytes) => bytes,
+        Err(e) => return HttpResponse::from(e),
+    };
+
+    match media_service.upload_logo(user_id, path.into_inner(), bytes).await {
+        Ok(image) => HttpResponse::Ok().json(image),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::{entities::CreateHoldRequest, traits::HoldService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/holds - временно забронировать слот на время оформления записи
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    hold_service: web::Data<Arc<dyn HoldService + Send + Sync>>,
+    request_data: web::Json<CreateHoldRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match hold_service.create_hold(user_id, request_data.into_inner()).await {
+        Ok(hold) => HttpResponse::Created().json(hold),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod confirm_hold;
+pub mod create_hold;
+pub mod release_hold;
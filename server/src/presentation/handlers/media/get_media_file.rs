@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+
+use crate::domain::traits::MediaService;
+
+// GET /v1/media/{key} - файл изображения из хранилища (открытый)
+pub async fn handler(
+    media_service: web::Data<Arc<dyn MediaService + Send + Sync>>,
+    path: web::Path<String>,
+) -> impl Responder {
+    match media_service.read_file(&path.into_inner()).await {
+        // Ключ файла уникален для каждой загрузки, поэтому содержимое по нему не меняется
+        Ok(Some((content_type, bytes))) => HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header(("Cache-Control", "public, max-age=31536000, immutable"))
+            .insert_header(("X-Content-Type-Options", "nosniff"))
+            .body(bytes),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Файл не найден"
+        })),
+        Err(e) => HttpResponse::from(e),
+    }
+}
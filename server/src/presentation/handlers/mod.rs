@@ -1,4 +1,15 @@
+pub mod admin;
+pub mod booking;
+pub mod booking_series;
+pub mod calendar;
+pub mod company;
 pub mod guest;
+pub mod hold;
+pub mod media;
+pub mod payment;
+pub mod queue;
+pub mod search;
 pub mod status;
 pub mod token;
 pub mod user;
+pub mod waitlist;
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::domain::errors::AppError;
+use crate::domain::traits::PaymentService;
+
+// POST /v1/payments/webhooks/{provider} - уведомление платежной системы, без авторизации:
+// подлинность проверяется по подписи
+pub async fn handler(
+    req: HttpRequest,
+    payment_service: web::Data<Arc<dyn PaymentService + Send + Sync>>,
+    path: web::Path<String>,
+    body: web::Bytes,
+) -> impl Responder {
+    // Подпись считается по телу как есть, поэтому оно не разбирается до проверки
+    let Ok(body) = std::str::from_utf8(&body) else {
+        return HttpResponse::from(AppError::Validation("Тело уведомления не в UTF-8".to_string()));
+    };
+    let headers: HashMap<String, String> = req
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str().to_lowercase(), value.to_str().ok()?.to_string())))
+        .collect();
+
+    match payment_service.handle_webhook(&path.into_inner(), &headers, body).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::domain::traits::QueueService;
+
+// GET /v1/queue-tickets/{id} - место в очереди и ожидаемое время (открытый, по id талона)
+pub async fn handler(
+    queue_service: web::Data<Arc<dyn QueueService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    match queue_service.get_ticket(path.into_inner()).await {
+        Ok(ticket) => HttpResponse::Ok().json(ticket),
+        Err(e) => HttpResponse::from(e),
+    }
+}
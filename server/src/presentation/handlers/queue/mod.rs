@@ -0,0 +1,3 @@
+pub mod change_ticket_status;
+pub mod get_ticket;
+pub mod stream_ticket;
@@ -0,0 +1,48 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::{HttpResponse, Responder, http::header, web};
+use futures_util::future::ready;
+use futures_util::stream::{self, StreamExt};
+use uuid::Uuid;
+
+use crate::domain::{entities::QueueTicketView, traits::QueueService};
+
+/// Комментарий раз в интервал не дает прокси закрыть молчащее соединение
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+fn sse_frame(ticket: &QueueTicketView) -> web::Bytes {
+    let data = serde_json::to_string(ticket).unwrap_or_default();
+    web::Bytes::from(format!("event: ticket\ndata: {}\n\n", data))
+}
+
+// GET /v1/queue-tickets/{id}/stream - изменения места в очереди (SSE, открытый)
+pub async fn handler(
+    queue_service: web::Data<Arc<dyn QueueService + Send + Sync>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let updates = match queue_service.ticket_updates(path.into_inner()).await {
+        Ok(updates) => updates,
+        Err(e) => return HttpResponse::from(e),
+    };
+
+    let keepalive = stream::unfold(actix_web::rt::time::interval(KEEPALIVE_INTERVAL), |mut interval| async move {
+        interval.tick().await;
+        Some((web::Bytes::from_static(b": keepalive\n\n"), interval))
+    });
+    // Конец обновлений помечается `None`: талон покинул очередь, и ответ закрывается несмотря на keepalive
+    let frames = updates
+        .map(|ticket| Some(sse_frame(&ticket)))
+        .chain(stream::once(ready(None)));
+    let body = stream::select(frames, keepalive.map(Some))
+        .take_while(|frame| ready(frame.is_some()))
+        .filter_map(ready)
+        .map(Ok::<_, Infallible>);
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(body)
+}
@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, web};
+
+use crate::domain::{entities::CompanySearchQuery, traits::SearchService};
+
+// GET /v1/search/companies?q=&city=&category=&...&cursor=&limit= - поиск компаний с фасетами
+pub async fn handler(
+    search_service: web::Data<Arc<dyn SearchService + Send + Sync>>,
+    query: web::Query<CompanySearchQuery>,
+) -> impl Responder {
+    match search_service.search_companies(query.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -2,11 +2,12 @@ use std::sync::Arc;
 
 use actix_web::{web, HttpResponse, Responder};
 
-use crate::{domain::{entities::{LoginRequest, LoginResponse, UserInfo}, traits::UserAuthRepository}, infrastructure::jwt::jwt_service::JwtService};
+use crate::{domain::{entities::{LoginRequest, LoginResponse, UserInfo}, traits::{MediaService, UserAuthRepository}}, infrastructure::jwt::jwt_service::JwtService};
 
 pub async fn handler(
     jwt_service: web::Data<JwtService>,
     user_auth_repository: web::Data<Arc<dyn UserAuthRepository + Send + Sync>>,
+    media_service: web::Data<Arc<dyn MediaService + Send + Sync>>,
     request_data: web::Json<LoginRequest>,
 ) -> impl Responder {
     let req = request_data.into_inner();
@@ -78,6 +79,11 @@ pub async fn handler(
             }
         };
 
+    let avatar = match media_service.user_avatar(user.id).await {
+        Ok(avatar) => avatar,
+        Err(e) => return HttpResponse::from(e),
+    };
+
     let response = LoginResponse {
         access_token,
         refresh_token,
@@ -87,7 +93,9 @@ pub async fn handler(
             first_name: user.first_name,
             last_name: user.last_name,
             email: user.email,
+            locale: user.locale,
             user_type_id: user.user_type_id,
+            avatar,
         },
     };
 
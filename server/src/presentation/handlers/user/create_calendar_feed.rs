@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::traits::CalendarService,
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/user/calendar-feed - выпустить ссылку на личный календарь, прежняя перестает работать
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    calendar_service: web::Data<Arc<dyn CalendarService + Send + Sync>>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match calendar_service.create_user_feed(user_id).await {
+        Ok(feed) => HttpResponse::Created().json(feed),
+        Err(e) => HttpResponse::from(e),
+    }
+}
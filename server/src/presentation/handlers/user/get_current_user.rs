@@ -3,7 +3,10 @@ use std::sync::Arc;
 use actix_web::{HttpRequest, HttpResponse, Responder, web};
 
 use crate::{
-    domain::{entities::UserInfo, traits::UserAuthRepository},
+    domain::{
+        entities::UserInfo,
+        traits::{MediaService, UserAuthRepository},
+    },
     infrastructure::jwt::{
         extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
     },
@@ -14,6 +17,7 @@ pub async fn handler(
     req: HttpRequest,
     jwt_service: web::Data<JwtService>,
     user_auth_repository: web::Data<Arc<dyn UserAuthRepository + Send + Sync>>,
+    media_service: web::Data<Arc<dyn MediaService + Send + Sync>>,
 ) -> impl Responder {
     // Извлекаем user_id из JWT токена
     let user_id = match extract_user_uuid(&req, &jwt_service).await {
@@ -24,13 +28,19 @@ pub async fn handler(
     // Ищем пользователя в БД по ID из токена
     match user_auth_repository.find_by_id(user_id).await {
         Ok(Some(user)) => {
+            let avatar = match media_service.user_avatar(user.id).await {
+                Ok(avatar) => avatar,
+                Err(e) => return HttpResponse::from(e),
+            };
             let user_info = UserInfo {
                 id: user.id,
                 username: user.username,
                 first_name: user.first_name,
                 last_name: user.last_name,
                 email: user.email,
+                locale: user.locale,
                 user_type_id: user.user_type_id,
+                avatar,
             };
             HttpResponse::Ok().json(user_info)
         }
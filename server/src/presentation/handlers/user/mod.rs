@@ -1,4 +1,11 @@
 pub mod get_current_user;
 pub mod update_current_user; 
 pub mod register_user;
-pub mod auth_user;
\ No newline at end of file
+pub mod auth_user;
+pub mod add_bookmark;
+pub mod list_bookmarks;
+pub mod remove_bookmark;
+pub mod create_calendar_feed;
+pub mod revoke_calendar_feed;
+pub mod upload_avatar;
+pub mod delete_avatar;
@@ -3,9 +3,10 @@ use std::sync::Arc;
 use actix_web::{HttpRequest, HttpResponse, Responder, web};
 
 use crate::{
+    application::notification_templates::is_supported_locale,
     domain::{
         entities::{UpdateUserRequest, UpdateUserResponse, UserInfo},
-        traits::UserAuthRepository,
+        traits::{MediaService, UserAuthRepository},
     },
     infrastructure::jwt::{
         extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
@@ -16,6 +17,7 @@ pub async fn handler(
     req: HttpRequest,
     jwt_service: web::Data<JwtService>,
     user_auth_repository: web::Data<Arc<dyn UserAuthRepository + Send + Sync>>,
+    media_service: web::Data<Arc<dyn MediaService + Send + Sync>>,
     request_data: web::Json<UpdateUserRequest>,
 ) -> impl Responder {
     let update_req = request_data.into_inner();
@@ -26,6 +28,14 @@ pub async fn handler(
         Err(response) => return response,
     };
 
+    if let Some(locale) = update_req.locale.as_deref()
+        && !is_supported_locale(locale)
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Неподдерживаемый язык уведомлений: {}", locale)
+        }));
+    }
+
     // Обновляем пользователя в БД
     match user_auth_repository
         .update_user_fields(
@@ -33,17 +43,24 @@ pub async fn handler(
             update_req.first_name.as_deref(),
             update_req.last_name.as_deref(),
             update_req.email.as_deref(),
+            update_req.locale.as_deref(),
         )
         .await
     {
         Ok(Some(updated_user)) => {
+            let avatar = match media_service.user_avatar(updated_user.id).await {
+                Ok(avatar) => avatar,
+                Err(e) => return HttpResponse::from(e),
+            };
             let user_info = UserInfo {
                 id: updated_user.id,
                 username: updated_user.username,
                 first_name: updated_user.first_name,
                 last_name: updated_user.last_name,
                 email: updated_user.email,
+                locale: updated_user.locale,
                 user_type_id: updated_user.user_type_id,
+                avatar,
             };
 
             HttpResponse::Ok().json(UpdateUserResponse {
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+
+use crate::{
+    domain::{entities::JoinWaitlistRequest, traits::WaitlistService},
+    infrastructure::jwt::{
+        extract_user_uuid::from_request as extract_user_uuid, jwt_service::JwtService,
+    },
+};
+
+// POST /v1/waitlist - встать в очередь на день без свободного времени
+pub async fn handler(
+    req: HttpRequest,
+    jwt_service: web::Data<JwtService>,
+    waitlist_service: web::Data<Arc<dyn WaitlistService + Send + Sync>>,
+    request_data: web::Json<JoinWaitlistRequest>,
+) -> impl Responder {
+    let user_id = match extract_user_uuid(&req, &jwt_service).await {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match waitlist_service.join_waitlist(user_id, request_data.into_inner()).await {
+        Ok(entry) => HttpResponse::Created().json(entry),
+        Err(e) => HttpResponse::from(e),
+    }
+}
@@ -0,0 +1,5 @@
+pub mod accept_offer;
+pub mod decline_offer;
+pub mod join_waitlist;
+pub mod leave_waitlist;
+pub mod list_waitlist;
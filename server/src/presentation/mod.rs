@@ -1,2 +1,4 @@
+pub mod errors;
 pub mod handlers;
+pub mod multipart;
 pub mod routes;
@@ -0,0 +1,29 @@
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
+
+use crate::domain::errors::AppError;
+
+/// Имя поля формы с файлом
+pub const FILE_FIELD: &str = "file";
+
+/// Содержимое поля `file` из multipart/form-data. Чтение прерывается, как только файл
+/// превышает `max_bytes`, поэтому большой запрос не оседает в памяти целиком.
+/// Заявленный клиентом тип содержимого не учитывается: формат определяется по самому файлу
+pub async fn read_file_field(mut payload: Multipart, max_bytes: usize) -> Result<Vec<u8>, AppError> {
+    let invalid = |e: actix_multipart::MultipartError| AppError::Validation(format!("Некорректная форма: {}", e));
+
+    while let Some(mut field) = payload.try_next().await.map_err(invalid)? {
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.try_next().await.map_err(invalid)? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(AppError::PayloadTooLarge(format!("Файл больше {} байт", max_bytes)));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        return Ok(bytes);
+    }
+    Err(AppError::Validation(format!("В форме нет поля {}", FILE_FIELD)))
+}
@@ -1,8 +1,39 @@
 use crate::presentation::handlers::{
+    admin::{list_jobs, retry_job, set_review_visibility},
+    booking::{
+        change_booking_status, create_booking, create_review, get_booking, get_booking_calendar,
+        get_booking_history, get_booking_payment, get_booking_reminders, get_checkin_qr, quote_booking,
+        reschedule_booking, start_booking_payment,
+    },
+    booking_series::{cancel_series, create_series, get_series, reschedule_series},
+    calendar::get_feed,
+    company::{
+        add_gallery_image, call_next_in_queue, check_in_booking, clear_customer_restriction,
+        create_company, create_customer_note, create_location, create_promo_code, create_resource,
+        create_schedule_exception, create_service, create_staff, create_staff_calendar_feed,
+        create_webhook, delete_customer_note, delete_gallery_image, delete_logo, delete_webhook,
+        get_analytics_report, get_booking_policy, get_booking_restriction, get_company,
+        get_customer, get_customer_restriction, get_queue_stats, get_reminder_rules,
+        get_service_resources, get_session, get_slots, join_queue, list_customers, list_promo_codes,
+        list_queue, list_resources, list_reviews, list_sessions, list_webhook_deliveries,
+        list_webhooks, reply_to_review, revoke_staff_calendar_feed, send_test_webhook,
+        set_customer_restriction, stream_slots, update_booking_policy, update_customer_note,
+        update_customer_tags, update_promo_code, update_reminder_rules, update_service_resources,
+        update_staff_schedule, update_webhook, upload_logo,
+    },
     guest::guest_zone,
+    hold::{confirm_hold, create_hold, release_hold},
+    media::get_media_file,
+    payment::payment_webhook,
+    queue::{change_ticket_status, get_ticket, stream_ticket},
+    search::search_companies,
     status::{db, server},
     token::refresh,
-    user::{auth_user, get_current_user, register_user, update_current_user},
+    user::{
+        add_bookmark, auth_user, create_calendar_feed, delete_avatar, get_current_user, list_bookmarks,
+        register_user, remove_bookmark, revoke_calendar_feed, update_current_user, upload_avatar,
+    },
+    waitlist::{accept_offer, decline_offer, join_waitlist, leave_waitlist, list_waitlist},
 };
 
 use actix_web::{Scope, web};
@@ -13,6 +44,17 @@ pub fn api_v1_routes() -> Scope {
         .service(status_routes())
         .service(user_routes())
         .service(guest_routes())
+        .service(company_routes())
+        .service(booking_routes())
+        .service(hold_routes())
+        .service(booking_series_routes())
+        .service(waitlist_routes())
+        .service(queue_routes())
+        .service(calendar_routes())
+        .service(search_routes())
+        .service(media_routes())
+        .service(payment_routes())
+        .service(admin_routes())
 }
 
 pub fn status_routes() -> Scope {
@@ -27,8 +69,143 @@ pub fn user_routes() -> Scope {
         .route("", web::patch().to(update_current_user::handler))
         .route("", web::put().to(register_user::handler))
         .route("", web::post().to(auth_user::handler))
+        .route("/bookmarks", web::get().to(list_bookmarks::handler))
+        .route("/bookmarks/{company_id}", web::put().to(add_bookmark::handler))
+        .route("/bookmarks/{company_id}", web::delete().to(remove_bookmark::handler))
+        .route("/calendar-feed", web::post().to(create_calendar_feed::handler))
+        .route("/calendar-feed", web::delete().to(revoke_calendar_feed::handler))
+        .route("/avatar", web::put().to(upload_avatar::handler))
+        .route("/avatar", web::delete().to(delete_avatar::handler))
 }
 
 pub fn guest_routes() -> Scope {
     web::scope("guest").route("", web::get().to(guest_zone::handler))
 }
+
+pub fn company_routes() -> Scope {
+    web::scope("companies")
+        .route("", web::post().to(create_company::handler))
+        .route("/{id}", web::get().to(get_company::handler))
+        .route("/{id}/logo", web::put().to(upload_logo::handler))
+        .route("/{id}/logo", web::delete().to(delete_logo::handler))
+        .route("/{id}/gallery", web::post().to(add_gallery_image::handler))
+        .route("/{id}/gallery/{media_id}", web::delete().to(delete_gallery_image::handler))
+        .route("/{id}/locations", web::post().to(create_location::handler))
+        .route("/{id}/services", web::post().to(create_service::handler))
+        .route("/{id}/services/{service_id}/resources", web::get().to(get_service_resources::handler))
+        .route("/{id}/services/{service_id}/resources", web::put().to(update_service_resources::handler))
+        .route("/{id}/resources", web::post().to(create_resource::handler))
+        .route("/{id}/resources", web::get().to(list_resources::handler))
+        .route("/{id}/staff", web::post().to(create_staff::handler))
+        .route("/{id}/staff/{staff_id}/schedule", web::put().to(update_staff_schedule::handler))
+        .route("/{id}/staff/{staff_id}/exceptions", web::post().to(create_schedule_exception::handler))
+        .route("/{id}/staff/{staff_id}/calendar-feed", web::post().to(create_staff_calendar_feed::handler))
+        .route("/{id}/staff/{staff_id}/calendar-feed", web::delete().to(revoke_staff_calendar_feed::handler))
+        .route("/{id}/slots", web::get().to(get_slots::handler))
+        .route("/{id}/slots/stream", web::get().to(stream_slots::handler))
+        .route("/{id}/sessions", web::get().to(list_sessions::handler))
+        .route("/{id}/sessions/{session_id}", web::get().to(get_session::handler))
+        .route("/{id}/booking-policy", web::get().to(get_booking_policy::handler))
+        .route("/{id}/booking-policy", web::put().to(update_booking_policy::handler))
+        .route("/{id}/booking-restriction", web::get().to(get_booking_restriction::handler))
+        .route("/{id}/reminder-rules", web::get().to(get_reminder_rules::handler))
+        .route("/{id}/reminder-rules", web::put().to(update_reminder_rules::handler))
+        .route("/{id}/reviews", web::get().to(list_reviews::handler))
+        .route("/{id}/reviews/{review_id}/reply", web::put().to(reply_to_review::handler))
+        .route("/{id}/customers", web::get().to(list_customers::handler))
+        .route("/{id}/customers/{customer_id}", web::get().to(get_customer::handler))
+        .route("/{id}/customers/{customer_id}/notes", web::post().to(create_customer_note::handler))
+        .route("/{id}/customers/{customer_id}/notes/{note_id}", web::patch().to(update_customer_note::handler))
+        .route("/{id}/customers/{customer_id}/notes/{note_id}", web::delete().to(delete_customer_note::handler))
+        .route("/{id}/customers/{customer_id}/tags", web::put().to(update_customer_tags::handler))
+        .route("/{id}/customers/{customer_id}/restriction", web::get().to(get_customer_restriction::handler))
+        .route("/{id}/customers/{customer_id}/restriction", web::put().to(set_customer_restriction::handler))
+        .route("/{id}/customers/{customer_id}/restriction", web::delete().to(clear_customer_restriction::handler))
+        .route("/{id}/checkin", web::post().to(check_in_booking::handler))
+        .route("/{id}/queue", web::post().to(join_queue::handler))
+        .route("/{id}/queue", web::get().to(list_queue::handler))
+        .route("/{id}/queue/call-next", web::post().to(call_next_in_queue::handler))
+        .route("/{id}/queue/stats", web::get().to(get_queue_stats::handler))
+        .route("/{id}/analytics/{report}", web::get().to(get_analytics_report::handler))
+        .route("/{id}/promo-codes", web::post().to(create_promo_code::handler))
+        .route("/{id}/promo-codes", web::get().to(list_promo_codes::handler))
+        .route("/{id}/promo-codes/{promo_code_id}", web::patch().to(update_promo_code::handler))
+        .route("/{id}/webhooks", web::post().to(create_webhook::handler))
+        .route("/{id}/webhooks", web::get().to(list_webhooks::handler))
+        .route("/{id}/webhooks/{webhook_id}", web::patch().to(update_webhook::handler))
+        .route("/{id}/webhooks/{webhook_id}", web::delete().to(delete_webhook::handler))
+        .route("/{id}/webhooks/{webhook_id}/deliveries", web::get().to(list_webhook_deliveries::handler))
+        .route("/{id}/webhooks/{webhook_id}/test", web::post().to(send_test_webhook::handler))
+}
+
+pub fn booking_routes() -> Scope {
+    web::scope("bookings")
+        .route("", web::post().to(create_booking::handler))
+        .route("/quote", web::post().to(quote_booking::handler))
+        // Раньше "/{id}", иначе "{id}.ics" целиком примется за идентификатор
+        .route("/{id}.ics", web::get().to(get_booking_calendar::handler))
+        .route("/{id}", web::get().to(get_booking::handler))
+        .route("/{id}/status", web::post().to(change_booking_status::handler))
+        .route("/{id}/reschedule", web::post().to(reschedule_booking::handler))
+        .route("/{id}/checkin-qr", web::get().to(get_checkin_qr::handler))
+        .route("/{id}/history", web::get().to(get_booking_history::handler))
+        .route("/{id}/reminders", web::get().to(get_booking_reminders::handler))
+        .route("/{id}/review", web::post().to(create_review::handler))
+        .route("/{id}/payment", web::get().to(get_booking_payment::handler))
+        .route("/{id}/payment", web::post().to(start_booking_payment::handler))
+}
+
+pub fn hold_routes() -> Scope {
+    web::scope("holds")
+        .route("", web::post().to(create_hold::handler))
+        .route("/{id}", web::delete().to(release_hold::handler))
+        .route("/{id}/confirm", web::post().to(confirm_hold::handler))
+}
+
+pub fn booking_series_routes() -> Scope {
+    web::scope("booking-series")
+        .route("", web::post().to(create_series::handler))
+        .route("/{id}", web::get().to(get_series::handler))
+        .route("/{id}/cancel", web::post().to(cancel_series::handler))
+        .route("/{id}/reschedule", web::post().to(reschedule_series::handler))
+}
+
+pub fn waitlist_routes() -> Scope {
+    web::scope("waitlist")
+        .route("", web::post().to(join_waitlist::handler))
+        .route("", web::get().to(list_waitlist::handler))
+        .route("/{id}", web::delete().to(leave_waitlist::handler))
+        .route("/{id}/accept", web::post().to(accept_offer::handler))
+        .route("/{id}/decline", web::post().to(decline_offer::handler))
+}
+
+pub fn queue_routes() -> Scope {
+    web::scope("queue-tickets")
+        .route("/{id}", web::get().to(get_ticket::handler))
+        .route("/{id}/stream", web::get().to(stream_ticket::handler))
+        .route("/{id}/status", web::post().to(change_ticket_status::handler))
+}
+
+pub fn calendar_routes() -> Scope {
+    web::scope("calendar").route("/{token}.ics", web::get().to(get_feed::handler))
+}
+
+pub fn search_routes() -> Scope {
+    web::scope("search").route("/companies", web::get().to(search_companies::handler))
+}
+
+pub fn media_routes() -> Scope {
+    // Ключ файла содержит `/`, поэтому сегмент захватывает остаток пути
+    web::scope("media").route("/{key:.*}", web::get().to(get_media_file::handler))
+}
+
+pub fn payment_routes() -> Scope {
+    web::scope("payments").route("/webhooks/{provider}", web::post().to(payment_webhook::handler))
+}
+
+pub fn admin_routes() -> Scope {
+    web::scope("admin")
+        .route("/jobs", web::get().to(list_jobs::handler))
+        .route("/jobs/{id}/retry", web::post().to(retry_job::handler))
+        .route("/reviews/{id}/visibility", web::put().to(set_review_visibility::handler))
+}
@@ -0,0 +1,311 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, NaiveDate, SubsecRound, Utc};
+use common::ManualClock;
+use serde_json::{Value, json};
+use server::application::analytics_service::AnalyticsServiceImpl;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::domain::entities::{
+    AnalyticsQuery, AnalyticsReportKind, BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest,
+    CreateStaffRequest, JobStatus, JobsQuery, ReportFormat,
+};
+use server::domain::traits::{AnalyticsService, BookingService, CatalogService, Clock, CompanyRepository, JobRepository};
+use server::infrastructure::job_queue::{JobQueue, JobWorker};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::maintenance_jobs::{
+    ANALYTICS_REFRESH_DEDUPE_KEY, AnalyticsRefreshHandler, AnalyticsRefreshJob,
+};
+use server::infrastructure::postgres_analytics_repository::PostgreSQLAnalyticsRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_job_repository::PostgreSQLJobRepository;
+use server::presentation::routes::api_v1_routes;
+use sqlx::PgPool;
+
+fn booking_at(seed: &common::Seed, day: NaiveDate, hour: u32) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: day.and_hms_opt(hour, 0, 0).unwrap().and_utc(),
+        promo_code: None,
+    }
+}
+
+fn status(status: BookingStatus) -> ChangeBookingStatusRequest {
+    ChangeBookingStatusRequest { status, reason: None }
+}
+
+/// Пересчитать представления аналитики так же, как это делает фоновая задача
+async fn refresh_analytics(pool: &PgPool, clock: Arc<ManualClock>) {
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = Arc::new(PostgreSQLJobRepository::new(pool.clone()));
+    let queue = JobQueue::new(job_repository.clone());
+    queue
+        .enqueue_at(
+            &AnalyticsRefreshJob { every_minutes: 15 },
+            Some(clock.now()),
+            Some(ANALYTICS_REFRESH_DEDUPE_KEY.to_string()),
+        )
+        .await
+        .unwrap();
+    let worker = JobWorker::new(job_repository, clock.clone())
+        .register(AnalyticsRefreshHandler::new(pool.clone(), queue, clock));
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+}
+
+#[actix_web::test]
+async fn managers_get_reports_after_refresh_in_json_and_csv() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let boris = common::create_user(&pool, "boris").await;
+    let carol = common::create_user(&pool, "carol").await;
+    let dave = common::create_user(&pool, "dave").await;
+    let nurse = common::create_user(&pool, "nurse").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    CatalogServiceImpl::new(company_repository.clone())
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: "Медсестра".to_string(),
+                user_id: Some(nurse.id),
+                service_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now().trunc_subsecs(0)));
+    let bookings = common::booking_services(&pool, clock.clone()).bookings;
+    let analytics_service: Arc<dyn AnalyticsService + Send + Sync> = Arc::new(AnalyticsServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLAnalyticsRepository::new(pool.clone())),
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(analytics_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let first_day = Utc::now().date_naive() + Duration::days(1);
+    let second_day = first_day + Duration::days(1);
+    let completed = bookings.create_booking(anna.id, booking_at(&seed, first_day, 10)).await.unwrap();
+    let missed = bookings.create_booking(boris.id, booking_at(&seed, first_day, 11)).await.unwrap();
+    let cancelled = bookings.create_booking(carol.id, booking_at(&seed, first_day, 12)).await.unwrap();
+    let arrived = bookings.create_booking(dave.id, booking_at(&seed, first_day, 14)).await.unwrap();
+    bookings.create_booking(anna.id, booking_at(&seed, second_day, 10)).await.unwrap();
+    bookings.create_booking(boris.id, booking_at(&seed, second_day, 11)).await.unwrap();
+
+    bookings.change_status(carol.id, cancelled.id, status(BookingStatus::CancelledByCustomer)).await.unwrap();
+    clock.advance(Duration::days(2));
+    bookings.change_status(seed.owner.id, completed.id, status(BookingStatus::Completed)).await.unwrap();
+    bookings.change_status(seed.owner.id, missed.id, status(BookingStatus::NoShow)).await.unwrap();
+    bookings.change_status(seed.owner.id, arrived.id, status(BookingStatus::CheckedIn)).await.unwrap();
+
+    // Новая цена услуги не меняет выручку по уже сделанным записям
+    sqlx::query("UPDATE services SET price_amount = 150000 WHERE id = $1")
+        .bind(seed.service_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let report = |user_id, name: &str, query: String| {
+        test::TestRequest::get()
+            .uri(&format!("/v1/companies/{}/analytics/{}?{}", seed.company_id, name, query))
+            .insert_header(("Authorization", bearer(user_id)))
+            .to_request()
+    };
+    let period = format!("from={}&to={}", first_day, second_day);
+    let rows = |body: &Value| body["rows"].as_array().unwrap().clone();
+
+    // До пересчета представления отчеты не видят новых записей
+    let response = test::call_service(&app, report(seed.owner.id, "bookings", period.clone())).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert!(rows(&body).is_empty());
+
+    refresh_analytics(&pool, clock.clone()).await;
+
+    let body: Value =
+        test::read_body_json(test::call_service(&app, report(seed.owner.id, "bookings", period.clone())).await).await;
+    assert_eq!(body["timezone"], "UTC");
+    assert_eq!(body["refreshed_at"], json!(clock.now()));
+    assert_eq!(
+        rows(&body),
+        vec![
+            json!({
+                "date": first_day, "service_id": seed.service_id, "service_name": "Консультация",
+                "staff_id": seed.staff_id, "staff_name": "Доктор",
+                "bookings": 4, "completed": 1, "cancelled": 1, "no_show": 1,
+            }),
+            json!({
+                "date": second_day, "service_id": seed.service_id, "service_name": "Консультация",
+                "staff_id": seed.staff_id, "staff_name": "Доктор",
+                "bookings": 2, "completed": 0, "cancelled": 0, "no_show": 0,
+            }),
+        ]
+    );
+
+    // Неявка и отмена не занимают время сотрудника; медсестра без расписания в отчет не попадает
+    let body: Value =
+        test::read_body_json(test::call_service(&app, report(seed.owner.id, "utilization", period.clone())).await)
+            .await;
+    assert_eq!(
+        rows(&body),
+        vec![
+            json!({
+                "date": first_day, "staff_id": seed.staff_id, "staff_name": "Доктор",
+                "available_minutes": 720, "booked_minutes": 120, "utilization": 0.167,
+            }),
+            json!({
+                "date": second_day, "staff_id": seed.staff_id, "staff_name": "Доктор",
+                "available_minutes": 720, "booked_minutes": 120, "utilization": 0.167,
+            }),
+        ]
+    );
+
+    let body: Value =
+        test::read_body_json(test::call_service(&app, report(seed.owner.id, "cancellations", period.clone())).await)
+            .await;
+    assert_eq!(
+        rows(&body)[0],
+        json!({
+            "date": first_day, "bookings": 4, "cancelled_by_customer": 1, "cancelled_by_company": 0,
+            "no_show": 1, "cancellation_rate": 0.25, "no_show_rate": 0.25,
+        })
+    );
+
+    // Выручка - по оказанным услугам и по цене на момент записи: завершенный визит и клиент,
+    // который уже пришел
+    let body: Value =
+        test::read_body_json(test::call_service(&app, report(seed.owner.id, "revenue", period.clone())).await).await;
+    assert_eq!(
+        rows(&body),
+        vec![json!({ "date": first_day, "currency": "RUB", "visits": 2, "revenue": 200_000, "discount": 0 })]
+    );
+
+    // Борис не пришел в первый день, поэтому новым клиентом считается во второй
+    let body: Value =
+        test::read_body_json(test::call_service(&app, report(seed.owner.id, "customers", period.clone())).await).await;
+    assert_eq!(
+        rows(&body),
+        vec![
+            json!({ "date": first_day, "new_customers": 2, "returning_customers": 0 }),
+            json!({ "date": second_day, "new_customers": 1, "returning_customers": 1 }),
+        ]
+    );
+
+    let response =
+        test::call_service(&app, report(seed.owner.id, "cancellations", format!("{}&format=csv", period))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/csv; charset=utf-8");
+    assert_eq!(
+        response.headers().get("content-disposition").unwrap().to_str().unwrap(),
+        format!("attachment; filename=\"cancellations-{}-{}.csv\"", first_day, second_day)
+    );
+    let csv = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    assert_eq!(
+        csv,
+        format!(
+            "date,bookings,cancelled_by_customer,cancelled_by_company,no_show,cancellation_rate,no_show_rate\n\
+             {},4,1,0,1,0.25,0.25\n{},2,0,0,0,0.0,0.0\n",
+            first_day, second_day
+        )
+    );
+
+    // Выручку видят только владелец и менеджеры
+    assert_eq!(
+        test::call_service(&app, report(nurse.id, "revenue", period.clone())).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let reversed = format!("from={}&to={}", second_day, first_day);
+    assert_eq!(
+        test::call_service(&app, report(seed.owner.id, "revenue", reversed)).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    let too_long = format!("from={}&to={}", first_day, first_day + Duration::days(366));
+    assert_eq!(
+        test::call_service(&app, report(seed.owner.id, "revenue", too_long)).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        test::call_service(&app, report(seed.owner.id, "profit", period)).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn reports_bucket_days_in_company_timezone_and_refresh_reschedules() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let clock = Arc::new(ManualClock::new(Utc::now().trunc_subsecs(0)));
+    let bookings = common::booking_services(&pool, clock.clone()).bookings;
+    let analytics = AnalyticsServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLAnalyticsRepository::new(pool.clone())),
+    );
+
+    // Вечерняя запись по UTC во Владивостоке (UTC+10) уже приходится на следующее утро
+    let day = Utc::now().date_naive() + Duration::days(1);
+    bookings.create_booking(anna.id, booking_at(&seed, day, 19)).await.unwrap();
+    sqlx::query("UPDATE companies SET timezone = 'Asia/Vladivostok' WHERE id = $1")
+        .bind(seed.company_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    refresh_analytics(&pool, clock.clone()).await;
+
+    let query = AnalyticsQuery {
+        from: day,
+        to: day + Duration::days(1),
+        format: ReportFormat::Json,
+    };
+    let report = analytics
+        .report(seed.owner.id, seed.company_id, AnalyticsReportKind::Bookings, &query)
+        .await
+        .unwrap();
+    assert_eq!(report.timezone, "Asia/Vladivostok");
+    let body = serde_json::to_value(&report.rows).unwrap();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["date"], json!(day + Duration::days(1)));
+    assert_eq!(body[0]["bookings"], 1);
+
+    let csv = analytics
+        .report_csv(seed.owner.id, seed.company_id, AnalyticsReportKind::Utilization, &query)
+        .await
+        .unwrap();
+    assert_eq!(
+        csv,
+        format!(
+            "date,staff_id,staff_name,available_minutes,booked_minutes,utilization\n\
+             {},{},Доктор,720,0,0.0\n{},{},Доктор,720,60,0.083\n",
+            day,
+            seed.staff_id,
+            day + Duration::days(1),
+            seed.staff_id
+        )
+    );
+
+    // Задача пересчета сама ставит следующий запуск
+    let job_repository = PostgreSQLJobRepository::new(pool.clone());
+    let next = job_repository
+        .list_jobs(&JobsQuery { status: Some(JobStatus::Pending), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(next.len(), 1);
+    assert_eq!(next[0].kind, "analytics_refresh");
+    assert_eq!(next[0].run_at, clock.now() + Duration::minutes(15));
+}
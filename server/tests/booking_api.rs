@@ -0,0 +1,128 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use futures_util::future::join_all;
+use server::domain::entities::{BookingStatus, NewBooking};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingRepository, BookingService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::presentation::routes::api_v1_routes;
+use sqlx::PgPool;
+
+fn booking_service(pool: &PgPool) -> Arc<dyn BookingService + Send + Sync> {
+    common::booking_services(pool, Arc::new(SystemClock)).bookings
+}
+
+#[actix_web::test]
+async fn concurrent_requests_for_same_slot_book_it_once() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let jwt_service = JwtService::new();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(booking_service(&pool)))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let body = serde_json::json!({
+        "company_id": seed.company_id,
+        "service_id": seed.service_id,
+        "staff_id": seed.staff_id,
+        "starts_at": common::tomorrow_at(10, 0),
+    });
+
+    let mut tokens = Vec::new();
+    for i in 0..8 {
+        let customer = common::create_user(&pool, &format!("customer_{}", i)).await;
+        tokens.push(jwt_service.generate_access_token(customer.id, "").unwrap());
+    }
+
+    let responses = join_all(tokens.iter().map(|token| {
+        let request = test::TestRequest::post()
+            .uri("/v1/bookings")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(&body)
+            .to_request();
+        test::call_service(&app, request)
+    }))
+    .await;
+
+    let created = responses.iter().filter(|r| r.status() == StatusCode::CREATED).count();
+    let conflicts = responses.iter().filter(|r| r.status() == StatusCode::CONFLICT).count();
+    assert_eq!(created, 1);
+    assert_eq!(conflicts, tokens.len() - 1);
+}
+
+#[actix_web::test]
+async fn database_rejects_overlapping_bookings_under_concurrency() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let repository = PostgreSQLBookingRepository::new(pool.clone());
+
+    // Разные, но пересекающиеся интервалы в обход проверки в сервисе
+    let inserts = (0..10).map(|i| {
+        let starts_at = common::tomorrow_at(10, 0) + chrono::Duration::minutes(i * 5);
+        let booking = NewBooking {
+            customer_id: seed.owner.id,
+            company_id: seed.company_id,
+            service_id: seed.service_id,
+            staff_id: seed.staff_id,
+            location_id: seed.location_id,
+            starts_at,
+            ends_at: starts_at + chrono::Duration::hours(1),
+            blocked_from: starts_at,
+            blocked_to: starts_at + chrono::Duration::hours(1),
+            status: BookingStatus::Confirmed,
+            capacity: 1,
+            resource_ids: Vec::new(),
+            promo: None,
+        };
+        let repository = &repository;
+        async move { repository.create_booking(&booking).await }
+    });
+    let results = join_all(inserts).await;
+
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+    assert!(results
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .all(|e| *e == AppError::Conflict("Слот уже занят".to_string())));
+}
+
+#[actix_web::test]
+async fn back_to_back_booking_is_accepted_and_overlap_is_rejected() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let service = booking_service(&pool);
+    let customer = common::create_user(&pool, "customer").await;
+
+    let request = |hour: u32, minute: u32| server::domain::entities::CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, minute),
+        promo_code: None,
+    };
+
+    let first = service.create_booking(customer.id, request(10, 0)).await.unwrap();
+    assert_eq!(first.status, BookingStatus::Confirmed);
+    assert_eq!(first.location_id, seed.location_id);
+
+    assert!(service.create_booking(customer.id, request(11, 0)).await.is_ok());
+    assert!(matches!(
+        service.create_booking(customer.id, request(10, 30)).await,
+        Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+        service.create_booking(customer.id, request(21, 0)).await,
+        Err(AppError::Validation(_))
+    ));
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use std::sync::Arc;
+
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, RescheduleBookingRequest,
+    UpdateBookingPolicyRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::BookingService;
+use server::infrastructure::clock::SystemClock;
+use sqlx::PgPool;
+
+fn booking_service(pool: &PgPool) -> Arc<dyn BookingService + Send + Sync> {
+    common::booking_services(pool, Arc::new(SystemClock)).bookings
+}
+
+fn booking_at(seed: &common::Seed, hour: u32, minute: u32) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, minute),
+        promo_code: None,
+    }
+}
+
+fn status(status: BookingStatus) -> ChangeBookingStatusRequest {
+    ChangeBookingStatusRequest { status, reason: None }
+}
+
+#[actix_web::test]
+async fn reschedule_frees_old_slot_and_records_history() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let service = booking_service(&pool);
+    let customer = common::create_user(&pool, "customer").await;
+    let other = common::create_user(&pool, "other").await;
+
+    let booking = service.create_booking(customer.id, booking_at(&seed, 10, 0)).await.unwrap();
+    service.create_booking(other.id, booking_at(&seed, 12, 0)).await.unwrap();
+
+    // Перенос на полчаса пересекается только с собственным старым интервалом
+    let moved = service
+        .reschedule_booking(
+            customer.id,
+            booking.id,
+            RescheduleBookingRequest {
+                starts_at: common::tomorrow_at(10, 30),
+                staff_id: None,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(moved.starts_at, common::tomorrow_at(10, 30));
+
+    // На занятый слот перенести нельзя
+    assert!(matches!(
+        service
+            .reschedule_booking(
+                customer.id,
+                booking.id,
+                RescheduleBookingRequest {
+                    starts_at: common::tomorrow_at(12, 0),
+                    staff_id: None,
+                    location_id: None,
+                },
+            )
+            .await,
+        Err(AppError::Conflict(_))
+    ));
+
+    // Старое время снова свободно
+    assert!(service.create_booking(other.id, booking_at(&seed, 9, 0)).await.is_ok());
+
+    let history = service.booking_history(customer.id, booking.id).await.unwrap();
+    let types: Vec<&str> = history.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, ["created", "rescheduled"]);
+    assert_eq!(history[1].previous_starts_at, Some(common::tomorrow_at(10, 0)));
+}
+
+#[actix_web::test]
+async fn cancelled_booking_releases_slot_and_is_final() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let service = booking_service(&pool);
+    let customer = common::create_user(&pool, "customer").await;
+    let other = common::create_user(&pool, "other").await;
+
+    let booking = service.create_booking(customer.id, booking_at(&seed, 10, 0)).await.unwrap();
+
+    // Клиент не может отметить себе визит
+    assert!(matches!(
+        service.change_status(customer.id, booking.id, status(BookingStatus::CheckedIn)).await,
+        Err(AppError::Forbidden(_))
+    ));
+
+    let cancelled = service
+        .change_status(customer.id, booking.id, status(BookingStatus::CancelledByCustomer))
+        .await
+        .unwrap();
+    assert_eq!(cancelled.status, BookingStatus::CancelledByCustomer);
+
+    assert!(matches!(
+        service.change_status(seed.owner.id, booking.id, status(BookingStatus::Completed)).await,
+        Err(AppError::Conflict(_))
+    ));
+    assert!(service.create_booking(other.id, booking_at(&seed, 10, 0)).await.is_ok());
+}
+
+#[actix_web::test]
+async fn policy_blocks_late_customer_cancellation_but_not_company() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let service = booking_service(&pool);
+    let customer = common::create_user(&pool, "customer").await;
+
+    let booking = service.create_booking(customer.id, booking_at(&seed, 10, 0)).await.unwrap();
+
+    let policy = || UpdateBookingPolicyRequest {
+        customer_cancel_min_hours: 72,
+        customer_reschedule_min_hours: 72,
+        no_show_limit: None,
+        no_show_window_days: None,
+        no_show_action: None,
+        deposit_refund_min_hours: None,
+    };
+    assert!(matches!(
+        service
+            .update_policy(customer.id, seed.company_id, policy())
+            .await,
+        Err(AppError::Forbidden(_))
+    ));
+    service.update_policy(seed.owner.id, seed.company_id, policy()).await.unwrap();
+
+    assert!(matches!(
+        service
+            .change_status(customer.id, booking.id, status(BookingStatus::CancelledByCustomer))
+            .await,
+        Err(AppError::Forbidden(_))
+    ));
+    let cancelled = service
+        .change_status(seed.owner.id, booking.id, status(BookingStatus::CancelledByCompany))
+        .await
+        .unwrap();
+    assert_eq!(cancelled.status, BookingStatus::CancelledByCompany);
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use server::domain::entities::{
+    BookingStatus, CancelSeriesRequest, CreateBookingRequest, CreateBookingSeriesRequest, RescheduleSeriesRequest,
+};
+use server::domain::traits::{BookingSeriesService, BookingService};
+use server::infrastructure::clock::SystemClock;
+
+fn series(seed: &common::Seed, rrule: &str, skip_conflicts: bool) -> CreateBookingSeriesRequest {
+    CreateBookingSeriesRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(10, 0),
+        rrule: rrule.to_string(),
+        skip_conflicts,
+    }
+}
+
+fn booking_at(seed: &common::Seed, starts_at: chrono::DateTime<chrono::Utc>) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at,
+        promo_code: None,
+    }
+}
+
+#[actix_web::test]
+async fn series_reports_conflicts_and_can_skip_them() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let services = common::booking_services(&pool, Arc::new(SystemClock));
+    let customer = common::create_user(&pool, "customer").await;
+    let other = common::create_user(&pool, "other").await;
+
+    let taken = common::tomorrow_at(10, 0) + Duration::weeks(1);
+    services.bookings.create_booking(other.id, booking_at(&seed, taken)).await.unwrap();
+
+    let rejected = services
+        .series
+        .create_series(customer.id, series(&seed, "FREQ=WEEKLY;COUNT=4", false))
+        .await
+        .unwrap();
+    assert!(rejected.series.is_none());
+    assert_eq!(rejected.conflicts.len(), 1);
+    assert_eq!(rejected.conflicts[0].starts_at, taken);
+
+    let created = services
+        .series
+        .create_series(customer.id, series(&seed, "FREQ=WEEKLY;COUNT=4", true))
+        .await
+        .unwrap();
+    let series_id = created.series.unwrap().id;
+    assert_eq!(created.bookings.len(), 3);
+    assert_eq!(created.conflicts.len(), 1);
+    assert!(created.bookings.iter().all(|b| b.series_id == Some(series_id)));
+
+    // Вхождение - обычная запись: его можно отменить по отдельности
+    let single = services
+        .bookings
+        .change_status(
+            customer.id,
+            created.bookings[0].id,
+            server::domain::entities::ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(single.status, BookingStatus::CancelledByCustomer);
+
+    // "Это и следующие" начиная со второго оставшегося вхождения
+    let cancelled = services
+        .series
+        .cancel_following(
+            customer.id,
+            series_id,
+            CancelSeriesRequest {
+                from_booking_id: created.bookings[1].id,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(cancelled.bookings.len(), 2);
+    assert!(cancelled.bookings.iter().all(|b| b.status == BookingStatus::CancelledByCustomer));
+}
+
+#[actix_web::test]
+async fn this_and_following_moves_atomically_even_onto_own_slots() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let services = common::booking_services(&pool, Arc::new(SystemClock));
+    let customer = common::create_user(&pool, "customer").await;
+    let other = common::create_user(&pool, "other").await;
+
+    let created = services
+        .series
+        .create_series(customer.id, series(&seed, "FREQ=DAILY;COUNT=3", false))
+        .await
+        .unwrap();
+    let series_id = created.series.unwrap().id;
+    let first = &created.bookings[0];
+
+    // Сдвиг на сутки: каждое вхождение переезжает на место следующего
+    let moved = services
+        .series
+        .reschedule_following(
+            customer.id,
+            series_id,
+            RescheduleSeriesRequest {
+                from_booking_id: first.id,
+                starts_at: first.starts_at + Duration::days(1),
+                staff_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(moved.conflicts.is_empty());
+    let starts: Vec<_> = moved.bookings.iter().map(|b| b.starts_at).collect();
+    assert_eq!(
+        starts,
+        (1..=3).map(|day| common::tomorrow_at(10, 0) + Duration::days(day)).collect::<Vec<_>>()
+    );
+
+    // Конфликт на одном вхождении: ничего не переносится
+    services
+        .bookings
+        .create_booking(other.id, booking_at(&seed, common::tomorrow_at(12, 0) + Duration::days(3)))
+        .await
+        .unwrap();
+    let second = &created.bookings[1];
+    let rejected = services
+        .series
+        .reschedule_following(
+            customer.id,
+            series_id,
+            RescheduleSeriesRequest {
+                from_booking_id: second.id,
+                starts_at: common::tomorrow_at(12, 0) + Duration::days(2),
+                staff_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(rejected.conflicts.len(), 1);
+    assert!(rejected.bookings.is_empty());
+
+    let current = services.series.get_series(customer.id, series_id).await.unwrap();
+    assert_eq!(
+        current.bookings.iter().map(|b| b.starts_at).collect::<Vec<_>>(),
+        starts
+    );
+}
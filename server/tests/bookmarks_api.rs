@@ -0,0 +1,95 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use server::application::bookmark_service::BookmarkServiceImpl;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::media_service::MediaServiceImpl;
+use server::domain::traits::{BookmarkRepository, BookmarkService, CatalogService, CompanyRepository, MediaService};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::local_media_storage::LocalMediaStorage;
+use server::infrastructure::postgres_bookmark_repository::PostgreSQLBookmarkRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_media_repository::PostgreSQLMediaRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::Value;
+
+#[actix_web::test]
+async fn bookmarks_are_listed_and_flagged_on_company() {
+    let Some(pool) = common::test_pool().await else { return };
+    let first = common::seed_company(&pool).await;
+    let second = common::seed_company(&pool).await;
+    let user = common::create_user(&pool, "customer").await;
+    let jwt_service = JwtService::new();
+    let token = format!("Bearer {}", jwt_service.generate_access_token(user.id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let bookmark_repository: Arc<dyn BookmarkRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookmarkRepository::new(pool.clone()));
+    let catalog_service: Arc<dyn CatalogService + Send + Sync> =
+        Arc::new(CatalogServiceImpl::new(company_repository.clone()));
+    let bookmark_service: Arc<dyn BookmarkService + Send + Sync> =
+        Arc::new(BookmarkServiceImpl::new(company_repository.clone(), bookmark_repository));
+    let media_service: Arc<dyn MediaService + Send + Sync> = Arc::new(MediaServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLMediaRepository::new(pool.clone())),
+        Arc::new(LocalMediaStorage::new(std::env::temp_dir().join("locationx_media"), "/v1/media")),
+    ));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(catalog_service))
+            .app_data(web::Data::new(bookmark_service))
+            .app_data(web::Data::new(media_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    // Повторное добавление не ошибка
+    for company_id in [first.company_id, second.company_id, second.company_id] {
+        let request = test::TestRequest::put()
+            .uri(&format!("/v1/user/bookmarks/{}", company_id))
+            .insert_header(("Authorization", token.as_str()))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NO_CONTENT);
+    }
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/v1/user/bookmarks/{}", uuid::Uuid::new_v4()))
+        .insert_header(("Authorization", token.as_str()))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get()
+        .uri("/v1/user/bookmarks?limit=1")
+        .insert_header(("Authorization", token.as_str()))
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(page["total"], 2);
+    assert_eq!(page["bookmarks"].as_array().unwrap().len(), 1);
+    assert_eq!(page["bookmarks"][0]["company"]["id"], second.company_id.to_string());
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/v1/companies/{}", first.company_id))
+        .insert_header(("Authorization", token.as_str()))
+        .to_request();
+    let company: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(company["bookmarked"], true);
+    assert_eq!(company["name"], "Клиника");
+
+    let request = test::TestRequest::delete()
+        .uri(&format!("/v1/user/bookmarks/{}", first.company_id))
+        .insert_header(("Authorization", token.as_str()))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NO_CONTENT);
+
+    // Без токена карточка открыта, отметки нет
+    let request = test::TestRequest::get()
+        .uri(&format!("/v1/companies/{}", first.company_id))
+        .to_request();
+    let company: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(company["bookmarked"], false);
+}
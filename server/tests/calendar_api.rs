@@ -0,0 +1,157 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use server::application::calendar_service::CalendarServiceImpl;
+use server::domain::entities::{CreateBookingRequest, RescheduleBookingRequest};
+use server::domain::traits::{BookingService, CalendarService, CompanyRepository};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_calendar_repository::PostgreSQLCalendarRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::Value;
+
+/// Строки календаря без переносов длинных строк
+fn lines(body: &[u8]) -> Vec<String> {
+    String::from_utf8(body.to_vec())
+        .unwrap()
+        .replace("\r\n ", "")
+        .split("\r\n")
+        .map(str::to_string)
+        .collect()
+}
+
+#[actix_web::test]
+async fn bookings_are_exported_to_ics_and_token_feeds() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "patient").await;
+    let stranger = common::create_user(&pool, "stranger").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+    let (customer_token, stranger_token, owner_token) =
+        (bearer(customer.id), bearer(stranger.id), bearer(seed.owner.id));
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let bookings = common::booking_services(&pool, Arc::new(SystemClock)).bookings;
+    let calendar_service: Arc<dyn CalendarService + Send + Sync> = Arc::new(CalendarServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLCalendarRepository::new(pool.clone())),
+        Arc::new(SystemClock),
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(calendar_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let booking = bookings
+        .create_booking(
+            customer.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: common::tomorrow_at(10, 0),
+                promo_code: None,
+            },
+        )
+        .await
+        .unwrap();
+    let uid = format!("UID:{}@locationx", booking.id);
+    let start = format!("DTSTART;TZID=UTC:{}", common::tomorrow_at(10, 0).format("%Y%m%dT%H%M%S"));
+
+    // Файл одной записи: клиенту и сотрудникам, посторонним запись не видна
+    let request = test::TestRequest::get()
+        .uri(&format!("/v1/bookings/{}.ics", booking.id))
+        .insert_header(("Authorization", customer_token.as_str()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/calendar; charset=utf-8");
+    let calendar = lines(&test::read_body(response).await);
+    assert!(calendar.contains(&uid));
+    assert!(calendar.contains(&start));
+    assert!(calendar.contains(&"SUMMARY:Консультация — Клиника".to_string()));
+    assert!(calendar.contains(&"SEQUENCE:0".to_string()));
+    assert!(calendar.contains(&"TZID:UTC".to_string()));
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/v1/bookings/{}.ics", booking.id))
+        .insert_header(("Authorization", stranger_token.as_str()))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+
+    // Личная лента: перевыпуск отзывает прежнюю ссылку
+    let mut urls = Vec::new();
+    for _ in 0..2 {
+        let request = test::TestRequest::post()
+            .uri("/v1/user/calendar-feed")
+            .insert_header(("Authorization", customer_token.as_str()))
+            .to_request();
+        let feed: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(feed["user_id"], customer.id.to_string());
+        urls.push(feed["url"].as_str().unwrap().to_string());
+    }
+    let request = test::TestRequest::get().uri(&urls[0]).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+
+    // Перенос меняет время и SEQUENCE, но не UID
+    bookings
+        .reschedule_booking(
+            customer.id,
+            booking.id,
+            RescheduleBookingRequest {
+                starts_at: common::tomorrow_at(12, 0),
+                staff_id: None,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    let request = test::TestRequest::get().uri(&urls[1]).to_request();
+    let calendar = lines(&test::call_and_read_body(&app, request).await);
+    assert!(calendar.contains(&uid));
+    assert!(calendar.contains(&"SEQUENCE:1".to_string()));
+    assert!(calendar.contains(&format!(
+        "DTSTART;TZID=UTC:{}",
+        common::tomorrow_at(12, 0).format("%Y%m%dT%H%M%S")
+    )));
+
+    let request = test::TestRequest::delete()
+        .uri("/v1/user/calendar-feed")
+        .insert_header(("Authorization", customer_token.as_str()))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NO_CONTENT);
+    let request = test::TestRequest::get().uri(&urls[1]).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+
+    // Лента сотрудника выдается только руководителям
+    let staff_feed_uri = format!("/v1/companies/{}/staff/{}/calendar-feed", seed.company_id, seed.staff_id);
+    let request = test::TestRequest::post()
+        .uri(&staff_feed_uri)
+        .insert_header(("Authorization", customer_token.as_str()))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::post()
+        .uri(&staff_feed_uri)
+        .insert_header(("Authorization", owner_token.as_str()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let feed: Value = test::read_body_json(response).await;
+    assert_eq!(feed["staff_id"], seed.staff_id.to_string());
+
+    let request = test::TestRequest::get().uri(feed["url"].as_str().unwrap()).to_request();
+    let calendar = lines(&test::call_and_read_body(&app, request).await);
+    assert!(calendar.contains(&uid));
+    assert!(calendar.contains(&"X-WR-CALNAME:Доктор".to_string()));
+    assert!(calendar.contains(&"SUMMARY:Консультация: patient".to_string()));
+}
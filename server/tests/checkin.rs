@@ -0,0 +1,207 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, Utc};
+use common::ManualClock;
+use serde_json::{Value, json};
+use server::application::checkin_service::CheckinServiceImpl;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CheckinRequest, CreateBookingRequest, CreateLocationRequest,
+    CreateStaffRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingService, CatalogService, CheckinService, Clock, CompanyRepository};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::presentation::routes::api_v1_routes;
+
+fn booking_at(seed: &common::Seed, hour: u32, minute: u32) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, minute),
+        promo_code: None,
+    }
+}
+
+#[actix_web::test]
+async fn staff_check_in_customers_by_scanned_qr_code() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let boris = common::create_user(&pool, "boris").await;
+    let admin = common::create_user(&pool, "admin").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let catalog = CatalogServiceImpl::new(company_repository.clone());
+    // Администратор на ресепшене сканирует коды со своего аккаунта
+    catalog
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: "Администратор".to_string(),
+                user_id: Some(admin.id),
+                service_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    let branch = catalog
+        .create_location(
+            seed.owner.id,
+            seed.company_id,
+            CreateLocationRequest {
+                name: "Филиал на Невском".to_string(),
+                address: None,
+                city: Some("Санкт-Петербург".to_string()),
+                latitude: None,
+                longitude: None,
+            },
+        )
+        .await
+        .unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let services = common::booking_services(&pool, clock.clone());
+    let booking_service: Arc<dyn BookingService + Send + Sync> = services.bookings;
+    let checkin_service: Arc<dyn CheckinService + Send + Sync> = services.checkin;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(booking_service.clone()))
+            .app_data(web::Data::new(checkin_service.clone()))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let booking = booking_service.create_booking(anna.id, booking_at(&seed, 10, 0)).await.unwrap();
+    let qr = |user_id, format: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/v1/bookings/{}/checkin-qr?format={}", booking.id, format))
+            .insert_header(("Authorization", bearer(user_id)))
+            .to_request()
+    };
+
+    // Клиент получает картинку кода в обоих форматах, остальным записи как будто нет
+    let response = test::call_service(&app, qr(anna.id, "png")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("content-type").unwrap(), "image/png");
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+    assert!(test::read_body(response).await.starts_with(b"\x89PNG"));
+    let response = test::call_service(&app, qr(anna.id, "svg")).await;
+    assert_eq!(response.headers().get("content-type").unwrap(), "image/svg+xml");
+    assert!(String::from_utf8(test::read_body(response).await.to_vec()).unwrap().contains("<svg"));
+    assert_eq!(test::call_service(&app, qr(boris.id, "png")).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(test::call_service(&app, qr(admin.id, "png")).await.status(), StatusCode::NOT_FOUND);
+
+    let token = checkin_service.checkin_token(anna.id, booking.id).await.unwrap();
+    assert_eq!(token.location_id, seed.location_id);
+    let scan = |user_id, location_id, token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/v1/companies/{}/checkin", seed.company_id))
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(json!({ "location_id": location_id, "token": token }))
+            .to_request()
+    };
+
+    // Код из одного филиала не принимают в другом, а клиент не отмечает себя сам
+    assert_eq!(
+        test::call_service(&app, scan(admin.id, branch.id, &token.token)).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        test::call_service(&app, scan(anna.id, seed.location_id, &token.token)).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let tampered = token.token.replacen(&booking.id.to_string(), &uuid::Uuid::new_v4().to_string(), 1);
+    assert_eq!(
+        test::call_service(&app, scan(admin.id, seed.location_id, &tampered)).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let response = test::call_service(&app, scan(admin.id, seed.location_id, &token.token)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let checked_in: Value = test::read_body_json(response).await;
+    assert_eq!(checked_in["id"], json!(booking.id));
+    assert_eq!(checked_in["status"], "checked_in");
+
+    // Повторное сканирование того же кода ничего не меняет
+    assert_eq!(
+        test::call_service(&app, scan(admin.id, seed.location_id, &token.token)).await.status(),
+        StatusCode::CONFLICT
+    );
+    // Для отмеченной записи новый код уже не выдается
+    assert_eq!(test::call_service(&app, qr(anna.id, "png")).await.status(), StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn qr_code_expires_and_is_issued_only_for_confirmed_bookings() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let services = common::booking_services(&pool, clock.clone());
+
+    let booking = services.bookings.create_booking(anna.id, booking_at(&seed, 10, 0)).await.unwrap();
+    let token = services.checkin.checkin_token(anna.id, booking.id).await.unwrap();
+    assert!(token.expires_at > clock.now() && token.expires_at <= clock.now() + Duration::minutes(5));
+
+    // Скриншот старого кода через несколько минут уже не подходит
+    clock.advance(Duration::minutes(6));
+    let scan = |token: String| CheckinRequest {
+        location_id: seed.location_id,
+        token,
+    };
+    assert!(matches!(
+        services.checkin.check_in(seed.owner.id, seed.company_id, scan(token.token.clone())).await,
+        Err(AppError::Validation(_))
+    ));
+    // Код, подписанный другим секретом, тоже отвергается
+    let foreign = CheckinServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone())),
+        services.bookings.clone(),
+        "other-secret".to_string(),
+        clock.clone(),
+    );
+    let foreign_token = foreign.checkin_token(anna.id, booking.id).await.unwrap();
+    assert!(matches!(
+        services.checkin.check_in(seed.owner.id, seed.company_id, scan(foreign_token.token)).await,
+        Err(AppError::Validation(_))
+    ));
+
+    let fresh = services.checkin.checkin_token(anna.id, booking.id).await.unwrap();
+    services
+        .bookings
+        .change_status(
+            anna.id,
+            booking.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    // Отмененную запись не отметить даже действующим кодом, и новый код не выдается
+    assert!(matches!(
+        services.checkin.check_in(seed.owner.id, seed.company_id, scan(fresh.token)).await,
+        Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+        services.checkin.checkin_token(anna.id, booking.id).await,
+        Err(AppError::Conflict(_))
+    ));
+}
@@ -0,0 +1,285 @@
+// Общие помощники для интеграционных тестов.
+// Тесты работают с настоящим PostgreSQL: адрес берется из TEST_DATABASE_URL,
+// для каждого теста создается отдельная база. Без переменной тесты пропускаются.
+#![allow(dead_code)]
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use server::application::booking_series_service::BookingSeriesServiceImpl;
+use server::application::booking_service::BookingServiceImpl;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::checkin_service::CheckinServiceImpl;
+use server::application::customer_restriction_service::CustomerRestrictionServiceImpl;
+use server::application::group_session_service::GroupSessionServiceImpl;
+use server::application::hold_service::HoldServiceImpl;
+use server::application::hold_sweeper::HoldSweeper;
+use server::application::payment_service::PaymentServiceImpl;
+use server::application::waitlist_service::WaitlistServiceImpl;
+use server::domain::entities::{
+    CreateCompanyRequest, CreateLocationRequest, CreateServiceRequest, CreateStaffRequest,
+    ScheduleEntryRequest, UpdateScheduleRequest, User,
+};
+use server::domain::traits::{CatalogService, Clock, CompanyRepository};
+use server::infrastructure::fake_payment_provider::FakePaymentProvider;
+use server::infrastructure::migrations::{ensure_database_exists, run_migrations};
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_payment_repository::PostgreSQLPaymentRepository;
+use server::infrastructure::postgres_promo_code_repository::PostgreSQLPromoCodeRepository;
+use server::infrastructure::postgres_user_repository::PostgreSQLUserRepository;
+use server::infrastructure::postgres_waitlist_repository::PostgreSQLWaitlistRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn test_pool() -> Option<PgPool> {
+    let base_url = match std::env::var("TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!("TEST_DATABASE_URL не задан, интеграционный тест пропущен");
+            return None;
+        }
+    };
+
+    let (server_url, database) = base_url
+        .rsplit_once('/')
+        .expect("TEST_DATABASE_URL должен содержать имя базы");
+    let database_url = format!("{}/{}_{}", server_url, database, Uuid::new_v4().simple());
+
+    ensure_database_exists(&database_url)
+        .await
+        .expect("Не удалось создать тестовую базу");
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(20)
+        .connect(&database_url)
+        .await
+        .expect("Не удалось подключиться к тестовой базе");
+    run_migrations(&pool).await.expect("Не удалось выполнить миграции");
+
+    Some(pool)
+}
+
+pub async fn create_user(pool: &PgPool, username: &str) -> User {
+    PostgreSQLUserRepository::new(pool.clone())
+        .create_user_with_password(
+            username,
+            "secret123",
+            None,
+            None,
+            Some(&format!("{}@example.com", username)),
+            None,
+        )
+        .await
+        .expect("Не удалось создать пользователя")
+}
+
+/// Компания в UTC с одним сотрудником, работающим каждый день с 08:00 до 20:00
+pub struct Seed {
+    pub owner: User,
+    pub company_id: Uuid,
+    pub location_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Uuid,
+}
+
+pub async fn seed_company(pool: &PgPool) -> Seed {
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let catalog = CatalogServiceImpl::new(company_repository);
+    let owner = create_user(pool, &format!("owner_{}", Uuid::new_v4().simple())).await;
+
+    let company = catalog
+        .create_company(
+            owner.id,
+            CreateCompanyRequest {
+                name: "Клиника".to_string(),
+                description: None,
+                website: None,
+                email: None,
+                phone: None,
+                timezone: "UTC".to_string(),
+                categories: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    let location = catalog
+        .create_location(
+            owner.id,
+            company.id,
+            CreateLocationRequest {
+                name: "Главный корпус".to_string(),
+                address: None,
+                city: Some("Москва".to_string()),
+                latitude: None,
+                longitude: None,
+            },
+        )
+        .await
+        .unwrap();
+    let service = catalog
+        .create_service(
+            owner.id,
+            company.id,
+            CreateServiceRequest {
+                name: "Консультация".to_string(),
+                description: None,
+                duration_minutes: 60,
+                buffer_before_minutes: None,
+                buffer_after_minutes: None,
+                slot_step_minutes: Some(30),
+                price_amount: Some(100_000),
+                currency: None,
+                capacity: None,
+                deposit_amount: None,
+            },
+        )
+        .await
+        .unwrap();
+    let staff = catalog
+        .create_staff(
+            owner.id,
+            company.id,
+            CreateStaffRequest {
+                display_name: "Доктор".to_string(),
+                user_id: None,
+                service_ids: vec![service.id],
+            },
+        )
+        .await
+        .unwrap();
+    catalog
+        .update_schedule(
+            owner.id,
+            company.id,
+            staff.id,
+            UpdateScheduleRequest {
+                entries: (1..=7)
+                    .map(|weekday| ScheduleEntryRequest {
+                        location_id: location.id,
+                        weekday,
+                        start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                        end_time: NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+                    })
+                    .collect(),
+            },
+        )
+        .await
+        .unwrap();
+
+    Seed {
+        owner,
+        company_id: company.id,
+        location_id: location.id,
+        service_id: service.id,
+        staff_id: staff.id,
+    }
+}
+
+/// Завтрашний день в указанное время (UTC)
+pub fn tomorrow_at(hour: u32, minute: u32) -> chrono::DateTime<Utc> {
+    (Utc::now().date_naive() + Duration::days(1))
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+        .and_utc()
+}
+
+/// Управляемые часы: время меняется только явно через `advance`
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Секрет подписи уведомлений тестовой платежной системы
+pub const PAYMENTS_SECRET: &str = "test-secret";
+
+/// Секрет подписи QR-кодов отметки о приходе
+pub const CHECKIN_SECRET: &str = "checkin-secret";
+
+/// Сервисы записи, связанные так же, как в main.rs, со сроками по умолчанию
+pub struct BookingServices {
+    pub bookings: Arc<BookingServiceImpl>,
+    pub holds: Arc<HoldServiceImpl>,
+    pub series: Arc<BookingSeriesServiceImpl>,
+    pub restrictions: Arc<CustomerRestrictionServiceImpl>,
+    pub waitlist: Arc<WaitlistServiceImpl>,
+    pub payments: Arc<PaymentServiceImpl>,
+    pub payment_provider: Arc<FakePaymentProvider>,
+    pub checkin: Arc<CheckinServiceImpl>,
+    pub sessions: Arc<GroupSessionServiceImpl>,
+    pub sweeper: HoldSweeper,
+}
+
+pub fn booking_services(pool: &PgPool, clock: Arc<dyn Clock + Send + Sync>) -> BookingServices {
+    let company_repository = Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let booking_repository = Arc::new(PostgreSQLBookingRepository::new(pool.clone()));
+    let payment_repository = Arc::new(PostgreSQLPaymentRepository::new(pool.clone()));
+    let payment_provider = Arc::new(FakePaymentProvider::new(PAYMENTS_SECRET));
+
+    let waitlist = Arc::new(WaitlistServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+        Arc::new(PostgreSQLWaitlistRepository::new(pool.clone())),
+        clock.clone(),
+    ));
+    let bookings = Arc::new(BookingServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+        payment_repository.clone(),
+        Arc::new(PostgreSQLPromoCodeRepository::new(pool.clone())),
+        waitlist.clone(),
+        clock.clone(),
+    ));
+    let payments = Arc::new(PaymentServiceImpl::new(
+        company_repository.clone(),
+        booking_repository.clone(),
+        payment_repository,
+        payment_provider.clone(),
+        waitlist.clone(),
+        clock.clone(),
+    ));
+
+    BookingServices {
+        holds: Arc::new(HoldServiceImpl::new(company_repository.clone(), booking_repository.clone(), clock.clone())),
+        series: Arc::new(BookingSeriesServiceImpl::new(
+            company_repository.clone(),
+            booking_repository.clone(),
+            waitlist.clone(),
+            clock.clone(),
+        )),
+        restrictions: Arc::new(CustomerRestrictionServiceImpl::new(
+            company_repository.clone(),
+            booking_repository.clone(),
+            Arc::new(PostgreSQLUserRepository::new(pool.clone())),
+            clock.clone(),
+        )),
+        checkin: Arc::new(CheckinServiceImpl::new(
+            company_repository.clone(),
+            booking_repository.clone(),
+            bookings.clone(),
+            CHECKIN_SECRET.to_string(),
+            clock.clone(),
+        )),
+        sessions: Arc::new(GroupSessionServiceImpl::new(company_repository, booking_repository.clone())),
+        sweeper: HoldSweeper::new(booking_repository, waitlist.clone(), payments.clone(), clock),
+        bookings,
+        waitlist,
+        payments,
+        payment_provider,
+    }
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, Utc};
+use common::ManualClock;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::customer_service::CustomerServiceImpl;
+use server::domain::entities::{BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateStaffRequest};
+use server::domain::traits::{BookingService, CatalogService, CompanyRepository, CustomerService};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_customer_repository::PostgreSQLCustomerRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+#[actix_web::test]
+async fn company_members_see_customer_history_and_keep_private_notes() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let boris = common::create_user(&pool, "boris").await;
+    let outsider = common::create_user(&pool, "outsider").await;
+    let nurse = common::create_user(&pool, "nurse").await;
+    sqlx::query("UPDATE users SET first_name = 'Анна', last_name = 'Иванова' WHERE id = $1")
+        .bind(anna.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET first_name = 'Мария' WHERE id = $1")
+        .bind(nurse.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    // Медсестра с аккаунтом становится участником компании с ролью staff
+    CatalogServiceImpl::new(company_repository.clone())
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: "Медсестра".to_string(),
+                user_id: Some(nurse.id),
+                service_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let bookings = common::booking_services(&pool, clock.clone()).bookings;
+    let customer_service: Arc<dyn CustomerService + Send + Sync> = Arc::new(CustomerServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLCustomerRepository::new(pool.clone())),
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(customer_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let book = |customer_id: Uuid, hour| {
+        let bookings = &bookings;
+        let seed = &seed;
+        async move {
+            bookings
+                .create_booking(
+                    customer_id,
+                    CreateBookingRequest {
+                        company_id: seed.company_id,
+                        service_id: seed.service_id,
+                        staff_id: seed.staff_id,
+                        location_id: None,
+                        starts_at: common::tomorrow_at(hour, 0),
+                        promo_code: None,
+                    },
+                )
+                .await
+                .unwrap()
+        }
+    };
+    let set_status = |booking_id, status| {
+        let bookings = &bookings;
+        async move {
+            bookings
+                .change_status(seed.owner.id, booking_id, ChangeBookingStatusRequest { status, reason: None })
+                .await
+                .unwrap();
+        }
+    };
+    let visited = book(anna.id, 9).await;
+    let missed = book(anna.id, 11).await;
+    book(anna.id, 13).await;
+    book(boris.id, 15).await;
+    book(nurse.id, 17).await;
+    // Неявка отмечается после начала приема
+    clock.advance(Duration::days(2));
+    set_status(visited.id, BookingStatus::Completed).await;
+    set_status(missed.id, BookingStatus::NoShow).await;
+
+    let get = |user_id: Uuid, uri: String| {
+        test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .to_request()
+    };
+    let customers_uri = format!("/v1/companies/{}/customers", seed.company_id);
+    let anna_uri = format!("{}/{}", customers_uri, anna.id);
+
+    // Клиенты и посторонние не видят ни списка, ни карточек
+    for user_id in [anna.id, outsider.id] {
+        assert_eq!(test::call_service(&app, get(user_id, customers_uri.clone())).await.status(), StatusCode::FORBIDDEN);
+        assert_eq!(test::call_service(&app, get(user_id, anna_uri.clone())).await.status(), StatusCode::FORBIDDEN);
+    }
+
+    let list: Value = test::call_and_read_body_json(&app, get(nurse.id, customers_uri.clone())).await;
+    assert_eq!(list["total"], 3);
+    // Сначала клиенты с самыми поздними записями
+    assert_eq!(list["customers"][0]["id"], nurse.id.to_string());
+    let summary = list["customers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|customer| customer["id"] == anna.id.to_string())
+        .unwrap();
+    assert_eq!(summary["first_name"], "Анна");
+    assert_eq!(summary["booking_count"], 3);
+    assert_eq!(summary["visit_count"], 1);
+    assert_eq!(summary["no_show_count"], 1);
+    assert!(summary["last_visit_at"].is_string());
+    assert!(summary.get("notes").is_none());
+
+    // Заметки добавляют все участники; автор подписан
+    let post_note = |user_id: Uuid, customer_id: Uuid, body: Value| {
+        test::TestRequest::post()
+            .uri(&format!("{}/{}/notes", customers_uri, customer_id))
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(body)
+            .to_request()
+    };
+    let response = test::call_service(&app, post_note(nurse.id, anna.id, json!({"body": "  Аллергия на латекс "}))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let nurse_note: Value = test::read_body_json(response).await;
+    assert_eq!(nurse_note["body"], "Аллергия на латекс");
+    assert_eq!(nurse_note["author_name"], "Мария");
+    let response = test::call_service(&app, post_note(seed.owner.id, anna.id, json!({"body": "Предпочитает утро"}))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let owner_note: Value = test::read_body_json(response).await;
+    let response = test::call_service(&app, post_note(nurse.id, anna.id, json!({"body": "  "}))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = test::call_service(&app, post_note(anna.id, anna.id, json!({"body": "Я не приду"}))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    // Пользователь без записей в компании - не ее клиент
+    let response = test::call_service(&app, post_note(nurse.id, outsider.id, json!({"body": "?"}))).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    // Сотрудник, записавшийся как клиент, не видит заметок о себе
+    let response = test::call_service(&app, get(nurse.id, format!("{}/{}", customers_uri, nurse.id))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let profile: Value = test::call_and_read_body_json(&app, get(nurse.id, anna_uri.clone())).await;
+    assert_eq!(profile["visit_count"], 1);
+    assert_eq!(profile["bookings"].as_array().unwrap().len(), 3);
+    assert_eq!(profile["bookings"][2]["id"], visited.id.to_string());
+    assert_eq!(profile["notes"].as_array().unwrap().len(), 2);
+    assert_eq!(profile["notes"][0]["id"], owner_note["id"]);
+
+    // Чужую заметку меняет только руководитель
+    let note_request = |method: test::TestRequest, user_id: Uuid, note: &Value| {
+        method
+            .uri(&format!("{}/notes/{}", anna_uri, note["id"].as_str().unwrap()))
+            .insert_header(("Authorization", bearer(user_id)))
+    };
+    let response = test::call_service(
+        &app,
+        note_request(test::TestRequest::patch(), nurse.id, &owner_note)
+            .set_json(json!({"body": "Исправлено"}))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = test::call_service(
+        &app,
+        note_request(test::TestRequest::patch(), seed.owner.id, &nurse_note)
+            .set_json(json!({"body": "Аллергия на латекс и йод"}))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let edited: Value = test::read_body_json(response).await;
+    assert_eq!(edited["body"], "Аллергия на латекс и йод");
+    assert_eq!(edited["author_id"], nurse.id.to_string());
+    let response = test::call_service(&app, note_request(test::TestRequest::delete(), nurse.id, &owner_note).to_request()).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = test::call_service(&app, note_request(test::TestRequest::delete(), nurse.id, &nurse_note).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = test::call_service(&app, note_request(test::TestRequest::delete(), nurse.id, &nurse_note).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Метки нормализуются и фильтруют список
+    let put_tags = |user_id: Uuid, customer_id: Uuid, tags: Value| {
+        test::TestRequest::put()
+            .uri(&format!("{}/{}/tags", customers_uri, customer_id))
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(json!({ "tags": tags }))
+            .to_request()
+    };
+    let tags: Value = test::call_and_read_body_json(&app, put_tags(nurse.id, anna.id, json!([" VIP ", "аллергия", "vip"]))).await;
+    assert_eq!(tags["tags"], json!(["vip", "аллергия"]));
+    let tags: Value = test::call_and_read_body_json(&app, put_tags(nurse.id, boris.id, json!(["новый"]))).await;
+    assert_eq!(tags["tags"], json!(["новый"]));
+    let response = test::call_service(&app, put_tags(anna.id, anna.id, json!([]))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let filtered: Value = test::call_and_read_body_json(&app, get(seed.owner.id, format!("{}?tag=VIP", customers_uri))).await;
+    assert_eq!(filtered["total"], 1);
+    assert_eq!(filtered["customers"][0]["id"], anna.id.to_string());
+    assert_eq!(filtered["customers"][0]["tags"], json!(["vip", "аллергия"]));
+    let found: Value = test::call_and_read_body_json(&app, get(seed.owner.id, format!("{}?q=BORIS", customers_uri))).await;
+    assert_eq!(found["total"], 1);
+    assert_eq!(found["customers"][0]["id"], boris.id.to_string());
+    let page: Value =
+        test::call_and_read_body_json(&app, get(seed.owner.id, format!("{}?limit=1&offset=5", customers_uri))).await;
+    assert_eq!(page["total"], 3);
+    assert_eq!(page["customers"].as_array().unwrap().len(), 0);
+    let response = test::call_service(&app, get(seed.owner.id, format!("{}?limit=0", customers_uri))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let tags: Value = test::call_and_read_body_json(&app, put_tags(seed.owner.id, anna.id, json!(["аллергия"]))).await;
+    assert_eq!(tags["tags"], json!(["аллергия"]));
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{NaiveTime, SubsecRound};
+use common::ManualClock;
+use futures_util::future::join_all;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::slot_service::SlotServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateServiceRequest, CreateStaffRequest,
+    ScheduleEntryRequest, SessionsQuery, SlotsQuery, UpdateScheduleRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{
+    BookingRepository, BookingService, CatalogService, CompanyRepository, GroupSessionService, SlotService,
+};
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+struct GroupSeed {
+    service_id: Uuid,
+    staff_id: Uuid,
+}
+
+/// Групповая услуга на троих и тренер, который ведет ее и обычные консультации
+async fn seed_group(pool: &PgPool, seed: &common::Seed) -> GroupSeed {
+    let catalog = CatalogServiceImpl::new(Arc::new(PostgreSQLCompanyRepository::new(pool.clone())));
+    let service = catalog
+        .create_service(
+            seed.owner.id,
+            seed.company_id,
+            CreateServiceRequest {
+                name: "Йога".to_string(),
+                description: None,
+                duration_minutes: 60,
+                buffer_before_minutes: None,
+                buffer_after_minutes: None,
+                slot_step_minutes: Some(60),
+                price_amount: None,
+                currency: None,
+                capacity: Some(3),
+                deposit_amount: None,
+            },
+        )
+        .await
+        .unwrap();
+    let staff = catalog
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: "Тренер".to_string(),
+                user_id: None,
+                service_ids: vec![service.id, seed.service_id],
+            },
+        )
+        .await
+        .unwrap();
+    catalog
+        .update_schedule(
+            seed.owner.id,
+            seed.company_id,
+            staff.id,
+            UpdateScheduleRequest {
+                entries: (1..=7)
+                    .map(|weekday| ScheduleEntryRequest {
+                        location_id: seed.location_id,
+                        weekday,
+                        start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                        end_time: NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+                    })
+                    .collect(),
+            },
+        )
+        .await
+        .unwrap();
+
+    GroupSeed {
+        service_id: service.id,
+        staff_id: staff.id,
+    }
+}
+
+fn services(pool: &PgPool) -> (common::BookingServices, SlotServiceImpl) {
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let booking_repository: Arc<dyn BookingRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone()));
+    let clock = Arc::new(ManualClock::new(chrono::Utc::now().trunc_subsecs(0)));
+
+    (
+        common::booking_services(pool, clock),
+        SlotServiceImpl::new(company_repository, booking_repository),
+    )
+}
+
+fn request(seed: &common::Seed, service_id: Uuid, staff_id: Uuid, hour: u32) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id,
+        staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, 0),
+        promo_code: None,
+    }
+}
+
+async fn seats_left(slots: &SlotServiceImpl, seed: &common::Seed, group: &GroupSeed, hour: u32) -> Option<i32> {
+    let date = common::tomorrow_at(0, 0).date_naive();
+    slots
+        .available_slots(
+            seed.company_id,
+            SlotsQuery {
+                service: group.service_id,
+                staff: Some(group.staff_id),
+                from: date,
+                to: date,
+            },
+        )
+        .await
+        .unwrap()
+        .slots
+        .into_iter()
+        .find(|slot| slot.starts_at == common::tomorrow_at(hour, 0))
+        .map(|slot| slot.seats_left)
+}
+
+#[actix_web::test]
+async fn seats_are_shared_until_the_session_is_full() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let group = seed_group(&pool, &seed).await;
+    let (services, slots) = services(&pool);
+    let mut customers = Vec::new();
+    for i in 0..4 {
+        customers.push(common::create_user(&pool, &format!("member{}", i)).await);
+    }
+
+    assert_eq!(seats_left(&slots, &seed, &group, 10).await, Some(3));
+    let mut booked = Vec::new();
+    for customer in &customers[..3] {
+        let booking = services
+            .bookings
+            .create_booking(customer.id, request(&seed, group.service_id, group.staff_id, 10))
+            .await
+            .unwrap();
+        booked.push(booking);
+    }
+    let session_id = booked[0].session_id.expect("запись группового занятия");
+    assert!(booked.iter().all(|b| b.session_id == Some(session_id)));
+
+    // Отмена освобождает место, но повторно на то же занятие не записаться
+    services
+        .bookings
+        .change_status(
+            customers[2].id,
+            booked[2].id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(seats_left(&slots, &seed, &group, 10).await, Some(1));
+    let twice = services
+        .bookings
+        .create_booking(customers[0].id, request(&seed, group.service_id, group.staff_id, 10))
+        .await;
+    assert_eq!(twice.unwrap_err(), AppError::Conflict("Вы уже записаны на это занятие".to_string()));
+
+    // Освободившееся место занимает следующий, после этого занятие пропадает из слотов
+    services
+        .bookings
+        .create_booking(customers[3].id, request(&seed, group.service_id, group.staff_id, 10))
+        .await
+        .unwrap();
+    let full = services
+        .bookings
+        .create_booking(customers[2].id, request(&seed, group.service_id, group.staff_id, 10))
+        .await;
+    assert!(matches!(full, Err(AppError::Validation(_)) | Err(AppError::Conflict(_))));
+    assert_eq!(seats_left(&slots, &seed, &group, 10).await, None);
+    assert_eq!(seats_left(&slots, &seed, &group, 11).await, Some(3));
+
+    // Тренер занят занятием: индивидуальная запись на это время невозможна
+    let individual = services
+        .bookings
+        .create_booking(customers[3].id, request(&seed, seed.service_id, group.staff_id, 10))
+        .await;
+    assert!(matches!(individual, Err(AppError::Validation(_)) | Err(AppError::Conflict(_))));
+
+    // Список участников видят только сотрудники
+    let details = services.sessions.get_session(seed.owner.id, seed.company_id, session_id).await.unwrap();
+    assert_eq!(details.session.seats_taken, 3);
+    assert_eq!(details.attendees.len(), 4);
+    assert_eq!(
+        details
+            .attendees
+            .iter()
+            .filter(|a| a.status == BookingStatus::CancelledByCustomer)
+            .count(),
+        1
+    );
+    let date = common::tomorrow_at(0, 0).date_naive();
+    let listed = services
+        .sessions
+        .list_sessions(
+            seed.owner.id,
+            seed.company_id,
+            SessionsQuery {
+                from: date,
+                to: date,
+                service: Some(group.service_id),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(listed.iter().map(|s| s.id).collect::<Vec<_>>(), vec![session_id]);
+    assert!(matches!(
+        services.sessions.get_session(customers[0].id, seed.company_id, session_id).await,
+        Err(AppError::Forbidden(_))
+    ));
+}
+
+#[actix_web::test]
+async fn concurrent_bookings_never_overfill_a_session() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let group = seed_group(&pool, &seed).await;
+    let (services, _) = services(&pool);
+    let mut customers = Vec::new();
+    for i in 0..8 {
+        customers.push(common::create_user(&pool, &format!("racer{}", i)).await);
+    }
+
+    let attempts = customers.iter().map(|customer| {
+        let bookings = services.bookings.clone();
+        let data = request(&seed, group.service_id, group.staff_id, 12);
+        let customer_id = customer.id;
+        async move { bookings.create_booking(customer_id, data).await }
+    });
+    let results = join_all(attempts).await;
+
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 3);
+    assert!(results
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .all(|e| matches!(e, AppError::Conflict(_) | AppError::Validation(_))));
+
+    let session_id = results.iter().find_map(|r| r.as_ref().ok()).unwrap().session_id.unwrap();
+    let details = services.sessions.get_session(seed.owner.id, seed.company_id, session_id).await.unwrap();
+    assert_eq!(details.session.seats_taken, 3);
+}
@@ -0,0 +1,281 @@
+mod common;
+
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use actix_web::{App, http::StatusCode, test, web};
+use async_trait::async_trait;
+use chrono::{Duration, SubsecRound, Utc};
+use common::ManualClock;
+use serde::{Deserialize, Serialize};
+use server::application::job_admin_service::JobAdminServiceImpl;
+use server::domain::entities::{JobStatus, JobsQuery, NewJob};
+use server::domain::errors::AppError;
+use server::domain::traits::{Clock, JobAdminService, JobRepository, UserAuthRepository};
+use server::infrastructure::job_queue::{Job, JobHandler, JobQueue, JobWorker};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::maintenance_jobs::{CLEANUP_DEDUPE_KEY, CleanupHandler, CleanupJob};
+use server::infrastructure::postgres_job_repository::PostgreSQLJobRepository;
+use server::infrastructure::postgres_user_repository::PostgreSQLUserRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::{Value, json};
+use sqlx::Row;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct EchoJob {
+    text: String,
+}
+
+impl Job for EchoJob {
+    const KIND: &'static str = "test_echo";
+    const MAX_ATTEMPTS: i32 = 3;
+}
+
+/// Обработчик, который падает заданное число раз, а потом запоминает текст задачи
+struct FlakyHandler {
+    failures_left: Arc<AtomicI32>,
+    handled: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl JobHandler<EchoJob> for FlakyHandler {
+    async fn handle(&self, job: EchoJob) -> Result<(), AppError> {
+        if self.failures_left.fetch_sub(1, Ordering::SeqCst) > 0 {
+            return Err(AppError::Internal("Сервис недоступен".to_string()));
+        }
+        self.handled.lock().unwrap().push(job.text);
+        Ok(())
+    }
+}
+
+#[actix_web::test]
+async fn failing_jobs_back_off_die_and_are_retried_by_admin() {
+    let Some(pool) = common::test_pool().await else { return };
+    let user = common::create_user(&pool, "operator").await;
+    let jwt_service = JwtService::new();
+    let bearer = format!("Bearer {}", jwt_service.generate_access_token(user.id, "").unwrap());
+
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = Arc::new(PostgreSQLJobRepository::new(pool.clone()));
+    let user_repository: Arc<dyn UserAuthRepository + Send + Sync> =
+        Arc::new(PostgreSQLUserRepository::new(pool.clone()));
+    let queue = JobQueue::new(job_repository.clone());
+    let clock = Arc::new(ManualClock::new((Utc::now() + Duration::seconds(1)).trunc_subsecs(0)));
+    let failures_left = Arc::new(AtomicI32::new(10));
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let worker = || {
+        JobWorker::new(job_repository.clone(), clock.clone()).register(FlakyHandler {
+            failures_left: failures_left.clone(),
+            handled: handled.clone(),
+        })
+    };
+    let first = worker();
+    let second = worker();
+
+    // Пауза между попытками растет, после третьей неудачи задача уходит в dead
+    let job = queue.enqueue(&EchoJob { text: "привет".to_string() }).await.unwrap().unwrap();
+    assert_eq!(first.run_once().await.unwrap(), 0);
+    assert_eq!(first.run_once().await.unwrap(), 0);
+    let pending = job_repository
+        .list_jobs(&JobsQuery { status: Some(JobStatus::Pending), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(pending[0].attempts, 1);
+    assert_eq!(pending[0].run_at, clock.now() + Duration::seconds(10));
+    assert_eq!(pending[0].last_error.as_deref(), Some("Сервис недоступен"));
+
+    clock.advance(Duration::seconds(10));
+    assert_eq!(first.run_once().await.unwrap(), 0);
+    clock.advance(Duration::seconds(19));
+    assert_eq!(first.run_once().await.unwrap(), 0);
+    clock.advance(Duration::seconds(1));
+    assert_eq!(first.run_once().await.unwrap(), 0);
+    clock.advance(Duration::hours(1));
+    assert_eq!(first.run_once().await.unwrap(), 0);
+
+    // Данные, которые не разбираются, сразу отправляют задачу в dead
+    job_repository
+        .enqueue(&NewJob {
+            kind: "test_echo".to_string(),
+            payload: json!({"message": 1}),
+            run_at: None,
+            max_attempts: 3,
+            dedupe_key: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(first.run_once().await.unwrap(), 0);
+
+    let admin_service: Arc<dyn JobAdminService + Send + Sync> =
+        Arc::new(JobAdminServiceImpl::new(user_repository, job_repository.clone()));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(admin_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+    let list = || {
+        test::TestRequest::get()
+            .uri("/v1/admin/jobs")
+            .insert_header(("Authorization", bearer.clone()))
+            .to_request()
+    };
+    let retry = || {
+        test::TestRequest::post()
+            .uri(&format!("/v1/admin/jobs/{}/retry", job.id))
+            .insert_header(("Authorization", bearer.clone()))
+            .to_request()
+    };
+
+    assert_eq!(test::call_service(&app, list()).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(test::call_service(&app, retry()).await.status(), StatusCode::FORBIDDEN);
+    sqlx::query("UPDATE users SET user_type_id = 3 WHERE id = $1")
+        .bind(user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let dead: Value = test::call_and_read_body_json(&app, list()).await;
+    let dead = dead.as_array().unwrap();
+    assert_eq!(dead.len(), 2);
+    let echo = dead.iter().find(|item| item["id"] == job.id.to_string()).unwrap();
+    assert_eq!(echo["status"], "dead");
+    assert_eq!(echo["attempts"], 3);
+    let broken = dead.iter().find(|item| item["id"] != job.id.to_string()).unwrap();
+    assert!(broken["last_error"].as_str().unwrap().starts_with("Некорректные данные задачи"));
+
+    // Повтор администратором: новый счетчик попыток, задача выполняется один раз
+    let retried: Value = test::call_and_read_body_json(&app, retry()).await;
+    assert_eq!(retried["status"], "pending");
+    assert_eq!(retried["attempts"], 0);
+    assert_eq!(test::call_service(&app, retry()).await.status(), StatusCode::NOT_FOUND);
+
+    failures_left.store(0, Ordering::SeqCst);
+    let (a, b) = futures_util::join!(first.run_once(), second.run_once());
+    assert_eq!(a.unwrap() + b.unwrap(), 1);
+    assert_eq!(*handled.lock().unwrap(), vec!["привет".to_string()]);
+    let row = sqlx::query("SELECT status, completed_at FROM jobs WHERE id = $1")
+        .bind(job.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>("status"), "completed");
+    assert!(row.get::<Option<chrono::DateTime<Utc>>, _>("completed_at").is_some());
+
+    // Ключ не дает поставить вторую такую же задачу, пока первая ждет
+    let key = Some("echo:once".to_string());
+    let text = EchoJob { text: "один раз".to_string() };
+    assert!(queue.enqueue_at(&text, None, key.clone()).await.unwrap().is_some());
+    assert!(queue.enqueue_at(&text, None, key.clone()).await.unwrap().is_none());
+
+    // Воркер взял задачу и пропал: после истечения аренды ее выполняет другой
+    let kinds = vec!["test_echo".to_string()];
+    let now = clock.now();
+    let claimed = job_repository.claim(now, now + Duration::minutes(5), &kinds, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(first.run_once().await.unwrap(), 0);
+    clock.advance(Duration::minutes(5));
+    assert_eq!(second.run_once().await.unwrap(), 1);
+    assert_eq!(handled.lock().unwrap().len(), 2);
+    assert!(queue.enqueue_at(&text, None, key).await.unwrap().is_some());
+}
+
+#[actix_web::test]
+async fn only_the_lease_holder_finishes_a_job() {
+    let Some(pool) = common::test_pool().await else { return };
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = Arc::new(PostgreSQLJobRepository::new(pool.clone()));
+    let queue = JobQueue::new(job_repository.clone());
+    let kinds = vec!["test_echo".to_string()];
+    let key = Some("echo:lease".to_string());
+    let text = EchoJob { text: "долгая".to_string() };
+    let now = Utc::now().trunc_subsecs(6) + Duration::seconds(1);
+
+    // Первый воркер не уложился в аренду, задачу забрал второй: результат первого не записывается
+    let job = queue.enqueue_at(&text, None, key.clone()).await.unwrap().unwrap();
+    let first_lease = now + Duration::minutes(5);
+    job_repository.claim(now, first_lease, &kinds, 10).await.unwrap();
+    let second_lease = first_lease + Duration::minutes(5);
+    assert_eq!(job_repository.claim(first_lease, second_lease, &kinds, 10).await.unwrap().len(), 1);
+    assert!(!job_repository.complete(job.id, first_lease).await.unwrap());
+    assert!(!job_repository.bury(job.id, first_lease, "поздно").await.unwrap());
+
+    // Пока задача выполнялась, встала такая же: повтор ей уступает и не считается сбоем
+    let duplicate = queue.enqueue_at(&text, None, key.clone()).await.unwrap().unwrap();
+    assert!(job_repository
+        .retry_later(job.id, second_lease, "Сервис недоступен", second_lease)
+        .await
+        .unwrap());
+    let completed = job_repository
+        .list_jobs(&JobsQuery { status: Some(JobStatus::Completed), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(completed.iter().map(|job| job.id).collect::<Vec<_>>(), vec![job.id]);
+    assert!(completed[0].last_error.as_deref().unwrap().starts_with("Сервис недоступен"));
+    let pending = job_repository
+        .list_jobs(&JobsQuery { status: Some(JobStatus::Pending), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(pending.iter().map(|job| job.id).collect::<Vec<_>>(), vec![duplicate.id]);
+
+    // Воркер падает на каждой попытке, не успевая записать итог: после последней задача уходит в dead
+    let mut lease = second_lease;
+    for _ in 0..EchoJob::MAX_ATTEMPTS {
+        let next_lease = lease + Duration::minutes(5);
+        assert_eq!(job_repository.claim(lease, next_lease, &kinds, 10).await.unwrap().len(), 1);
+        lease = next_lease;
+    }
+    assert!(job_repository.claim(lease, lease + Duration::minutes(5), &kinds, 10).await.unwrap().is_empty());
+    let dead = job_repository
+        .list_jobs(&JobsQuery { status: Some(JobStatus::Dead), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(dead.iter().map(|job| job.id).collect::<Vec<_>>(), vec![duplicate.id]);
+    assert_eq!(dead[0].attempts, EchoJob::MAX_ATTEMPTS);
+}
+
+#[actix_web::test]
+async fn cleanup_removes_stale_rows_and_schedules_itself() {
+    let Some(pool) = common::test_pool().await else { return };
+    let user = common::create_user(&pool, "patient").await;
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = Arc::new(PostgreSQLJobRepository::new(pool.clone()));
+    let queue = JobQueue::new(job_repository.clone());
+    let clock = Arc::new(ManualClock::new((Utc::now() + Duration::seconds(1)).trunc_subsecs(0)));
+
+    sqlx::query(
+        r#"
+        INSERT INTO notification_outbox (user_id, kind, status, sent_at)
+        VALUES ($1, 'booking_confirmed', 'sent', NOW() - INTERVAL '40 days'),
+               ($1, 'booking_confirmed', 'sent', NOW() - INTERVAL '1 day'),
+               ($1, 'booking_confirmed', 'failed', NULL)
+        "#,
+    )
+    .bind(user.id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let job = CleanupJob { retention_days: 30 };
+    let dedupe_key = Some(CLEANUP_DEDUPE_KEY.to_string());
+    assert!(queue.enqueue_at(&job, None, dedupe_key.clone()).await.unwrap().is_some());
+    assert!(queue.enqueue_at(&job, None, dedupe_key).await.unwrap().is_none());
+
+    let worker = JobWorker::new(job_repository.clone(), clock.clone())
+        .register(CleanupHandler::new(pool.clone(), queue.clone(), clock.clone()));
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+
+    let remaining: i64 = sqlx::query("SELECT COUNT(*) AS count FROM notification_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(remaining, 2);
+
+    let next = job_repository
+        .list_jobs(&JobsQuery { status: Some(JobStatus::Pending), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(next.len(), 1);
+    assert_eq!(next[0].kind, "cleanup");
+    assert_eq!(next[0].run_at, clock.now() + Duration::days(1));
+    assert_eq!(worker.run_once().await.unwrap(), 0);
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage, Rgba, RgbaImage};
+use server::application::bookmark_service::BookmarkServiceImpl;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::media_service::MediaServiceImpl;
+use server::domain::traits::{BookmarkService, CatalogService, CompanyRepository, MediaService, UserAuthRepository};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::local_media_storage::LocalMediaStorage;
+use server::infrastructure::postgres_bookmark_repository::PostgreSQLBookmarkRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_media_repository::PostgreSQLMediaRepository;
+use server::infrastructure::postgres_user_repository::PostgreSQLUserRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::Value;
+use uuid::Uuid;
+
+const BOUNDARY: &str = "locationx-test-boundary";
+
+/// Тело multipart/form-data с одним файлом; тип содержимого задает клиент и может врать
+fn multipart(field: &str, content_type: &str, bytes: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"upload\"\r\nContent-Type: {}\r\n\r\n",
+        BOUNDARY, field, content_type
+    )
+    .into_bytes();
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([30, 60, 90, 200])))
+        .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+        .unwrap();
+    buffer
+}
+
+fn jpeg(width: u32, height: u32) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([250, 200, 10])))
+        .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Jpeg)
+        .unwrap();
+    buffer
+}
+
+#[actix_web::test]
+async fn images_are_sniffed_reencoded_stored_and_linked_from_profiles() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "customer").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let media_dir = std::env::temp_dir().join(format!("locationx_media_{}", Uuid::new_v4().simple()));
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let user_auth_repository: Arc<dyn UserAuthRepository + Send + Sync> =
+        Arc::new(PostgreSQLUserRepository::new(pool.clone()));
+    let catalog_service: Arc<dyn CatalogService + Send + Sync> =
+        Arc::new(CatalogServiceImpl::new(company_repository.clone()));
+    let bookmark_service: Arc<dyn BookmarkService + Send + Sync> = Arc::new(BookmarkServiceImpl::new(
+        company_repository.clone(),
+        Arc::new(PostgreSQLBookmarkRepository::new(pool.clone())),
+    ));
+    let media_service: Arc<dyn MediaService + Send + Sync> = Arc::new(
+        MediaServiceImpl::new(
+            company_repository,
+            Arc::new(PostgreSQLMediaRepository::new(pool.clone())),
+            Arc::new(LocalMediaStorage::new(&media_dir, "/v1/media")),
+        )
+        .with_max_upload_bytes(200 * 1024),
+    );
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(user_auth_repository))
+            .app_data(web::Data::new(catalog_service))
+            .app_data(web::Data::new(bookmark_service))
+            .app_data(web::Data::new(media_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let upload = |method: test::TestRequest, uri: String, user_id: Option<Uuid>, body: Vec<u8>| {
+        let mut request = method
+            .uri(&uri)
+            .insert_header(("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY)))
+            .set_payload(body);
+        if let Some(user_id) = user_id {
+            request = request.insert_header(("Authorization", bearer(user_id)));
+        }
+        request.to_request()
+    };
+    let fetch = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+    let current_user = || {
+        test::TestRequest::get()
+            .uri("/v1/user")
+            .insert_header(("Authorization", bearer(customer.id)))
+            .to_request()
+    };
+    let company_card = || fetch(&format!("/v1/companies/{}", seed.company_id));
+
+    // Аватар: PNG с прозрачностью сохраняется в PNG, миниатюра квадратная
+    let avatar_uri = "/v1/user/avatar".to_string();
+    let response = test::call_service(
+        &app,
+        upload(test::TestRequest::put(), avatar_uri.clone(), Some(customer.id), multipart("file", "image/png", &png(300, 200))),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let avatar: Value = test::read_body_json(response).await;
+    assert_eq!(avatar["content_type"], "image/png");
+    assert_eq!((avatar["width"].as_i64(), avatar["height"].as_i64()), (Some(300), Some(200)));
+    let avatar_url = avatar["url"].as_str().unwrap().to_string();
+    assert!(avatar_url.starts_with(&format!("/v1/media/avatars/{}/", customer.id)), "{}", avatar_url);
+
+    let response = test::call_service(&app, fetch(&avatar_url)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "image/png");
+    assert!(
+        response.headers().get("Cache-Control").unwrap().to_str().unwrap().contains("immutable")
+    );
+    let stored = image::load_from_memory(&test::read_body(response).await).unwrap();
+    assert_eq!((stored.width(), stored.height()), (300, 200));
+    let thumbnail = test::call_and_read_body(&app, fetch(avatar["thumbnail_url"].as_str().unwrap())).await;
+    let thumbnail = image::load_from_memory(&thumbnail).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (256, 256));
+
+    let user: Value = test::call_and_read_body_json(&app, current_user()).await;
+    assert_eq!(user["avatar"]["url"], avatar_url.as_str());
+
+    // Новый аватар заменяет прежний, файлы прежнего удаляются; заявленный тип не важен
+    let response = test::call_service(
+        &app,
+        upload(test::TestRequest::put(), avatar_uri.clone(), Some(customer.id), multipart("file", "image/png", &jpeg(120, 80))),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let replaced: Value = test::read_body_json(response).await;
+    assert_eq!(replaced["content_type"], "image/jpeg");
+    assert_eq!(test::call_service(&app, fetch(&avatar_url)).await.status(), StatusCode::NOT_FOUND);
+    let user: Value = test::call_and_read_body_json(&app, current_user()).await;
+    assert_eq!(user["avatar"]["id"], replaced["id"]);
+
+    // Содержимое проверяется по сигнатуре, размер - по мере чтения
+    let rejected = [
+        (multipart("file", "image/png", b"<?php system($_GET['c']); ?>"), StatusCode::UNSUPPORTED_MEDIA_TYPE),
+        (multipart("file", "image/png", &[&png(1, 1)[..8], &vec![0u8; 300 * 1024]].concat()), StatusCode::PAYLOAD_TOO_LARGE),
+        (multipart("photo", "image/png", &png(10, 10)), StatusCode::BAD_REQUEST),
+        (multipart("file", "image/png", b""), StatusCode::BAD_REQUEST),
+    ];
+    for (body, status) in rejected {
+        let response = test::call_service(&app, upload(test::TestRequest::put(), avatar_uri.clone(), Some(customer.id), body)).await;
+        assert_eq!(response.status(), status);
+    }
+    let response = test::call_service(
+        &app,
+        upload(test::TestRequest::put(), avatar_uri.clone(), None, multipart("file", "image/png", &png(10, 10))),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Логотип и галерея доступны только руководителям компании
+    let logo_uri = format!("/v1/companies/{}/logo", seed.company_id);
+    let response = test::call_service(
+        &app,
+        upload(test::TestRequest::put(), logo_uri.clone(), Some(customer.id), multipart("file", "image/png", &png(64, 64))),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let mut logos = Vec::new();
+    for _ in 0..2 {
+        let response = test::call_service(
+            &app,
+            upload(test::TestRequest::put(), logo_uri.clone(), Some(seed.owner.id), multipart("file", "image/png", &png(64, 32))),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        logos.push(test::read_body_json::<Value, _>(response).await);
+    }
+    assert_eq!(
+        test::call_service(&app, fetch(logos[0]["url"].as_str().unwrap())).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    let gallery_uri = format!("/v1/companies/{}/gallery", seed.company_id);
+    let mut gallery = Vec::new();
+    for _ in 0..2 {
+        let response = test::call_service(
+            &app,
+            upload(test::TestRequest::post(), gallery_uri.clone(), Some(seed.owner.id), multipart("file", "image/jpeg", &jpeg(640, 480))),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        gallery.push(test::read_body_json::<Value, _>(response).await);
+    }
+
+    let card: Value = test::call_and_read_body_json(&app, company_card()).await;
+    assert_eq!(card["logo"]["id"], logos[1]["id"]);
+    assert_eq!(card["gallery"].as_array().unwrap().len(), 2);
+    assert_eq!(card["gallery"][0]["id"], gallery[0]["id"]);
+    let preview = test::call_and_read_body(&app, fetch(card["gallery"][1]["thumbnail_url"].as_str().unwrap())).await;
+    let preview = image::load_from_memory(&preview).unwrap();
+    assert_eq!((preview.width(), preview.height()), (480, 360));
+
+    let delete = |uri: String, user_id: Uuid| {
+        test::TestRequest::delete()
+            .uri(&uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .to_request()
+    };
+    let first_image = format!("{}/{}", gallery_uri, gallery[0]["id"].as_str().unwrap());
+    assert_eq!(test::call_service(&app, delete(first_image.clone(), customer.id)).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(test::call_service(&app, delete(first_image.clone(), seed.owner.id)).await.status(), StatusCode::NO_CONTENT);
+    assert_eq!(test::call_service(&app, delete(first_image, seed.owner.id)).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        test::call_service(&app, fetch(gallery[0]["url"].as_str().unwrap())).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(test::call_service(&app, delete(logo_uri, seed.owner.id)).await.status(), StatusCode::NO_CONTENT);
+    let card: Value = test::call_and_read_body_json(&app, company_card()).await;
+    assert!(card["logo"].is_null());
+    assert_eq!(card["gallery"].as_array().unwrap().len(), 1);
+
+    assert_eq!(test::call_service(&app, delete(avatar_uri, customer.id)).await.status(), StatusCode::NO_CONTENT);
+    let user: Value = test::call_and_read_body_json(&app, current_user()).await;
+    assert!(user["avatar"].is_null());
+    assert_eq!(
+        test::call_service(&app, fetch(replaced["url"].as_str().unwrap())).await.status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // Ключ из адреса не выводит за пределы каталога хранилища
+    for uri in ["/v1/media/..%2F..%2Fetc%2Fpasswd", "/v1/media/avatars/.hidden.png", "/v1/media/avatars/x.txt"] {
+        assert_eq!(test::call_service(&app, fetch(uri)).await.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+
+    std::fs::remove_dir_all(&media_dir).ok();
+}
@@ -0,0 +1,289 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, Utc};
+use common::ManualClock;
+use serde_json::{Value, json};
+use server::application::catalog_service::CatalogServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateHoldRequest, CreateStaffRequest,
+    CustomerRestriction, DEFAULT_DEPOSIT_REFUND_MIN_HOURS, DEFAULT_NO_SHOW_WINDOW_DAYS, JoinWaitlistRequest,
+    UpdateBookingPolicyRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{
+    BookingService, CatalogService, CompanyRepository, CustomerRestrictionService, HoldService, WaitlistService,
+};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::presentation::routes::api_v1_routes;
+use uuid::Uuid;
+
+fn policy(limit: Option<i32>, window_days: Option<i32>, action: Option<CustomerRestriction>) -> UpdateBookingPolicyRequest {
+    UpdateBookingPolicyRequest {
+        customer_cancel_min_hours: 0,
+        customer_reschedule_min_hours: 0,
+        no_show_limit: limit,
+        no_show_window_days: window_days,
+        no_show_action: action,
+        deposit_refund_min_hours: None,
+    }
+}
+
+#[actix_web::test]
+async fn repeated_no_shows_restrict_online_booking_until_manager_overrides() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let nurse = common::create_user(&pool, "nurse").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    CatalogServiceImpl::new(company_repository.clone())
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: "Медсестра".to_string(),
+                user_id: Some(nurse.id),
+                service_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let services = common::booking_services(&pool, clock.clone());
+    let bookings = services.bookings.clone();
+    let restriction_service: Arc<dyn CustomerRestrictionService + Send + Sync> = services.restrictions.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(restriction_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    // Некорректные правила отклоняются
+    for invalid in [
+        policy(Some(0), None, None),
+        policy(Some(2), Some(0), None),
+        policy(Some(2), Some(366), None),
+        policy(Some(2), None, Some(CustomerRestriction::None)),
+    ] {
+        let result = bookings.update_policy(seed.owner.id, seed.company_id, invalid).await;
+        assert!(matches!(result, Err(AppError::Validation(_))), "{:?}", result);
+    }
+    // PUT заменяет политику целиком: опущенные поля принимают значения по умолчанию
+    let mut full = policy(Some(5), Some(60), Some(CustomerRestriction::Blocked));
+    full.deposit_refund_min_hours = Some(48);
+    bookings.update_policy(seed.owner.id, seed.company_id, full).await.unwrap();
+    let replaced = bookings
+        .update_policy(seed.owner.id, seed.company_id, policy(None, None, None))
+        .await
+        .unwrap();
+    assert_eq!(replaced.no_show_limit, None);
+    assert_eq!(replaced.no_show_window_days, DEFAULT_NO_SHOW_WINDOW_DAYS);
+    assert_eq!(replaced.no_show_action, CustomerRestriction::RequireConfirmation);
+    assert_eq!(replaced.deposit_refund_min_hours, DEFAULT_DEPOSIT_REFUND_MIN_HOURS);
+
+    let saved = bookings
+        .update_policy(seed.owner.id, seed.company_id, policy(Some(2), Some(30), None))
+        .await
+        .unwrap();
+    assert_eq!(saved.no_show_action, CustomerRestriction::RequireConfirmation);
+
+    let booking_at = |days: i64, hour| CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, 0) + Duration::days(days),
+        promo_code: None,
+    };
+    let mark_no_show = |booking_id| {
+        let bookings = &bookings;
+        async move {
+            bookings
+                .change_status(
+                    seed.owner.id,
+                    booking_id,
+                    ChangeBookingStatusRequest {
+                        status: BookingStatus::NoShow,
+                        reason: None,
+                    },
+                )
+                .await
+        }
+    };
+    let first = bookings.create_booking(anna.id, booking_at(0, 9)).await.unwrap();
+    let second = bookings.create_booking(anna.id, booking_at(0, 10)).await.unwrap();
+    assert_eq!(first.status, BookingStatus::Confirmed);
+    // До начала приема неявку не отметить
+    assert!(matches!(mark_no_show(first.id).await, Err(AppError::Conflict(_))));
+
+    clock.advance(Duration::days(2));
+    mark_no_show(first.id).await.unwrap();
+    mark_no_show(second.id).await.unwrap();
+
+    // Клиент видит свой статус без причины ручной настройки
+    let my_status = || {
+        test::TestRequest::get()
+            .uri(&format!("/v1/companies/{}/booking-restriction", seed.company_id))
+            .insert_header(("Authorization", bearer(anna.id)))
+            .to_request()
+    };
+    let status: Value = test::call_and_read_body_json(&app, my_status()).await;
+    assert_eq!(status["restriction"], "require_confirmation");
+    assert_eq!(status["no_show_count"], 2);
+    assert_eq!(status["no_show_limit"], 2);
+    assert_eq!(status["no_show_window_days"], 30);
+    assert_eq!(status["overridden"], false);
+
+    // При ограничении запись ждет подтверждения компании
+    let pending = bookings.create_booking(anna.id, booking_at(3, 9)).await.unwrap();
+    assert_eq!(pending.status, BookingStatus::Pending);
+
+    // Блокировка закрывает все пути онлайн-записи
+    bookings
+        .update_policy(
+            seed.owner.id,
+            seed.company_id,
+            policy(Some(2), Some(30), Some(CustomerRestriction::Blocked)),
+        )
+        .await
+        .unwrap();
+    let result = bookings.create_booking(anna.id, booking_at(3, 11)).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))), "{:?}", result);
+    let result = services
+        .holds
+        .create_hold(
+            anna.id,
+            CreateHoldRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: booking_at(3, 12).starts_at,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))), "{:?}", result);
+    let result = services
+        .waitlist
+        .join_waitlist(
+            anna.id,
+            JoinWaitlistRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: None,
+                location_id: None,
+                date: booking_at(4, 9).starts_at.date_naive(),
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))), "{:?}", result);
+
+    let restriction_uri = format!("/v1/companies/{}/customers/{}/restriction", seed.company_id, anna.id);
+    let restriction_request = |method: test::TestRequest, user_id: Uuid, uri: &str| {
+        method.uri(uri).insert_header(("Authorization", bearer(user_id)))
+    };
+    // Сотрудник видит ограничение, но менять его может только руководитель
+    let details: Value = test::call_and_read_body_json(
+        &app,
+        restriction_request(test::TestRequest::get(), nurse.id, &restriction_uri).to_request(),
+    )
+    .await;
+    assert_eq!(details["restriction"], "blocked");
+    assert!(details["override"].is_null());
+    let response = test::call_service(
+        &app,
+        restriction_request(test::TestRequest::get(), anna.id, &restriction_uri).to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = test::call_service(
+        &app,
+        restriction_request(test::TestRequest::put(), nurse.id, &restriction_uri)
+            .set_json(json!({"restriction": "none"}))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let unknown_uri = format!("/v1/companies/{}/customers/{}/restriction", seed.company_id, Uuid::new_v4());
+    let response = test::call_service(
+        &app,
+        restriction_request(test::TestRequest::put(), seed.owner.id, &unknown_uri)
+            .set_json(json!({"restriction": "none"}))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let details: Value = test::call_and_read_body_json(
+        &app,
+        restriction_request(test::TestRequest::put(), seed.owner.id, &restriction_uri)
+            .set_json(json!({"restriction": "none", "reason": "  Болел, предупредил по телефону "}))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(details["restriction"], "none");
+    assert_eq!(details["overridden"], true);
+    assert_eq!(details["no_show_count"], 2);
+    assert_eq!(details["override"]["reason"], "Болел, предупредил по телефону");
+    assert_eq!(details["override"]["set_by"], seed.owner.id.to_string());
+    let status: Value = test::call_and_read_body_json(&app, my_status()).await;
+    assert_eq!(status["restriction"], "none");
+    assert!(status.get("override").is_none());
+    let booking = bookings.create_booking(anna.id, booking_at(3, 11)).await.unwrap();
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+
+    // Снятие ручной настройки возвращает правило компании
+    let details: Value = test::call_and_read_body_json(
+        &app,
+        restriction_request(test::TestRequest::delete(), seed.owner.id, &restriction_uri).to_request(),
+    )
+    .await;
+    assert_eq!(details["restriction"], "blocked");
+    assert_eq!(details["overridden"], false);
+
+    // Администратор платформы видит и меняет ограничение, не будучи сотрудником компании
+    let admin = common::create_user(&pool, "admin").await;
+    sqlx::query("UPDATE users SET user_type_id = 3 WHERE id = $1")
+        .bind(admin.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let details: Value = test::call_and_read_body_json(
+        &app,
+        restriction_request(test::TestRequest::put(), admin.id, &restriction_uri)
+            .set_json(json!({"restriction": "require_confirmation"}))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(details["restriction"], "require_confirmation");
+    assert_eq!(details["override"]["set_by"], admin.id.to_string());
+    let details: Value = test::call_and_read_body_json(
+        &app,
+        restriction_request(test::TestRequest::get(), admin.id, &restriction_uri).to_request(),
+    )
+    .await;
+    assert_eq!(details["restriction"], "require_confirmation");
+    assert_eq!(details["override"]["set_by"], admin.id.to_string());
+    let details: Value = test::call_and_read_body_json(
+        &app,
+        restriction_request(test::TestRequest::delete(), admin.id, &restriction_uri).to_request(),
+    )
+    .await;
+    assert_eq!(details["restriction"], "blocked");
+    assert_eq!(details["overridden"], false);
+
+    // Неявки за пределами окна не учитываются
+    clock.advance(Duration::days(30));
+    let status: Value = test::call_and_read_body_json(&app, my_status()).await;
+    assert_eq!(status["restriction"], "none");
+    assert_eq!(status["no_show_count"], 0);
+}
@@ -0,0 +1,246 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use common::ManualClock;
+use server::application::booking_service::BookingServiceImpl;
+use server::application::notification_dispatcher::NotificationDispatcher;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, NotificationKind, OutgoingMessage,
+    RescheduleBookingRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingService, Notifier};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::postgres_outbox_repository::PostgreSQLOutboxRepository;
+use server::infrastructure::postgres_user_repository::PostgreSQLUserRepository;
+use server::infrastructure::smtp_notifier::SmtpNotifier;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// Запоминает отправленные уведомления
+#[derive(Default)]
+struct RecordingNotifier {
+    sent: Mutex<Vec<OutgoingMessage>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    fn channel(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn send(&self, message: &OutgoingMessage) -> Result<(), AppError> {
+        self.sent.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+}
+
+/// Канал, который отказывает, пока не включен
+#[derive(Default)]
+struct FlakyNotifier {
+    available: AtomicBool,
+    sent: Mutex<Vec<Uuid>>,
+}
+
+#[async_trait]
+impl Notifier for FlakyNotifier {
+    fn channel(&self) -> &'static str {
+        "flaky"
+    }
+
+    async fn send(&self, message: &OutgoingMessage) -> Result<(), AppError> {
+        if !self.available.load(Ordering::SeqCst) {
+            return Err(AppError::Internal("канал недоступен".to_string()));
+        }
+        self.sent.lock().unwrap().push(message.id);
+        Ok(())
+    }
+}
+
+fn booking_service(pool: &PgPool) -> Arc<BookingServiceImpl> {
+    common::booking_services(pool, Arc::new(SystemClock)).bookings
+}
+
+fn create_request(seed: &common::Seed, hour: u32) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, 0),
+        promo_code: None,
+    }
+}
+
+async fn outbox_rows(pool: &PgPool, user_id: Uuid) -> Vec<(String, Vec<String>, i32)> {
+    sqlx::query(
+        "SELECT status, delivered_channels, attempts FROM notification_outbox WHERE user_id = $1 ORDER BY created_at",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+    .iter()
+    .map(|row| (row.get("status"), row.get("delivered_channels"), row.get("attempts")))
+    .collect()
+}
+
+#[actix_web::test]
+async fn booking_changes_are_delivered_from_outbox_with_retries() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "patient").await;
+    let rival = common::create_user(&pool, "rival").await;
+    PostgreSQLUserRepository::new(pool.clone())
+        .update_user_fields(customer.id, None, None, None, Some("en"))
+        .await
+        .unwrap();
+    let bookings = booking_service(&pool);
+
+    let booking = bookings.create_booking(customer.id, create_request(&seed, 10)).await.unwrap();
+    bookings
+        .reschedule_booking(
+            customer.id,
+            booking.id,
+            RescheduleBookingRequest {
+                starts_at: common::tomorrow_at(12, 0),
+                staff_id: None,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    bookings
+        .change_status(
+            customer.id,
+            booking.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: Some("Plans changed".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+
+    // Неудавшаяся запись откатывается вместе со своим уведомлением
+    bookings.create_booking(customer.id, create_request(&seed, 15)).await.unwrap();
+    assert!(matches!(
+        bookings.create_booking(rival.id, create_request(&seed, 15)).await,
+        Err(AppError::Conflict(_))
+    ));
+    assert!(outbox_rows(&pool, rival.id).await.is_empty());
+
+    let recording = Arc::new(RecordingNotifier::default());
+    let flaky = Arc::new(FlakyNotifier::default());
+    let clock = Arc::new(ManualClock::new(Utc::now() + Duration::seconds(1)));
+    let dispatcher = NotificationDispatcher::new(Arc::new(PostgreSQLOutboxRepository::new(pool.clone())), clock.clone())
+        .with_channel(recording.clone())
+        .with_channel(flaky.clone());
+
+    // Первый проход: один канал доставил, другой отказал - сообщения ждут повтора
+    assert_eq!(dispatcher.run_once().await.unwrap(), 0);
+    {
+        let sent = recording.sent.lock().unwrap();
+        let kinds: Vec<NotificationKind> = sent.iter().map(|message| message.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                NotificationKind::BookingConfirmed,
+                NotificationKind::BookingRescheduled,
+                NotificationKind::BookingCancelled,
+                NotificationKind::BookingConfirmed,
+            ]
+        );
+        assert_eq!(sent[0].subject, "Booking confirmed: Консультация");
+        assert!(sent[1].body.contains(&common::tomorrow_at(10, 0).format("%b %-d, %Y 10:00").to_string()));
+        assert!(sent[2].body.ends_with("Reason: Plans changed"));
+    }
+    for (status, delivered, attempts) in outbox_rows(&pool, customer.id).await {
+        assert_eq!((status.as_str(), delivered, attempts), ("pending", vec!["recording".to_string()], 1));
+    }
+
+    // Повтор возможен только после паузы
+    assert_eq!(dispatcher.run_once().await.unwrap(), 0);
+    flaky.available.store(true, Ordering::SeqCst);
+    clock.advance(Duration::seconds(31));
+    assert_eq!(dispatcher.run_once().await.unwrap(), 4);
+    assert_eq!(recording.sent.lock().unwrap().len(), 4);
+    assert_eq!(flaky.sent.lock().unwrap().len(), 4);
+    for (status, delivered, attempts) in outbox_rows(&pool, customer.id).await {
+        assert_eq!(status, "sent");
+        assert_eq!(delivered, vec!["recording".to_string(), "flaky".to_string()]);
+        assert_eq!(attempts, 2);
+    }
+}
+
+/// Простейший SMTP-сервер на одно соединение: отвечает согласием и возвращает диалог целиком
+fn smtp_catcher() -> (u16, std::thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut transcript = String::new();
+        writer.write_all(b"220 catcher ESMTP\r\n").unwrap();
+        let mut in_data = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            transcript.push_str(&line);
+            let reply: &[u8] = if in_data {
+                if line != ".\r\n" {
+                    continue;
+                }
+                in_data = false;
+                b"250 queued\r\n"
+            } else if line.starts_with("DATA") {
+                in_data = true;
+                b"354 go ahead\r\n"
+            } else if line.starts_with("QUIT") {
+                writer.write_all(b"221 bye\r\n").unwrap();
+                break;
+            } else {
+                b"250 ok\r\n"
+            };
+            writer.write_all(reply).unwrap();
+        }
+        transcript
+    });
+    (port, handle)
+}
+
+#[actix_web::test]
+async fn email_channel_sends_through_smtp() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "patient").await;
+    booking_service(&pool)
+        .create_booking(customer.id, create_request(&seed, 10))
+        .await
+        .unwrap();
+
+    let (port, catcher) = smtp_catcher();
+    let smtp = SmtpNotifier::unencrypted("127.0.0.1", port, "LocationX <noreply@example.com>").unwrap();
+    let dispatcher = NotificationDispatcher::new(
+        Arc::new(PostgreSQLOutboxRepository::new(pool.clone())),
+        Arc::new(ManualClock::new(Utc::now() + Duration::seconds(1))),
+    )
+    .with_channel(Arc::new(smtp));
+    assert_eq!(dispatcher.run_once().await.unwrap(), 1);
+    drop(dispatcher);
+
+    let transcript = catcher.join().unwrap();
+    assert!(transcript.contains("MAIL FROM:<noreply@example.com>"));
+    assert!(transcript.contains("RCPT TO:<patient@example.com>"));
+    assert!(transcript.contains("Subject: "));
+    assert_eq!(outbox_rows(&pool, customer.id).await[0].0, "sent");
+}
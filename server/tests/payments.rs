@@ -0,0 +1,274 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, Utc};
+use common::ManualClock;
+use serde_json::Value;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateServiceRequest, PaymentOutcome,
+    UpdateBookingPolicyRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingService, CatalogService, CompanyRepository, PaymentService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::fake_payment_provider::{FAKE_SIGNATURE_HEADER, FakePaymentProvider, FakeRefund};
+use server::infrastructure::job_queue::JobWorker;
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::payment_refund::PaymentRefundHandler;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_job_repository::PostgreSQLJobRepository;
+use server::infrastructure::postgres_payment_repository::PostgreSQLPaymentRepository;
+use server::presentation::routes::api_v1_routes;
+use uuid::Uuid;
+
+#[actix_web::test]
+async fn deposit_confirms_booking_and_is_refunded_by_policy() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let boris = common::create_user(&pool, "boris").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    // Депозит положителен и не больше цены услуги
+    let deposit_service = |deposit_amount| CreateServiceRequest {
+        name: "Чистка".to_string(),
+        description: None,
+        duration_minutes: 60,
+        buffer_before_minutes: None,
+        buffer_after_minutes: None,
+        slot_step_minutes: None,
+        price_amount: Some(100_000),
+        currency: None,
+        capacity: None,
+        deposit_amount: Some(deposit_amount),
+    };
+    let catalog = CatalogServiceImpl::new(company_repository.clone());
+    for invalid in [0, 100_001] {
+        let result = catalog
+            .create_service(seed.owner.id, seed.company_id, deposit_service(invalid))
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))), "{:?}", result);
+    }
+    let created = catalog
+        .create_service(seed.owner.id, seed.company_id, deposit_service(30_000))
+        .await
+        .unwrap();
+    assert_eq!(created.deposit_amount, Some(30_000));
+    sqlx::query("UPDATE services SET deposit_amount = 30000 WHERE id = $1")
+        .bind(seed.service_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let services = common::booking_services(&pool, clock.clone());
+    let provider = services.payment_provider.clone();
+    let payment_repository = Arc::new(PostgreSQLPaymentRepository::new(pool.clone()));
+    let bookings = services.bookings.clone();
+    let payment_service: Arc<dyn PaymentService + Send + Sync> = services.payments.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(payment_service.clone()))
+            .service(api_v1_routes()),
+    )
+    .await;
+    let refunds = JobWorker::new(Arc::new(PostgreSQLJobRepository::new(pool.clone())), Arc::new(SystemClock))
+        .register(PaymentRefundHandler::new(payment_repository, provider.clone(), Arc::new(SystemClock)));
+
+    let book = |days: i64, hour| {
+        let bookings = &bookings;
+        async move {
+            bookings
+                .create_booking(
+                    anna.id,
+                    CreateBookingRequest {
+                        company_id: seed.company_id,
+                        service_id: seed.service_id,
+                        staff_id: seed.staff_id,
+                        location_id: None,
+                        starts_at: common::tomorrow_at(hour, 0) + Duration::days(days),
+                        promo_code: None,
+                    },
+                )
+                .await
+                .unwrap()
+        }
+    };
+    let payment_request = |method: test::TestRequest, user_id: Uuid, booking_id: Uuid| {
+        method
+            .uri(&format!("/v1/bookings/{}/payment", booking_id))
+            .insert_header(("Authorization", bearer(user_id)))
+            .to_request()
+    };
+    let notify = |provider_payment_id: &Value, outcome| {
+        let body = FakePaymentProvider::event_body(provider_payment_id.as_str().unwrap(), outcome);
+        test::TestRequest::post()
+            .uri("/v1/payments/webhooks/fake")
+            .insert_header((FAKE_SIGNATURE_HEADER, provider.sign(&body)))
+            .set_payload(body)
+            .to_request()
+    };
+    let status_of = |booking_id| {
+        let bookings = &bookings;
+        async move { bookings.get_booking(anna.id, booking_id).await.unwrap().status }
+    };
+    let cancel = |user_id, booking_id, status| {
+        let bookings = &bookings;
+        async move {
+            bookings
+                .change_status(user_id, booking_id, ChangeBookingStatusRequest { status, reason: None })
+                .await
+                .unwrap()
+        }
+    };
+
+    // Запись на услугу с депозитом ждет оплаты
+    let booking = book(2, 9).await;
+    assert_eq!(booking.status, BookingStatus::Pending);
+    let payment: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, booking.id)).await;
+    assert_eq!(payment["status"], "pending");
+    assert_eq!(payment["amount"], 30_000);
+    assert_eq!(payment["currency"], "RUB");
+    assert!(payment["provider"].is_null());
+    // Без оплаты сотрудник запись не подтвердит
+    let result = bookings
+        .change_status(
+            seed.owner.id,
+            booking.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::Confirmed,
+                reason: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", result);
+
+    let response = test::call_service(&app, payment_request(test::TestRequest::post(), boris.id, booking.id)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let started: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::post(), anna.id, booking.id)).await;
+    assert_eq!(started["provider"], "fake");
+    assert!(started["client_secret"].is_string());
+    // Повторный запрос не создает второй платеж
+    let again: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::post(), anna.id, booking.id)).await;
+    assert_eq!(again["provider_payment_id"], started["provider_payment_id"]);
+    // Сотрудники компании видят депозит без секрета оплаты
+    let seen: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), seed.owner.id, booking.id)).await;
+    assert_eq!(seen["status"], "pending");
+    assert!(seen.get("client_secret").is_none());
+
+    // Уведомления проверяются по подписи
+    let body = FakePaymentProvider::event_body(started["provider_payment_id"].as_str().unwrap(), PaymentOutcome::Succeeded);
+    let forged = test::TestRequest::post()
+        .uri("/v1/payments/webhooks/fake")
+        .insert_header((FAKE_SIGNATURE_HEADER, FakePaymentProvider::new("other").sign(&body)))
+        .set_payload(body.clone())
+        .to_request();
+    assert_eq!(test::call_service(&app, forged).await.status(), StatusCode::BAD_REQUEST);
+    let unknown = test::TestRequest::post()
+        .uri("/v1/payments/webhooks/stripe")
+        .insert_header((FAKE_SIGNATURE_HEADER, provider.sign(&body)))
+        .set_payload(body)
+        .to_request();
+    assert_eq!(test::call_service(&app, unknown).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(status_of(booking.id).await, BookingStatus::Pending);
+
+    // Оплата подтверждает запись, повтор уведомления ничего не меняет
+    for _ in 0..2 {
+        let response = test::call_service(&app, notify(&started["provider_payment_id"], PaymentOutcome::Succeeded)).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+    assert_eq!(status_of(booking.id).await, BookingStatus::Confirmed);
+    let paid: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, booking.id)).await;
+    assert_eq!(paid["status"], "succeeded");
+    assert!(paid["paid_at"].is_string());
+    let response = test::call_service(&app, payment_request(test::TestRequest::post(), anna.id, booking.id)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Ранняя отмена клиентом возвращает депозит через очередь задач
+    cancel(anna.id, booking.id, BookingStatus::CancelledByCustomer).await;
+    let refunding: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, booking.id)).await;
+    assert_eq!(refunding["status"], "refund_pending");
+    assert_eq!(refunding["refund_amount"], 30_000);
+    assert_eq!(refunds.run_once().await.unwrap(), 1);
+    assert_eq!(
+        provider.refunds(),
+        vec![FakeRefund {
+            provider_payment_id: started["provider_payment_id"].as_str().unwrap().to_string(),
+            amount: 30_000,
+            currency: "RUB".to_string(),
+        }]
+    );
+    let refunded: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, booking.id)).await;
+    assert_eq!(refunded["status"], "refunded");
+    assert!(refunded["refunded_at"].is_string());
+
+    // Поздняя отмена клиентом оставляет депозит компании, отмена компанией возвращает его
+    bookings
+        .update_policy(
+            seed.owner.id,
+            seed.company_id,
+            UpdateBookingPolicyRequest {
+                customer_cancel_min_hours: 0,
+                customer_reschedule_min_hours: 0,
+                no_show_limit: None,
+                no_show_window_days: None,
+                no_show_action: None,
+                deposit_refund_min_hours: Some(24 * 7),
+            },
+        )
+        .await
+        .unwrap();
+    let late = book(2, 11).await;
+    let by_company = book(2, 13).await;
+    for booking_id in [late.id, by_company.id] {
+        let started: Value =
+            test::call_and_read_body_json(&app, payment_request(test::TestRequest::post(), anna.id, booking_id)).await;
+        test::call_service(&app, notify(&started["provider_payment_id"], PaymentOutcome::Succeeded)).await;
+        assert_eq!(status_of(booking_id).await, BookingStatus::Confirmed);
+    }
+    cancel(anna.id, late.id, BookingStatus::CancelledByCustomer).await;
+    cancel(seed.owner.id, by_company.id, BookingStatus::CancelledByCompany).await;
+    let kept: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, late.id)).await;
+    assert_eq!(kept["status"], "succeeded");
+    assert!(kept["refund_amount"].is_null());
+    let returned: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, by_company.id)).await;
+    assert_eq!(returned["status"], "refund_pending");
+    assert_eq!(refunds.run_once().await.unwrap(), 1);
+    assert_eq!(provider.refunds().len(), 2);
+
+    // После неудачной попытки оплату можно начать заново
+    let unpaid = book(3, 9).await;
+    let first: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::post(), anna.id, unpaid.id)).await;
+    test::call_service(&app, notify(&first["provider_payment_id"], PaymentOutcome::Failed)).await;
+    let failed: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, unpaid.id)).await;
+    assert_eq!(failed["status"], "failed");
+    let second: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::post(), anna.id, unpaid.id)).await;
+    assert_eq!(second["status"], "pending");
+    assert_ne!(second["provider_payment_id"], first["provider_payment_id"]);
+    // Уведомление о прежней попытке уже ни на что не влияет
+    test::call_service(&app, notify(&first["provider_payment_id"], PaymentOutcome::Succeeded)).await;
+    assert_eq!(status_of(unpaid.id).await, BookingStatus::Pending);
+
+    // Неоплаченная вовремя запись отменяется и освобождает время
+    services.sweeper.run_once().await.unwrap();
+    assert_eq!(status_of(unpaid.id).await, BookingStatus::Pending);
+    clock.advance(Duration::minutes(31));
+    services.sweeper.run_once().await.unwrap();
+    assert_eq!(status_of(unpaid.id).await, BookingStatus::CancelledByCustomer);
+    let cancelled: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, unpaid.id)).await;
+    assert_eq!(cancelled["status"], "cancelled");
+
+    // Оплата, дошедшая после отмены, возвращается целиком
+    test::call_service(&app, notify(&second["provider_payment_id"], PaymentOutcome::Succeeded)).await;
+    let returned: Value = test::call_and_read_body_json(&app, payment_request(test::TestRequest::get(), anna.id, unpaid.id)).await;
+    assert_eq!(returned["status"], "refund_pending");
+    assert_eq!(returned["refund_amount"], 30_000);
+    assert_eq!(status_of(unpaid.id).await, BookingStatus::CancelledByCustomer);
+}
@@ -0,0 +1,467 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, NaiveTime, Utc};
+use common::ManualClock;
+use serde_json::{Value, json};
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::promo_code_service::PromoCodeServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateLocationRequest, CreatePromoCodeRequest,
+    CreateServiceRequest, CreateStaffRequest, DiscountType, RescheduleBookingRequest, ScheduleEntryRequest,
+    UpdatePromoCodeRequest, UpdateScheduleRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingService, CatalogService, Clock, CompanyRepository, PromoCodeService};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_promo_code_repository::PostgreSQLPromoCodeRepository;
+use server::presentation::routes::api_v1_routes;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn percent_code(code: &str, discount_value: i64) -> CreatePromoCodeRequest {
+    CreatePromoCodeRequest {
+        code: code.to_string(),
+        discount_type: DiscountType::Percent,
+        discount_value,
+        currency: None,
+        valid_from: None,
+        valid_until: None,
+        max_redemptions: None,
+        max_per_customer: None,
+        service_ids: Vec::new(),
+        location_ids: Vec::new(),
+    }
+}
+
+async fn redemption_count(pool: &PgPool, promo_code_id: Uuid) -> i32 {
+    sqlx::query_scalar("SELECT redemption_count FROM promo_codes WHERE id = $1")
+        .bind(promo_code_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn managers_create_codes_and_customers_get_quotes() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let nurse = common::create_user(&pool, "nurse").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let catalog = CatalogServiceImpl::new(company_repository.clone());
+    catalog
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: "Медсестра".to_string(),
+                user_id: Some(nurse.id),
+                service_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    let branch = catalog
+        .create_location(
+            seed.owner.id,
+            seed.company_id,
+            CreateLocationRequest {
+                name: "Филиал на Невском".to_string(),
+                address: None,
+                city: Some("Санкт-Петербург".to_string()),
+                latitude: None,
+                longitude: None,
+            },
+        )
+        .await
+        .unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let promo_code_service: Arc<dyn PromoCodeService + Send + Sync> = Arc::new(PromoCodeServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLPromoCodeRepository::new(pool.clone())),
+        clock.clone(),
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(promo_code_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let codes_uri = format!("/v1/companies/{}/promo-codes", seed.company_id);
+    let create = |user_id, body: Value| {
+        test::TestRequest::post()
+            .uri(&codes_uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(body)
+            .to_request()
+    };
+    let quote = |promo_code: Option<&str>, location_id: Option<Uuid>| {
+        test::TestRequest::post()
+            .uri("/v1/bookings/quote")
+            .insert_header(("Authorization", bearer(anna.id)))
+            .set_json(json!({
+                "company_id": seed.company_id,
+                "service_id": seed.service_id,
+                "location_id": location_id,
+                "promo_code": promo_code,
+            }))
+            .to_request()
+    };
+
+    // Промокоды заводят только руководители
+    let spring = json!({ "code": "spring-15", "discount_type": "percent", "discount_value": 15 });
+    for user_id in [anna.id, nurse.id] {
+        let response = test::call_service(&app, create(user_id, spring.clone())).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+    let response = test::call_service(&app, create(seed.owner.id, spring.clone())).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: Value = test::read_body_json(response).await;
+    assert_eq!(created["code"], "SPRING-15");
+    assert_eq!(created["redemption_count"], 0);
+    let duplicate = json!({ "code": "SPRING-15", "discount_type": "fixed", "discount_value": 500, "currency": "RUB" });
+    let response = test::call_service(&app, create(seed.owner.id, duplicate)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let invalid = [
+        json!({ "code": "X", "discount_type": "percent", "discount_value": 10 }),
+        json!({ "code": "HALF", "discount_type": "percent", "discount_value": 101 }),
+        json!({ "code": "HALF", "discount_type": "percent", "discount_value": 50, "currency": "RUB" }),
+        json!({ "code": "MINUS", "discount_type": "fixed", "discount_value": 500 }),
+        json!({ "code": "ONCE", "discount_type": "percent", "discount_value": 10, "max_per_customer": 0 }),
+        json!({ "code": "OTHER", "discount_type": "percent", "discount_value": 10, "service_ids": [Uuid::new_v4()] }),
+        json!({
+            "code": "WINDOW", "discount_type": "percent", "discount_value": 10,
+            "valid_from": clock.now() + Duration::days(2), "valid_until": clock.now() + Duration::days(1),
+        }),
+    ];
+    for body in invalid {
+        let response = test::call_service(&app, create(seed.owner.id, body.clone())).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", body);
+    }
+
+    // Без промокода - полная цена, код вводится без учета регистра
+    let full: Value = test::call_and_read_body_json(&app, quote(None, None)).await;
+    assert_eq!(full["price_amount"], 100_000);
+    assert_eq!(full["discount_amount"], 0);
+    assert_eq!(full["total_amount"], 100_000);
+    let discounted: Value = test::call_and_read_body_json(&app, quote(Some(" spring-15"), None)).await;
+    assert_eq!(discounted["discount_amount"], 15_000);
+    assert_eq!(discounted["total_amount"], 85_000);
+    assert_eq!(discounted["promo_code"], "SPRING-15");
+    let response = test::call_service(&app, quote(Some("WINTER"), None)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Фиксированная скидка только в филиале на Невском
+    let body = json!({
+        "code": "NEVSKY", "discount_type": "fixed", "discount_value": 20_000, "currency": "rub",
+        "location_ids": [branch.id],
+    });
+    let response = test::call_service(&app, create(seed.owner.id, body)).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    for location_id in [None, Some(seed.location_id)] {
+        let response = test::call_service(&app, quote(Some("NEVSKY"), location_id)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    let nevsky: Value = test::call_and_read_body_json(&app, quote(Some("NEVSKY"), Some(branch.id))).await;
+    assert_eq!(nevsky["discount_amount"], 20_000);
+    assert_eq!(nevsky["total_amount"], 80_000);
+
+    // Промокод на другую услугу к консультации не применить
+    let massage = catalog
+        .create_service(
+            seed.owner.id,
+            seed.company_id,
+            CreateServiceRequest {
+                name: "Массаж".to_string(),
+                description: None,
+                duration_minutes: 60,
+                buffer_before_minutes: None,
+                buffer_after_minutes: None,
+                slot_step_minutes: None,
+                price_amount: Some(300_000),
+                currency: None,
+                capacity: None,
+                deposit_amount: None,
+            },
+        )
+        .await
+        .unwrap();
+    let body = json!({
+        "code": "MASSAGE", "discount_type": "percent", "discount_value": 10, "service_ids": [massage.id],
+    });
+    assert_eq!(test::call_service(&app, create(seed.owner.id, body)).await.status(), StatusCode::CREATED);
+    let response = test::call_service(&app, quote(Some("MASSAGE"), None)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Окно действия считается по часам сервиса
+    let body = json!({
+        "code": "LATER", "discount_type": "percent", "discount_value": 10,
+        "valid_from": clock.now() + Duration::days(1), "valid_until": clock.now() + Duration::days(2),
+    });
+    let later: Value = test::call_and_read_body_json(&app, create(seed.owner.id, body)).await;
+    assert_eq!(test::call_service(&app, quote(Some("LATER"), None)).await.status(), StatusCode::BAD_REQUEST);
+    clock.advance(Duration::days(1));
+    assert_eq!(test::call_service(&app, quote(Some("LATER"), None)).await.status(), StatusCode::OK);
+    clock.advance(Duration::days(1));
+    assert_eq!(test::call_service(&app, quote(Some("LATER"), None)).await.status(), StatusCode::BAD_REQUEST);
+
+    // Отключенный промокод не действует, список видят только руководители
+    let response = test::call_service(
+        &app,
+        test::TestRequest::patch()
+            .uri(&format!("{}/{}", codes_uri, created["id"].as_str().unwrap()))
+            .insert_header(("Authorization", bearer(seed.owner.id)))
+            .set_json(json!({ "is_active": false }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(test::call_service(&app, quote(Some("SPRING-15"), None)).await.status(), StatusCode::BAD_REQUEST);
+    let list = |user_id| {
+        test::TestRequest::get()
+            .uri(&codes_uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .to_request()
+    };
+    assert_eq!(test::call_service(&app, list(nurse.id)).await.status(), StatusCode::FORBIDDEN);
+    let codes: Value = test::call_and_read_body_json(&app, list(seed.owner.id)).await;
+    assert_eq!(codes.as_array().unwrap().len(), 4);
+    assert_eq!(codes[0]["id"], later["id"]);
+    assert_eq!(codes[3]["is_active"], false);
+}
+
+#[actix_web::test]
+async fn bookings_redeem_codes_within_limits() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let boris = common::create_user(&pool, "boris").await;
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let promo_repository = Arc::new(PostgreSQLPromoCodeRepository::new(pool.clone()));
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let promo_codes = PromoCodeServiceImpl::new(company_repository, promo_repository, clock.clone());
+    let bookings = common::booking_services(&pool, clock.clone()).bookings;
+
+    let book = |customer_id, hour, promo_code: &str| {
+        bookings.create_booking(
+            customer_id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: common::tomorrow_at(hour, 0),
+                promo_code: Some(promo_code.to_string()),
+            },
+        )
+    };
+
+    // Один раз на клиента: повторное использование отклоняется, запись не создается
+    let once = promo_codes
+        .create_promo_code(
+            seed.owner.id,
+            seed.company_id,
+            CreatePromoCodeRequest {
+                max_per_customer: Some(1),
+                ..percent_code("WELCOME", 20)
+            },
+        )
+        .await
+        .unwrap();
+    let booking = book(anna.id, 9, "welcome").await.unwrap();
+    let (price_amount, discount_amount): (i64, i64) = sqlx::query_as(
+        "SELECT price_amount, discount_amount FROM promo_redemptions WHERE booking_id = $1 AND customer_id = $2",
+    )
+    .bind(booking.id)
+    .bind(anna.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((price_amount, discount_amount), (100_000, 20_000));
+    let result = book(anna.id, 11, "WELCOME").await;
+    assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", result);
+    book(boris.id, 11, "WELCOME").await.unwrap();
+    assert_eq!(redemption_count(&pool, once.id).await, 2);
+    let bookings_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bookings WHERE customer_id = $1")
+        .bind(anna.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(bookings_count, 1);
+
+    // Отмена записи возвращает использование: клиент может применить промокод снова
+    bookings
+        .change_status(
+            anna.id,
+            booking.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(redemption_count(&pool, once.id).await, 1);
+    book(anna.id, 12, "WELCOME").await.unwrap();
+    assert_eq!(redemption_count(&pool, once.id).await, 2);
+
+    // Последнее использование достается одной из одновременных записей
+    let last = promo_codes
+        .create_promo_code(
+            seed.owner.id,
+            seed.company_id,
+            CreatePromoCodeRequest {
+                max_redemptions: Some(1),
+                ..percent_code("LAST-ONE", 50)
+            },
+        )
+        .await
+        .unwrap();
+    let (first, second) = futures_util::join!(book(anna.id, 14, "LAST-ONE"), book(boris.id, 16, "LAST-ONE"));
+    let results = [first, second];
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1, "{:?}", results);
+    assert!(results.iter().any(|result| matches!(result, Err(AppError::Conflict(_)))));
+    assert_eq!(redemption_count(&pool, last.id).await, 1);
+
+    // Лимит нельзя опустить ниже уже сделанных использований
+    let result = promo_codes
+        .update_promo_code(
+            seed.owner.id,
+            seed.company_id,
+            once.id,
+            UpdatePromoCodeRequest {
+                is_active: None,
+                valid_until: None,
+                max_redemptions: Some(1),
+                max_per_customer: None,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))), "{:?}", result);
+}
+
+#[actix_web::test]
+async fn rescheduling_to_another_location_releases_a_location_bound_code() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let catalog = CatalogServiceImpl::new(company_repository.clone());
+    let branch = catalog
+        .create_location(
+            seed.owner.id,
+            seed.company_id,
+            CreateLocationRequest {
+                name: "Филиал на Невском".to_string(),
+                address: None,
+                city: Some("Санкт-Петербург".to_string()),
+                latitude: None,
+                longitude: None,
+            },
+        )
+        .await
+        .unwrap();
+    // До обеда сотрудник принимает в главном корпусе, после - в филиале
+    catalog
+        .update_schedule(
+            seed.owner.id,
+            seed.company_id,
+            seed.staff_id,
+            UpdateScheduleRequest {
+                entries: (1..=7)
+                    .flat_map(|weekday| {
+                        [(seed.location_id, 8, 14), (branch.id, 14, 20)].map(|(location_id, from, to)| {
+                            ScheduleEntryRequest {
+                                location_id,
+                                weekday,
+                                start_time: NaiveTime::from_hms_opt(from, 0, 0).unwrap(),
+                                end_time: NaiveTime::from_hms_opt(to, 0, 0).unwrap(),
+                            }
+                        })
+                    })
+                    .collect(),
+            },
+        )
+        .await
+        .unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let promo_codes = PromoCodeServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLPromoCodeRepository::new(pool.clone())),
+        clock.clone(),
+    );
+    let bookings = common::booking_services(&pool, clock.clone()).bookings;
+    let nevsky = promo_codes
+        .create_promo_code(
+            seed.owner.id,
+            seed.company_id,
+            CreatePromoCodeRequest {
+                location_ids: vec![branch.id],
+                ..percent_code("NEVSKY", 10)
+            },
+        )
+        .await
+        .unwrap();
+    let booking = bookings
+        .create_booking(
+            anna.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: Some(branch.id),
+                starts_at: common::tomorrow_at(15, 0),
+                promo_code: Some("NEVSKY".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(redemption_count(&pool, nevsky.id).await, 1);
+    let reschedule = |hour| {
+        let bookings = &bookings;
+        async move {
+            bookings
+                .reschedule_booking(
+                    anna.id,
+                    booking.id,
+                    RescheduleBookingRequest {
+                        starts_at: common::tomorrow_at(hour, 0),
+                        staff_id: None,
+                        location_id: None,
+                    },
+                )
+                .await
+                .unwrap()
+        }
+    };
+    let redeemed = || {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM promo_redemptions WHERE booking_id = $1)")
+            .bind(booking.id)
+            .fetch_one(&pool)
+    };
+
+    // Перенос в том же филиале скидку сохраняет
+    assert_eq!(reschedule(17).await.location_id, branch.id);
+    assert!(redeemed().await.unwrap());
+    assert_eq!(redemption_count(&pool, nevsky.id).await, 1);
+
+    // В главном корпусе промокод не действует: использование возвращается
+    assert_eq!(reschedule(9).await.location_id, seed.location_id);
+    assert!(!redeemed().await.unwrap());
+    assert_eq!(redemption_count(&pool, nevsky.id).await, 0);
+}
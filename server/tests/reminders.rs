@@ -0,0 +1,178 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::Duration;
+use common::ManualClock;
+use server::application::reminder_scheduler::ReminderScheduler;
+use server::application::reminder_service::ReminderServiceImpl;
+use server::domain::entities::{
+    BookingReminder, BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, ReminderStatus,
+    RescheduleBookingRequest,
+};
+use server::domain::traits::{BookingRepository, BookingService, CompanyRepository, ReminderRepository, ReminderService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_reminder_repository::PostgreSQLReminderRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::{Value, json};
+use sqlx::Row;
+use uuid::Uuid;
+
+/// Статус и время напоминаний записи по порядку
+fn summary(reminders: &[BookingReminder]) -> Vec<(ReminderStatus, i32, chrono::DateTime<chrono::Utc>)> {
+    reminders
+        .iter()
+        .map(|reminder| (reminder.status, reminder.offset_minutes, reminder.remind_at))
+        .collect()
+}
+
+#[actix_web::test]
+async fn reminders_follow_booking_changes_and_are_sent_once() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "patient").await;
+    let other = common::create_user(&pool, "other").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let booking_repository: Arc<dyn BookingRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone()));
+    let reminder_repository: Arc<dyn ReminderRepository + Send + Sync> =
+        Arc::new(PostgreSQLReminderRepository::new(pool.clone()));
+    let bookings = common::booking_services(&pool, Arc::new(SystemClock)).bookings;
+    let reminders = Arc::new(ReminderServiceImpl::new(
+        company_repository,
+        booking_repository,
+        reminder_repository.clone(),
+    ));
+    let reminder_service: Arc<dyn ReminderService + Send + Sync> = reminders.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(reminder_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+    let rules_uri = format!("/v1/companies/{}/reminder-rules", seed.company_id);
+    let put_rules = |user_id: Uuid, body: Value| {
+        test::TestRequest::put()
+            .uri(&rules_uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(body)
+            .to_request()
+    };
+
+    // Правила меняют только руководители; правило за 48 часов для завтрашних записей уже опоздало
+    let request = put_rules(customer.id, json!({"rules": [{"offset_minutes": 120}]}));
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+    let request = put_rules(seed.owner.id, json!({"rules": [{"offset_minutes": 120}, {"offset_minutes": 120}]}));
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::BAD_REQUEST);
+    let request = put_rules(seed.owner.id, json!({"rules": [{"offset_minutes": 120}, {"offset_minutes": 2880}]}));
+    let rules: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(rules[0]["offset_minutes"], 2880);
+    assert_eq!(rules[1]["offset_minutes"], 120);
+
+    let request_at = |hour| CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, 0),
+        promo_code: None,
+    };
+    let booking = bookings.create_booking(customer.id, request_at(10)).await.unwrap();
+    let list = reminders.booking_reminders(customer.id, booking.id).await.unwrap();
+    assert_eq!(summary(&list), vec![(ReminderStatus::Scheduled, 120, common::tomorrow_at(8, 0))]);
+
+    // Перенос: прежнее напоминание отменяется, новое планируется от нового времени
+    bookings
+        .reschedule_booking(
+            customer.id,
+            booking.id,
+            RescheduleBookingRequest {
+                starts_at: common::tomorrow_at(12, 0),
+                staff_id: None,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    let list = reminders.booking_reminders(customer.id, booking.id).await.unwrap();
+    assert_eq!(
+        summary(&list),
+        vec![
+            (ReminderStatus::Cancelled, 120, common::tomorrow_at(8, 0)),
+            (ReminderStatus::Scheduled, 120, common::tomorrow_at(10, 0)),
+        ]
+    );
+
+    // Отмена записи отменяет напоминания
+    let cancelled = bookings.create_booking(other.id, request_at(15)).await.unwrap();
+    bookings
+        .change_status(
+            other.id,
+            cancelled.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    let list = reminders.booking_reminders(other.id, cancelled.id).await.unwrap();
+    assert_eq!(summary(&list), vec![(ReminderStatus::Cancelled, 120, common::tomorrow_at(13, 0))]);
+
+    // Новое правило действует и для уже созданных записей
+    let request = put_rules(seed.owner.id, json!({"rules": [{"offset_minutes": 120}, {"offset_minutes": 60}]}));
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+    let list = reminders.booking_reminders(customer.id, booking.id).await.unwrap();
+    assert_eq!(list.len(), 3);
+    assert_eq!(
+        summary(&list)[2],
+        (ReminderStatus::Scheduled, 60, common::tomorrow_at(11, 0))
+    );
+
+    // Два экземпляра планировщика одновременно: напоминание уходит один раз
+    let clock = Arc::new(ManualClock::new(common::tomorrow_at(9, 59)));
+    let first = ReminderScheduler::new(reminder_repository.clone(), clock.clone());
+    let second = ReminderScheduler::new(reminder_repository.clone(), clock.clone());
+    assert_eq!(first.run_once().await.unwrap(), 0);
+    clock.advance(Duration::minutes(1));
+    let (a, b) = futures_util::join!(first.run_once(), second.run_once());
+    assert_eq!(a.unwrap() + b.unwrap(), 1);
+    assert_eq!(first.run_once().await.unwrap(), 0);
+
+    let outbox = sqlx::query("SELECT payload FROM notification_outbox WHERE user_id = $1 AND kind = 'booking_reminder'")
+        .bind(customer.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(outbox.len(), 1);
+    let payload: Value = outbox[0].get("payload");
+    assert_eq!(payload["booking_id"], booking.id.to_string());
+    assert_eq!(payload["offset_minutes"], 120);
+
+    // Сервер простоял до начала приема: опоздавшее напоминание не отправляется
+    clock.advance(Duration::hours(2) + Duration::minutes(30));
+    assert_eq!(first.run_once().await.unwrap(), 0);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/v1/bookings/{}/reminders", booking.id))
+        .insert_header(("Authorization", bearer(customer.id)))
+        .to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    let statuses: Vec<&str> = list.as_array().unwrap().iter().map(|r| r["status"].as_str().unwrap()).collect();
+    assert_eq!(statuses, vec!["cancelled", "sent", "missed"]);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/v1/bookings/{}/reminders", booking.id))
+        .insert_header(("Authorization", bearer(other.id)))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+}
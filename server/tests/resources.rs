@@ -0,0 +1,255 @@
+mod common;
+
+use std::sync::Arc;
+
+use chrono::NaiveTime;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::slot_service::SlotServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateResourceRequest, CreateStaffRequest,
+    NewBooking, RescheduleBookingRequest, ResourceRequirementRequest, ScheduleEntryRequest, SlotsQuery,
+    UpdateScheduleRequest, UpdateServiceResourcesRequest,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingRepository, BookingService, CatalogService, CompanyRepository, SlotService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Еще один сотрудник с консультациями и тем же расписанием, что у сотрудника из `seed_company`
+async fn add_staff(catalog: &CatalogServiceImpl, seed: &common::Seed, name: &str) -> Uuid {
+    let staff = catalog
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: name.to_string(),
+                user_id: None,
+                service_ids: vec![seed.service_id],
+            },
+        )
+        .await
+        .unwrap();
+    catalog
+        .update_schedule(
+            seed.owner.id,
+            seed.company_id,
+            staff.id,
+            UpdateScheduleRequest {
+                entries: (1..=7)
+                    .map(|weekday| ScheduleEntryRequest {
+                        location_id: seed.location_id,
+                        weekday,
+                        start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                        end_time: NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+                    })
+                    .collect(),
+            },
+        )
+        .await
+        .unwrap();
+    staff.id
+}
+
+async fn add_room(catalog: &CatalogServiceImpl, seed: &common::Seed, name: &str) -> Uuid {
+    catalog
+        .create_resource(
+            seed.owner.id,
+            seed.company_id,
+            CreateResourceRequest {
+                location_id: seed.location_id,
+                name: name.to_string(),
+            },
+        )
+        .await
+        .unwrap()
+        .id
+}
+
+fn request(seed: &common::Seed, staff_id: Uuid, hour: u32) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, 0),
+        promo_code: None,
+    }
+}
+
+async fn slot_starts(pool: &PgPool, seed: &common::Seed, staff_id: Uuid) -> Vec<chrono::DateTime<chrono::Utc>> {
+    let slots = SlotServiceImpl::new(
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone())),
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone())),
+    );
+    let date = common::tomorrow_at(0, 0).date_naive();
+    slots
+        .available_slots(
+            seed.company_id,
+            SlotsQuery {
+                service: seed.service_id,
+                staff: Some(staff_id),
+                from: date,
+                to: date,
+            },
+        )
+        .await
+        .unwrap()
+        .slots
+        .into_iter()
+        .map(|slot| slot.starts_at.to_utc())
+        .collect()
+}
+
+#[actix_web::test]
+async fn staff_cannot_book_more_rooms_than_the_location_has() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let booking_repository: Arc<dyn BookingRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone()));
+    let catalog = CatalogServiceImpl::new(company_repository.clone());
+    let bookings = common::booking_services(&pool, Arc::new(SystemClock)).bookings;
+
+    let room_a = add_room(&catalog, &seed, "Кабинет А").await;
+    let room_b = add_room(&catalog, &seed, "Кабинет Б").await;
+    let second = add_staff(&catalog, &seed, "Второй доктор").await;
+    let third = add_staff(&catalog, &seed, "Третий доктор").await;
+    let requirements = catalog
+        .update_service_resources(
+            seed.owner.id,
+            seed.company_id,
+            seed.service_id,
+            UpdateServiceResourcesRequest {
+                requirements: vec![ResourceRequirementRequest {
+                    resource_ids: vec![room_b, room_a],
+                }],
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(requirements.len(), 1);
+    assert_eq!(requirements[0].resources.len(), 2);
+
+    let customers = [
+        common::create_user(&pool, "patient1").await,
+        common::create_user(&pool, "patient2").await,
+        common::create_user(&pool, "patient3").await,
+    ];
+    let first_booking = bookings
+        .create_booking(customers[0].id, request(&seed, seed.staff_id, 10))
+        .await
+        .unwrap();
+    assert_eq!(first_booking.resource_ids, vec![room_a]);
+    let second_booking = bookings
+        .create_booking(customers[1].id, request(&seed, second, 10))
+        .await
+        .unwrap();
+    assert_eq!(second_booking.resource_ids, vec![room_b]);
+
+    // Третий доктор свободен, но кабинетов в 10:00 не осталось
+    let starts = slot_starts(&pool, &seed, third).await;
+    assert!(!starts.contains(&common::tomorrow_at(10, 0)));
+    assert!(starts.contains(&common::tomorrow_at(11, 0)));
+    let refused = bookings
+        .create_booking(customers[2].id, request(&seed, third, 10))
+        .await;
+    assert!(matches!(refused, Err(AppError::Validation(_))));
+
+    // В обход сервиса занять кабинет не даст ограничение в базе
+    let starts_at = common::tomorrow_at(10, 30);
+    let direct = booking_repository
+        .create_booking(&NewBooking {
+            customer_id: customers[2].id,
+            company_id: seed.company_id,
+            service_id: seed.service_id,
+            staff_id: third,
+            location_id: seed.location_id,
+            starts_at,
+            ends_at: starts_at + chrono::Duration::hours(1),
+            blocked_from: starts_at,
+            blocked_to: starts_at + chrono::Duration::hours(1),
+            status: BookingStatus::Confirmed,
+            capacity: 1,
+            resource_ids: vec![room_a],
+            promo: None,
+        })
+        .await;
+    assert_eq!(direct.unwrap_err(), AppError::Conflict("Ресурс уже занят".to_string()));
+
+    // Отмена освобождает кабинет
+    bookings
+        .change_status(
+            customers[0].id,
+            first_booking.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    let third_booking = bookings
+        .create_booking(customers[2].id, request(&seed, third, 10))
+        .await
+        .unwrap();
+    assert_eq!(third_booking.resource_ids, vec![room_a]);
+
+    // При переносе кабинет выбирается заново: в 12:00 свободны оба, берется первый по названию
+    let moved = bookings
+        .reschedule_booking(
+            customers[1].id,
+            second_booking.id,
+            RescheduleBookingRequest {
+                starts_at: common::tomorrow_at(12, 0),
+                staff_id: None,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(moved.resource_ids, vec![room_a]);
+}
+
+#[actix_web::test]
+async fn requirements_accept_only_company_resources() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let other = common::seed_company(&pool).await;
+    let catalog = CatalogServiceImpl::new(Arc::new(PostgreSQLCompanyRepository::new(pool.clone())));
+    let foreign_room = add_room(&catalog, &other, "Чужой кабинет").await;
+
+    let result = catalog
+        .update_service_resources(
+            seed.owner.id,
+            seed.company_id,
+            seed.service_id,
+            UpdateServiceResourcesRequest {
+                requirements: vec![ResourceRequirementRequest {
+                    resource_ids: vec![foreign_room],
+                }],
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let empty = catalog
+        .update_service_resources(
+            seed.owner.id,
+            seed.company_id,
+            seed.service_id,
+            UpdateServiceResourcesRequest {
+                requirements: vec![ResourceRequirementRequest { resource_ids: Vec::new() }],
+            },
+        )
+        .await;
+    assert!(matches!(empty, Err(AppError::Validation(_))));
+    assert!(catalog
+        .get_service_resources(seed.company_id, seed.service_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use server::application::review_service::ReviewServiceImpl;
+use server::domain::entities::{BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest};
+use server::domain::traits::{BookingRepository, BookingService, CompanyRepository, ReviewService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_review_repository::PostgreSQLReviewRepository;
+use server::infrastructure::postgres_user_repository::PostgreSQLUserRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+#[actix_web::test]
+async fn reviews_require_completed_visit_and_feed_company_rating() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "reviewer").await;
+    let outsider = common::create_user(&pool, "outsider").await;
+    let moderator = common::create_user(&pool, "moderator").await;
+    sqlx::query("UPDATE users SET user_type_id = 2 WHERE id = $1")
+        .bind(moderator.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET first_name = 'Анна' WHERE id = $1")
+        .bind(customer.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let booking_repository: Arc<dyn BookingRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone()));
+    let bookings = common::booking_services(&pool, Arc::new(SystemClock)).bookings;
+    let review_service: Arc<dyn ReviewService + Send + Sync> = Arc::new(ReviewServiceImpl::new(
+        Arc::new(PostgreSQLUserRepository::new(pool.clone())),
+        company_repository.clone(),
+        booking_repository,
+        Arc::new(PostgreSQLReviewRepository::new(pool.clone())),
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(review_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let book = |hour| {
+        let bookings = &bookings;
+        let seed = &seed;
+        async move {
+            bookings
+                .create_booking(
+                    customer.id,
+                    CreateBookingRequest {
+                        company_id: seed.company_id,
+                        service_id: seed.service_id,
+                        staff_id: seed.staff_id,
+                        location_id: None,
+                        starts_at: common::tomorrow_at(hour, 0),
+                        promo_code: None,
+                    },
+                )
+                .await
+                .unwrap()
+        }
+    };
+    let complete = |booking_id| {
+        let bookings = &bookings;
+        async move {
+            bookings
+                .change_status(
+                    seed.owner.id,
+                    booking_id,
+                    ChangeBookingStatusRequest {
+                        status: BookingStatus::Completed,
+                        reason: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+    };
+    let post_review = |user_id: Uuid, booking_id: Uuid, body: Value| {
+        test::TestRequest::post()
+            .uri(&format!("/v1/bookings/{}/review", booking_id))
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(body)
+            .to_request()
+    };
+
+    // Отзыв возможен только после завершенного визита и только автором записи
+    let first = book(10).await;
+    let response = test::call_service(&app, post_review(customer.id, first.id, json!({"rating": 5}))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    complete(first.id).await;
+    let response = test::call_service(&app, post_review(outsider.id, first.id, json!({"rating": 5}))).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = test::call_service(&app, post_review(customer.id, first.id, json!({"rating": 6}))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = test::call_service(
+        &app,
+        post_review(customer.id, first.id, json!({"rating": 4, "text": "  Внимательный врач  "})),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let review: Value = test::read_body_json(response).await;
+    assert_eq!(review["text"], "Внимательный врач");
+    assert_eq!(review["author_name"], "Анна");
+    assert_eq!(review["staff_id"], seed.staff_id.to_string());
+    assert!(review.get("customer_id").is_none());
+    let response = test::call_service(&app, post_review(customer.id, first.id, json!({"rating": 1}))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let second = book(12).await;
+    complete(second.id).await;
+    let response = test::call_service(&app, post_review(customer.id, second.id, json!({"rating": 1}))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let bad_review: Value = test::read_body_json(response).await;
+
+    let company = company_repository.find_company(seed.company_id).await.unwrap().unwrap();
+    assert_eq!(company.rating_count, 2);
+    assert_eq!(company.rating_avg, Some(2.5));
+
+    // Ответ публикуют только руководители компании
+    let reply_uri = format!("/v1/companies/{}/reviews/{}/reply", seed.company_id, review["id"].as_str().unwrap());
+    let reply = |user_id: Uuid, body: Value| {
+        test::TestRequest::put()
+            .uri(&reply_uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(body)
+            .to_request()
+    };
+    let response = test::call_service(&app, reply(customer.id, json!({"reply": "Спасибо"}))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = test::call_service(&app, reply(seed.owner.id, json!({"reply": "   "}))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let replied: Value = test::call_and_read_body_json(&app, reply(seed.owner.id, json!({"reply": "Спасибо!"}))).await;
+    assert_eq!(replied["reply"], "Спасибо!");
+    assert!(replied["replied_at"].is_string());
+
+    // Модератор скрывает отзыв: он пропадает из списка и рейтинга
+    let visibility_uri = format!("/v1/admin/reviews/{}/visibility", bad_review["id"].as_str().unwrap());
+    let visibility = |user_id: Uuid, body: Value| {
+        test::TestRequest::put()
+            .uri(&visibility_uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(body)
+            .to_request()
+    };
+    let hide = json!({"is_hidden": true, "reason": "Оскорбления"});
+    let response = test::call_service(&app, visibility(seed.owner.id, hide.clone())).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let hidden: Value = test::call_and_read_body_json(&app, visibility(moderator.id, hide)).await;
+    assert_eq!(hidden["is_hidden"], true);
+    assert_eq!(hidden["hidden_reason"], "Оскорбления");
+
+    let list = |query: &str| test::TestRequest::get()
+        .uri(&format!("/v1/companies/{}/reviews{}", seed.company_id, query))
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, list("")).await;
+    assert_eq!(page["rating_count"], 1);
+    assert_eq!(page["rating_avg"], 4.0);
+    assert_eq!(page["reviews"].as_array().unwrap().len(), 1);
+    assert_eq!(page["reviews"][0]["reply"], "Спасибо!");
+    let company = company_repository.find_company(seed.company_id).await.unwrap().unwrap();
+    assert_eq!((company.rating_avg, company.rating_count), (Some(4.0), 1));
+
+    // Возвращенный отзыв снова учитывается
+    let shown: Value =
+        test::call_and_read_body_json(&app, visibility(moderator.id, json!({"is_hidden": false}))).await;
+    assert_eq!(shown["is_hidden"], false);
+    assert!(shown["hidden_reason"].is_null());
+    let page: Value = test::call_and_read_body_json(&app, list(&format!("?staff={}", seed.staff_id))).await;
+    assert_eq!(page["rating_count"], 2);
+    assert_eq!(page["rating_avg"], 2.5);
+    let page: Value = test::call_and_read_body_json(&app, list(&format!("?staff={}", Uuid::new_v4()))).await;
+    assert_eq!(page["rating_count"], 0);
+    assert!(page["rating_avg"].is_null());
+    let response = test::call_service(&app, list("?limit=0")).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
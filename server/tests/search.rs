@@ -0,0 +1,370 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use common::ManualClock;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::search_service::SearchServiceImpl;
+use server::domain::entities::{
+    CreateCompanyRequest, CreateLocationRequest, CreateScheduleExceptionRequest, CreateServiceRequest,
+    CreateStaffRequest, ScheduleEntryRequest, UpdateScheduleRequest,
+};
+use server::domain::traits::{CatalogService, SearchService};
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_search_repository::PostgreSQLSearchRepository;
+use server::presentation::routes::api_v1_routes;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+struct CompanySpec {
+    name: &'static str,
+    description: &'static str,
+    categories: &'static [&'static str],
+    city: &'static str,
+    coordinates: (f64, f64),
+    service: &'static str,
+    price: i64,
+    weekdays: &'static [i16],
+    rating: Option<(f64, i32)>,
+}
+
+/// Компания в UTC с одним филиалом, услугой и сотрудником, работающим с 09:00 до 18:00
+async fn create_company(pool: &PgPool, catalog: &CatalogServiceImpl, spec: CompanySpec) -> (Uuid, Uuid) {
+    let owner = common::create_user(pool, &format!("owner_{}", Uuid::new_v4().simple())).await;
+    let company = catalog
+        .create_company(
+            owner.id,
+            CreateCompanyRequest {
+                name: spec.name.to_string(),
+                description: Some(spec.description.to_string()),
+                website: None,
+                email: None,
+                phone: None,
+                timezone: "UTC".to_string(),
+                categories: spec.categories.iter().map(|c| c.to_string()).collect(),
+            },
+        )
+        .await
+        .unwrap();
+    let location = catalog
+        .create_location(
+            owner.id,
+            company.id,
+            CreateLocationRequest {
+                name: "Филиал".to_string(),
+                address: None,
+                city: Some(spec.city.to_string()),
+                latitude: Some(spec.coordinates.0),
+                longitude: Some(spec.coordinates.1),
+            },
+        )
+        .await
+        .unwrap();
+    let service = catalog
+        .create_service(
+            owner.id,
+            company.id,
+            CreateServiceRequest {
+                name: spec.service.to_string(),
+                description: None,
+                duration_minutes: 60,
+                buffer_before_minutes: None,
+                buffer_after_minutes: None,
+                slot_step_minutes: None,
+                price_amount: Some(spec.price),
+                currency: None,
+                capacity: None,
+                deposit_amount: None,
+            },
+        )
+        .await
+        .unwrap();
+    let staff = catalog
+        .create_staff(
+            owner.id,
+            company.id,
+            CreateStaffRequest {
+                display_name: "Специалист".to_string(),
+                user_id: None,
+                service_ids: vec![service.id],
+            },
+        )
+        .await
+        .unwrap();
+    if !spec.weekdays.is_empty() {
+        catalog
+            .update_schedule(
+                owner.id,
+                company.id,
+                staff.id,
+                UpdateScheduleRequest {
+                    entries: spec
+                        .weekdays
+                        .iter()
+                        .map(|&weekday| ScheduleEntryRequest {
+                            location_id: location.id,
+                            weekday,
+                            start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                            end_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                        })
+                        .collect(),
+                },
+            )
+            .await
+            .unwrap();
+    }
+    if let Some((rating_avg, rating_count)) = spec.rating {
+        sqlx::query("UPDATE companies SET rating_avg = $2, rating_count = $3 WHERE id = $1")
+            .bind(company.id)
+            .bind(rating_avg)
+            .bind(rating_count)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+    (owner.id, company.id)
+}
+
+/// Ближайший после сегодняшнего день недели
+fn next_weekday(weekday: Weekday) -> NaiveDate {
+    let mut date = Utc::now().date_naive() + Duration::days(1);
+    while date.weekday() != weekday {
+        date += Duration::days(1);
+    }
+    date
+}
+
+/// Кириллица в строке запроса должна быть закодирована, иначе URI не разберется
+fn encode_query(query: &str) -> String {
+    query
+        .bytes()
+        .map(|byte| match byte {
+            0x21..=0x7e => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn names(page: &Value) -> Vec<String> {
+    page["companies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|company| company["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[actix_web::test]
+async fn search_filters_ranks_paginates_and_counts_facets() {
+    let Some(pool) = common::test_pool().await else { return };
+    let catalog = CatalogServiceImpl::new(Arc::new(PostgreSQLCompanyRepository::new(pool.clone())));
+    let everyday: &[i16] = &[1, 2, 3, 4, 5, 6, 7];
+    let (smile_owner, smile) = create_company(
+        &pool,
+        &catalog,
+        CompanySpec {
+            name: "Улыбка",
+            description: "Стоматологическая клиника у метро",
+            categories: &["Стоматология"],
+            city: "Москва",
+            coordinates: (55.7558, 37.6173),
+            service: "Лечение кариеса",
+            price: 300_000,
+            weekdays: everyday,
+            rating: Some((4.5, 10)),
+        },
+    )
+    .await;
+    let (bright_owner, bright) = create_company(
+        &pool,
+        &catalog,
+        CompanySpec {
+            name: "Bright Smile Dental",
+            description: "Modern dental care",
+            categories: &["dentistry", "стоматология"],
+            city: "Санкт-Петербург",
+            coordinates: (59.9343, 30.3351),
+            service: "Teeth whitening",
+            price: 900_000,
+            weekdays: &[1, 2, 3, 4, 5],
+            rating: Some((4.8, 3)),
+        },
+    )
+    .await;
+    create_company(
+        &pool,
+        &catalog,
+        CompanySpec {
+            name: "Фитнес Клуб",
+            description: "Тренажерный зал",
+            categories: &["фитнес"],
+            city: "Москва",
+            coordinates: (55.7652, 37.6339),
+            service: "Персональная тренировка",
+            price: 200_000,
+            weekdays: &[],
+            rating: None,
+        },
+    )
+    .await;
+
+    // Суббота, полдень по UTC: работает только ежедневная клиника
+    let saturday = next_weekday(Weekday::Sat);
+    let clock = Arc::new(ManualClock::new(saturday.and_hms_opt(12, 0, 0).unwrap().and_utc()));
+    let search_service: Arc<dyn SearchService + Send + Sync> = Arc::new(SearchServiceImpl::new(
+        Arc::new(PostgreSQLSearchRepository::new(pool.clone())),
+        clock,
+    ));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(search_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+    let search = |query: String| {
+        let app = &app;
+        async move {
+            let request = test::TestRequest::get()
+                .uri(&format!("/v1/search/companies{}", encode_query(&query)))
+                .to_request();
+            let response = test::call_service(app, request).await;
+            let status = response.status();
+            let body: Value = test::read_body_json(response).await;
+            (status, body)
+        }
+    };
+    let found = |query: &'static str| {
+        let search = &search;
+        async move {
+            let (status, page) = search(query.to_string()).await;
+            assert_eq!(status, StatusCode::OK, "{}: {}", query, page);
+            names(&page)
+        }
+    };
+
+    // Русская и английская морфология: категория, описание и названия услуг
+    let (_, page) = search("?q=стоматолог".to_string()).await;
+    assert_eq!(page["total"], 2);
+    let mut matched = names(&page);
+    matched.sort();
+    assert_eq!(matched, vec!["Bright Smile Dental", "Улыбка"]);
+    assert_eq!(
+        page["facets"]["categories"],
+        json!([{"value": "стоматология", "count": 2}, {"value": "dentistry", "count": 1}])
+    );
+    assert_eq!(
+        page["facets"]["cities"],
+        json!([{"value": "Москва", "count": 1}, {"value": "Санкт-Петербург", "count": 1}])
+    );
+    assert_eq!(found("?q=whitening").await, vec!["Bright Smile Dental"]);
+    assert_eq!(found("?q=кариес").await, vec!["Улыбка"]);
+    assert!(found("?q=массаж").await.is_empty());
+
+    // Фильтры
+    assert_eq!(found("?city=москва&sort=rating").await, vec!["Улыбка", "Фитнес Клуб"]);
+    assert_eq!(found("?category=Фитнес").await, vec!["Фитнес Клуб"]);
+    assert_eq!(found("?min_rating=4.6").await, vec!["Bright Smile Dental"]);
+    assert_eq!(found("?price_max=250000").await, vec!["Фитнес Клуб"]);
+    assert_eq!(found("?price_min=250000&price_max=500000").await, vec!["Улыбка"]);
+    assert_eq!(found("?open_now=true").await, vec!["Улыбка"]);
+    let (_, page) = search(format!("?available_on={}", saturday)).await;
+    assert_eq!(names(&page), vec!["Улыбка"]);
+
+    // Выходной в понедельник убирает компанию из доступных в этот день
+    let monday = next_weekday(Weekday::Mon);
+    let (_, page) = search(format!("?available_on={}", monday)).await;
+    assert_eq!(names(&page), vec!["Bright Smile Dental", "Улыбка"]);
+    let bright_staff: Uuid = sqlx::query_scalar("SELECT id FROM staff WHERE company_id = $1")
+        .bind(bright)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    catalog
+        .add_schedule_exception(
+            bright_owner,
+            bright,
+            bright_staff,
+            CreateScheduleExceptionRequest {
+                exception_date: monday,
+                start_time: None,
+                end_time: None,
+                is_available: false,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    let (_, page) = search(format!("?available_on={}", monday)).await;
+    assert_eq!(names(&page), vec!["Улыбка"]);
+
+    // Свободного времени у Улыбки много, но оно раздроблено перерывами на получасовые окна:
+    // часовую услугу не вместить
+    let smile_staff: Uuid = sqlx::query_scalar("SELECT id FROM staff WHERE company_id = $1")
+        .bind(smile)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let smile_location: Uuid = sqlx::query_scalar("SELECT id FROM locations WHERE company_id = $1")
+        .bind(smile)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let smile_exception = |hour, minute, length, is_available| {
+        let start_time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        catalog.add_schedule_exception(
+            smile_owner,
+            smile,
+            smile_staff,
+            CreateScheduleExceptionRequest {
+                exception_date: monday,
+                start_time: Some(start_time),
+                end_time: Some(start_time + Duration::minutes(length)),
+                is_available,
+                location_id: Some(smile_location),
+            },
+        )
+    };
+    for hour in 9..18 {
+        smile_exception(hour, 30, 30, false).await.unwrap();
+    }
+    let (_, page) = search(format!("?available_on={}", monday)).await;
+    assert!(names(&page).is_empty());
+    // Дополнительные 45 минут сливаются с соседними рабочими окнами в полтора часа
+    smile_exception(12, 20, 45, true).await.unwrap();
+    let (_, page) = search(format!("?available_on={}", monday)).await;
+    assert_eq!(names(&page), vec!["Улыбка"]);
+
+    // Радиус и сортировка по расстоянию
+    let (_, page) = search("?lat=55.7558&lon=37.6173&radius_km=10&sort=distance".to_string()).await;
+    assert_eq!(names(&page), vec!["Улыбка", "Фитнес Клуб"]);
+    assert_eq!(page["companies"][0]["id"], smile.to_string());
+    assert!(page["companies"][0]["distance_km"].as_f64().unwrap() < 0.01);
+    let distance = page["companies"][1]["distance_km"].as_f64().unwrap();
+    assert!((1.0..2.0).contains(&distance), "{}", distance);
+
+    // Постраничный вывод по рейтингу курсором
+    let mut pages = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let (status, page) = search(format!("?sort=rating&limit=1{}", cursor)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 3);
+        pages.extend(names(&page));
+        match page["next_cursor"].as_str() {
+            Some(next) => cursor = format!("&cursor={}", next),
+            None => break,
+        }
+    }
+    assert_eq!(pages, vec!["Bright Smile Dental", "Улыбка", "Фитнес Клуб"]);
+    let (_, page) = search("?sort=rating&limit=2".to_string()).await;
+    assert_eq!(page["companies"][0]["price_from"], 900_000);
+    assert_eq!(page["companies"][0]["cities"], json!(["Санкт-Петербург"]));
+    assert_eq!(page["companies"][1]["categories"], json!(["стоматология"]));
+
+    for invalid in ["?radius_km=5", "?sort=distance", "?cursor=zz", "?limit=0", "?price_min=5&price_max=1"] {
+        assert_eq!(search(invalid.to_string()).await.0, StatusCode::BAD_REQUEST, "{}", invalid);
+    }
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{Duration, SubsecRound};
+use common::ManualClock;
+use server::domain::traits::Clock;
+use server::application::booking_service::BookingServiceImpl;
+use server::application::hold_service::HoldServiceImpl;
+use server::application::hold_sweeper::HoldSweeper;
+use server::application::slot_service::SlotServiceImpl;
+use server::domain::entities::{BookingStatus, CreateBookingRequest, CreateHoldRequest, SlotsQuery};
+use server::domain::errors::AppError;
+use server::domain::traits::{
+    BookingRepository, BookingService, CompanyRepository, HoldService, SlotService,
+};
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use sqlx::PgPool;
+
+struct Services {
+    clock: Arc<ManualClock>,
+    bookings: Arc<BookingServiceImpl>,
+    holds: Arc<HoldServiceImpl>,
+    slots: SlotServiceImpl,
+    sweeper: HoldSweeper,
+}
+
+fn services(pool: &PgPool) -> Services {
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let booking_repository: Arc<dyn BookingRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone()));
+    // Целые секунды: PostgreSQL хранит время с точностью до микросекунд
+    let clock = Arc::new(ManualClock::new(chrono::Utc::now().trunc_subsecs(0)));
+
+    let booking_services = common::booking_services(pool, clock.clone());
+
+    Services {
+        bookings: booking_services.bookings,
+        holds: booking_services.holds,
+        slots: SlotServiceImpl::new(company_repository, booking_repository),
+        sweeper: booking_services.sweeper,
+        clock,
+    }
+}
+
+fn hold_at(seed: &common::Seed, hour: u32) -> CreateHoldRequest {
+    CreateHoldRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, 0),
+    }
+}
+
+fn booking_at(seed: &common::Seed, hour: u32) -> CreateBookingRequest {
+    CreateBookingRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: seed.staff_id,
+        location_id: None,
+        starts_at: common::tomorrow_at(hour, 0),
+        promo_code: None,
+    }
+}
+
+#[actix_web::test]
+async fn hold_blocks_slot_until_sweeper_releases_it() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let services = services(&pool);
+    let first = common::create_user(&pool, "first").await;
+    let second = common::create_user(&pool, "second").await;
+
+    services.holds.create_hold(first.id, hold_at(&seed, 10)).await.unwrap();
+
+    // Слот пропал из выдачи и недоступен другим клиентам
+    let tomorrow = common::tomorrow_at(0, 0).date_naive();
+    let slots = services
+        .slots
+        .available_slots(
+            seed.company_id,
+            SlotsQuery { service: seed.service_id, staff: None, from: tomorrow, to: tomorrow },
+        )
+        .await
+        .unwrap();
+    assert!(slots.slots.iter().all(|slot| slot.starts_at != common::tomorrow_at(10, 0)));
+    assert!(matches!(
+        services.bookings.create_booking(second.id, booking_at(&seed, 10)).await,
+        Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+        services.holds.create_hold(second.id, hold_at(&seed, 10)).await,
+        Err(AppError::Conflict(_))
+    ));
+
+    // До истечения срока проход ничего не снимает
+    services.clock.advance(Duration::minutes(9));
+    assert_eq!(services.sweeper.run_once().await.unwrap(), 0);
+
+    services.clock.advance(Duration::minutes(1));
+    assert_eq!(services.sweeper.run_once().await.unwrap(), 1);
+
+    let booking = services.bookings.create_booking(second.id, booking_at(&seed, 10)).await.unwrap();
+    assert_eq!(booking.customer_id, second.id);
+}
+
+#[actix_web::test]
+async fn only_owner_converts_live_hold_into_booking() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let services = services(&pool);
+    let customer = common::create_user(&pool, "customer").await;
+    let stranger = common::create_user(&pool, "stranger").await;
+
+    let hold = services.holds.create_hold(customer.id, hold_at(&seed, 10)).await.unwrap();
+    assert_eq!(hold.expires_at, services.clock.now() + Duration::minutes(10));
+
+    assert!(matches!(
+        services.holds.confirm_hold(stranger.id, hold.id).await,
+        Err(AppError::NotFound(_))
+    ));
+
+    let booking = services.holds.confirm_hold(customer.id, hold.id).await.unwrap();
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.starts_at, hold.starts_at);
+
+    // Подтвержденная запись не снимается проходом и не подтверждается повторно
+    services.clock.advance(Duration::hours(1));
+    assert_eq!(services.sweeper.run_once().await.unwrap(), 0);
+    assert!(matches!(
+        services.holds.confirm_hold(customer.id, hold.id).await,
+        Err(AppError::NotFound(_))
+    ));
+
+    let history = services.bookings.booking_history(customer.id, booking.id).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].event_type, "created");
+}
+
+#[actix_web::test]
+async fn expired_hold_cannot_be_confirmed_and_new_hold_replaces_old() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let services = services(&pool);
+    let customer = common::create_user(&pool, "customer").await;
+    let other = common::create_user(&pool, "other").await;
+
+    let first = services.holds.create_hold(customer.id, hold_at(&seed, 10)).await.unwrap();
+
+    // Новый выбор слота освобождает предыдущий
+    let second = services.holds.create_hold(customer.id, hold_at(&seed, 12)).await.unwrap();
+    assert!(services.bookings.create_booking(other.id, booking_at(&seed, 10)).await.is_ok());
+    assert!(matches!(
+        services.holds.confirm_hold(customer.id, first.id).await,
+        Err(AppError::NotFound(_))
+    ));
+
+    // Истекшая, но еще не снятая бронь не подтверждается
+    services.clock.advance(Duration::minutes(11));
+    assert!(matches!(
+        services.holds.confirm_hold(customer.id, second.id).await,
+        Err(AppError::Conflict(_))
+    ));
+}
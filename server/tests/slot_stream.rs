@@ -0,0 +1,169 @@
+mod common;
+
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::{App, http::StatusCode, test, web};
+use server::application::slot_service::SlotServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateHoldRequest, RescheduleBookingRequest,
+};
+use server::domain::traits::{BookingRepository, BookingService, CompanyRepository, HoldService, SlotService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::postgres_booking_repository::PostgreSQLBookingRepository;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::slot_event_bus::PostgresSlotEventBus;
+use server::presentation::routes::api_v1_routes;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Следующий кусок тела ответа или `None`, если за отведенное время ничего не пришло
+async fn next_chunk(body: &mut BoxBody, wait: Duration) -> Option<String> {
+    let chunk = poll_fn(|cx| Pin::new(&mut *body).poll_next(cx));
+    match actix_web::rt::time::timeout(wait, chunk).await {
+        Ok(Some(Ok(bytes))) => Some(String::from_utf8(bytes.to_vec()).unwrap()),
+        Ok(_) => panic!("Поток событий закрылся"),
+        Err(_) => None,
+    }
+}
+
+/// Следующее событие потока: имя и данные кадра SSE
+async fn next_event(body: &mut BoxBody) -> (String, Value) {
+    let frame = next_chunk(body, Duration::from_secs(5)).await.expect("Событие не пришло");
+    let (name, data) = frame
+        .strip_prefix("event: ")
+        .and_then(|rest| rest.trim_end().split_once("\ndata: "))
+        .unwrap_or_else(|| panic!("Неожиданный кадр: {:?}", frame));
+    (name.to_string(), serde_json::from_str(data).unwrap())
+}
+
+#[actix_web::test]
+async fn booking_changes_are_streamed_as_slot_events() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "stream").await;
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let booking_repository: Arc<dyn BookingRepository + Send + Sync> =
+        Arc::new(PostgreSQLBookingRepository::new(pool.clone()));
+    let bus = PostgresSlotEventBus::listen(&pool).await.unwrap();
+    let slot_service: Arc<dyn SlotService + Send + Sync> = Arc::new(
+        SlotServiceImpl::new(company_repository.clone(), booking_repository.clone()).with_events(bus),
+    );
+    let services = common::booking_services(&pool, Arc::new(SystemClock));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(slot_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+    let open_stream = |query: String| {
+        let app = &app;
+        let uri = format!("/v1/companies/{}/slots/stream{}", seed.company_id, query);
+        async move {
+            let response = test::call_service(app, test::TestRequest::get().uri(&uri).to_request()).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers().get("content-type").unwrap(), "text/event-stream");
+            let mut body = response.into_body();
+            // Поток начинается с комментария, чтобы клиент сразу получил заголовки
+            assert_eq!(next_chunk(&mut body, Duration::from_secs(5)).await.unwrap(), ": keepalive\n\n");
+            body
+        }
+    };
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/v1/companies/{}/slots/stream", Uuid::new_v4()))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+
+    let mut stream = open_stream(String::new()).await;
+    let mut other_staff = open_stream(format!("?staff={}", Uuid::new_v4())).await;
+
+    // Бронь занимает слот, отпущенная бронь освобождает
+    let hold = services
+        .holds
+        .create_hold(
+            customer.id,
+            CreateHoldRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: common::tomorrow_at(9, 0),
+            },
+        )
+        .await
+        .unwrap();
+    let (name, data) = next_event(&mut stream).await;
+    assert_eq!(name, "slot_taken");
+    assert_eq!(data["staff_id"], seed.staff_id.to_string());
+    assert_eq!(data["service_id"], seed.service_id.to_string());
+    assert_eq!(data["location_id"], seed.location_id.to_string());
+    assert_eq!(data["starts_at"].as_str().unwrap().parse::<chrono::DateTime<chrono::Utc>>().unwrap(), hold.starts_at);
+    assert!(data.get("customer_id").is_none());
+    services.holds.release_hold(customer.id, hold.id).await.unwrap();
+    assert_eq!(next_event(&mut stream).await.0, "slot_freed");
+
+    // Перенос освобождает старое время и занимает новое, отмена освобождает
+    let booking = services
+        .bookings
+        .create_booking(
+            customer.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: common::tomorrow_at(10, 0),
+                promo_code: None,
+            },
+        )
+        .await
+        .unwrap();
+    let (name, data) = next_event(&mut stream).await;
+    assert_eq!(name, "slot_taken");
+    assert_eq!(data["kind"], "taken");
+    services
+        .bookings
+        .reschedule_booking(
+            customer.id,
+            booking.id,
+            RescheduleBookingRequest {
+                starts_at: common::tomorrow_at(12, 0),
+                staff_id: None,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    let (name, freed) = next_event(&mut stream).await;
+    assert_eq!(name, "slot_freed");
+    assert_eq!(freed["starts_at"], data["starts_at"]);
+    let (name, taken) = next_event(&mut stream).await;
+    assert_eq!(name, "slot_taken");
+    let starts_at: chrono::DateTime<chrono::Utc> = taken["starts_at"].as_str().unwrap().parse().unwrap();
+    assert_eq!(starts_at, common::tomorrow_at(12, 0));
+
+    services
+        .bookings
+        .change_status(
+            customer.id,
+            booking.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+    let (name, data) = next_event(&mut stream).await;
+    assert_eq!(name, "slot_freed");
+    assert_eq!(data["starts_at"], taken["starts_at"]);
+
+    // Поток с фильтром по другому сотруднику ничего из этого не получил
+    assert!(next_chunk(&mut other_staff, Duration::from_millis(300)).await.is_none());
+}
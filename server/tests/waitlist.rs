@@ -0,0 +1,277 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, NaiveTime, SubsecRound};
+use common::ManualClock;
+use server::application::booking_service::BookingServiceImpl;
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::hold_service::HoldServiceImpl;
+use server::application::hold_sweeper::HoldSweeper;
+use server::application::notification_dispatcher::NotificationDispatcher;
+use server::application::waitlist_service::WaitlistServiceImpl;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateHoldRequest,
+    CreateScheduleExceptionRequest, JoinWaitlistRequest, NotificationKind, OutgoingMessage, WaitlistStatus,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingService, CatalogService, HoldService, Notifier, WaitlistService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_outbox_repository::PostgreSQLOutboxRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Запоминает отправленные уведомления
+#[derive(Default)]
+struct RecordingNotifier {
+    sent: Mutex<Vec<OutgoingMessage>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    fn channel(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn send(&self, message: &OutgoingMessage) -> Result<(), AppError> {
+        self.sent.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+}
+
+struct Services {
+    clock: Arc<ManualClock>,
+    notifier: Arc<RecordingNotifier>,
+    dispatcher: NotificationDispatcher,
+    bookings: Arc<BookingServiceImpl>,
+    holds: Arc<HoldServiceImpl>,
+    waitlist: Arc<WaitlistServiceImpl>,
+    sweeper: HoldSweeper,
+}
+
+impl Services {
+    /// Разослать накопленные уведомления и вернуть получателей предложений из листа ожидания
+    async fn offer_recipients(&self) -> Vec<Uuid> {
+        self.dispatcher.run_once().await.unwrap();
+        self.notifier
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|message| message.kind == NotificationKind::WaitlistOffer)
+            .map(|message| message.recipient.user_id)
+            .collect()
+    }
+}
+
+fn services(pool: &PgPool) -> Services {
+    let clock = Arc::new(ManualClock::new(chrono::Utc::now().trunc_subsecs(0)));
+    let notifier = Arc::new(RecordingNotifier::default());
+    let booking_services = common::booking_services(pool, clock.clone());
+    // Время постановки в outbox задает база, поэтому диспетчер идет по настоящим часам
+    let dispatcher = NotificationDispatcher::new(
+        Arc::new(PostgreSQLOutboxRepository::new(pool.clone())),
+        Arc::new(SystemClock),
+    )
+    .with_channel(notifier.clone());
+
+    Services {
+        clock,
+        notifier,
+        dispatcher,
+        bookings: booking_services.bookings,
+        holds: booking_services.holds,
+        waitlist: booking_services.waitlist,
+        sweeper: booking_services.sweeper,
+    }
+}
+
+/// Послезавтра сотрудник принимает только с 10:00 до 11:00 - ровно одна запись на день
+async fn short_day(pool: &PgPool, seed: &common::Seed) -> NaiveDate {
+    let date = common::tomorrow_at(0, 0).date_naive() + Duration::days(1);
+    let catalog = CatalogServiceImpl::new(Arc::new(PostgreSQLCompanyRepository::new(pool.clone())));
+    for (is_available, start, end, location_id) in [
+        (false, None, None, None),
+        (true, Some(10), Some(11), Some(seed.location_id)),
+    ] {
+        catalog
+            .add_schedule_exception(
+                seed.owner.id,
+                seed.company_id,
+                seed.staff_id,
+                CreateScheduleExceptionRequest {
+                    exception_date: date,
+                    start_time: start.map(|hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap()),
+                    end_time: end.map(|hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap()),
+                    is_available,
+                    location_id,
+                },
+            )
+            .await
+            .unwrap();
+    }
+    date
+}
+
+fn join(seed: &common::Seed, date: NaiveDate) -> JoinWaitlistRequest {
+    JoinWaitlistRequest {
+        company_id: seed.company_id,
+        service_id: seed.service_id,
+        staff_id: None,
+        location_id: None,
+        date,
+    }
+}
+
+async fn cancel(services: &Services, customer_id: Uuid, booking_id: Uuid) {
+    services
+        .bookings
+        .change_status(
+            customer_id,
+            booking_id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: None,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[actix_web::test]
+async fn freed_slot_goes_down_the_queue_until_someone_takes_it() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let services = services(&pool);
+    let date = short_day(&pool, &seed).await;
+    let owner_of_slot = common::create_user(&pool, "first").await;
+    let second = common::create_user(&pool, "second").await;
+    let third = common::create_user(&pool, "third").await;
+
+    // Пока время есть, в очередь не ставим
+    let refused = services.waitlist.join_waitlist(second.id, join(&seed, date)).await;
+    assert!(matches!(refused, Err(AppError::Conflict(_))));
+
+    let starts_at = date.and_hms_opt(10, 0, 0).unwrap().and_utc();
+    let booking = services
+        .bookings
+        .create_booking(
+            owner_of_slot.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at,
+                promo_code: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let second_entry = services.waitlist.join_waitlist(second.id, join(&seed, date)).await.unwrap();
+    let third_entry = services.waitlist.join_waitlist(third.id, join(&seed, date)).await.unwrap();
+    assert!(matches!(
+        services.waitlist.join_waitlist(second.id, join(&seed, date)).await,
+        Err(AppError::Conflict(_))
+    ));
+
+    // Отмена: слот предлагается первому в очереди и держится за ним бронью
+    cancel(&services, owner_of_slot.id, booking.id).await;
+    let offered = services.waitlist.list_waitlist(second.id).await.unwrap();
+    assert_eq!(offered[0].status, WaitlistStatus::Offered);
+    assert_eq!(offered[0].offered_starts_at, Some(starts_at));
+    assert!(offered[0].hold_id.is_some());
+    assert_eq!(services.offer_recipients().await, vec![second.id]);
+
+    // Отказ - предложение уходит следующему
+    services.waitlist.decline_offer(second.id, second_entry.id).await.unwrap();
+    assert_eq!(services.offer_recipients().await, vec![second.id, third.id]);
+    let third_offer = &services.waitlist.list_waitlist(third.id).await.unwrap()[0];
+    assert_eq!(third_offer.status, WaitlistStatus::Offered);
+
+    // Третий не успел ответить: предложение просрочено, бронь снята, очередь пуста
+    services.clock.advance(Duration::minutes(31));
+    services.sweeper.run_once().await.unwrap();
+    let expired = &services.waitlist.list_waitlist(third.id).await.unwrap()[0];
+    assert_eq!(expired.status, WaitlistStatus::Expired);
+    assert!(matches!(
+        services.waitlist.accept_offer(third.id, third_entry.id).await,
+        Err(AppError::Conflict(_))
+    ));
+
+    // Слот снова свободен для обычной записи
+    services
+        .bookings
+        .create_booking(
+            owner_of_slot.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at,
+                promo_code: None,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[actix_web::test]
+async fn accepted_offer_becomes_a_booking() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let services = services(&pool);
+    let date = short_day(&pool, &seed).await;
+    let first = common::create_user(&pool, "first").await;
+    let waiting = common::create_user(&pool, "waiting").await;
+
+    let booking = services
+        .bookings
+        .create_booking(
+            first.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: date.and_hms_opt(10, 0, 0).unwrap().and_utc(),
+                promo_code: None,
+            },
+        )
+        .await
+        .unwrap();
+    let entry = services.waitlist.join_waitlist(waiting.id, join(&seed, date)).await.unwrap();
+
+    cancel(&services, first.id, booking.id).await;
+    // Обычная бронь другого слота не снимает бронь предложения
+    services
+        .holds
+        .create_hold(
+            waiting.id,
+            CreateHoldRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: common::tomorrow_at(12, 0),
+            },
+        )
+        .await
+        .unwrap();
+    let accepted = services.waitlist.accept_offer(waiting.id, entry.id).await.unwrap();
+    assert_eq!(accepted.customer_id, waiting.id);
+    assert_eq!(accepted.status, BookingStatus::Confirmed);
+
+    let entries = services.waitlist.list_waitlist(waiting.id).await.unwrap();
+    assert_eq!(entries[0].status, WaitlistStatus::Booked);
+
+    // Подтвержденная запись не просрочивается вместе с предложением
+    services.clock.advance(Duration::minutes(31));
+    services.sweeper.run_once().await.unwrap();
+    let still = services.bookings.get_booking(waiting.id, accepted.id).await.unwrap();
+    assert_eq!(still.status, BookingStatus::Confirmed);
+}
@@ -0,0 +1,305 @@
+mod common;
+
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, Utc};
+use common::ManualClock;
+use serde_json::{Value, json};
+use server::application::catalog_service::CatalogServiceImpl;
+use server::application::queue_service::QueueServiceImpl;
+use server::domain::entities::{
+    CallNextRequest, ChangeQueueTicketStatusRequest, CreateStaffRequest, JoinQueueRequest, QueueStatsQuery,
+    QueueTicketStatus,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{CatalogService, CompanyRepository, JobRepository, QueueService};
+use server::infrastructure::job_queue::{JobHandler, JobQueue};
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::maintenance_jobs::{CleanupHandler, CleanupJob};
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_job_repository::PostgreSQLJobRepository;
+use server::infrastructure::postgres_queue_repository::PostgreSQLQueueRepository;
+use server::infrastructure::queue_event_bus::PostgresQueueEventBus;
+use server::presentation::routes::api_v1_routes;
+
+/// Следующее событие потока талона или `None`, если поток закрылся
+async fn next_ticket_event(body: &mut BoxBody) -> Option<Value> {
+    loop {
+        let chunk = poll_fn(|cx| Pin::new(&mut *body).poll_next(cx));
+        let frame = match actix_web::rt::time::timeout(StdDuration::from_secs(5), chunk).await {
+            Ok(Some(Ok(bytes))) => String::from_utf8(bytes.to_vec()).unwrap(),
+            Ok(_) => return None,
+            Err(_) => panic!("Событие не пришло"),
+        };
+        if frame.starts_with(':') {
+            continue;
+        }
+        let data = frame
+            .strip_prefix("event: ticket\ndata: ")
+            .unwrap_or_else(|| panic!("Неожиданный кадр: {:?}", frame));
+        return Some(serde_json::from_str(data.trim_end()).unwrap());
+    }
+}
+
+#[actix_web::test]
+async fn walk_in_customers_are_called_in_order_with_live_positions() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let boris = common::create_user(&pool, "boris").await;
+    let kiosk = common::create_user(&pool, "kiosk").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    // Киоск в холле работает под аккаунтом участника компании
+    CatalogServiceImpl::new(company_repository.clone())
+        .create_staff(
+            seed.owner.id,
+            seed.company_id,
+            CreateStaffRequest {
+                display_name: "Киоск".to_string(),
+                user_id: Some(kiosk.id),
+                service_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let bus = PostgresQueueEventBus::listen(&pool).await.unwrap();
+    let queue_service: Arc<dyn QueueService + Send + Sync> = Arc::new(
+        QueueServiceImpl::new(
+            company_repository,
+            Arc::new(PostgreSQLQueueRepository::new(pool.clone())),
+            clock.clone(),
+        )
+        .with_events(bus),
+    );
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(queue_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+
+    let queue_uri = format!("/v1/companies/{}/queue", seed.company_id);
+    let join = |user_id, customer_name: Option<&str>| {
+        test::TestRequest::post()
+            .uri(&queue_uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(json!({ "location_id": seed.location_id, "customer_name": customer_name }))
+            .to_request()
+    };
+    let call_next = |user_id| {
+        test::TestRequest::post()
+            .uri(&format!("{}/call-next", queue_uri))
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(json!({ "location_id": seed.location_id }))
+            .to_request()
+    };
+    let set_status = |user_id, ticket: &Value, status: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/v1/queue-tickets/{}/status", ticket["id"].as_str().unwrap()))
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(json!({ "status": status }))
+            .to_request()
+    };
+    let get_ticket = |ticket: &Value| {
+        test::TestRequest::get()
+            .uri(&format!("/v1/queue-tickets/{}", ticket["id"].as_str().unwrap()))
+            .to_request()
+    };
+
+    // Клиенты встают в очередь из приложения, киоск выдает талон по имени
+    let response = test::call_service(&app, join(anna.id, None)).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let first: Value = test::read_body_json(response).await;
+    assert_eq!(first["number"], 1);
+    assert_eq!(first["position"], 1);
+    assert_eq!(first["estimated_wait_minutes"], 0);
+    assert_eq!(test::call_service(&app, join(anna.id, None)).await.status(), StatusCode::CONFLICT);
+    assert_eq!(test::call_service(&app, join(boris.id, Some("Иван"))).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(test::call_service(&app, join(kiosk.id, Some("  "))).await.status(), StatusCode::BAD_REQUEST);
+    let walk_in: Value = test::call_and_read_body_json(&app, join(kiosk.id, Some("Иван"))).await;
+    let second: Value = test::call_and_read_body_json(&app, join(boris.id, None)).await;
+    // Пока истории нет, на каждого впереди приходится 15 минут
+    assert_eq!(second["number"], 3);
+    assert_eq!(second["position"], 3);
+    assert_eq!(second["estimated_wait_minutes"], 30);
+
+    let stream_uri = format!("/v1/queue-tickets/{}/stream", second["id"].as_str().unwrap());
+    let response = test::call_service(&app, test::TestRequest::get().uri(&stream_uri).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut stream = response.into_body();
+    let current = next_ticket_event(&mut stream).await.unwrap();
+    assert_eq!(current["position"], 3);
+
+    // Вызывают только сотрудники, по порядку номеров
+    assert_eq!(test::call_service(&app, call_next(anna.id)).await.status(), StatusCode::FORBIDDEN);
+    let called: Value = test::call_and_read_body_json(&app, call_next(seed.owner.id)).await;
+    assert_eq!(called["id"], first["id"]);
+    assert_eq!(called["status"], "called");
+    assert_eq!(called["called_by"], seed.owner.id.to_string());
+    let update = next_ticket_event(&mut stream).await.unwrap();
+    assert_eq!(update["position"], 2);
+    assert_eq!(update["estimated_wait_minutes"], 15);
+
+    // Обслуживание за 10 минут становится историей филиала
+    clock.advance(Duration::minutes(10));
+    let response = test::call_service(&app, set_status(anna.id, &first, "served")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = test::call_service(&app, set_status(seed.owner.id, &walk_in, "served")).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let served: Value = test::call_and_read_body_json(&app, set_status(seed.owner.id, &first, "served")).await;
+    assert_eq!(served["status"], "served");
+    let update = next_ticket_event(&mut stream).await.unwrap();
+    assert_eq!(update["position"], 2);
+    assert_eq!(update["estimated_wait_minutes"], 10);
+
+    // Посторонний не может отменить чужой талон, а клиент киоска уходит через сотрудника
+    let response = test::call_service(&app, set_status(anna.id, &walk_in, "cancelled")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let cancelled: Value = test::call_and_read_body_json(&app, set_status(kiosk.id, &walk_in, "cancelled")).await;
+    assert_eq!(cancelled["status"], "cancelled");
+    let update = next_ticket_event(&mut stream).await.unwrap();
+    assert_eq!(update["position"], 1);
+    assert_eq!(update["estimated_wait_minutes"], 0);
+
+    let called: Value = test::call_and_read_body_json(&app, call_next(kiosk.id)).await;
+    assert_eq!(called["id"], second["id"]);
+    let update = next_ticket_event(&mut stream).await.unwrap();
+    assert_eq!(update["status"], "called");
+    assert_eq!(update["position"], Value::Null);
+    assert_eq!(test::call_service(&app, call_next(kiosk.id)).await.status(), StatusCode::NOT_FOUND);
+    clock.advance(Duration::minutes(20));
+    let response = test::call_service(&app, set_status(kiosk.id, &second, "no_show")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    // Талон покинул очередь - поток закрывается
+    assert_eq!(next_ticket_event(&mut stream).await.unwrap()["status"], "no_show");
+    assert!(next_ticket_event(&mut stream).await.is_none());
+    let view: Value = test::call_and_read_body_json(&app, get_ticket(&second)).await;
+    assert_eq!(view["status"], "no_show");
+    assert!(view.get("customer_id").is_none());
+
+    let queue: Value = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("{}?location={}", queue_uri, seed.location_id))
+            .insert_header(("Authorization", bearer(kiosk.id)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(queue["average_service_minutes"], 10.0);
+    let statuses: Vec<&str> = queue["tickets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ticket| ticket["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["served", "cancelled", "no_show"]);
+    assert_eq!(queue["tickets"][1]["customer_name"], "Иван");
+}
+
+#[actix_web::test]
+async fn queue_restarts_every_day_and_keeps_statistics() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let anna = common::create_user(&pool, "anna").await;
+    let boris = common::create_user(&pool, "boris").await;
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let clock = Arc::new(ManualClock::new(common::tomorrow_at(9, 0)));
+    let queue = QueueServiceImpl::new(
+        company_repository,
+        Arc::new(PostgreSQLQueueRepository::new(pool.clone())),
+        clock.clone(),
+    );
+    let join = |customer_id| {
+        queue.join_queue(
+            customer_id,
+            seed.company_id,
+            JoinQueueRequest {
+                location_id: seed.location_id,
+                service_id: None,
+                customer_name: None,
+            },
+        )
+    };
+    let call_next = || {
+        queue.call_next(
+            seed.owner.id,
+            seed.company_id,
+            CallNextRequest { location_id: seed.location_id },
+        )
+    };
+
+    let first = join(anna.id).await.unwrap();
+    let left_over = join(boris.id).await.unwrap();
+    clock.advance(Duration::minutes(6));
+    let called = call_next().await.unwrap();
+    assert_eq!(called.id, first.id);
+    clock.advance(Duration::minutes(12));
+    queue
+        .change_ticket_status(
+            seed.owner.id,
+            first.id,
+            ChangeQueueTicketStatusRequest { status: QueueTicketStatus::Served },
+        )
+        .await
+        .unwrap();
+
+    // Следующий день: номера заново, вчерашний талон не мешает встать в очередь
+    clock.advance(Duration::days(1));
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = Arc::new(PostgreSQLJobRepository::new(pool.clone()));
+    CleanupHandler::new(pool.clone(), JobQueue::new(job_repository), clock.clone())
+        .handle(CleanupJob { retention_days: 30 })
+        .await
+        .unwrap();
+    assert_eq!(queue.get_ticket(left_over.id).await.unwrap().status, QueueTicketStatus::Expired);
+    let today = join(boris.id).await.unwrap();
+    assert_eq!(today.number, 1);
+    assert_eq!(today.queue_date, first.queue_date + Duration::days(1));
+    assert_eq!(today.estimated_wait_minutes, Some(0));
+    let called = call_next().await.unwrap();
+    assert_eq!(called.id, today.id);
+
+    let stats = queue
+        .queue_stats(
+            seed.owner.id,
+            seed.company_id,
+            QueueStatsQuery {
+                location: seed.location_id,
+                from: first.queue_date,
+                to: today.queue_date,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.len(), 2);
+    assert_eq!((stats[0].joined, stats[0].served, stats[0].expired), (2, 1, 1));
+    assert_eq!(stats[0].average_wait_minutes, Some(6.0));
+    assert_eq!(stats[0].average_service_minutes, Some(12.0));
+    assert_eq!((stats[1].joined, stats[1].served), (1, 0));
+    assert_eq!(stats[1].average_service_minutes, None);
+
+    // Статистику видят только сотрудники
+    let result = queue
+        .queue_stats(
+            anna.id,
+            seed.company_id,
+            QueueStatsQuery {
+                location: seed.location_id,
+                from: first.queue_date,
+                to: first.queue_date,
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))), "{:?}", result);
+}
@@ -0,0 +1,329 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+
+use actix_web::{App, http::StatusCode, test, web};
+use chrono::{Duration, Utc};
+use common::ManualClock;
+use server::application::webhook_service::WebhookServiceImpl;
+use server::application::webhook_signature::verify;
+use server::domain::entities::{
+    BookingStatus, ChangeBookingStatusRequest, CreateBookingRequest, CreateWebhookRequest, RescheduleBookingRequest,
+    WebhookDeliveriesQuery, WebhookEvent,
+};
+use server::domain::errors::AppError;
+use server::domain::traits::{BookingService, CompanyRepository, JobRepository, WebhookRepository, WebhookService};
+use server::infrastructure::clock::SystemClock;
+use server::infrastructure::job_queue::JobWorker;
+use server::infrastructure::jwt::jwt_service::JwtService;
+use server::infrastructure::postgres_company_repository::PostgreSQLCompanyRepository;
+use server::infrastructure::postgres_job_repository::PostgreSQLJobRepository;
+use server::infrastructure::postgres_webhook_repository::PostgreSQLWebhookRepository;
+use server::infrastructure::webhook_delivery::WebhookDeliveryHandler;
+use server::presentation::routes::api_v1_routes;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Полученный заглушкой запрос: заголовки в нижнем регистре и тело
+struct StubRequest {
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl StubRequest {
+    fn header(&self, name: &str) -> &str {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == &name.to_lowercase())
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// HTTP-сервер на несколько соединений: на каждое отвечает очередным статусом из списка
+fn http_stub(statuses: Vec<u16>) -> (String, std::thread::JoinHandle<Vec<StubRequest>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((key, value)) = line.split_once(':') {
+                    headers.push((key.trim().to_lowercase(), value.trim().to_string()));
+                }
+            }
+            let length: usize = headers
+                .iter()
+                .find(|(key, _)| key == "content-length")
+                .map(|(_, value)| value.parse().unwrap())
+                .unwrap_or(0);
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            requests.push(StubRequest {
+                headers,
+                body: String::from_utf8(body).unwrap(),
+            });
+
+            let answer = if status == 200 { "ok" } else { "unavailable" };
+            write!(
+                writer,
+                "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                answer.len(),
+                answer
+            )
+            .unwrap();
+        }
+        requests
+    });
+    (url, handle)
+}
+
+#[actix_web::test]
+async fn booking_events_are_signed_retried_and_logged() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let customer = common::create_user(&pool, "patient").await;
+    let jwt_service = JwtService::new();
+    let bearer = |user_id| format!("Bearer {}", jwt_service.generate_access_token(user_id, "").unwrap());
+
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let webhook_repository: Arc<dyn WebhookRepository + Send + Sync> =
+        Arc::new(PostgreSQLWebhookRepository::new(pool.clone()));
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = Arc::new(PostgreSQLJobRepository::new(pool.clone()));
+    let bookings = common::booking_services(&pool, Arc::new(SystemClock)).bookings;
+    // Заглушка получателя слушает loopback
+    let webhook_service: Arc<dyn WebhookService + Send + Sync> = Arc::new(
+        WebhookServiceImpl::new(company_repository, webhook_repository.clone()).with_private_urls(true),
+    );
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_service.clone()))
+            .app_data(web::Data::new(webhook_service))
+            .service(api_v1_routes()),
+    )
+    .await;
+    let webhooks_uri = format!("/v1/companies/{}/webhooks", seed.company_id);
+    let create = |user_id: Uuid, body: Value| {
+        test::TestRequest::post()
+            .uri(&webhooks_uri)
+            .insert_header(("Authorization", bearer(user_id)))
+            .set_json(body)
+            .to_request()
+    };
+
+    let (url, stub) = http_stub(vec![500, 200, 200, 200]);
+    let subscription = json!({"url": url, "events": ["booking.created", "booking.cancelled"]});
+    assert_eq!(
+        test::call_service(&app, create(customer.id, subscription.clone())).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let invalid = json!({"url": "ftp://crm.local", "events": ["booking.created"]});
+    assert_eq!(test::call_service(&app, create(seed.owner.id, invalid)).await.status(), StatusCode::BAD_REQUEST);
+    let invalid = json!({"url": url, "events": ["webhook.test"]});
+    assert_eq!(test::call_service(&app, create(seed.owner.id, invalid)).await.status(), StatusCode::BAD_REQUEST);
+    let response = test::call_service(&app, create(seed.owner.id, subscription)).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let webhook: Value = test::read_body_json(response).await;
+    let secret = webhook["secret"].as_str().unwrap().to_string();
+    assert!(secret.starts_with("whsec_"));
+    let webhook_uri = format!("{}/{}", webhooks_uri, webhook["id"].as_str().unwrap());
+
+    // Создание и отмена попадают в журнал, перенос - нет: на него не подписывались
+    let booking = bookings
+        .create_booking(
+            customer.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: common::tomorrow_at(10, 0),
+                promo_code: None,
+            },
+        )
+        .await
+        .unwrap();
+    bookings
+        .reschedule_booking(
+            customer.id,
+            booking.id,
+            RescheduleBookingRequest {
+                starts_at: common::tomorrow_at(12, 0),
+                staff_id: None,
+                location_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    bookings
+        .change_status(
+            customer.id,
+            booking.id,
+            ChangeBookingStatusRequest {
+                status: BookingStatus::CancelledByCustomer,
+                reason: Some("Заболел".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+
+    // Первая попытка получает 500 и повторяется через паузу очереди задач
+    let clock = Arc::new(ManualClock::new(Utc::now() + Duration::seconds(1)));
+    let worker = JobWorker::new(job_repository, clock.clone())
+        .register(WebhookDeliveryHandler::new(webhook_repository, clock.clone()).with_private_urls(true));
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+    clock.advance(Duration::seconds(10));
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+
+    let deliveries_request = |query: &str| {
+        test::TestRequest::get()
+            .uri(&format!("{}/deliveries{}", webhook_uri, query))
+            .insert_header(("Authorization", bearer(seed.owner.id)))
+            .to_request()
+    };
+    let deliveries: Value = test::call_and_read_body_json(&app, deliveries_request("")).await;
+    let deliveries = deliveries.as_array().unwrap();
+    assert_eq!(deliveries.len(), 2);
+    let created = deliveries.iter().find(|d| d["event_type"] == "booking.created").unwrap();
+    assert_eq!(created["status"], "delivered");
+    assert_eq!(created["attempts"], 2);
+    assert_eq!(created["response_status"], 200);
+    assert_eq!(created["payload"]["data"]["booking_id"], booking.id.to_string());
+    let cancelled = deliveries.iter().find(|d| d["event_type"] == "booking.cancelled").unwrap();
+    assert_eq!(cancelled["attempts"], 1);
+    assert_eq!(cancelled["payload"]["data"]["reason"], "Заболел");
+    assert_eq!(cancelled["payload"]["data"]["previous_status"], booking.status.as_str());
+    let failed: Value = test::call_and_read_body_json(&app, deliveries_request("?status=failed")).await;
+    assert_eq!(failed, json!([]));
+
+    // Проверочное событие по запросу владельца
+    let request = test::TestRequest::post()
+        .uri(&format!("{}/test", webhook_uri))
+        .insert_header(("Authorization", bearer(seed.owner.id)))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let test_delivery: Value = test::read_body_json(response).await;
+    assert_eq!(test_delivery["event_type"], "webhook.test");
+    assert_eq!(test_delivery["status"], "pending");
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+
+    let requests = stub.join().unwrap();
+    assert_eq!(requests.len(), 4);
+    let events: Vec<&str> = requests.iter().map(|r| r.header("X-LocationX-Event")).collect();
+    assert_eq!(events, vec!["booking.created", "booking.cancelled", "booking.created", "webhook.test"]);
+    for request in &requests {
+        let timestamp: i64 = request.header("X-LocationX-Timestamp").parse().unwrap();
+        let signature = request.header("X-LocationX-Signature");
+        assert!(verify(&secret, timestamp, &request.body, signature));
+        assert!(!verify("whsec_other", timestamp, &request.body, signature));
+        let body: Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["type"], request.header("X-LocationX-Event"));
+    }
+    // Повтор несет то же событие, получатель может отсеять его по id
+    assert_eq!(requests[0].body, requests[2].body);
+    assert_eq!(requests[3].header("X-LocationX-Delivery"), test_delivery["id"].as_str().unwrap());
+
+    // Выключенный вебхук событий не получает; удаление убирает его из списка
+    let request = test::TestRequest::patch()
+        .uri(&webhook_uri)
+        .insert_header(("Authorization", bearer(seed.owner.id)))
+        .set_json(json!({"is_active": false}))
+        .to_request();
+    let updated: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(updated["is_active"], false);
+    assert_eq!(updated["events"], json!(["booking.created", "booking.cancelled"]));
+    assert!(updated.get("secret").is_none());
+    bookings
+        .create_booking(
+            customer.id,
+            CreateBookingRequest {
+                company_id: seed.company_id,
+                service_id: seed.service_id,
+                staff_id: seed.staff_id,
+                location_id: None,
+                starts_at: common::tomorrow_at(15, 0),
+                promo_code: None,
+            },
+        )
+        .await
+        .unwrap();
+    let deliveries: Value = test::call_and_read_body_json(&app, deliveries_request("")).await;
+    assert_eq!(deliveries.as_array().unwrap().len(), 3);
+
+    let request = test::TestRequest::delete()
+        .uri(&webhook_uri)
+        .insert_header(("Authorization", bearer(seed.owner.id)))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NO_CONTENT);
+    let request = test::TestRequest::get()
+        .uri(&webhooks_uri)
+        .insert_header(("Authorization", bearer(seed.owner.id)))
+        .to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(list, json!([]));
+    assert_eq!(test::call_service(&app, deliveries_request("")).await.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn internal_addresses_are_refused() {
+    let Some(pool) = common::test_pool().await else { return };
+    let seed = common::seed_company(&pool).await;
+    let company_repository: Arc<dyn CompanyRepository + Send + Sync> =
+        Arc::new(PostgreSQLCompanyRepository::new(pool.clone()));
+    let webhook_repository: Arc<dyn WebhookRepository + Send + Sync> =
+        Arc::new(PostgreSQLWebhookRepository::new(pool.clone()));
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = Arc::new(PostgreSQLJobRepository::new(pool.clone()));
+    let strict = WebhookServiceImpl::new(company_repository.clone(), webhook_repository.clone());
+    let request = |url: &str| CreateWebhookRequest {
+        url: url.to_string(),
+        events: vec![WebhookEvent::BookingCreated],
+    };
+
+    for url in [
+        "http://127.0.0.1:8080/hooks",
+        "http://localhost/hooks",
+        "http://[::1]/hooks",
+        "http://10.0.0.5/hooks",
+        "http://169.254.169.254/latest/meta-data",
+    ] {
+        let refused = strict.create_webhook(seed.owner.id, seed.company_id, request(url)).await;
+        assert!(matches!(refused, Err(AppError::Validation(_))), "{}", url);
+    }
+
+    // Адрес сохранили при отключенной проверке: доставка все равно не уходит во внутреннюю сеть
+    let (url, _stub) = http_stub(Vec::new());
+    let created = WebhookServiceImpl::new(company_repository, webhook_repository.clone())
+        .with_private_urls(true)
+        .create_webhook(seed.owner.id, seed.company_id, request(&url))
+        .await
+        .unwrap();
+    let webhook_id = created.subscription.id;
+    strict.send_test_event(seed.owner.id, seed.company_id, webhook_id).await.unwrap();
+    let clock = Arc::new(ManualClock::new(Utc::now() + Duration::seconds(1)));
+    let worker = JobWorker::new(job_repository, clock.clone())
+        .register(WebhookDeliveryHandler::new(webhook_repository, clock.clone()));
+    assert_eq!(worker.run_once().await.unwrap(), 0);
+
+    let deliveries = strict
+        .list_deliveries(seed.owner.id, seed.company_id, webhook_id, WebhookDeliveriesQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(deliveries[0].attempts, 1);
+    assert_eq!(deliveries[0].response_status, None);
+    assert_eq!(deliveries[0].last_error.as_deref(), Some("Адрес вебхука указывает на внутреннюю сеть"));
+}